@@ -0,0 +1,302 @@
+//! row and column equilibration of a matrix.
+//!
+//! given a matrix $A$, these routines compute diagonal scaling factors $R$ and $C$ such that the
+//! entries of $R A C$ are better balanced, which improves the behavior of pivoting strategies and
+//! the accuracy of the computed factorizations
+//!
+//! a system $A x = b$ can then be solved through the equilibrated system
+//! $(R A C) y = R b$, with $x = C y$. the $LU$ solvers do this transparently when constructed
+//! with [`PartialPivLu::new_equilibrated`](crate::linalg::solvers::PartialPivLu::new_equilibrated)
+//! or [`FullPivLu::new_equilibrated`](crate::linalg::solvers::FullPivLu::new_equilibrated)
+
+use crate::assert;
+use crate::internal_prelude::*;
+
+/// ruiz equilibration parameters
+#[derive(Copy, Clone, Debug)]
+pub struct RuizParams<T: RealField> {
+	/// maximum number of scaling passes
+	pub max_iters: usize,
+	/// the iteration stops once the infinity norm of every row and column of the scaled matrix is
+	/// within `tolerance` of one
+	pub tolerance: T,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for RuizParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			max_iters: 20,
+			tolerance: from_f64::<T>(1e-3),
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// equilibration method
+#[derive(Copy, Clone, Debug)]
+pub enum EquilibrationMethod<T: RealField> {
+	/// scales the rows, then the columns, by powers of two so that the largest entry of each has
+	/// magnitude in $[1, 2)$, similarly to lapack's `geequb`. the scaling is exact in floating
+	/// point arithmetic
+	PowerOfTwo,
+	/// ruiz's iterative scaling, which makes the infinity norm of every row and column of the
+	/// scaled matrix approach one
+	Ruiz(RuizParams<T>),
+}
+
+impl<T: RealField> Default for EquilibrationMethod<T> {
+	#[inline]
+	fn default() -> Self {
+		Self::PowerOfTwo
+	}
+}
+
+/// equilibration result
+#[derive(Copy, Clone, Debug)]
+pub struct EquilibrationInfo<T: RealField> {
+	/// ratio of the smallest to the largest row scaling factor
+	pub row_ratio: T,
+	/// ratio of the smallest to the largest column scaling factor
+	pub col_ratio: T,
+	/// largest magnitude of the entries of the original matrix
+	pub abs_max: T,
+	/// number of scaling passes
+	pub iter_count: usize,
+	/// whether the matrix has a row or column with no nonzero entry, in which case the matrix is
+	/// singular and the corresponding scaling factor is set to one
+	pub has_zero_line: bool,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// returns the largest power of two that is not greater than `value`, which must be positive and
+/// finite
+#[math]
+fn pow2_floor<T: RealField>(value: T) -> T {
+	let two = from_f64::<T>(2.0);
+	let mut x = copy(value);
+	let mut p = one::<T>();
+
+	while x >= two {
+		let mut s = copy(two);
+		while x >= s * s {
+			s = s * s;
+		}
+		x = x / s;
+		p = p * s;
+	}
+	while x < one() {
+		let mut s = copy(two);
+		while x * s * s <= one() {
+			s = s * s;
+		}
+		x = x * s;
+		p = p / s;
+	}
+	p
+}
+
+#[math]
+fn ratio<T: RealField>(scale: ColRef<'_, T>) -> T {
+	if scale.nrows() == 0 {
+		return one();
+	}
+	let mut min_ = copy(scale[0]);
+	let mut max_ = copy(scale[0]);
+	for i in 1..scale.nrows() {
+		min_ = min(min_, scale[i]);
+		max_ = max(max_, scale[i]);
+	}
+	min_ / max_
+}
+
+/// computes the scaling factors, given a callback that computes the largest magnitude of each row
+/// and column of $R A C$
+#[math]
+pub(crate) fn equilibrate_imp<T: RealField>(
+	row_scale: ColMut<'_, T>,
+	col_scale: ColMut<'_, T>,
+	mut scaled_abs_max: impl FnMut(ColMut<'_, T>, ColMut<'_, T>, ColRef<'_, T>, ColRef<'_, T>),
+	method: EquilibrationMethod<T>,
+) -> EquilibrationInfo<T> {
+	let mut r = row_scale;
+	let mut c = col_scale;
+	let m = r.nrows();
+	let n = c.nrows();
+
+	r.fill(one());
+	c.fill(one());
+
+	let mut row_max = Col::<T>::zeros(m);
+	let mut col_max = Col::<T>::zeros(n);
+
+	scaled_abs_max(row_max.as_mut(), col_max.as_mut(), r.rb(), c.rb());
+	let mut abs_max = zero::<T>();
+	for i in 0..m {
+		abs_max = max(abs_max, row_max[i]);
+	}
+
+	let mut has_zero_line = false;
+	let mut iter_count = 0;
+
+	match method {
+		EquilibrationMethod::PowerOfTwo => {
+			for i in 0..m {
+				if row_max[i] > zero() {
+					r[i] = recip(pow2_floor(copy(row_max[i])));
+				} else {
+					has_zero_line = true;
+				}
+			}
+
+			scaled_abs_max(row_max.as_mut(), col_max.as_mut(), r.rb(), c.rb());
+			for j in 0..n {
+				if col_max[j] > zero() {
+					c[j] = recip(pow2_floor(copy(col_max[j])));
+				} else {
+					has_zero_line = true;
+				}
+			}
+			iter_count = 1;
+		},
+		EquilibrationMethod::Ruiz(params) => {
+			for i in 0..m {
+				has_zero_line = has_zero_line || row_max[i] == zero();
+			}
+			for j in 0..n {
+				has_zero_line = has_zero_line || col_max[j] == zero();
+			}
+
+			while iter_count < params.max_iters {
+				let mut dist = zero::<T>();
+				for i in 0..m {
+					if row_max[i] > zero() {
+						dist = max(dist, abs(one::<T>() - row_max[i]));
+					}
+				}
+				for j in 0..n {
+					if col_max[j] > zero() {
+						dist = max(dist, abs(one::<T>() - col_max[j]));
+					}
+				}
+				if dist <= params.tolerance {
+					break;
+				}
+
+				for i in 0..m {
+					if row_max[i] > zero() {
+						r[i] = r[i] / sqrt(row_max[i]);
+					}
+				}
+				for j in 0..n {
+					if col_max[j] > zero() {
+						c[j] = c[j] / sqrt(col_max[j]);
+					}
+				}
+				iter_count += 1;
+
+				scaled_abs_max(row_max.as_mut(), col_max.as_mut(), r.rb(), c.rb());
+			}
+		},
+	}
+
+	EquilibrationInfo {
+		row_ratio: ratio(r.rb()),
+		col_ratio: ratio(c.rb()),
+		abs_max,
+		iter_count,
+		has_zero_line,
+		non_exhaustive: NonExhaustive(()),
+	}
+}
+
+/// computes the row and column scaling factors $R$ and $C$ that equilibrate $A$, so that the
+/// entries of $R A C$ are better balanced
+#[track_caller]
+#[math]
+pub fn equilibrate<T: ComplexField>(
+	row_scale: ColMut<'_, T::Real>,
+	col_scale: ColMut<'_, T::Real>,
+	A: MatRef<'_, T>,
+	method: EquilibrationMethod<T::Real>,
+) -> EquilibrationInfo<T::Real> {
+	assert!(all(row_scale.nrows() == A.nrows(), col_scale.nrows() == A.ncols()));
+
+	equilibrate_imp(
+		row_scale,
+		col_scale,
+		|mut row_max, mut col_max, r, c| {
+			row_max.fill(zero());
+			for j in 0..A.ncols() {
+				let mut cmax = zero::<T::Real>();
+				for i in 0..A.nrows() {
+					let v = abs(A[(i, j)]) * r[i] * c[j];
+					row_max[i] = max(row_max[i], v);
+					cmax = max(cmax, v);
+				}
+				col_max[j] = cmax;
+			}
+		},
+		method,
+	)
+}
+
+/// computes $R A C$ in place, given the scaling factors
+#[track_caller]
+#[math]
+pub fn scale_in_place<T: ComplexField>(A: MatMut<'_, T>, row_scale: ColRef<'_, T::Real>, col_scale: ColRef<'_, T::Real>) {
+	assert!(all(row_scale.nrows() == A.nrows(), col_scale.nrows() == A.ncols()));
+
+	let mut A = A;
+	for j in 0..A.ncols() {
+		let cj = copy(col_scale[j]);
+		z!(A.rb_mut().col_mut(j), row_scale).for_each(|uz!(a, r)| *a = mul_real(*a, *r * cj));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+
+	#[test]
+	fn test_equilibrate() {
+		let n = 6;
+		// badly scaled matrix
+		let A = Mat::from_fn(n, n, |i, j| {
+			let a = if i == j { 4.0 } else { 1.0 / (1.0 + (i + j) as f64) };
+			a * 10.0f64.powi(3 * i as i32 - 8) * 10.0f64.powi(2 * j as i32)
+		});
+
+		for method in [EquilibrationMethod::PowerOfTwo, EquilibrationMethod::Ruiz(default())] {
+			let mut r = Col::zeros(n);
+			let mut c = Col::zeros(n);
+			let info = equilibrate(r.as_mut(), c.as_mut(), A.as_ref(), method);
+			assert!(!info.has_zero_line);
+
+			let mut S = A.clone();
+			scale_in_place(S.as_mut(), r.as_ref(), c.as_ref());
+
+			for i in 0..n {
+				let row_max = S.row(i).iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+				assert!(row_max > 0.1 && row_max <= 2.0);
+			}
+			for j in 0..n {
+				let col_max = S.col(j).iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+				assert!(col_max > 0.1 && col_max <= 2.0);
+			}
+
+			if let EquilibrationMethod::PowerOfTwo = method {
+				for i in 0..n {
+					assert!(r[i].log2().fract() == 0.0);
+					assert!(c[i].log2().fract() == 0.0);
+				}
+			}
+		}
+	}
+}
@@ -489,7 +489,7 @@ fn aggressive_early_deflation<T: ComplexField>(
 	(ns, nd)
 }
 
-fn schur_move<T: ComplexField>(mut a: MatMut<'_, T>, mut q: Option<MatMut<'_, T>>, ifst: usize, ilst: &mut usize) -> isize {
+pub(crate) fn schur_move<T: ComplexField>(mut a: MatMut<'_, T>, mut q: Option<MatMut<'_, T>>, ifst: usize, ilst: &mut usize) -> isize {
 	let n = a.nrows();
 
 	// Quick return
@@ -1405,6 +1405,36 @@ mod tests {
 		assert!(h_reconstructed ~ h);
 	}
 
+	#[test]
+	fn test_zero_bulge() {
+		// the first rotation of the sweep is close enough to the identity that the bulge
+		// underflows to zero, so the second rotation is computed from `(h10, 0)`, and its `r`
+		// must preserve the tiny subdiagonal entry
+		let n = 3;
+		let h = MatRef::from_row_major_array(try_const! {
+			&[
+				[c64::new(1e25, 0.0), c64::new(1e25, 0.0), c64::new(0.5, 0.5)],
+				[c64::new(1e-290, 0.0), c64::new(0.0, 0.0), c64::new(0.0, 0.0)],
+				[c64::new(0.0, 0.0), c64::new(1e-10, 0.0), c64::new(1.0, 0.0)],
+			]
+		});
+
+		let mut q = Mat::from_fn(n, n, |i, j| if i == j { c64::ONE } else { c64::ZERO });
+		let mut w = Col::zeros(n);
+		let mut t = h.cloned();
+		assert!(super::lahqr(true, t.as_mut(), Some(q.as_mut()), w.as_mut(), 0, n) == 0);
+
+		let mut w = w.iter().copied().collect::<Vec<_>>();
+		w.sort_by(|a, b| a.norm().total_cmp(&b.norm()));
+
+		assert!(w[0].norm() < 1e-8);
+		assert!((w[1] - c64::ONE).norm() < 1e-8);
+		assert!((w[2] - c64::new(1e25, 0.0)).norm() < 1e25 * 1e-12);
+
+		let err = &q * &t * q.adjoint() - h;
+		assert!(err.norm_max() < 1e25 * 1e-13);
+	}
+
 	#[test]
 	fn test_n() {
 		let rng = &mut StdRng::seed_from_u64(4);
@@ -1,4 +1,5 @@
 use super::*;
+use crate::assert;
 
 pub(crate) mod complex_schur;
 pub(crate) mod real_schur;
@@ -95,3 +96,526 @@ fn default_recommended_deflation_window(dim: usize, _active_block_dim: usize) ->
 		384
 	}
 }
+
+/// computes the size and alignment of the workspace required to compute a matrix's schur
+/// decomposition
+pub fn schur_in_place_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	let n = dim;
+	if n == 0 {
+		return StackReq::EMPTY;
+	}
+
+	let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n - 1, n - 1);
+	let householder = temp_mat_scratch::<T>(bs, n - 1);
+	let apply = linalg::householder::apply_block_householder_sequence_on_the_right_in_place_scratch::<T>(n - 1, bs, n - 1);
+
+	StackReq::any_of(&[
+		householder.and(linalg::evd::hessenberg::hessenberg_in_place_scratch::<T>(n, bs, par, default()).or(apply)),
+		temp_mat_scratch::<T>(n, 2).and(multishift_qr_scratch::<T>(n, n, true, true, par, auto!(T))),
+	])
+}
+
+/// overwrites $A$ with its schur form $T$, and $Z$ with the schur vectors, such that
+/// $A = Z T Z^H$
+///
+/// $T$ is upper triangular if `T` is complex, and upper quasi-triangular with $1 \times 1$ and
+/// $2 \times 2$ diagonal blocks if `T` is real
+#[math]
+pub fn schur_in_place<T: ComplexField>(A: MatMut<'_, T>, Z: MatMut<'_, T>, par: Par, stack: &mut MemStack) -> Result<(), EvdError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, Z.nrows() == n, Z.ncols() == n));
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	for j in 0..n {
+		for i in 0..n {
+			if !is_finite(A[(i, j)]) {
+				return Err(EvdError::NoConvergence);
+			}
+		}
+	}
+
+	let mut A = A;
+	let mut Z = Z;
+
+	{
+		let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n - 1, n - 1);
+		let (mut householder, stack) = unsafe { temp_mat_uninit::<T, _, _>(bs, n - 1, stack) };
+		let mut householder = householder.as_mat_mut();
+
+		linalg::evd::hessenberg::hessenberg_in_place(A.rb_mut(), householder.rb_mut(), par, stack, default());
+
+		Z.fill(zero());
+		Z.rb_mut().diagonal_mut().fill(one());
+		linalg::householder::apply_block_householder_sequence_on_the_right_in_place_with_conj(
+			A.rb().submatrix(1, 0, n - 1, n - 1),
+			householder.rb(),
+			Conj::No,
+			Z.rb_mut().submatrix_mut(1, 1, n - 1, n - 1),
+			par,
+			stack,
+		);
+
+		for j in 0..n {
+			for i in j + 2..n {
+				A[(i, j)] = zero();
+			}
+		}
+	}
+
+	let (mut w, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 2, stack) };
+	let mut w = w.as_mat_mut();
+	let (w_re, w_im) = w.rb_mut().two_cols_mut(0, 1);
+
+	let (info, _, _) = if try_const! { T::IS_REAL } {
+		real_schur::multishift_qr::<T::Real>(
+			true,
+			unsafe { core::mem::transmute(A.rb_mut()) },
+			unsafe { core::mem::transmute(Some(Z.rb_mut())) },
+			unsafe { core::mem::transmute(w_re) },
+			unsafe { core::mem::transmute(w_im) },
+			0,
+			n,
+			par,
+			stack,
+			auto!(T::Real),
+		)
+	} else {
+		complex_schur::multishift_qr::<T>(true, A.rb_mut(), Some(Z.rb_mut()), w_re, 0, n, par, stack, auto!(T))
+	};
+
+	if info != 0 {
+		return Err(EvdError::NoConvergence);
+	}
+
+	for j in 0..n {
+		for i in j + 2..n {
+			A[(i, j)] = zero();
+		}
+	}
+
+	Ok(())
+}
+
+/// returns the size of the diagonal block of the quasi-triangular matrix $T$ starting at `i`
+#[inline]
+#[math]
+pub(crate) fn block_size_at<T: ComplexField>(T: MatRef<'_, T>, i: usize) -> usize {
+	if i + 1 < T.nrows() && T[(i + 1, i)] != zero() { 2 } else { 1 }
+}
+
+/// returns the eigenvalue of the $1 \times 1$ or $2 \times 2$ diagonal block of the
+/// quasi-triangular matrix $T$ starting at `i`. for a $2 \times 2$ block, the eigenvalue with the
+/// positive imaginary part is returned
+#[math]
+pub(crate) fn block_eigenvalue_at<T: ComplexField>(T: MatRef<'_, T>, i: usize, block_size: usize) -> Complex<T::Real> {
+	if block_size == 1 {
+		return Complex::new(real(T[(i, i)]), imag(T[(i, i)]));
+	}
+
+	let a = real(T[(i, i)]);
+	let b = real(T[(i, i + 1)]);
+	let c = real(T[(i + 1, i)]);
+	let d = real(T[(i + 1, i + 1)]);
+
+	let half = from_f64::<T::Real>(0.5);
+	let p = half * (a - d);
+	let disc = p * p + b * c;
+	Complex::new(half * (a + d), sqrt(abs(disc)))
+}
+
+/// moves the diagonal block of the schur form $T$ starting at `ifst` to the position `ilst`,
+/// updating the schur vectors $Q$. returns `false` if a swap failed
+fn schur_move<T: ComplexField>(T: MatMut<'_, T>, Q: Option<MatMut<'_, T>>, ifst: usize, ilst: usize) -> bool {
+	let mut ilst = ilst;
+	let info = if try_const! { T::IS_REAL } {
+		real_schur::schur_move::<T::Real>(unsafe { core::mem::transmute(T) }, unsafe { core::mem::transmute(Q) }, ifst, &mut ilst)
+	} else {
+		complex_schur::schur_move::<T>(T, Q, ifst, &mut ilst)
+	};
+	info == 0
+}
+
+/// schur reordering error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SchurReorderError {
+	/// two diagonal blocks could not be swapped because their eigenvalues are too close. the schur
+	/// form is left partially reordered, and is still a valid schur form of the same matrix
+	SwapFailed,
+}
+
+impl core::fmt::Display for SchurReorderError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for SchurReorderError {}
+
+/// reorders the schur form $A = Q T Q^H$ so that the eigenvalues for which `select` returns
+/// `true` appear in the leading diagonal blocks of $T$, and updates the schur vectors $Q$
+///
+/// the leading columns of $Q$ then span the invariant subspace associated with the selected
+/// eigenvalues. the complex conjugate eigenvalues of a $2 \times 2$ block are selected together,
+/// based on the one with the positive imaginary part
+///
+/// returns the dimension of the invariant subspace
+#[track_caller]
+pub fn reorder_schur<T: ComplexField>(
+	T: MatMut<'_, T>,
+	Q: Option<MatMut<'_, T>>,
+	select: impl FnMut(Complex<T::Real>) -> bool,
+) -> Result<usize, SchurReorderError> {
+	let n = T.nrows();
+	assert!(T.ncols() == n);
+	if let Some(Q) = Q.rb() {
+		assert!(Q.ncols() == n);
+	}
+
+	let mut T = T;
+	let mut Q = Q;
+	let mut select = select;
+
+	let mut ks = 0;
+	let mut k = 0;
+	while k < n {
+		let bs = block_size_at(T.rb(), k);
+		if select(block_eigenvalue_at(T.rb(), k, bs)) {
+			if k != ks && !schur_move(T.rb_mut(), Q.rb_mut(), k, ks) {
+				return Err(SchurReorderError::SwapFailed);
+			}
+			ks += bs;
+		}
+		k += bs;
+	}
+
+	Ok(ks)
+}
+
+/// condition estimates of a cluster of eigenvalues of a schur form, and of the associated invariant
+/// subspace
+#[derive(Copy, Clone, Debug)]
+pub struct SchurClusterCondition<T> {
+	/// reciprocal condition number of the average of the eigenvalues of the cluster
+	pub eigenvalues: T,
+	/// estimate of the reciprocal condition number of the invariant subspace, i.e. of
+	/// $\text{sep}(T_{11}, T_{22})$
+	pub invariant_subspace: T,
+}
+
+/// computes the size and alignment of the workspace required to compute the condition estimates
+/// of a cluster of eigenvalues of a schur form
+pub fn schur_cluster_condition_scratch<T: ComplexField>(dim: usize, cluster_dim: usize, par: Par) -> StackReq {
+	assert!(cluster_dim <= dim);
+	let k = cluster_dim;
+	let m = dim - cluster_dim;
+	_ = par;
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(k, k).array(2),
+		temp_mat_scratch::<T>(m, m).array(2),
+		temp_mat_scratch::<T>(k, m).array(2),
+	])
+}
+
+/// computes the condition estimates of the cluster formed by the eigenvalues of the leading
+/// `cluster_dim` × `cluster_dim` block $T_{11}$ of the schur form
+/// $T = \begin{bmatrix} T_{11} & T_{12} \\ 0 & T_{22} \end{bmatrix}$, as computed by
+/// [`reorder_schur`]
+///
+/// the separation $\text{sep}(T_{11}, T_{22})$ is estimated with the $1$-norm of the inverse of
+/// the sylvester operator $R \mapsto T_{11} R - R T_{22}$. both estimates are zero if $T_{11}$ and
+/// $T_{22}$ have an eigenvalue in common
+#[math]
+#[track_caller]
+pub fn schur_cluster_condition<T: ComplexField>(
+	T: MatRef<'_, T>,
+	cluster_dim: usize,
+	par: Par,
+	stack: &mut MemStack,
+) -> SchurClusterCondition<T::Real> {
+	let n = T.nrows();
+	let k = cluster_dim;
+	assert!(all(T.ncols() == n, k <= n));
+	if k < n && k > 0 {
+		assert!(T[(k, k - 1)] == zero());
+	}
+	let m = n - k;
+
+	if k == 0 || m == 0 {
+		return SchurClusterCondition {
+			eigenvalues: one(),
+			invariant_subspace: T.norm_l1(),
+		};
+	}
+
+	let singular = SchurClusterCondition {
+		eigenvalues: zero(),
+		invariant_subspace: zero(),
+	};
+
+	let (mut T11, stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack) };
+	let (mut T11_adj, stack) = unsafe { temp_mat_uninit::<T, _, _>(k, k, stack) };
+	let (mut T22, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, m, stack) };
+	let (mut T22_adj, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, m, stack) };
+	let (mut X, stack) = unsafe { temp_mat_uninit::<T, _, _>(k, m, stack) };
+	let (mut Y, _) = unsafe { temp_mat_uninit::<T, _, _>(k, m, stack) };
+	let mut T11 = T11.as_mat_mut();
+	let mut T11_adj = T11_adj.as_mat_mut();
+	let mut T22 = T22.as_mat_mut();
+	let mut T22_adj = T22_adj.as_mat_mut();
+	let mut X = X.as_mat_mut();
+	let mut Y = Y.as_mat_mut();
+
+	// $T_{11} R - R T_{22} = T_{12}$ is solved as $T_{11} R + R (-T_{22}) = T_{12}$. the adjoint
+	// operator involves lower quasi-triangular matrices, which are made upper quasi-triangular by
+	// reversing the order of their rows and columns
+	T11.copy_from(T.submatrix(0, 0, k, k));
+	T11_adj.copy_from(T.submatrix(0, 0, k, k).adjoint().reverse_rows_and_cols());
+	z!(T22.rb_mut(), T.submatrix(k, k, m, m)).for_each(|uz!(dst, src)| *dst = -*src);
+	T22_adj.copy_from(T.submatrix(k, k, m, m).adjoint().reverse_rows_and_cols());
+	z!(T22_adj.rb_mut()).for_each(|uz!(x)| *x = -*x);
+
+	let sylvester = linalg::sylvester::solve_quasi_triangular_sylvester_in_place::<T>;
+
+	X.copy_from(T.submatrix(0, k, k, m));
+	if sylvester(T11.rb(), T22.rb(), X.rb_mut(), par, default()).is_err() {
+		return singular;
+	}
+	let r = X.norm_l2();
+	let eigenvalues = recip(sqrt(one::<T::Real>() + r * r));
+
+	// hager's estimate of $\|\Phi^{-1}\|_1$, where $\Phi$ is the sylvester operator
+	let total = from_f64::<T::Real>((k * m) as f64);
+	X.fill(from_real(recip(total)));
+
+	let mut est = zero::<T::Real>();
+	// `None` if the current input is the uniform vector, otherwise the index of the unit vector
+	let mut last = None;
+	for _ in 0..5 {
+		if sylvester(T11.rb(), T22.rb(), X.rb_mut(), par, default()).is_err() {
+			return singular;
+		}
+		est = X.norm_l1();
+
+		// $Y = \text{sign}(X)$
+		z!(Y.rb_mut(), X.rb()).for_each(|uz!(y, x)| {
+			let a = abs(*x);
+			*y = if a == zero() { one() } else { mul_real(*x, recip(a)) };
+		});
+
+		// $Z = \Phi^{-H} Y$, stored in $Y$
+		if sylvester(T11_adj.rb(), T22_adj.rb(), Y.rb_mut().reverse_rows_and_cols_mut(), par, default()).is_err() {
+			return singular;
+		}
+
+		let mut best = (0usize, 0usize);
+		let mut best_val = zero::<T::Real>();
+		let mut sum = zero::<T::Real>();
+		for j in 0..m {
+			for i in 0..k {
+				let val = abs(Y[(i, j)]);
+				sum = sum + real(Y[(i, j)]);
+				if val > best_val {
+					best_val = val;
+					best = (i, j);
+				}
+			}
+		}
+
+		// the estimate is a local maximum once $\|Z\|_\infty \leq \text{Re}(Z^H X)$
+		let zx = match last {
+			None => sum * recip(total),
+			Some(last) => real(Y[last]),
+		};
+		if best_val <= zx {
+			break;
+		}
+		last = Some(best);
+
+		X.fill(zero());
+		X[best] = one();
+	}
+
+	SchurClusterCondition {
+		eigenvalues,
+		invariant_subspace: if est == zero() { zero() } else { recip(est) },
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	fn check_reorder<T: ComplexField<Real = f64>>(A: MatRef<'_, T>, select: impl Fn(c64) -> bool) {
+		let n = A.nrows();
+		let mut T = A.to_owned();
+		let mut Q = Mat::<T>::zeros(n, n);
+		schur_in_place(
+			T.as_mut(),
+			Q.as_mut(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(schur_in_place_scratch::<T>(n, Par::Seq))),
+		)
+		.unwrap();
+
+		let expected = (0..n)
+			.filter(|&i| {
+				let bs = block_size_at(T.as_ref(), i);
+				if bs == 1 && i > 0 && T[(i, i - 1)] != zero() {
+					return false;
+				}
+				let s = block_eigenvalue_at(T.as_ref(), i, bs);
+				select(c64::new(s.re, s.im))
+			})
+			.map(|i| block_size_at(T.as_ref(), i))
+			.sum::<usize>();
+
+		let dim = reorder_schur(T.as_mut(), Some(Q.as_mut()), |s| select(c64::new(s.re, s.im))).unwrap();
+		assert!(dim == expected);
+
+		let approx_eq = CwiseMat(ApproxEq::<f64>::eps() * 16.0 * (n as f64 + 1.0));
+		assert!(&Q * &T * Q.adjoint() ~ A);
+		assert!(Q.adjoint() * &Q ~ Mat::<T>::identity(n, n));
+
+		let mut i = 0;
+		while i < n {
+			for j in 0..i.saturating_sub(1) {
+				assert!(T[(i, j)] == zero());
+			}
+			let bs = block_size_at(T.as_ref(), i);
+			let s = block_eigenvalue_at(T.as_ref(), i, bs);
+			assert!(select(c64::new(s.re, s.im)) == (i < dim));
+			i += bs;
+		}
+	}
+
+	#[test]
+	fn test_reorder_schur() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			check_reorder(A.as_ref(), |s| s.re < 0.0);
+			check_reorder(A.as_ref(), |s| s.im != 0.0);
+			check_reorder(A.as_ref(), |s| s.norm() > 1.0);
+
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			check_reorder(A.as_ref(), |s| s.re < 0.0);
+			check_reorder(A.as_ref(), |s| s.im > 0.0);
+		}
+	}
+
+	#[test]
+	fn test_schur_cluster_condition() {
+		// for a block diagonal schur form, the sylvester operator is diagonal, and the estimate of
+		// the separation is exact
+		let T = crate::mat![[1.0, 0.0, 0.0, 0.0], [0.0, 2.0, 0.0, 0.0], [0.0, 0.0, 4.0, 0.0], [0.0, 0.0, 0.0, 7.0f64],];
+		let cond = schur_cluster_condition(
+			T.as_ref(),
+			2,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(schur_cluster_condition_scratch::<f64>(4, 2, Par::Seq))),
+		);
+		assert!((cond.eigenvalues - 1.0).abs() < 1e-14);
+		assert!((cond.invariant_subspace - 2.0).abs() < 1e-14);
+
+		// a nonzero coupling block makes the cluster more sensitive
+		let T = crate::mat![[c64::new(1.0, 0.0), c64::new(3.0, 0.0)], [c64::new(0.0, 0.0), c64::new(2.0, 0.0)],];
+		let cond = schur_cluster_condition(
+			T.as_ref(),
+			1,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(schur_cluster_condition_scratch::<c64>(2, 1, Par::Seq))),
+		);
+		// $R = 3 / (1 - 2) = -3$
+		assert!((cond.eigenvalues - 1.0 / 10.0f64.sqrt()).abs() < 1e-14);
+		assert!((cond.invariant_subspace - 1.0).abs() < 1e-14);
+
+		// a repeated eigenvalue across the cluster boundary
+		let T = crate::mat![[1.0, 1.0], [0.0, 1.0f64]];
+		let cond = schur_cluster_condition(
+			T.as_ref(),
+			1,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(schur_cluster_condition_scratch::<f64>(2, 1, Par::Seq))),
+		);
+		assert!(cond.eigenvalues == 0.0);
+		assert!(cond.invariant_subspace == 0.0);
+	}
+
+	#[test]
+	fn test_schur_cluster_condition_sep() {
+		use crate::linalg::solvers::DenseSolveCore;
+
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (n, k) in [(4, 2), (5, 2), (6, 3), (8, 3)] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+
+			let mut T = A.clone();
+			let mut Q = Mat::<f64>::zeros(n, n);
+			schur_in_place(
+				T.as_mut(),
+				Q.as_mut(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(schur_in_place_scratch::<f64>(n, Par::Seq))),
+			)
+			.unwrap();
+			if k < n && T[(k, k - 1)] != 0.0 {
+				continue;
+			}
+			let m = n - k;
+
+			// kronecker form of the sylvester operator, $I \otimes T_{11} - T_{22}^\top \otimes I$
+			let T11 = T.submatrix(0, 0, k, k);
+			let T22 = T.submatrix(k, k, m, m);
+			let K = Mat::<f64>::from_fn(k * m, k * m, |row, col| {
+				let (i, j) = (row % k, row / k);
+				let (p, q) = (col % k, col / k);
+				let mut val = 0.0;
+				if j == q {
+					val += T11[(i, p)];
+				}
+				if i == p {
+					val -= T22[(q, j)];
+				}
+				val
+			});
+			let K_inv = K.full_piv_lu().inverse();
+			let exact = (0..k * m).map(|j| K_inv.col(j).norm_l1()).fold(0.0f64, f64::max);
+
+			let cond = schur_cluster_condition(
+				T.as_ref(),
+				k,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(schur_cluster_condition_scratch::<f64>(n, k, Par::Seq))),
+			);
+
+			// the estimate of $\|\Phi^{-1}\|_1$ is a lower bound, and close to the exact value for
+			// these small examples
+			let ratio = cond.invariant_subspace * exact;
+			assert!(all(ratio >= 1.0 - 1e-10, ratio <= 2.0));
+		}
+	}
+}
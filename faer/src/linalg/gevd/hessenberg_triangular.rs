@@ -0,0 +1,180 @@
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::jacobi::JacobiRotation;
+
+/// computes the size and alignment of the workspace required to reduce a matrix pencil to
+/// hessenberg-triangular form
+pub fn hessenberg_triangular_in_place_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	let n = dim;
+	if n == 0 {
+		return StackReq::EMPTY;
+	}
+	let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n, n);
+
+	temp_mat_scratch::<T>(bs, n).and(StackReq::any_of(&[
+		linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(n, n, bs, par, default()),
+		linalg::householder::apply_block_householder_sequence_transpose_on_the_left_in_place_scratch::<T>(n, bs, n),
+		linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(n, bs, n),
+	]))
+}
+
+/// reduces the matrix pencil $(A, B)$ to hessenberg-triangular form
+///
+/// on exit, $A$ is overwritten with the upper hessenberg matrix $H$ and $B$ with the upper
+/// triangular matrix $T$, such that $A = Q H Z^H$ and $B = Q T Z^H$, where $Q$ and $Z$ are
+/// unitary. if requested, $Q$ and $Z$ are stored in `q` and `z`
+#[track_caller]
+#[math]
+pub fn hessenberg_triangular_in_place<T: ComplexField>(
+	A: MatMut<'_, T>,
+	B: MatMut<'_, T>,
+	q: Option<MatMut<'_, T>>,
+	z: Option<MatMut<'_, T>>,
+	par: Par,
+	stack: &mut MemStack,
+) {
+	let n = A.nrows();
+	assert!(all(A.nrows() == n, A.ncols() == n, B.nrows() == n, B.ncols() == n));
+	if let Some(q) = q.rb() {
+		assert!(all(q.nrows() == n, q.ncols() == n));
+	}
+	if let Some(z) = z.rb() {
+		assert!(all(z.nrows() == n, z.ncols() == n));
+	}
+
+	if n == 0 {
+		return;
+	}
+
+	let mut A = A;
+	let mut B = B;
+	let mut q = q;
+	let mut z = z;
+
+	{
+		let bs = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n, n);
+		let (mut householder, stack) = unsafe { temp_mat_uninit::<T, _, _>(bs, n, stack) };
+		let mut householder = householder.as_mat_mut();
+
+		linalg::qr::no_pivoting::factor::qr_in_place(B.rb_mut(), householder.rb_mut(), par, stack, default());
+
+		linalg::householder::apply_block_householder_sequence_transpose_on_the_left_in_place_with_conj(
+			B.rb(),
+			householder.rb(),
+			Conj::Yes,
+			A.rb_mut(),
+			par,
+			stack,
+		);
+
+		if let Some(mut q) = q.rb_mut() {
+			q.fill(zero());
+			q.rb_mut().diagonal_mut().fill(one());
+			linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
+				B.rb(),
+				householder.rb(),
+				Conj::No,
+				q.rb_mut(),
+				par,
+				stack,
+			);
+		}
+
+		for j in 0..n {
+			for i in j + 1..n {
+				B[(i, j)] = zero();
+			}
+		}
+	}
+
+	if let Some(mut z) = z.rb_mut() {
+		z.fill(zero());
+		z.rb_mut().diagonal_mut().fill(one());
+	}
+
+	for j in 0..n.saturating_sub(2) {
+		for i in (j + 2..n).rev() {
+			// annihilate A[(i, j)] from the left, which introduces a nonzero in B[(i, i - 1)]
+			let (rot, r) = JacobiRotation::rotg(copy(A[(i - 1, j)]), copy(A[(i, j)]));
+			A[(i - 1, j)] = r;
+			A[(i, j)] = zero();
+
+			rot.adjoint()
+				.apply_on_the_left_in_place(A.rb_mut().get_mut(.., j + 1..).two_rows_mut(i - 1, i));
+			rot.adjoint()
+				.apply_on_the_left_in_place(B.rb_mut().get_mut(.., i - 1..).two_rows_mut(i - 1, i));
+			if let Some(q) = q.rb_mut() {
+				rot.apply_on_the_right_in_place(q.two_cols_mut(i - 1, i));
+			}
+
+			// restore the triangular structure of B from the right
+			let (rot, r) = JacobiRotation::rotg(conj(B[(i, i)]), conj(B[(i, i - 1)]));
+			B[(i, i)] = conj(r);
+			B[(i, i - 1)] = zero();
+
+			rot.apply_on_the_right_in_place(B.rb_mut().get_mut(..i, ..).two_cols_mut(i, i - 1));
+			rot.apply_on_the_right_in_place(A.rb_mut().two_cols_mut(i, i - 1));
+			if let Some(z) = z.rb_mut() {
+				rot.apply_on_the_right_in_place(z.two_cols_mut(i, i - 1));
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_hessenberg_triangular() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+
+			let mut H = A.clone();
+			let mut T = B.clone();
+			let mut Q = Mat::zeros(n, n);
+			let mut Z = Mat::zeros(n, n);
+
+			hessenberg_triangular_in_place(
+				H.as_mut(),
+				T.as_mut(),
+				Some(Q.as_mut()),
+				Some(Z.as_mut()),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(hessenberg_triangular_in_place_scratch::<c64>(n, Par::Seq))),
+			);
+
+			for j in 0..n {
+				for i in j + 2..n {
+					assert!(H[(i, j)] == c64::ZERO);
+				}
+				for i in j + 1..n {
+					assert!(T[(i, j)] == c64::ZERO);
+				}
+			}
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * (n as f64 + 1.0) * 8.0);
+			assert!(&Q * &H * Z.adjoint() ~ A);
+			assert!(&Q * &T * Z.adjoint() ~ B);
+			assert!(Q.adjoint() * &Q ~ Mat::<c64>::identity(n, n));
+			assert!(Z.adjoint() * &Z ~ Mat::<c64>::identity(n, n));
+		}
+	}
+}
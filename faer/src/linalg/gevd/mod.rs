@@ -0,0 +1,680 @@
+//! low level implementation of the generalized eigenvalue decomposition of a square matrix pencil.
+//!
+//! the generalized eigenvalue problem for a pencil $(A, B)$ of shape $(n, n)$ consists of finding
+//! scalar pairs $(\alpha_j, \beta_j)$ and nonzero vectors $u_j$, $v_j$ such that
+//!
+//! $$\beta_j A u_j = \alpha_j B u_j$$
+//! $$\beta_j v_j^H A = \alpha_j v_j^H B$$
+//!
+//! the generalized eigenvalues are $\lambda_j = \alpha_j / \beta_j$. a zero $\beta_j$ corresponds
+//! to an infinite eigenvalue, which can occur when $B$ is singular
+//!
+//! the decomposition is computed by reducing the pencil to hessenberg-triangular form, then
+//! computing its generalized schur form with the $QZ$ algorithm
+
+/// hessenberg-triangular reduction
+pub mod hessenberg_triangular;
+pub(crate) mod qz;
+
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::evd::{ComputeEigenvectors, EvdError};
+
+/// computes the size and alignment of the workspace required to compute a matrix pencil's
+/// generalized eigendecomposition
+pub fn gevd_scratch<T: ComplexField>(dim: usize, eigen_left: ComputeEigenvectors, eigen_right: ComputeEigenvectors, par: Par) -> StackReq {
+	let n = dim;
+
+	if n == 0 {
+		return StackReq::EMPTY;
+	}
+
+	let left = eigen_left == ComputeEigenvectors::Yes;
+	let right = eigen_right == ComputeEigenvectors::Yes;
+
+	let H = temp_mat_scratch::<T>(n, n);
+	let Q = temp_mat_scratch::<T>(n, if left { n } else { 0 });
+	let Z = temp_mat_scratch::<T>(n, if right { n } else { 0 });
+	let X = temp_mat_scratch::<T>(n, n);
+	let pencil = temp_mat_scratch::<Complex<T::Real>>(n, n).array(2);
+	let x = temp_mat_scratch::<Complex<T::Real>>(n, 1);
+
+	StackReq::all_of(&[
+		H,
+		H,
+		Q,
+		Z,
+		StackReq::any_of(&[
+			hessenberg_triangular::hessenberg_triangular_in_place_scratch::<T>(n, par),
+			StackReq::all_of(&[pencil, X, x]),
+		]),
+	])
+}
+
+/// solves $(\beta S - \alpha P) x = 0$, where $(S, P)$ is an upper quasi-triangular pencil and the
+/// eigenvalue's diagonal block starts at index `k`
+#[math]
+fn quasi_triangular_eigenvector<T: RealField>(
+	S: MatRef<'_, Complex<T>>,
+	P: MatRef<'_, Complex<T>>,
+	alpha: Complex<T>,
+	beta: Complex<T>,
+	k: usize,
+	x: ColMut<'_, Complex<T>>,
+	smin: T,
+) {
+	let mut x = x;
+	let bignum = sqrt(max_positive::<T>());
+
+	let e = |i: usize, j: usize| beta * S[(i, j)] - alpha * P[(i, j)];
+
+	x.fill(zero());
+
+	let top = if k + 1 < S.nrows() && S[(k + 1, k)] != zero() {
+		// the 2x2 diagonal block is singular, pick a vector from its kernel
+		let (m00, m01, m10, m11) = (e(k, k), e(k, k + 1), e(k + 1, k), e(k + 1, k + 1));
+		let (r0, r1) = if abs1(m00) + abs1(m01) >= abs1(m10) + abs1(m11) {
+			(m00, m01)
+		} else {
+			(m10, m11)
+		};
+		if r0 == zero() && r1 == zero() {
+			x[k] = one();
+		} else {
+			x[k] = r1;
+			x[k + 1] = -r0;
+		}
+		k + 2
+	} else {
+		x[k] = one();
+		k + 1
+	};
+
+	for j in k..top {
+		let xj = copy(x[j]);
+		for i in 0..k {
+			x[i] = x[i] - e(i, j) * xj;
+		}
+	}
+
+	let mut i = k;
+	while i > 0 {
+		let i1 = i - 1;
+
+		let i0 = if i1 > 0 && S[(i1, i1 - 1)] != zero() {
+			// 2x2 diagonal block, solved with partial pivoting
+			let i0 = i1 - 1;
+			let (mut m00, mut m01, mut m10, mut m11) = (e(i0, i0), e(i0, i1), e(i1, i0), e(i1, i1));
+			let (mut r0, mut r1) = (copy(x[i0]), copy(x[i1]));
+			if abs1(m10) > abs1(m00) {
+				(m00, m10) = (m10, m00);
+				(m01, m11) = (m11, m01);
+				(r0, r1) = (r1, r0);
+			}
+			if abs(m00) < smin {
+				m00 = from_real(smin);
+			}
+			let l = m10 / m00;
+			let mut u11 = m11 - l * m01;
+			if abs(u11) < smin {
+				u11 = from_real(smin);
+			}
+			let y1 = (r1 - l * r0) / u11;
+			let y0 = (r0 - m01 * y1) / m00;
+			x[i0] = y0;
+			x[i1] = y1;
+			i0
+		} else {
+			let mut d = e(i1, i1);
+			if abs(d) < smin {
+				d = from_real(smin);
+			}
+			x[i1] = x[i1] / d;
+			i1
+		};
+
+		for j in i0..i {
+			let xj = copy(x[j]);
+			for r in 0..i0 {
+				x[r] = x[r] - e(r, j) * xj;
+			}
+		}
+
+		let xmax = x.rb().norm_max();
+		if xmax > bignum {
+			let s = recip(xmax);
+			for r in 0..top {
+				x[r] = mul_real(x[r], s);
+			}
+		}
+
+		i = i0;
+	}
+}
+
+/// computes the eigenvectors of the upper quasi-triangular pencil $(S, P)$, and stores them in the
+/// columns of $X$
+///
+/// if `left` is true, the left eigenvectors are computed instead. if $T$ is real, the eigenvectors
+/// of a complex conjugate pair are stored as the real and imaginary parts of the first one
+#[math]
+fn pencil_eigenvectors<T: ComplexField>(S: MatRef<'_, T>, P: MatRef<'_, T>, left: bool, X: MatMut<'_, T>, stack: &mut MemStack) {
+	let n = S.nrows();
+	let mut X = X;
+
+	let (mut S_c, stack) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(n, n, stack) };
+	let (mut P_c, stack) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(n, n, stack) };
+	let (mut x, _) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(n, 1, stack) };
+	let mut S_c = S_c.as_mat_mut();
+	let mut P_c = P_c.as_mat_mut();
+	let mut x = x.as_mat_mut().col_mut(0);
+
+	// left eigenvectors of (S, P) are the right eigenvectors of the reversed adjoint pencil
+	for j in 0..n {
+		for i in 0..n {
+			let (s, p) = if left {
+				(&S[(n - 1 - j, n - 1 - i)], &P[(n - 1 - j, n - 1 - i)])
+			} else {
+				(&S[(i, j)], &P[(i, j)])
+			};
+			S_c[(i, j)] = Complex::new(real(*s), imag(*s));
+			P_c[(i, j)] = Complex::new(real(*p), imag(*p));
+			if left {
+				S_c[(i, j)] = conj(S_c[(i, j)]);
+				P_c[(i, j)] = conj(P_c[(i, j)]);
+			}
+		}
+	}
+
+	let S_c = S_c.rb();
+	let P_c = P_c.rb();
+	let snorm = S_c.norm_l2();
+	let pnorm = P_c.norm_l2();
+
+	X.fill(zero());
+
+	let mut j = 0;
+	while j < n {
+		let pair = j + 1 < n && S[(j + 1, j)] != zero();
+
+		let (a, b) = if pair {
+			let (m, p, disc) = qz::eig22::<T::Real>(unsafe { crate::hacks::coerce(S.submatrix(j, j, 2, 2)) }, unsafe {
+				crate::hacks::coerce(P.submatrix(j, j, 2, 2))
+			});
+			(Complex::new(m + p, sqrt(-disc)), one::<Complex<T::Real>>())
+		} else {
+			(
+				Complex::new(real(S[(j, j)]), imag(S[(j, j)])),
+				Complex::new(real(P[(j, j)]), imag(P[(j, j)])),
+			)
+		};
+		let smin = max(eps::<T::Real>() * (abs(b) * snorm + abs(a) * pnorm), min_positive::<T::Real>());
+
+		if left {
+			let k = if pair { n - 2 - j } else { n - 1 - j };
+			quasi_triangular_eigenvector(S_c, P_c, conj(a), conj(b), k, x.rb_mut(), smin);
+		} else {
+			quasi_triangular_eigenvector(S_c, P_c, a, b, j, x.rb_mut(), smin);
+		}
+
+		for i in 0..n {
+			let xi = if left { copy(x[n - 1 - i]) } else { copy(x[i]) };
+			if try_const! { T::IS_REAL } {
+				X[(i, j)] = from_real(xi.re);
+				if pair {
+					X[(i, j + 1)] = from_real(xi.im);
+				}
+			} else {
+				X[(i, j)] = unsafe { crate::hacks::coerce::<Complex<T::Real>, T>(xi) };
+			}
+		}
+
+		j += if pair { 2 } else { 1 };
+	}
+}
+
+/// normalizes the columns of $U$, where the eigenvectors of complex conjugate pairs are stored as
+/// their real and imaginary parts
+#[math]
+fn normalize_eigenvectors<T: ComplexField>(U: MatMut<'_, T>, s_im: Option<ColRef<'_, T>>) {
+	let n = U.ncols();
+	let mut U = U;
+
+	let mut j = 0;
+	while j < n {
+		let pair = match s_im {
+			Some(s_im) => s_im[j] != zero(),
+			None => false,
+		};
+
+		if pair {
+			let norm = hypot(U.rb().col(j).norm_l2(), U.rb().col(j + 1).norm_l2());
+			if norm > zero() {
+				let norm = recip(norm);
+				zip!(U.rb_mut().col_mut(j)).for_each(|unzip!(x)| *x = mul_real(*x, norm));
+				zip!(U.rb_mut().col_mut(j + 1)).for_each(|unzip!(x)| *x = mul_real(*x, norm));
+			}
+			j += 2;
+		} else {
+			let norm = U.rb().col(j).norm_l2();
+			if norm > zero() {
+				let norm = recip(norm);
+				zip!(U.rb_mut().col_mut(j)).for_each(|unzip!(x)| *x = mul_real(*x, norm));
+			}
+			j += 1;
+		}
+	}
+}
+
+#[math]
+fn gevd_imp<T: ComplexField>(
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	alpha: ColMut<'_, T>,
+	alpha_im: Option<ColMut<'_, T>>,
+	beta: ColMut<'_, T>,
+	u_left: Option<MatMut<'_, T>>,
+	u_right: Option<MatMut<'_, T>>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	for j in 0..n {
+		for i in 0..n {
+			if !is_finite(A[(i, j)]) || !is_finite(B[(i, j)]) {
+				return Err(EvdError::NoConvergence);
+			}
+		}
+	}
+
+	let mut alpha = alpha;
+	let mut alpha_im = alpha_im;
+	let mut beta = beta;
+
+	let (mut H, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut T_, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut Q, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, if u_left.is_some() { n } else { 0 }, stack) };
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, if u_right.is_some() { n } else { 0 }, stack) };
+
+	let mut H = H.as_mat_mut();
+	let mut T_ = T_.as_mat_mut();
+	let mut Q = if u_left.is_some() { Some(Q.as_mat_mut()) } else { None };
+	let mut Z = if u_right.is_some() { Some(Z.as_mat_mut()) } else { None };
+
+	H.copy_from(A);
+	T_.copy_from(B);
+
+	hessenberg_triangular::hessenberg_triangular_in_place(H.rb_mut(), T_.rb_mut(), Q.rb_mut(), Z.rb_mut(), par, stack);
+
+	let want_t = Q.is_some() || Z.is_some();
+
+	if try_const! { T::IS_REAL } {
+		qz::real_qz::<T::Real>(
+			want_t,
+			unsafe { crate::hacks::coerce(H.rb_mut()) },
+			unsafe { crate::hacks::coerce(T_.rb_mut()) },
+			unsafe { crate::hacks::coerce(Q.rb_mut()) },
+			unsafe { crate::hacks::coerce(Z.rb_mut()) },
+			unsafe { crate::hacks::coerce(alpha.rb_mut()) },
+			unsafe { crate::hacks::coerce(alpha_im.rb_mut().unwrap()) },
+			unsafe { crate::hacks::coerce(beta.rb_mut()) },
+		)?;
+	} else {
+		qz::complex_qz::<T>(want_t, H.rb_mut(), T_.rb_mut(), Q.rb_mut(), Z.rb_mut(), alpha.rb_mut(), beta.rb_mut())?;
+	}
+
+	let H = H.rb();
+	let T_ = T_.rb();
+
+	for (u, basis, left) in [(u_right, Z.rb(), false), (u_left, Q.rb(), true)] {
+		if let (Some(mut u), Some(basis)) = (u, basis) {
+			let (mut X, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+			let mut X = X.as_mat_mut();
+
+			pencil_eigenvectors(H, T_, left, X.rb_mut(), stack);
+			linalg::matmul::matmul(u.rb_mut(), Accum::Replace, basis, X.rb(), one(), par);
+			normalize_eigenvectors(u.rb_mut(), alpha_im.rb());
+		}
+	}
+
+	Ok(())
+}
+
+/// computes the generalized eigendecomposition of the pencil $(A, B)$
+///
+/// the eigenvalues are given by $\alpha_j / \beta_j$, where $\alpha$ is stored in `alpha` and
+/// $\beta$ in `beta`. the diagonal of $\beta$ is real and nonnegative
+///
+/// the left eigenvectors are stored in $U_L$, and the right eigenvectors in $U_R$, normalized to
+/// have a unit norm
+#[track_caller]
+pub fn gevd_cplx<T: RealField>(
+	A: MatRef<'_, Complex<T>>,
+	B: MatRef<'_, Complex<T>>,
+	alpha: DiagMut<'_, Complex<T>>,
+	beta: DiagMut<'_, Complex<T>>,
+	u_left: Option<MatMut<'_, Complex<T>>>,
+	u_right: Option<MatMut<'_, Complex<T>>>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+	assert!(all(
+		A.nrows() == n,
+		A.ncols() == n,
+		B.nrows() == n,
+		B.ncols() == n,
+		alpha.dim() == n,
+		beta.dim() == n,
+	));
+	if let Some(u) = u_left.rb() {
+		assert!(all(u.nrows() == n, u.ncols() == n));
+	}
+	if let Some(u) = u_right.rb() {
+		assert!(all(u.nrows() == n, u.ncols() == n));
+	}
+
+	gevd_imp(
+		A,
+		B,
+		alpha.column_vector_mut(),
+		None,
+		beta.column_vector_mut(),
+		u_left,
+		u_right,
+		par,
+		stack,
+	)
+}
+
+/// computes the generalized eigendecomposition of the real pencil $(A, B)$
+///
+/// the eigenvalues are given by $(\alpha^{re}_j + i \alpha^{im}_j) / \beta_j$, where $\beta$ is
+/// nonnegative. complex eigenvalues come in conjugate pairs, with the one that has a positive
+/// imaginary part stored first
+///
+/// the left eigenvectors are stored in $U_L$, and the right eigenvectors in $U_R$, normalized to
+/// have a unit norm. the eigenvectors of a complex conjugate pair are stored as the real part and
+/// imaginary part of the eigenvector of the first eigenvalue in the pair
+#[track_caller]
+pub fn gevd_real<T: RealField>(
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	alpha_re: DiagMut<'_, T>,
+	alpha_im: DiagMut<'_, T>,
+	beta: DiagMut<'_, T>,
+	u_left: Option<MatMut<'_, T>>,
+	u_right: Option<MatMut<'_, T>>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+	assert!(all(
+		A.nrows() == n,
+		A.ncols() == n,
+		B.nrows() == n,
+		B.ncols() == n,
+		alpha_re.dim() == n,
+		alpha_im.dim() == n,
+		beta.dim() == n,
+	));
+	if let Some(u) = u_left.rb() {
+		assert!(all(u.nrows() == n, u.ncols() == n));
+	}
+	if let Some(u) = u_right.rb() {
+		assert!(all(u.nrows() == n, u.ncols() == n));
+	}
+
+	gevd_imp(
+		A,
+		B,
+		alpha_re.column_vector_mut(),
+		Some(alpha_im.column_vector_mut()),
+		beta.column_vector_mut(),
+		u_left,
+		u_right,
+		par,
+		stack,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	fn test_cplx_gevd(A: MatRef<'_, c64>, B: MatRef<'_, c64>) {
+		let n = A.nrows();
+		let approx_eq = CwiseMat(ApproxEq::eps() * 16.0 * (n as f64));
+
+		let mut alpha = Diag::zeros(n);
+		let mut beta = Diag::zeros(n);
+		let mut ul = Mat::zeros(n, n);
+		let mut ur = Mat::zeros(n, n);
+
+		gevd_cplx(
+			A,
+			B,
+			alpha.as_mut(),
+			beta.as_mut(),
+			Some(ul.as_mut()),
+			Some(ur.as_mut()),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(gevd_scratch::<c64>(
+				n,
+				ComputeEigenvectors::Yes,
+				ComputeEigenvectors::Yes,
+				Par::Seq,
+			))),
+		)
+		.unwrap();
+
+		for j in 0..n {
+			assert!(beta[j].im == 0.0);
+			assert!(beta[j].re >= 0.0);
+		}
+
+		assert!(A * &ur * &beta ~ B * &ur * &alpha);
+		assert!(&beta * ul.adjoint() * A ~ &alpha * ul.adjoint() * B);
+
+		let mut alpha2 = Diag::zeros(n);
+		let mut beta2 = Diag::zeros(n);
+		gevd_cplx(
+			A,
+			B,
+			alpha2.as_mut(),
+			beta2.as_mut(),
+			None,
+			None,
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(gevd_scratch::<c64>(
+				n,
+				ComputeEigenvectors::No,
+				ComputeEigenvectors::No,
+				Par::Seq,
+			))),
+		)
+		.unwrap();
+
+		// the eigenvalues are the same up to ordering
+		for j in 0..n {
+			let found = (0..n).any(|i| (alpha[j] * beta2[i] - alpha2[i] * beta[j]).norm() < 1e-8 * (n as f64));
+			assert!(found);
+		}
+	}
+
+	fn test_real_gevd(A: MatRef<'_, f64>, B: MatRef<'_, f64>) {
+		let n = A.nrows();
+		let approx_eq = CwiseMat(ApproxEq::eps() * 16.0 * (n as f64));
+
+		let mut alpha_re = Diag::zeros(n);
+		let mut alpha_im = Diag::zeros(n);
+		let mut beta = Diag::zeros(n);
+		let mut ul = Mat::zeros(n, n);
+		let mut ur = Mat::zeros(n, n);
+
+		gevd_real(
+			A,
+			B,
+			alpha_re.as_mut(),
+			alpha_im.as_mut(),
+			beta.as_mut(),
+			Some(ul.as_mut()),
+			Some(ur.as_mut()),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(gevd_scratch::<f64>(
+				n,
+				ComputeEigenvectors::Yes,
+				ComputeEigenvectors::Yes,
+				Par::Seq,
+			))),
+		)
+		.unwrap();
+
+		let mut alpha = Diag::<c64>::zeros(n);
+		let mut ul_c = Mat::<c64>::zeros(n, n);
+		let mut ur_c = Mat::<c64>::zeros(n, n);
+
+		let mut j = 0;
+		while j < n {
+			assert!(beta[j] >= 0.0);
+			if alpha_im[j] == 0.0 {
+				alpha[j] = c64::new(alpha_re[j], 0.0);
+				for i in 0..n {
+					ul_c[(i, j)] = c64::new(ul[(i, j)], 0.0);
+					ur_c[(i, j)] = c64::new(ur[(i, j)], 0.0);
+				}
+				j += 1;
+			} else {
+				assert!(alpha_im[j] > 0.0);
+				alpha[j] = c64::new(alpha_re[j], alpha_im[j]);
+				alpha[j + 1] = c64::new(alpha_re[j + 1], alpha_im[j + 1]);
+				for i in 0..n {
+					ul_c[(i, j)] = c64::new(ul[(i, j)], ul[(i, j + 1)]);
+					ul_c[(i, j + 1)] = c64::new(ul[(i, j)], -ul[(i, j + 1)]);
+					ur_c[(i, j)] = c64::new(ur[(i, j)], ur[(i, j + 1)]);
+					ur_c[(i, j + 1)] = c64::new(ur[(i, j)], -ur[(i, j + 1)]);
+				}
+				j += 2;
+			}
+		}
+
+		let beta = Col::from_fn(n, |j| c64::new(beta[j], 0.0)).into_diagonal();
+		let A = Mat::from_fn(n, n, |i, j| c64::new(A[(i, j)], 0.0));
+		let B = Mat::from_fn(n, n, |i, j| c64::new(B[(i, j)], 0.0));
+
+		assert!(&A * &ur_c * &beta ~ &B * &ur_c * &alpha);
+		assert!(&beta * ul_c.adjoint() * &A ~ &alpha * ul_c.adjoint() * &B);
+	}
+
+	#[test]
+	fn test_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 4, 5, 10, 16, 33, 64, 100] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+
+			test_cplx_gevd(A.as_ref(), B.as_ref());
+		}
+	}
+
+	#[test]
+	fn test_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 4, 5, 10, 16, 33, 64, 100] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+
+			test_real_gevd(A.as_ref(), B.as_ref());
+		}
+	}
+
+	#[test]
+	fn test_singular_b() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [2, 3, 5, 10, 33] {
+			let rank = n / 2;
+
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let L = CwiseMatDistribution {
+				nrows: n,
+				ncols: rank,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let R = CwiseMatDistribution {
+				nrows: rank,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let B = &L * &R;
+
+			test_real_gevd(A.as_ref(), B.as_ref());
+
+			// the pencil has n - rank infinite eigenvalues
+			let mut alpha_re = Diag::<f64>::zeros(n);
+			let mut alpha_im = Diag::<f64>::zeros(n);
+			let mut beta = Diag::<f64>::zeros(n);
+			gevd_real(
+				A.as_ref(),
+				B.as_ref(),
+				alpha_re.as_mut(),
+				alpha_im.as_mut(),
+				beta.as_mut(),
+				None,
+				None,
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(gevd_scratch::<f64>(
+					n,
+					ComputeEigenvectors::No,
+					ComputeEigenvectors::No,
+					Par::Seq,
+				))),
+			)
+			.unwrap();
+			let n_infinite = (0..n).filter(|&j| beta[j] < 1e-10 * (alpha_re[j].abs() + alpha_im[j].abs())).count();
+			assert!(n_infinite == n - rank);
+
+			let A = Mat::from_fn(n, n, |i, j| c64::new(A[(i, j)], 0.0));
+			let B = Mat::from_fn(n, n, |i, j| c64::new(B[(i, j)], 0.0));
+			test_cplx_gevd(A.as_ref(), B.as_ref());
+		}
+	}
+}
@@ -0,0 +1,728 @@
+use crate::internal_prelude::*;
+use linalg::evd::EvdError;
+use linalg::jacobi::JacobiRotation;
+
+enum Action {
+	// the bottom diagonal entry of the active block can be deflated
+	Deflate,
+	// B[(ilast, ilast)] is zero, the last subdiagonal entry of A must be annihilated before deflating
+	ZeroDiagonal,
+	// perform a qz sweep on the active block starting at the given index
+	Sweep(usize),
+}
+
+/// looks for a negligible subdiagonal entry in $A$ or a negligible diagonal entry in $B$
+///
+/// this follows the deflation strategy of lapack's `xHGEQZ`
+#[math]
+fn find_deflation<T: ComplexField>(
+	A: MatMut<'_, T>,
+	B: MatMut<'_, T>,
+	q: Option<MatMut<'_, T>>,
+	z: Option<MatMut<'_, T>>,
+	ilo: usize,
+	ilast: usize,
+	ifrstm: usize,
+	ilastm: usize,
+	atol: T::Real,
+	btol: T::Real,
+) -> Action {
+	let mut A = A;
+	let mut B = B;
+	let mut q = q;
+	let mut z = z;
+
+	if ilast == ilo {
+		return Action::Deflate;
+	}
+	if abs1(A[(ilast, ilast - 1)]) <= atol {
+		A[(ilast, ilast - 1)] = zero();
+		return Action::Deflate;
+	}
+	if abs(B[(ilast, ilast)]) <= btol {
+		B[(ilast, ilast)] = zero();
+		return Action::ZeroDiagonal;
+	}
+
+	for j in (ilo..ilast).rev() {
+		let ilazro = if j == ilo {
+			true
+		} else if abs1(A[(j, j - 1)]) <= atol {
+			A[(j, j - 1)] = zero();
+			true
+		} else {
+			false
+		};
+
+		if abs(B[(j, j)]) < btol {
+			B[(j, j)] = zero();
+
+			// check whether two consecutive small subdiagonal entries allow splitting the block
+			let mut ilazr2 = !ilazro && abs1(A[(j, j - 1)]) * abs1(A[(j + 1, j)]) <= abs1(A[(j, j)]) * atol;
+
+			if ilazro || ilazr2 {
+				// chase the zero upwards, annihilating the subdiagonal of A
+				for jch in j..ilast {
+					let (rot, r) = JacobiRotation::rotg(copy(A[(jch, jch)]), copy(A[(jch + 1, jch)]));
+					A[(jch, jch)] = r;
+					A[(jch + 1, jch)] = zero();
+
+					rot.adjoint()
+						.apply_on_the_left_in_place(A.rb_mut().get_mut(.., jch + 1..ilastm + 1).two_rows_mut(jch, jch + 1));
+					rot.adjoint()
+						.apply_on_the_left_in_place(B.rb_mut().get_mut(.., jch..ilastm + 1).two_rows_mut(jch, jch + 1));
+					if let Some(q) = q.rb_mut() {
+						rot.apply_on_the_right_in_place(q.two_cols_mut(jch, jch + 1));
+					}
+
+					if ilazr2 {
+						A[(jch, jch - 1)] = A[(jch, jch - 1)] * rot.c;
+					}
+					ilazr2 = false;
+
+					if abs1(B[(jch + 1, jch + 1)]) >= btol {
+						if jch + 1 >= ilast {
+							return Action::Deflate;
+						} else {
+							return Action::Sweep(jch + 1);
+						}
+					}
+					B[(jch + 1, jch + 1)] = zero();
+				}
+			} else {
+				// chase the zero down to B[(ilast, ilast)]
+				for jch in j..ilast {
+					let (rot, r) = JacobiRotation::rotg(copy(B[(jch, jch + 1)]), copy(B[(jch + 1, jch + 1)]));
+					B[(jch, jch + 1)] = r;
+					B[(jch + 1, jch + 1)] = zero();
+
+					rot.adjoint()
+						.apply_on_the_left_in_place(B.rb_mut().get_mut(.., jch + 2..ilastm + 1).two_rows_mut(jch, jch + 1));
+					rot.adjoint()
+						.apply_on_the_left_in_place(A.rb_mut().get_mut(.., jch - 1..ilastm + 1).two_rows_mut(jch, jch + 1));
+					if let Some(q) = q.rb_mut() {
+						rot.apply_on_the_right_in_place(q.two_cols_mut(jch, jch + 1));
+					}
+
+					let (rot, r) = JacobiRotation::rotg(conj(A[(jch + 1, jch)]), conj(A[(jch + 1, jch - 1)]));
+					A[(jch + 1, jch)] = conj(r);
+					A[(jch + 1, jch - 1)] = zero();
+
+					rot.apply_on_the_right_in_place(A.rb_mut().get_mut(ifrstm..jch + 1, ..).two_cols_mut(jch, jch - 1));
+					rot.apply_on_the_right_in_place(B.rb_mut().get_mut(ifrstm..jch, ..).two_cols_mut(jch, jch - 1));
+					if let Some(z) = z.rb_mut() {
+						rot.apply_on_the_right_in_place(z.two_cols_mut(jch, jch - 1));
+					}
+				}
+			}
+
+			return Action::ZeroDiagonal;
+		} else if ilazro {
+			return Action::Sweep(j);
+		}
+	}
+
+	unreachable!()
+}
+
+/// annihilates `A[(ilast, ilast - 1)]` from the right, given that `B[(ilast, ilast)]` is zero
+#[math]
+fn deflate_zero_diagonal<T: ComplexField>(A: MatMut<'_, T>, B: MatMut<'_, T>, z: Option<MatMut<'_, T>>, ilast: usize, ifrstm: usize) {
+	let mut A = A;
+	let mut B = B;
+
+	let (rot, r) = JacobiRotation::rotg(conj(A[(ilast, ilast)]), conj(A[(ilast, ilast - 1)]));
+	A[(ilast, ilast)] = conj(r);
+	A[(ilast, ilast - 1)] = zero();
+
+	rot.apply_on_the_right_in_place(A.rb_mut().get_mut(ifrstm..ilast, ..).two_cols_mut(ilast, ilast - 1));
+	rot.apply_on_the_right_in_place(B.rb_mut().get_mut(ifrstm..ilast, ..).two_cols_mut(ilast, ilast - 1));
+	if let Some(z) = z {
+		rot.apply_on_the_right_in_place(z.two_cols_mut(ilast, ilast - 1));
+	}
+}
+
+/// overwrites `x` with the householder vector that maps it to a multiple of the first unit vector,
+/// and returns the householder factor $\tau$, such that the reflector is $I - \tau v v^\top$
+#[math]
+fn make_householder<T: RealField>(x: &mut [T]) -> T {
+	let alpha = copy(x[0]);
+	let mut tail_norm = zero::<T>();
+	for xi in &x[1..] {
+		tail_norm = hypot(tail_norm, *xi);
+	}
+
+	x[0] = one();
+	if tail_norm == zero() {
+		return zero();
+	}
+
+	let mut beta = hypot(alpha, tail_norm);
+	if alpha >= zero() {
+		beta = -beta;
+	}
+	let tau = (beta - alpha) / beta;
+	let scale = recip(alpha - beta);
+	for xi in &mut x[1..] {
+		*xi = *xi * scale;
+	}
+	tau
+}
+
+#[math]
+fn apply_householder_on_the_left<T: RealField>(v: &[T], tau: T, mat: MatMut<'_, T>) {
+	let mut mat = mat;
+	for j in 0..mat.ncols() {
+		let mut dot = zero::<T>();
+		for i in 0..v.len() {
+			dot = dot + v[i] * mat[(i, j)];
+		}
+		let dot = tau * dot;
+		for i in 0..v.len() {
+			mat[(i, j)] = mat[(i, j)] - dot * v[i];
+		}
+	}
+}
+
+#[math]
+fn apply_householder_on_the_right<T: RealField>(v: &[T], tau: T, mat: MatMut<'_, T>) {
+	let mut mat = mat;
+	for i in 0..mat.nrows() {
+		let mut dot = zero::<T>();
+		for j in 0..v.len() {
+			dot = dot + mat[(i, j)] * v[j];
+		}
+		let dot = tau * dot;
+		for j in 0..v.len() {
+			mat[(i, j)] = mat[(i, j)] - dot * v[j];
+		}
+	}
+}
+
+/// computes the eigenvalues of the $2\times 2$ real pencil $(A, B)$, where $B$ is upper triangular
+/// and invertible
+///
+/// returns $(m, p, d)$ such that the eigenvalues are $m + p \pm \sqrt{d}$
+#[math]
+pub(crate) fn eig22<T: RealField>(A: MatRef<'_, T>, B: MatRef<'_, T>) -> (T, T, T) {
+	// eigenvalues of A * B^-1
+	let m00 = A[(0, 0)] / B[(0, 0)];
+	let m10 = A[(1, 0)] / B[(0, 0)];
+	let m01 = (A[(0, 1)] - m00 * B[(0, 1)]) / B[(1, 1)];
+	let m11 = (A[(1, 1)] - m10 * B[(0, 1)]) / B[(1, 1)];
+
+	let p = mul_pow2(m00 - m11, from_f64::<T>(0.5));
+	let disc = p * p + m01 * m10;
+	(m11, p, disc)
+}
+
+/// computes the generalized schur form of the real hessenberg-triangular pencil $(A, B)$ using the
+/// double shift $QZ$ algorithm
+///
+/// on exit, $A$ is upper quasi-triangular with $1\times 1$ and $2\times 2$ diagonal blocks, the
+/// latter corresponding to complex conjugate eigenvalue pairs, and $B$ is upper triangular with a
+/// nonnegative diagonal for the $1\times 1$ blocks
+#[math]
+pub(crate) fn real_qz<T: RealField>(
+	want_t: bool,
+	A: MatMut<'_, T>,
+	B: MatMut<'_, T>,
+	q: Option<MatMut<'_, T>>,
+	z: Option<MatMut<'_, T>>,
+	alpha_re: ColMut<'_, T>,
+	alpha_im: ColMut<'_, T>,
+	beta: ColMut<'_, T>,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+	if n == 0 {
+		return Ok(());
+	}
+
+	let mut A = A;
+	let mut B = B;
+	let mut q = q;
+	let mut z = z;
+	let mut alpha_re = alpha_re;
+	let mut alpha_im = alpha_im;
+	let mut beta = beta;
+
+	let ulp = eps::<T>();
+	let safmin = min_positive::<T>();
+	let atol = max(safmin, ulp * A.rb().norm_l2());
+	let btol = max(safmin, ulp * B.rb().norm_l2());
+
+	let ilo = 0;
+	let mut ilast = n - 1;
+	let mut ifrstm = ilo;
+	let mut ilastm = if want_t { n - 1 } else { ilast };
+	let mut iiter = 0usize;
+	let maxit = 30 * n;
+
+	for _ in 0..maxit {
+		let f = match find_deflation(
+			A.rb_mut(),
+			B.rb_mut(),
+			q.rb_mut(),
+			z.rb_mut(),
+			ilo,
+			ilast,
+			ifrstm,
+			ilastm,
+			copy(atol),
+			copy(btol),
+		) {
+			Action::Sweep(ifirst) => ifirst,
+			action => {
+				if let Action::ZeroDiagonal = action {
+					deflate_zero_diagonal(A.rb_mut(), B.rb_mut(), z.rb_mut(), ilast, ifrstm);
+				}
+
+				// standardize the 1x1 block so that B[(ilast, ilast)] is nonnegative
+				if B[(ilast, ilast)] < zero() {
+					for i in ifrstm..ilast + 1 {
+						A[(i, ilast)] = -A[(i, ilast)];
+						B[(i, ilast)] = -B[(i, ilast)];
+					}
+					if let Some(mut z) = z.rb_mut() {
+						for i in 0..n {
+							z[(i, ilast)] = -z[(i, ilast)];
+						}
+					}
+				}
+
+				alpha_re[ilast] = copy(A[(ilast, ilast)]);
+				alpha_im[ilast] = zero();
+				beta[ilast] = copy(B[(ilast, ilast)]);
+
+				if ilast == ilo {
+					return Ok(());
+				}
+				ilast -= 1;
+				iiter = 0;
+				if !want_t {
+					ilastm = ilast;
+					if ifrstm > ilast {
+						ifrstm = ilo;
+					}
+				}
+				continue;
+			},
+		};
+
+		iiter += 1;
+		if !want_t {
+			ifrstm = f;
+		}
+		let l = ilast;
+
+		if f + 1 == l {
+			// isolated 2x2 block
+			let i0 = l - 1;
+			let i1 = l;
+
+			let a00 = copy(A[(i0, i0)]);
+			let a01 = copy(A[(i0, i1)]);
+			let a10 = copy(A[(i1, i0)]);
+			let a11 = copy(A[(i1, i1)]);
+			let b00 = copy(B[(i0, i0)]);
+			let b01 = copy(B[(i0, i1)]);
+			let b11 = copy(B[(i1, i1)]);
+
+			let (m11, p, disc) = eig22(A.rb().submatrix(i0, i0, 2, 2), B.rb().submatrix(i0, i0, 2, 2));
+
+			if disc >= zero() {
+				// real eigenvalues, split the block into two 1x1 blocks
+				let sq = sqrt(disc);
+				let lambda = if p >= zero() { m11 + (p + sq) } else { m11 + (p - sq) };
+
+				let r00 = a00 - lambda * b00;
+				let r01 = a01 - lambda * b01;
+				let r11 = a11 - lambda * b11;
+				let (x, y) = if hypot(r00, r01) >= hypot(a10, r11) { (r00, r01) } else { (a10, r11) };
+
+				// the first column of the rotation spans the kernel of A - lambda B
+				let norm = hypot(x, y);
+				let rot = if norm == zero() {
+					JacobiRotation { c: one(), s: zero() }
+				} else {
+					JacobiRotation { c: y / norm, s: -x / norm }
+				};
+				rot.apply_on_the_right_in_place(A.rb_mut().get_mut(ifrstm..l + 1, ..).two_cols_mut(i0, i1));
+				rot.apply_on_the_right_in_place(B.rb_mut().get_mut(ifrstm..l + 1, ..).two_cols_mut(i0, i1));
+				if let Some(z) = z.rb_mut() {
+					rot.apply_on_the_right_in_place(z.two_cols_mut(i0, i1));
+				}
+
+				// the first columns of both blocks are now parallel, use the more accurate one
+				let na = hypot(A[(i0, i0)], A[(i1, i0)]);
+				let nb = hypot(B[(i0, i0)], B[(i1, i0)]);
+				let (rot, _) = if na * btol >= nb * atol {
+					JacobiRotation::rotg(copy(A[(i0, i0)]), copy(A[(i1, i0)]))
+				} else {
+					JacobiRotation::rotg(copy(B[(i0, i0)]), copy(B[(i1, i0)]))
+				};
+				rot.adjoint()
+					.apply_on_the_left_in_place(A.rb_mut().get_mut(.., i0..ilastm + 1).two_rows_mut(i0, i1));
+				rot.adjoint()
+					.apply_on_the_left_in_place(B.rb_mut().get_mut(.., i0..ilastm + 1).two_rows_mut(i0, i1));
+				if let Some(q) = q.rb_mut() {
+					rot.apply_on_the_right_in_place(q.two_cols_mut(i0, i1));
+				}
+				A[(i1, i0)] = zero();
+				B[(i1, i0)] = zero();
+			} else {
+				// complex conjugate pair
+				let re = m11 + p;
+				let im = sqrt(-disc);
+				let s0 = abs(b00);
+				let s1 = abs(b11);
+
+				alpha_re[i0] = re * s0;
+				alpha_im[i0] = im * s0;
+				beta[i0] = s0;
+				alpha_re[i1] = re * s1;
+				alpha_im[i1] = -im * s1;
+				beta[i1] = s1;
+
+				if i0 == ilo {
+					return Ok(());
+				}
+				ilast = i0 - 1;
+				iiter = 0;
+				if !want_t {
+					ilastm = ilast;
+					if ifrstm > ilast {
+						ifrstm = ilo;
+					}
+				}
+			}
+			continue;
+		}
+
+		// double shift sweep on the active block f..=l
+		let (s, p) = if iiter % 10 != 0 {
+			let b00 = copy(B[(l - 1, l - 1)]);
+			let b01 = copy(B[(l - 1, l)]);
+			let b11 = copy(B[(l, l)]);
+			let m00 = A[(l - 1, l - 1)] / b00;
+			let m10 = A[(l, l - 1)] / b00;
+			let m01 = (A[(l - 1, l)] - m00 * b01) / b11;
+			let m11 = (A[(l, l)] - m10 * b01) / b11;
+			(m00 + m11, m00 * m11 - m01 * m10)
+		} else {
+			// exceptional shift
+			let w = abs(A[(l, l - 1)] / B[(l - 1, l - 1)]) + abs(A[(l - 1, l - 2)] / B[(l - 2, l - 2)]);
+			(from_f64::<T>(1.5) * w, w * w)
+		};
+
+		// first column of (M - s1 I)(M - s2 I), where M = A B^-1
+		let u0 = A[(f, f)] / B[(f, f)];
+		let u1 = A[(f + 1, f)] / B[(f, f)];
+		let y1 = u1 / B[(f + 1, f + 1)];
+		let y0 = (u0 - B[(f, f + 1)] * y1) / B[(f, f)];
+		let mut v = [
+			A[(f, f)] * y0 + A[(f, f + 1)] * y1 - s * u0 + p,
+			A[(f + 1, f)] * y0 + A[(f + 1, f + 1)] * y1 - s * u1,
+			A[(f + 2, f + 1)] * y1,
+		];
+
+		for k in f..l {
+			let nr = Ord::min(3, l + 1 - k);
+			if k > f {
+				v[0] = copy(A[(k, k - 1)]);
+				v[1] = copy(A[(k + 1, k - 1)]);
+				if nr == 3 {
+					v[2] = copy(A[(k + 2, k - 1)]);
+				}
+			}
+
+			let tau = make_householder(&mut v[..nr]);
+			let col_start = if k > f { k - 1 } else { k };
+			apply_householder_on_the_left(&v[..nr], copy(tau), A.rb_mut().get_mut(k..k + nr, col_start..ilastm + 1));
+			apply_householder_on_the_left(&v[..nr], copy(tau), B.rb_mut().get_mut(k..k + nr, k..ilastm + 1));
+			if let Some(q) = q.rb_mut() {
+				apply_householder_on_the_right(&v[..nr], copy(tau), q.get_mut(.., k..k + nr));
+			}
+			if k > f {
+				A[(k + 1, k - 1)] = zero();
+				if nr == 3 {
+					A[(k + 2, k - 1)] = zero();
+				}
+			}
+
+			let row_end = Ord::min(k + 3, l) + 1;
+
+			// restore the triangular structure of B from the right
+			if nr == 3 {
+				let mut w = [copy(B[(k + 2, k + 2)]), copy(B[(k + 2, k + 1)]), copy(B[(k + 2, k)])];
+				let tau = make_householder(&mut w);
+				let w = [copy(w[2]), copy(w[1]), copy(w[0])];
+
+				apply_householder_on_the_right(&w, copy(tau), A.rb_mut().get_mut(ifrstm..row_end, k..k + 3));
+				apply_householder_on_the_right(&w, copy(tau), B.rb_mut().get_mut(ifrstm..k + 3, k..k + 3));
+				if let Some(z) = z.rb_mut() {
+					apply_householder_on_the_right(&w, copy(tau), z.get_mut(.., k..k + 3));
+				}
+				B[(k + 2, k)] = zero();
+				B[(k + 2, k + 1)] = zero();
+			}
+
+			let mut w = [copy(B[(k + 1, k + 1)]), copy(B[(k + 1, k)])];
+			let tau = make_householder(&mut w);
+			let w = [copy(w[1]), copy(w[0])];
+
+			apply_householder_on_the_right(&w, copy(tau), A.rb_mut().get_mut(ifrstm..row_end, k..k + 2));
+			apply_householder_on_the_right(&w, copy(tau), B.rb_mut().get_mut(ifrstm..k + 2, k..k + 2));
+			if let Some(z) = z.rb_mut() {
+				apply_householder_on_the_right(&w, copy(tau), z.get_mut(.., k..k + 2));
+			}
+			B[(k + 1, k)] = zero();
+		}
+	}
+
+	Err(EvdError::NoConvergence)
+}
+
+/// computes the generalized schur form of the complex hessenberg-triangular pencil $(A, B)$ using
+/// the single shift $QZ$ algorithm
+///
+/// on exit, $A$ and $B$ are upper triangular, and the diagonal of $B$ is real and nonnegative
+#[math]
+pub(crate) fn complex_qz<T: ComplexField>(
+	want_t: bool,
+	A: MatMut<'_, T>,
+	B: MatMut<'_, T>,
+	q: Option<MatMut<'_, T>>,
+	z: Option<MatMut<'_, T>>,
+	alpha: ColMut<'_, T>,
+	beta: ColMut<'_, T>,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+	if n == 0 {
+		return Ok(());
+	}
+
+	let mut A = A;
+	let mut B = B;
+	let mut q = q;
+	let mut z = z;
+	let mut alpha = alpha;
+	let mut beta = beta;
+
+	let ulp = eps::<T::Real>();
+	let safmin = min_positive::<T::Real>();
+	let atol = max(safmin, ulp * A.rb().norm_l2());
+	let btol = max(safmin, ulp * B.rb().norm_l2());
+
+	let ilo = 0;
+	let mut ilast = n - 1;
+	let mut ifrstm = ilo;
+	let mut ilastm = if want_t { n - 1 } else { ilast };
+	let mut iiter = 0usize;
+	let mut eshift = zero::<T>();
+	let maxit = 30 * n;
+
+	for _ in 0..maxit {
+		let f = match find_deflation(
+			A.rb_mut(),
+			B.rb_mut(),
+			q.rb_mut(),
+			z.rb_mut(),
+			ilo,
+			ilast,
+			ifrstm,
+			ilastm,
+			copy(atol),
+			copy(btol),
+		) {
+			Action::Sweep(ifirst) => ifirst,
+			action => {
+				if let Action::ZeroDiagonal = action {
+					deflate_zero_diagonal(A.rb_mut(), B.rb_mut(), z.rb_mut(), ilast, ifrstm);
+				}
+
+				// standardize B[(ilast, ilast)] to be real and nonnegative
+				let absb = abs(B[(ilast, ilast)]);
+				if absb > safmin {
+					let sign = conj(mul_real(B[(ilast, ilast)], recip(absb)));
+					B[(ilast, ilast)] = from_real(absb);
+					for i in ifrstm..ilast {
+						B[(i, ilast)] = B[(i, ilast)] * sign;
+					}
+					for i in ifrstm..ilast + 1 {
+						A[(i, ilast)] = A[(i, ilast)] * sign;
+					}
+					if let Some(mut z) = z.rb_mut() {
+						for i in 0..n {
+							z[(i, ilast)] = z[(i, ilast)] * sign;
+						}
+					}
+				} else {
+					B[(ilast, ilast)] = zero();
+				}
+
+				alpha[ilast] = copy(A[(ilast, ilast)]);
+				beta[ilast] = copy(B[(ilast, ilast)]);
+
+				if ilast == ilo {
+					return Ok(());
+				}
+				ilast -= 1;
+				iiter = 0;
+				eshift = zero();
+				if !want_t {
+					ilastm = ilast;
+					if ifrstm > ilast {
+						ifrstm = ilo;
+					}
+				}
+				continue;
+			},
+		};
+
+		iiter += 1;
+		if !want_t {
+			ifrstm = f;
+		}
+		let l = ilast;
+
+		let shift = if iiter % 10 != 0 {
+			// wilkinson shift from the trailing 2x2 block of A B^-1
+			let u12 = B[(l - 1, l)] * recip(B[(l, l)]);
+			let ad11 = A[(l - 1, l - 1)] * recip(B[(l - 1, l - 1)]);
+			let ad21 = A[(l, l - 1)] * recip(B[(l - 1, l - 1)]);
+			let ad12 = A[(l - 1, l)] * recip(B[(l, l)]);
+			let ad22 = A[(l, l)] * recip(B[(l, l)]);
+			let abi22 = ad22 - u12 * ad21;
+			let abi12 = ad12 - u12 * ad11;
+
+			let mut shift = copy(abi22);
+			let ctemp = sqrt(abi12) * sqrt(ad21);
+			if ctemp != zero() {
+				let x = mul_real(ad11 - shift, from_f64::<T::Real>(0.5));
+				let xabs = abs1(x);
+				let scale = max(abs1(ctemp), xabs);
+				let xs = mul_real(x, recip(scale));
+				let cs = mul_real(ctemp, recip(scale));
+				let mut y = mul_real(sqrt(xs * xs + cs * cs), scale);
+				if xabs > zero() {
+					let xn = mul_real(x, recip(xabs));
+					if real(xn) * real(y) + imag(xn) * imag(y) < zero() {
+						y = -y;
+					}
+				}
+				shift = shift - ctemp * (ctemp * recip(x + y));
+			}
+			shift
+		} else {
+			// exceptional shift
+			eshift = eshift + A[(l, l - 1)] * recip(B[(l - 1, l - 1)]);
+			copy(eshift)
+		};
+
+		let (mut rot, _) = JacobiRotation::rotg(A[(f, f)] - shift * B[(f, f)], copy(A[(f + 1, f)]));
+
+		for j in f..l {
+			if j > f {
+				let r;
+				(rot, r) = JacobiRotation::rotg(copy(A[(j, j - 1)]), copy(A[(j + 1, j - 1)]));
+				A[(j, j - 1)] = r;
+				A[(j + 1, j - 1)] = zero();
+			}
+
+			rot.adjoint()
+				.apply_on_the_left_in_place(A.rb_mut().get_mut(.., j..ilastm + 1).two_rows_mut(j, j + 1));
+			rot.adjoint()
+				.apply_on_the_left_in_place(B.rb_mut().get_mut(.., j..ilastm + 1).two_rows_mut(j, j + 1));
+			if let Some(q) = q.rb_mut() {
+				rot.apply_on_the_right_in_place(q.two_cols_mut(j, j + 1));
+			}
+
+			let (rot, r) = JacobiRotation::rotg(conj(B[(j + 1, j + 1)]), conj(B[(j + 1, j)]));
+			B[(j + 1, j + 1)] = conj(r);
+			B[(j + 1, j)] = zero();
+
+			let row_end = Ord::min(j + 2, l) + 1;
+			rot.apply_on_the_right_in_place(A.rb_mut().get_mut(ifrstm..row_end, ..).two_cols_mut(j + 1, j));
+			rot.apply_on_the_right_in_place(B.rb_mut().get_mut(ifrstm..j + 1, ..).two_cols_mut(j + 1, j));
+			if let Some(z) = z.rb_mut() {
+				rot.apply_on_the_right_in_place(z.two_cols_mut(j + 1, j));
+			}
+		}
+	}
+
+	Err(EvdError::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+	use linalg::gevd::hessenberg_triangular::*;
+
+	#[test]
+	fn test_complex_qz_singular() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [2, 3, 5, 10, 33] {
+			let rank = n / 2;
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let L = CwiseMatDistribution {
+				nrows: n,
+				ncols: rank,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let R = CwiseMatDistribution {
+				nrows: rank,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let B = &L * &R;
+
+			let mut H = A.clone();
+			let mut T = B.clone();
+			let mut Q = Mat::zeros(n, n);
+			let mut Z = Mat::zeros(n, n);
+			hessenberg_triangular_in_place(
+				H.as_mut(),
+				T.as_mut(),
+				Some(Q.as_mut()),
+				Some(Z.as_mut()),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(hessenberg_triangular_in_place_scratch::<c64>(n, Par::Seq))),
+			);
+			let mut alpha = Col::zeros(n);
+			let mut beta = Col::zeros(n);
+			complex_qz(
+				true,
+				H.as_mut(),
+				T.as_mut(),
+				Some(Q.as_mut()),
+				Some(Z.as_mut()),
+				alpha.as_mut(),
+				beta.as_mut(),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 16.0 * (n as f64));
+			assert!(&Q * &H * Z.adjoint() ~ A);
+			assert!(&Q * &T * Z.adjoint() ~ B);
+			for j in 0..n {
+				for i in j + 1..n {
+					assert!(H[(i, j)] == c64::ZERO);
+					assert!(T[(i, j)] == c64::ZERO);
+				}
+			}
+		}
+	}
+}
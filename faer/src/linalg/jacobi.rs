@@ -122,7 +122,7 @@ impl<T: ComplexField> JacobiRotation<T> {
 				let rtmax = recip(rtmin);
 
 				if b == zero() {
-					return (Self { c: one(), s: zero() }, one());
+					return (Self { c: one(), s: zero() }, a);
 				}
 
 				let (c, s, r);
@@ -392,3 +392,42 @@ impl<T: ComplexField> JacobiRotation<T> {
 		Self { c: copy(self.c), s: -self.s }
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::approx::*;
+	use crate::{assert, c64};
+
+	#[test]
+	fn test_rotg_zero() {
+		let approx_eq = ApproxEq::<f64>::eps();
+
+		for a in [c64::new(0.5, -2.0), c64::new(-3.0, 0.0), c64::new(0.0, 0.0)] {
+			let (rot, r) = JacobiRotation::rotg(a, c64::ZERO);
+			assert!(rot.c == c64::ONE);
+			assert!(rot.s == c64::ZERO);
+			assert!(r == a);
+		}
+
+		for a in [0.5, -3.0, 0.0] {
+			let (rot, r) = JacobiRotation::rotg(a, 0.0);
+			assert!(rot.c == 1.0);
+			assert!(rot.s == 0.0);
+			assert!(r == a);
+		}
+
+		for (a, b) in [
+			(c64::new(0.5, -2.0), c64::ZERO),
+			(c64::new(0.5, -2.0), c64::new(1.0, 3.0)),
+			(c64::ZERO, c64::new(1.0, 3.0)),
+		] {
+			let (rot, r) = JacobiRotation::rotg(a, b);
+			let mut m = Mat::from_fn(2, 1, |i, _| if i == 0 { a } else { b });
+			rot.adjoint().apply_on_the_left_in_place(m.as_mut().two_rows_mut(0, 1));
+
+			assert!(m[(0, 0)] ~ r);
+			assert!(m[(1, 0)] ~ c64::ZERO);
+		}
+	}
+}
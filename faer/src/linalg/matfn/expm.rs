@@ -0,0 +1,406 @@
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::matfn::{lu_solve_in_place, lu_solve_in_place_scratch, norm_l1_operator};
+
+// padé degrees and the corresponding maximum norms for which the approximant is accurate to double
+// precision, from higham, "the scaling and squaring method for the matrix exponential revisited"
+const DEGREES: [usize; 5] = [3, 5, 7, 9, 13];
+const THETA: [f64; 5] = [
+	1.495585217958292e-2,
+	2.53939833006323e-1,
+	9.504178996162932e-1,
+	2.097847961257068e0,
+	5.371920351148152e0,
+];
+
+const B3: &[f64] = &[120.0, 60.0, 12.0, 1.0];
+const B5: &[f64] = &[30240.0, 15120.0, 3360.0, 420.0, 30.0, 1.0];
+const B7: &[f64] = &[17297280.0, 8648640.0, 1995840.0, 277200.0, 25200.0, 1512.0, 56.0, 1.0];
+const B9: &[f64] = &[
+	17643225600.0,
+	8821612800.0,
+	2075673600.0,
+	302702400.0,
+	30270240.0,
+	2162160.0,
+	110880.0,
+	3960.0,
+	90.0,
+	1.0,
+];
+const B13: &[f64] = &[
+	64764752532480000.0,
+	32382376266240000.0,
+	7771770303897600.0,
+	1187353796428800.0,
+	129060195264000.0,
+	10559470521600.0,
+	670442572800.0,
+	33522128640.0,
+	1323241920.0,
+	40840800.0,
+	960960.0,
+	16380.0,
+	182.0,
+	1.0,
+];
+
+/// overwrites `dst` with $\sum_k c_k M_k + c I$, or adds it to `dst`
+#[math]
+fn linear_combination<T: ComplexField>(dst: MatMut<'_, T>, beta: Accum, terms: &[(MatRef<'_, T>, f64)], identity: f64) {
+	let mut dst = dst;
+	let n = dst.nrows();
+
+	for j in 0..n {
+		for i in 0..n {
+			let mut acc = if i == j { from_real(from_f64::<T::Real>(identity)) } else { zero::<T>() };
+			for (M, c) in terms {
+				acc = acc + mul_real(M[(i, j)], from_f64::<T::Real>(*c));
+			}
+			dst[(i, j)] = match beta {
+				Accum::Replace => acc,
+				Accum::Add => dst[(i, j)] + acc,
+			};
+		}
+	}
+}
+
+/// computes the size and alignment of the workspace required to compute a matrix's exponential
+pub fn expm_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	temp_mat_scratch::<T>(dim, dim)
+		.array(8)
+		.and(lu_solve_in_place_scratch::<T>(dim, dim, par))
+}
+
+/// computes the exponential of the matrix $A$, and stores the result in `out`
+///
+/// the exponential is computed with the scaling and squaring method, using a padé approximant of
+/// degree $3$, $5$, $7$, $9$ or $13$ chosen from the $\ell_1$ norm of $A$
+#[math]
+#[track_caller]
+pub fn expm<T: ComplexField>(out: MatMut<'_, T>, A: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, out.nrows() == n, out.ncols() == n));
+
+	let mut out = out;
+	if n == 0 {
+		return;
+	}
+
+	let norm = norm_l1_operator(A);
+	if !is_finite(norm) {
+		out.fill(nan());
+		return;
+	}
+
+	let (mut X, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut A2, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut A4, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut A6, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut A8, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut U, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut V, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut W, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut X = X.as_mat_mut();
+	let mut A2 = A2.as_mat_mut();
+	let mut A4 = A4.as_mat_mut();
+	let mut A6 = A6.as_mat_mut();
+	let mut A8 = A8.as_mat_mut();
+	let mut U = U.as_mat_mut();
+	let mut V = V.as_mat_mut();
+	let mut W = W.as_mat_mut();
+
+	let mut degree = 13;
+	for (m, theta) in core::iter::zip(DEGREES, THETA) {
+		if norm <= from_f64::<T::Real>(theta) {
+			degree = m;
+			break;
+		}
+	}
+
+	// scale $A$ by $2^{-s}$ so that its norm is small enough for the degree $13$ approximant
+	let mut s = 0usize;
+	let mut scale = one::<T::Real>();
+	if degree == 13 {
+		let theta = from_f64::<T::Real>(THETA[4]);
+		let half = from_f64::<T::Real>(0.5);
+		let mut norm = norm;
+		while norm > theta {
+			norm = norm * half;
+			scale = scale * half;
+			s += 1;
+		}
+	}
+	z!(X.rb_mut(), A).for_each(|uz!(dst, src)| *dst = mul_real(*src, scale));
+
+	linalg::matmul::matmul(A2.rb_mut(), Accum::Replace, X.rb(), X.rb(), one(), par);
+
+	// $p(X) = V + U$, $q(X) = V - U$, where $U$ is odd and $V$ is even
+	if degree < 13 {
+		let b = match degree {
+			3 => B3,
+			5 => B5,
+			7 => B7,
+			_ => B9,
+		};
+
+		// W holds the even polynomial that is multiplied by $X$ to form $U$
+		linear_combination(V.rb_mut(), Accum::Replace, &[(A2.rb(), b[2])], b[0]);
+		linear_combination(W.rb_mut(), Accum::Replace, &[(A2.rb(), b[3])], b[1]);
+		if degree >= 5 {
+			linalg::matmul::matmul(A4.rb_mut(), Accum::Replace, A2.rb(), A2.rb(), one(), par);
+			linear_combination(V.rb_mut(), Accum::Add, &[(A4.rb(), b[4])], 0.0);
+			linear_combination(W.rb_mut(), Accum::Add, &[(A4.rb(), b[5])], 0.0);
+		}
+		if degree >= 7 {
+			linalg::matmul::matmul(A6.rb_mut(), Accum::Replace, A4.rb(), A2.rb(), one(), par);
+			linear_combination(V.rb_mut(), Accum::Add, &[(A6.rb(), b[6])], 0.0);
+			linear_combination(W.rb_mut(), Accum::Add, &[(A6.rb(), b[7])], 0.0);
+		}
+		if degree >= 9 {
+			linalg::matmul::matmul(A8.rb_mut(), Accum::Replace, A6.rb(), A2.rb(), one(), par);
+			linear_combination(V.rb_mut(), Accum::Add, &[(A8.rb(), b[8])], 0.0);
+			linear_combination(W.rb_mut(), Accum::Add, &[(A8.rb(), b[9])], 0.0);
+		}
+		linalg::matmul::matmul(U.rb_mut(), Accum::Replace, X.rb(), W.rb(), one(), par);
+	} else {
+		let b = B13;
+
+		linalg::matmul::matmul(A4.rb_mut(), Accum::Replace, A2.rb(), A2.rb(), one(), par);
+		linalg::matmul::matmul(A6.rb_mut(), Accum::Replace, A4.rb(), A2.rb(), one(), par);
+
+		linear_combination(W.rb_mut(), Accum::Replace, &[(A6.rb(), b[13]), (A4.rb(), b[11]), (A2.rb(), b[9])], 0.0);
+		linalg::matmul::matmul(A8.rb_mut(), Accum::Replace, A6.rb(), W.rb(), one(), par);
+		linear_combination(A8.rb_mut(), Accum::Add, &[(A6.rb(), b[7]), (A4.rb(), b[5]), (A2.rb(), b[3])], b[1]);
+		linalg::matmul::matmul(U.rb_mut(), Accum::Replace, X.rb(), A8.rb(), one(), par);
+
+		linear_combination(W.rb_mut(), Accum::Replace, &[(A6.rb(), b[12]), (A4.rb(), b[10]), (A2.rb(), b[8])], 0.0);
+		linalg::matmul::matmul(V.rb_mut(), Accum::Replace, A6.rb(), W.rb(), one(), par);
+		linear_combination(V.rb_mut(), Accum::Add, &[(A6.rb(), b[6]), (A4.rb(), b[4]), (A2.rb(), b[2])], b[0]);
+	}
+
+	z!(out.rb_mut(), W.rb_mut(), U.rb(), V.rb()).for_each(|uz!(p, q, u, v)| {
+		*p = *v + *u;
+		*q = *v - *u;
+	});
+	lu_solve_in_place(W.rb_mut(), out.rb_mut(), par, stack);
+
+	for _ in 0..s {
+		linalg::matmul::matmul(W.rb_mut(), Accum::Replace, out.rb(), out.rb(), one(), par);
+		out.copy_from(W.rb());
+	}
+}
+
+/// computes the size and alignment of the workspace required to compute a matrix's exponential
+/// and its fréchet derivative
+pub fn expm_frechet_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	temp_mat_scratch::<T>(2 * dim, 2 * dim).array(2).and(expm_scratch::<T>(2 * dim, par))
+}
+
+/// computes the exponential of the matrix $A$ and its fréchet derivative $L(A, E)$ in the
+/// direction $E$, and stores them in `exp` and `frechet`
+///
+/// the fréchet derivative is the linear map such that
+/// $e^{A + E} = e^A + L(A, E) + o(\|E\|)$
+///
+/// both are computed from the exponential of the block matrix
+/// $\begin{bmatrix} A & E \\ 0 & A \end{bmatrix}$, whose upper right block is $L(A, E)$
+#[math]
+#[track_caller]
+pub fn expm_frechet<T: ComplexField>(exp: MatMut<'_, T>, frechet: MatMut<'_, T>, A: MatRef<'_, T>, E: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+	let n = A.nrows();
+	assert!(all(
+		A.ncols() == n,
+		E.nrows() == n,
+		E.ncols() == n,
+		exp.nrows() == n,
+		exp.ncols() == n,
+		frechet.nrows() == n,
+		frechet.ncols() == n,
+	));
+
+	let mut exp = exp;
+	let mut frechet = frechet;
+
+	let norm_A = norm_l1_operator(A);
+	let norm_E = norm_l1_operator(E);
+
+	if norm_E == zero() {
+		frechet.fill(zero());
+		expm(exp, A, par, stack);
+		return;
+	}
+
+	// $L$ is linear in $E$, so $E$ is scaled to have the same norm as $A$, to avoid increasing the
+	// number of squarings
+	let t = if norm_A == zero() { one() } else { norm_A * recip(norm_E) };
+
+	let (mut M, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+	let (mut expM, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+	let mut M = M.as_mat_mut();
+	let mut expM = expM.as_mat_mut();
+
+	M.rb_mut().submatrix_mut(0, 0, n, n).copy_from(A);
+	M.rb_mut().submatrix_mut(n, n, n, n).copy_from(A);
+	M.rb_mut().submatrix_mut(n, 0, n, n).fill(zero());
+	z!(M.rb_mut().submatrix_mut(0, n, n, n), E).for_each(|uz!(dst, src)| *dst = mul_real(*src, t));
+
+	expm(expM.rb_mut(), M.rb(), par, stack);
+
+	let t_inv = recip(t);
+	exp.copy_from(expM.rb().submatrix(0, 0, n, n));
+	z!(frechet.rb_mut(), expM.rb().submatrix(0, n, n, n)).for_each(|uz!(dst, src)| *dst = mul_real(*src, t_inv));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+	use linalg::matfn::funm::{self_adjoint_funm, self_adjoint_funm_scratch};
+
+	#[test]
+	fn test_expm_self_adjoint() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			// scales chosen to exercise every padé degree as well as scaling and squaring
+			for scale in [1e-3, 1e-1, 0.5, 1.0, 2.0, 10.0] {
+				let B = CwiseMatDistribution {
+					nrows: n,
+					ncols: n,
+					dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+				}
+				.rand::<Mat<c64>>(rng);
+				let A = (&B + B.adjoint()) * Scale(c64::new(scale / (n as f64 + 1.0), 0.0));
+
+				let mut E = Mat::zeros(n, n);
+				expm(
+					E.as_mut(),
+					A.as_ref(),
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(expm_scratch::<c64>(n, Par::Seq))),
+				);
+
+				let mut target = Mat::zeros(n, n);
+				self_adjoint_funm(
+					target.as_mut(),
+					A.as_ref(),
+					|x| c64::new(x.exp(), 0.0),
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(self_adjoint_funm_scratch::<c64>(n, Par::Seq, default()))),
+					default(),
+				)
+				.unwrap();
+
+				let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64 + 1.0) * target.norm_max().max(1.0));
+				assert!(E ~ target);
+			}
+		}
+	}
+
+	#[test]
+	fn test_expm_inverse() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [1, 2, 3, 5, 10, 33, 64] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let A = A * Scale(2.0 / (n as f64).sqrt());
+
+			let mut E = Mat::zeros(n, n);
+			let mut E_neg = Mat::zeros(n, n);
+			let stack = &mut MemBuffer::new(expm_scratch::<f64>(n, Par::Seq));
+			expm(E.as_mut(), A.as_ref(), Par::Seq, MemStack::new(stack));
+			expm(E_neg.as_mut(), (-&A).as_ref(), Par::Seq, MemStack::new(stack));
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0 * (n as f64));
+			assert!(&E * &E_neg ~ Mat::<f64>::identity(n, n));
+		}
+	}
+
+	#[test]
+	fn test_expm_nilpotent() {
+		let A = crate::mat![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 0.0f64]] * Scale(100.0);
+		let mut E = Mat::zeros(3, 3);
+		expm(
+			E.as_mut(),
+			A.as_ref(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(expm_scratch::<f64>(3, Par::Seq))),
+		);
+
+		let target = crate::mat![[1.0, 100.0, 5000.0], [0.0, 1.0, 100.0], [0.0, 0.0, 1.0f64]];
+		let approx_eq = CwiseMat(ApproxEq::eps() * 5000.0 * 16.0);
+		assert!(E ~ target);
+	}
+
+	#[test]
+	fn test_expm_frechet() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let E = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let A = A * Scale(c64::new(1.0 / (n as f64 + 1.0).sqrt(), 0.0));
+
+			let mut exp = Mat::zeros(n, n);
+			let mut L = Mat::zeros(n, n);
+			expm_frechet(
+				exp.as_mut(),
+				L.as_mut(),
+				A.as_ref(),
+				E.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(expm_frechet_scratch::<c64>(n, Par::Seq))),
+			);
+
+			let stack = &mut MemBuffer::new(expm_scratch::<c64>(n, Par::Seq));
+			let mut target = Mat::zeros(n, n);
+			expm(target.as_mut(), A.as_ref(), Par::Seq, MemStack::new(stack));
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64 + 1.0));
+			assert!(exp ~ target);
+
+			// central finite differences
+			let h = 1e-5;
+			let mut exp_p = Mat::zeros(n, n);
+			let mut exp_m = Mat::zeros(n, n);
+			expm(
+				exp_p.as_mut(),
+				(&A + &E * Scale(c64::new(h, 0.0))).as_ref(),
+				Par::Seq,
+				MemStack::new(stack),
+			);
+			expm(
+				exp_m.as_mut(),
+				(&A - &E * Scale(c64::new(h, 0.0))).as_ref(),
+				Par::Seq,
+				MemStack::new(stack),
+			);
+			let fd = (exp_p - exp_m) * Scale(c64::new(0.5 / h, 0.0));
+
+			let approx_eq = CwiseMat(ApproxEq {
+				abs_tol: 1e-6,
+				rel_tol: 1e-6,
+			});
+			assert!(L ~ fd);
+		}
+	}
+}
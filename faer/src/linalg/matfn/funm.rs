@@ -0,0 +1,101 @@
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::evd::{ComputeEigenvectors, EvdError, SelfAdjointEvdParams};
+
+/// computes the size and alignment of the workspace required to compute a function of a
+/// self-adjoint matrix
+pub fn self_adjoint_funm_scratch<T: ComplexField>(dim: usize, par: Par, params: Spec<SelfAdjointEvdParams, T>) -> StackReq {
+	if dim == 0 {
+		return StackReq::EMPTY;
+	}
+
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(dim, dim).array(2),
+		temp_mat_scratch::<T>(dim, 1),
+		linalg::evd::self_adjoint_evd_scratch::<T>(dim, ComputeEigenvectors::Yes, par, params),
+	])
+}
+
+/// computes $f(A) = U f(S) U^H$, where $A = U S U^H$ is the eigendecomposition of the
+/// self-adjoint matrix $A$, and stores the result in `out`
+///
+/// `f` is evaluated once at each eigenvalue. if it returns real values for real arguments, the
+/// result is self-adjoint
+///
+/// only the lower triangular half of $A$ is accessed
+#[math]
+#[track_caller]
+pub fn self_adjoint_funm<T: ComplexField>(
+	out: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	f: impl FnMut(T::Real) -> T,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SelfAdjointEvdParams, T>,
+) -> Result<(), EvdError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, out.nrows() == n, out.ncols() == n));
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	let mut f = f;
+
+	let (mut U, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut Uf, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut s, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+	let mut U = U.as_mat_mut();
+	let mut Uf = Uf.as_mat_mut();
+	let mut s = s.as_mat_mut().col_mut(0);
+
+	linalg::evd::self_adjoint_evd(A, s.rb_mut().as_diagonal_mut(), Some(U.rb_mut()), par, stack, params)?;
+
+	for j in 0..n {
+		let fj = f(real(s[j]));
+		z!(Uf.rb_mut().col_mut(j), U.rb().col(j)).for_each(|uz!(dst, src)| *dst = *src * fj);
+	}
+
+	linalg::matmul::matmul(out, Accum::Replace, Uf.rb(), U.rb().adjoint(), one(), par);
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_self_adjoint_funm() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+			let A = &B * B.adjoint() + Mat::<c64>::identity(n, n);
+
+			let mut X = Mat::zeros(n, n);
+			self_adjoint_funm(
+				X.as_mut(),
+				A.as_ref(),
+				|x| c64::new(x.sqrt(), 0.0),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(self_adjoint_funm_scratch::<c64>(n, Par::Seq, default()))),
+				default(),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64 + 1.0));
+			assert!(&X * &X ~ A);
+			assert!(X.adjoint().to_owned() ~ X);
+		}
+	}
+}
@@ -0,0 +1,243 @@
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::evd::schur::{block_size_at, schur_in_place, schur_in_place_scratch};
+use linalg::matfn::sqrtm::sqrt_quasi_triangular_in_place;
+use linalg::matfn::{MatFnError, lu_solve_in_place, lu_solve_in_place_scratch, schur_back_transform, schur_back_transform_scratch};
+
+// nodes and weights of the 7 point gauss-legendre quadrature on $[-1, 1]$
+const GAUSS_LEGENDRE_NODES: [f64; 7] = [
+	-0.9491079123427585,
+	-0.7415311855993945,
+	-0.4058451513773972,
+	0.0,
+	0.4058451513773972,
+	0.7415311855993945,
+	0.9491079123427585,
+];
+const GAUSS_LEGENDRE_WEIGHTS: [f64; 7] = [
+	0.1294849661688697,
+	0.2797053914892766,
+	0.3818300505051189,
+	0.4179591836734694,
+	0.3818300505051189,
+	0.2797053914892766,
+	0.1294849661688697,
+];
+
+// maximum norm of $T - I$ for which the degree 7 padé approximant of $\log(I + X)$ is accurate
+// to double precision, from al-mohy and higham, "improved inverse scaling and squaring algorithms
+// for the matrix logarithm"
+const THETA: f64 = 0.25;
+const MAX_SQRT: usize = 64;
+
+/// computes the size and alignment of the workspace required to compute a matrix's principal
+/// logarithm
+pub fn logm_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	temp_mat_scratch::<T>(dim, dim).array(2).and(StackReq::any_of(&[
+		schur_in_place_scratch::<T>(dim, par),
+		schur_back_transform_scratch::<T>(dim, par),
+		temp_mat_scratch::<T>(dim, dim)
+			.array(3)
+			.and(lu_solve_in_place_scratch::<T>(dim, dim, par)),
+	]))
+}
+
+/// computes the principal logarithm $X$ of the matrix $A$, such that $e^X = A$ and the
+/// eigenvalues of $X$ have imaginary parts in $(-\pi, \pi]$, and stores the result in `out`
+///
+/// the logarithm is computed with the inverse scaling and squaring method applied to the schur
+/// form of $A$. if `T` is real, the real schur form is used, and an error is returned if $A$ has a
+/// negative real eigenvalue. an error is returned if $A$ is singular
+#[math]
+#[track_caller]
+pub fn logm<T: ComplexField>(out: MatMut<'_, T>, A: MatRef<'_, T>, par: Par, stack: &mut MemStack) -> Result<(), MatFnError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, out.nrows() == n, out.ncols() == n));
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	let (mut T, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut T = T.as_mat_mut();
+	let mut Z = Z.as_mat_mut();
+
+	T.copy_from(A);
+	schur_in_place(T.rb_mut(), Z.rb_mut(), par, stack).map_err(|_| MatFnError::NoConvergence)?;
+
+	// zero eigenvalues are never mapped close to one by repeated square roots
+	let mut i = 0;
+	while i < n {
+		let bs = block_size_at(T.rb(), i);
+		let det = if bs == 1 {
+			copy(T[(i, i)])
+		} else {
+			T[(i, i)] * T[(i + 1, i + 1)] - T[(i, i + 1)] * T[(i + 1, i)]
+		};
+		if det == zero() {
+			return Err(MatFnError::Singular);
+		}
+		i += bs;
+	}
+
+	// take square roots until $T$ is close enough to the identity
+	let mut k = 0usize;
+	loop {
+		let mut norm = zero::<T::Real>();
+		for j in 0..n {
+			let mut sum = zero::<T::Real>();
+			for i in 0..n {
+				sum = sum + if i == j { abs(T[(i, j)] - one::<T>()) } else { abs(T[(i, j)]) };
+			}
+			norm = max(norm, sum);
+		}
+		if !is_finite(norm) {
+			return Err(MatFnError::NoConvergence);
+		}
+		if norm <= from_f64::<T::Real>(THETA) {
+			break;
+		}
+		if k == MAX_SQRT {
+			return Err(MatFnError::NoConvergence);
+		}
+		sqrt_quasi_triangular_in_place(T.rb_mut(), par)?;
+		k += 1;
+	}
+
+	{
+		let mut X = T.rb_mut();
+		for i in 0..n {
+			X[(i, i)] = X[(i, i)] - one::<T>();
+		}
+	}
+	let X = T.rb();
+
+	let (mut L, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut M, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut R, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut L = L.as_mat_mut();
+	let mut M = M.as_mat_mut();
+	let mut R = R.as_mat_mut();
+
+	// $\log(I + X) = \int_0^1 X (I + t X)^{-1} dt$, and the gauss-legendre quadrature of this
+	// integral is the diagonal padé approximant of the logarithm
+	L.fill(zero());
+	let half = from_f64::<T::Real>(0.5);
+	for (node, weight) in core::iter::zip(GAUSS_LEGENDRE_NODES, GAUSS_LEGENDRE_WEIGHTS) {
+		let t = half * (one::<T::Real>() + from_f64::<T::Real>(node));
+		let w = half * from_f64::<T::Real>(weight);
+
+		z!(M.rb_mut(), X).for_each(|uz!(m, x)| *m = mul_real(*x, t));
+		for i in 0..n {
+			M[(i, i)] = M[(i, i)] + one::<T>();
+		}
+		R.copy_from(X);
+		lu_solve_in_place(M.rb_mut(), R.rb_mut(), par, stack);
+
+		z!(L.rb_mut(), R.rb()).for_each(|uz!(l, r)| *l = *l + mul_real(*r, w));
+	}
+
+	// undo the square roots, $\log A = 2^k \log A^{1 / 2^k}$
+	let mut scale = one::<T::Real>();
+	for _ in 0..k {
+		scale = scale + scale;
+	}
+	z!(L.rb_mut()).for_each(|uz!(l)| *l = mul_real(*l, scale));
+
+	schur_back_transform(out, Z.rb(), L.rb(), par, stack);
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+	use linalg::matfn::expm::{expm, expm_scratch};
+
+	#[test]
+	fn test_logm_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			let A = &B + Mat::<f64>::identity(n, n) * Scale(2.0 * (n as f64).sqrt() + 1.0);
+
+			let mut L = Mat::zeros(n, n);
+			logm(
+				L.as_mut(),
+				A.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(logm_scratch::<f64>(n, Par::Seq))),
+			)
+			.unwrap();
+
+			let mut E = Mat::zeros(n, n);
+			expm(
+				E.as_mut(),
+				L.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(expm_scratch::<f64>(n, Par::Seq))),
+			);
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 512.0 * (n as f64 + 1.0));
+			assert!(E ~ A);
+		}
+	}
+
+	#[test]
+	fn test_logm_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+
+			let mut L = Mat::zeros(n, n);
+			logm(
+				L.as_mut(),
+				A.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(logm_scratch::<c64>(n, Par::Seq))),
+			)
+			.unwrap();
+
+			let mut E = Mat::zeros(n, n);
+			expm(
+				E.as_mut(),
+				L.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(expm_scratch::<c64>(n, Par::Seq))),
+			);
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 512.0 * (n as f64 + 1.0));
+			assert!(E ~ A);
+		}
+	}
+
+	#[test]
+	fn test_logm_singular() {
+		let A = crate::mat![[1.0, 2.0], [0.0, 0.0f64]];
+		let mut L = Mat::zeros(2, 2);
+		let res = logm(
+			L.as_mut(),
+			A.as_ref(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(logm_scratch::<f64>(2, Par::Seq))),
+		);
+		assert!(res == Err(MatFnError::Singular));
+	}
+}
@@ -0,0 +1,95 @@
+//! low level implementation of matrix functions.
+//!
+//! a matrix function $f(A)$ extends a scalar function $f$ to square matrices, such that if
+//! $A = U S U^{-1}$ is diagonalizable, then
+//!
+//! $$f(A) = U f(S) U^{-1}$$
+//!
+//! the exponential is computed with the scaling and squaring method using padé approximants, and
+//! does not require an eigendecomposition. the square root and logarithm are computed from the
+//! real or complex schur form of the matrix. general functions of self-adjoint matrices are
+//! computed from the self-adjoint eigendecomposition
+
+/// matrix exponential
+pub mod expm;
+/// self-adjoint matrix functions
+pub mod funm;
+/// matrix logarithm
+pub mod logm;
+/// matrix square root
+pub mod sqrtm;
+
+use crate::internal_prelude::*;
+
+/// matrix function error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MatFnError {
+	/// the schur decomposition did not converge
+	NoConvergence,
+	/// the matrix is singular, and the function is not defined at one of its eigenvalues
+	Singular,
+	/// the matrix is real and has a negative real eigenvalue, so the principal value of the
+	/// function is not real
+	NegativeEigenvalue,
+}
+
+impl core::fmt::Display for MatFnError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for MatFnError {}
+
+/// returns the $\ell_1$ operator norm of $A$, i.e. its maximum absolute column sum
+#[math]
+pub(crate) fn norm_l1_operator<T: ComplexField>(A: MatRef<'_, T>) -> T::Real {
+	let mut norm = zero::<T::Real>();
+	for j in 0..A.ncols() {
+		let mut sum = zero::<T::Real>();
+		for i in 0..A.nrows() {
+			sum = sum + abs(A[(i, j)]);
+		}
+		norm = max(norm, sum);
+	}
+	norm
+}
+
+pub(crate) fn schur_back_transform_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	_ = par;
+	temp_mat_scratch::<T>(dim, dim)
+}
+
+/// computes $A = Z T Z^H$
+#[math]
+pub(crate) fn schur_back_transform<T: ComplexField>(A: MatMut<'_, T>, Z: MatRef<'_, T>, T: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+	let n = Z.nrows();
+	let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut tmp = tmp.as_mat_mut();
+
+	linalg::matmul::matmul(tmp.rb_mut(), Accum::Replace, Z, T, one(), par);
+	linalg::matmul::matmul(A, Accum::Replace, tmp.rb(), Z.adjoint(), one(), par);
+}
+
+pub(crate) fn lu_solve_in_place_scratch<T: ComplexField>(dim: usize, rhs_ncols: usize, par: Par) -> StackReq {
+	StackReq::all_of(&[
+		StackReq::new::<usize>(dim).array(2),
+		StackReq::any_of(&[
+			linalg::lu::partial_pivoting::factor::lu_in_place_scratch::<usize, T>(dim, dim, par, default()),
+			linalg::lu::partial_pivoting::solve::solve_in_place_scratch::<usize, T>(dim, rhs_ncols, par),
+		]),
+	])
+}
+
+/// overwrites `rhs` with $A^{-1} \text{rhs}$, using the $LU$ decomposition with partial pivoting
+/// of $A$, which is computed in place
+pub(crate) fn lu_solve_in_place<T: ComplexField>(A: MatMut<'_, T>, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+	let n = A.nrows();
+	let mut A = A;
+
+	let (mut perm, stack) = stack.make_with(n, |_| 0usize);
+	let (mut perm_inv, stack) = stack.make_with(n, |_| 0usize);
+
+	let (_, perm) = linalg::lu::partial_pivoting::factor::lu_in_place(A.rb_mut(), &mut perm, &mut perm_inv, par, stack, default());
+	linalg::lu::partial_pivoting::solve::solve_in_place_with_conj(A.rb(), A.rb(), perm, Conj::No, rhs, par, stack);
+}
@@ -0,0 +1,216 @@
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::evd::schur::{block_size_at, schur_in_place, schur_in_place_scratch};
+use linalg::matfn::{MatFnError, schur_back_transform, schur_back_transform_scratch};
+use linalg::sylvester::solve_small_sylvester;
+
+/// overwrites the $1 \times 1$ or $2 \times 2$ diagonal block `T` with its principal square root
+#[math]
+fn sqrt_diagonal_block<T: ComplexField>(T: MatMut<'_, T>) -> Result<(), MatFnError> {
+	let mut T = T;
+
+	if T.nrows() == 1 {
+		if try_const! { T::IS_REAL } && real(T[(0, 0)]) < zero() {
+			return Err(MatFnError::NegativeEigenvalue);
+		}
+		T[(0, 0)] = sqrt(T[(0, 0)]);
+		return Ok(());
+	}
+
+	// a $2 \times 2$ block only occurs in the real schur form. with $\delta = \sqrt{\det T}$, its
+	// square root is $(T + \delta I) / \sqrt{\operatorname{tr} T + 2 \delta}$
+	let det = real(T[(0, 0)] * T[(1, 1)] - T[(0, 1)] * T[(1, 0)]);
+	let tr = real(T[(0, 0)] + T[(1, 1)]);
+	if det < zero() {
+		return Err(MatFnError::NegativeEigenvalue);
+	}
+	let delta = sqrt(det);
+	let denom = tr + from_f64::<T::Real>(2.0) * delta;
+	if denom <= zero() {
+		if det == zero() {
+			return Err(MatFnError::Singular);
+		}
+		return Err(MatFnError::NegativeEigenvalue);
+	}
+	let scale = recip(sqrt(denom));
+
+	T[(0, 0)] = mul_real(T[(0, 0)] + from_real(delta), scale);
+	T[(1, 1)] = mul_real(T[(1, 1)] + from_real(delta), scale);
+	T[(0, 1)] = mul_real(T[(0, 1)], scale);
+	T[(1, 0)] = mul_real(T[(1, 0)], scale);
+
+	Ok(())
+}
+
+/// overwrites the upper quasi-triangular matrix $T$ with its principal square root, which is also
+/// upper quasi-triangular
+#[math]
+pub(crate) fn sqrt_quasi_triangular_in_place<T: ComplexField>(T: MatMut<'_, T>, par: Par) -> Result<(), MatFnError> {
+	let n = T.nrows();
+	let mut T = T;
+
+	let mut j = 0;
+	while j < n {
+		let q = block_size_at(T.rb(), j);
+		sqrt_diagonal_block(T.rb_mut().submatrix_mut(j, j, q, q))?;
+
+		// the blocks of the column are computed from the bottom up, since
+		// $R_{ii} R_{ij} + R_{ij} R_{jj} = T_{ij} - \sum_{i < k < j} R_{ik} R_{kj}$
+		let mut i_end = j;
+		while i_end > 0 {
+			let p = if i_end >= 2 && T[(i_end - 1, i_end - 2)] != zero() { 2 } else { 1 };
+			let i = i_end - p;
+
+			let (left, right) = T.rb_mut().split_at_col_mut(j);
+			let (top, bot) = right.split_at_row_mut(i_end);
+			let mut X = top.submatrix_mut(i, 0, p, q);
+
+			linalg::matmul::matmul(
+				X.rb_mut(),
+				Accum::Add,
+				left.rb().submatrix(i, i_end, p, j - i_end),
+				bot.rb().submatrix(0, 0, j - i_end, q),
+				-one::<T>(),
+				par,
+			);
+			solve_small_sylvester(left.rb().submatrix(i, i, p, p), bot.rb().submatrix(j - i_end, 0, q, q), X).map_err(|_| MatFnError::Singular)?;
+
+			i_end = i;
+		}
+
+		j += q;
+	}
+
+	Ok(())
+}
+
+/// computes the size and alignment of the workspace required to compute a matrix's principal
+/// square root
+pub fn sqrtm_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	temp_mat_scratch::<T>(dim, dim)
+		.array(2)
+		.and(schur_in_place_scratch::<T>(dim, par).or(schur_back_transform_scratch::<T>(dim, par)))
+}
+
+/// computes the principal square root $X$ of the matrix $A$, such that $X^2 = A$ and the
+/// eigenvalues of $X$ have nonnegative real parts, and stores the result in `out`
+///
+/// the square root is computed from the schur form of $A$. if `T` is real, the real schur form is
+/// used, and an error is returned if $A$ has a negative real eigenvalue
+#[math]
+#[track_caller]
+pub fn sqrtm<T: ComplexField>(out: MatMut<'_, T>, A: MatRef<'_, T>, par: Par, stack: &mut MemStack) -> Result<(), MatFnError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, out.nrows() == n, out.ncols() == n));
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	let (mut T, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut T = T.as_mat_mut();
+	let mut Z = Z.as_mat_mut();
+
+	T.copy_from(A);
+	schur_in_place(T.rb_mut(), Z.rb_mut(), par, stack).map_err(|_| MatFnError::NoConvergence)?;
+	sqrt_quasi_triangular_in_place(T.rb_mut(), par)?;
+	schur_back_transform(out, Z.rb(), T.rb(), par, stack);
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	#[test]
+	fn test_sqrtm_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let B = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.rand::<Mat<f64>>(rng);
+			// eigenvalues of a random matrix shifted away from the negative real axis
+			let A = &B + Mat::<f64>::identity(n, n) * Scale(2.0 * (n as f64).sqrt() + 1.0);
+
+			let mut X = Mat::zeros(n, n);
+			sqrtm(
+				X.as_mut(),
+				A.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(sqrtm_scratch::<f64>(n, Par::Seq))),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64 + 1.0));
+			assert!(&X * &X ~ A);
+		}
+	}
+
+	#[test]
+	fn test_sqrtm_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let A = CwiseMatDistribution {
+				nrows: n,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			}
+			.rand::<Mat<c64>>(rng);
+
+			let mut X = Mat::zeros(n, n);
+			sqrtm(
+				X.as_mut(),
+				A.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(sqrtm_scratch::<c64>(n, Par::Seq))),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64 + 1.0));
+			assert!(&X * &X ~ A);
+
+			// the principal square root has eigenvalues in the right half plane
+			for s in X.eigenvalues().unwrap() {
+				assert!(s.re >= 0.0);
+			}
+		}
+	}
+
+	#[test]
+	fn test_sqrtm_negative_eigenvalue() {
+		let A = crate::mat![[-1.0, 0.0], [0.0, 4.0f64]];
+		let mut X = Mat::zeros(2, 2);
+		assert!(
+			sqrtm(
+				X.as_mut(),
+				A.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(sqrtm_scratch::<f64>(2, Par::Seq)))
+			) == Err(MatFnError::NegativeEigenvalue)
+		);
+
+		let A = crate::mat![[c64::new(-1.0, 0.0), c64::new(0.0, 0.0)], [c64::new(0.0, 0.0), c64::new(4.0, 0.0)]];
+		let mut X = Mat::zeros(2, 2);
+		sqrtm(
+			X.as_mut(),
+			A.as_ref(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(sqrtm_scratch::<c64>(2, Par::Seq))),
+		)
+		.unwrap();
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 8.0);
+		assert!(&X * &X ~ A);
+	}
+}
@@ -195,8 +195,13 @@ pub mod cholesky;
 pub mod lu;
 pub mod qr;
 
+pub mod equilibrate;
 pub mod evd;
+pub mod gevd;
+pub mod matfn;
+pub mod riccati;
 pub mod svd;
+pub mod sylvester;
 
 mod mat_ops;
 
@@ -0,0 +1,753 @@
+//! low level implementation of solvers for the continuous and discrete algebraic riccati
+//! equations.
+//!
+//! the continuous algebraic riccati equation (care) is
+//!
+//! $$A^H X + X A - X B R^{-1} B^H X + Q = 0$$
+//!
+//! and the discrete algebraic riccati equation (dare) is
+//!
+//! $$A^H X A - X - A^H X B (R + B^H X B)^{-1} B^H X A + Q = 0$$
+//!
+//! where $Q$ is self-adjoint and $R$ is self-adjoint positive definite
+//!
+//! both equations are solved with the schur method: the stable invariant subspace of the
+//! associated hamiltonian matrix (resp. the stable deflating subspace of the associated symplectic
+//! pencil) is computed by reordering its schur (resp. generalized schur) form, and the stabilizing
+//! solution $X$ is recovered from its basis $\begin{bmatrix} U_1 \\ U_2 \end{bmatrix}$ as
+//! $X = U_2 U_1^{-1}$
+
+use crate::assert;
+use crate::internal_prelude::*;
+use linalg::cholesky::llt::factor::LltError;
+use linalg::evd::schur::{block_eigenvalue_at, block_size_at, reorder_schur, schur_in_place, schur_in_place_scratch};
+use linalg::gevd::hessenberg_triangular::{hessenberg_triangular_in_place, hessenberg_triangular_in_place_scratch};
+use linalg::jacobi::JacobiRotation;
+use linalg::matfn::{lu_solve_in_place, lu_solve_in_place_scratch};
+
+/// riccati equation error
+#[derive(Copy, Clone, Debug)]
+pub enum RiccatiError {
+	/// the schur decomposition did not converge, or its reordering failed
+	NoConvergence,
+	/// $R$ is not positive definite
+	NonPositiveDefinite(LltError),
+	/// the equation has no stabilizing solution
+	NoStabilizingSolution,
+}
+
+impl core::fmt::Display for RiccatiError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for RiccatiError {}
+
+/// computes $G = B R^{-1} B^H$ and stores the result in `G`, using the cholesky decomposition of
+/// $R$
+#[math]
+fn gain_matrix<T: ComplexField>(G: MatMut<'_, T>, B: MatRef<'_, T>, R: MatRef<'_, T>, par: Par, stack: &mut MemStack) -> Result<(), RiccatiError> {
+	let n = B.nrows();
+	let m = B.ncols();
+
+	let (mut L, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, m, stack) };
+	let (mut W, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, n, stack) };
+	let mut L = L.as_mat_mut();
+	let mut W = W.as_mat_mut();
+
+	L.copy_from_triangular_lower(R);
+	linalg::cholesky::llt::factor::cholesky_in_place(L.rb_mut(), default(), par, stack, default()).map_err(RiccatiError::NonPositiveDefinite)?;
+
+	// $G = (L^{-1} B^H)^H (L^{-1} B^H)$
+	W.copy_from(B.adjoint());
+	linalg::triangular_solve::solve_lower_triangular_in_place(L.rb(), W.rb_mut(), par);
+	linalg::matmul::matmul(G, Accum::Replace, W.rb().adjoint(), W.rb(), one(), par);
+
+	Ok(())
+}
+
+fn gain_matrix_scratch<T: ComplexField>(state_dim: usize, input_dim: usize, par: Par) -> StackReq {
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(input_dim, input_dim),
+		temp_mat_scratch::<T>(input_dim, state_dim),
+		linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(input_dim, par, default()),
+	])
+}
+
+/// returns `true` if the $LU$ factors stored in `LU` are those of a singular matrix
+#[math]
+fn is_lu_singular<T: ComplexField>(LU: MatRef<'_, T>) -> bool {
+	for i in 0..LU.nrows() {
+		let d = copy(LU[(i, i)]);
+		if d == zero() || !is_finite(d) {
+			return true;
+		}
+	}
+	false
+}
+
+fn solution_from_basis_scratch<T: ComplexField>(state_dim: usize, par: Par) -> StackReq {
+	let n = state_dim;
+	temp_mat_scratch::<T>(n, n).array(2).and(lu_solve_in_place_scratch::<T>(n, n, par))
+}
+
+/// computes $X = U_2 U_1^{-1}$ from the basis $\begin{bmatrix} U_1 \\ U_2 \end{bmatrix}$ of the
+/// stable subspace, stored in the $n$ leading columns of `Z`
+#[math]
+fn solution_from_basis<T: ComplexField>(X: MatMut<'_, T>, Z: MatRef<'_, T>, par: Par, stack: &mut MemStack) -> Result<(), RiccatiError> {
+	let n = X.nrows();
+	let mut X = X;
+
+	// $X U_1 = U_2$, so $U_1^H X^H = U_2^H$
+	let (mut U1, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut Y, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut U1 = U1.as_mat_mut();
+	let mut Y = Y.as_mat_mut();
+
+	U1.copy_from(Z.submatrix(0, 0, n, n).adjoint());
+	Y.copy_from(Z.submatrix(n, 0, n, n).adjoint());
+	lu_solve_in_place(U1.rb_mut(), Y.rb_mut(), par, stack);
+	if is_lu_singular(U1.rb()) {
+		return Err(RiccatiError::NoStabilizingSolution);
+	}
+
+	// the solution is self-adjoint, so the rounding errors are averaged out
+	let half = from_f64::<T::Real>(0.5);
+	for j in 0..n {
+		for i in 0..n {
+			X[(i, j)] = mul_real(conj(Y[(j, i)]) + Y[(i, j)], half);
+		}
+	}
+
+	Ok(())
+}
+
+fn stable_subspace_scratch<T: ComplexField>(state_dim: usize, par: Par) -> StackReq {
+	let n = state_dim;
+	StackReq::any_of(&[schur_in_place_scratch::<T>(2 * n, par), solution_from_basis_scratch::<T>(n, par)])
+}
+
+/// computes the schur form of the $2n \times 2n$ matrix $H$, selects the $n$ stable eigenvalues,
+/// and computes $X = U_2 U_1^{-1}$ from the basis of the corresponding invariant subspace
+#[math]
+fn stable_subspace<T: ComplexField>(
+	X: MatMut<'_, T>,
+	closed_loop_eigenvalues: Option<DiagMut<'_, Complex<T::Real>>>,
+	H: MatMut<'_, T>,
+	Z: MatMut<'_, T>,
+	is_stable: impl FnMut(Complex<T::Real>) -> bool,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), RiccatiError> {
+	let n = X.nrows();
+	let mut H = H;
+	let mut Z = Z;
+
+	schur_in_place(H.rb_mut(), Z.rb_mut(), par, stack).map_err(|_| RiccatiError::NoConvergence)?;
+	let dim = reorder_schur(H.rb_mut(), Some(Z.rb_mut()), is_stable).map_err(|_| RiccatiError::NoConvergence)?;
+	if dim != n {
+		return Err(RiccatiError::NoStabilizingSolution);
+	}
+
+	if let Some(eigenvalues) = closed_loop_eigenvalues {
+		let mut eigenvalues = eigenvalues.column_vector_mut();
+		let H = H.rb().submatrix(0, 0, n, n);
+		let mut i = 0;
+		while i < n {
+			let bs = block_size_at(H, i);
+			let s = block_eigenvalue_at(H, i, bs);
+			if bs == 2 {
+				eigenvalues[i + 1] = Complex::new(copy(s.re), -s.im);
+			}
+			eigenvalues[i] = s;
+			i += bs;
+		}
+	}
+
+	solution_from_basis(X, Z.rb(), par, stack)
+}
+
+/// computes the size and alignment of the workspace required to solve a continuous algebraic
+/// riccati equation
+pub fn care_scratch<T: ComplexField>(state_dim: usize, input_dim: usize, par: Par) -> StackReq {
+	let n = state_dim;
+	temp_mat_scratch::<T>(2 * n, 2 * n).array(2).and(StackReq::any_of(&[
+		gain_matrix_scratch::<T>(n, input_dim, par),
+		stable_subspace_scratch::<T>(n, par),
+	]))
+}
+
+/// computes the stabilizing solution $X$ of the continuous algebraic riccati equation
+/// $A^H X + X A - X B R^{-1} B^H X + Q = 0$
+///
+/// the eigenvalues of the closed-loop matrix $A - B R^{-1} B^H X$, which all have negative real
+/// parts, are stored in `closed_loop_eigenvalues` if it is provided
+///
+/// only the lower triangular half of $R$ is accessed
+#[math]
+#[track_caller]
+pub fn care<T: ComplexField>(
+	X: MatMut<'_, T>,
+	closed_loop_eigenvalues: Option<DiagMut<'_, Complex<T::Real>>>,
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	Q: MatRef<'_, T>,
+	R: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), RiccatiError> {
+	let n = A.nrows();
+	let m = B.ncols();
+	assert!(all(
+		A.ncols() == n,
+		B.nrows() == n,
+		Q.nrows() == n,
+		Q.ncols() == n,
+		R.nrows() == m,
+		R.ncols() == m,
+		X.nrows() == n,
+		X.ncols() == n,
+	));
+	if let Some(s) = closed_loop_eigenvalues.rb() {
+		assert!(s.dim() == n);
+	}
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	let (mut H, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+	let mut H = H.as_mat_mut();
+	let Z = Z.as_mat_mut();
+
+	// $H = \begin{bmatrix} A & -G \\ -Q & -A^H \end{bmatrix}$
+	{
+		let (mut H11, mut H12, mut H21, mut H22) = H.rb_mut().split_at_mut(n, n);
+		gain_matrix(H12.rb_mut(), B, R, par, stack)?;
+		z!(H12.rb_mut()).for_each(|uz!(h)| *h = -*h);
+		H11.copy_from(A);
+		z!(H21.rb_mut(), Q).for_each(|uz!(h, q)| *h = -*q);
+		H22.copy_from(A.adjoint());
+		z!(H22.rb_mut()).for_each(|uz!(h)| *h = -*h);
+	}
+
+	stable_subspace(X, closed_loop_eigenvalues, H, Z, |s| s.re < zero(), par, stack)
+}
+
+/// swaps the adjacent eigenvalues at positions `j` and `j + 1` of the upper triangular pencil
+/// $(S, P)$, and updates the right schur vectors $Z$
+#[math]
+fn pencil_swap<T: ComplexField>(S: MatMut<'_, T>, P: MatMut<'_, T>, Z: MatMut<'_, T>, j: usize) {
+	let mut S = S;
+	let mut P = P;
+	let j0 = j;
+	let j1 = j + 1;
+
+	// the first column of the right rotation spans the eigenvector of the $2 \times 2$ pencil
+	// associated with its second eigenvalue $S_{11} / P_{11}$
+	let f0 = P[(j1, j1)] * S[(j0, j0)] - S[(j1, j1)] * P[(j0, j0)];
+	let f1 = P[(j1, j1)] * S[(j0, j1)] - S[(j1, j1)] * P[(j0, j1)];
+	let (rot, _) = JacobiRotation::rotg(f1, -f0);
+	rot.apply_on_the_right_in_place(S.rb_mut().get_mut(..j1 + 1, ..).two_cols_mut(j0, j1));
+	rot.apply_on_the_right_in_place(P.rb_mut().get_mut(..j1 + 1, ..).two_cols_mut(j0, j1));
+	rot.apply_on_the_right_in_place(Z.two_cols_mut(j0, j1));
+
+	// the first columns of both blocks are now parallel, and the larger one is more accurate
+	let (rot, _) = if abs2(S[(j0, j0)]) + abs2(S[(j1, j0)]) >= abs2(P[(j0, j0)]) + abs2(P[(j1, j0)]) {
+		JacobiRotation::rotg(copy(S[(j0, j0)]), copy(S[(j1, j0)]))
+	} else {
+		JacobiRotation::rotg(copy(P[(j0, j0)]), copy(P[(j1, j0)]))
+	};
+	rot.adjoint()
+		.apply_on_the_left_in_place(S.rb_mut().get_mut(.., j0..).two_rows_mut(j0, j1));
+	rot.adjoint()
+		.apply_on_the_left_in_place(P.rb_mut().get_mut(.., j0..).two_rows_mut(j0, j1));
+	S[(j1, j0)] = zero();
+	P[(j1, j0)] = zero();
+}
+
+fn stable_deflating_subspace_scratch<T: ComplexField>(state_dim: usize, par: Par) -> StackReq {
+	let n = state_dim;
+	StackReq::any_of(&[
+		hessenberg_triangular_in_place_scratch::<T>(2 * n, par),
+		temp_mat_scratch::<T>(2 * n, 1).array(2),
+		solution_from_basis_scratch::<T>(n, par),
+	])
+}
+
+/// computes the generalized schur form of the $2n \times 2n$ pencil $(S, P)$, selects the $n$
+/// eigenvalues inside the unit circle, and computes $X = U_2 U_1^{-1}$ from the basis of the
+/// corresponding deflating subspace
+///
+/// `T` must be a complex type, so that the generalized schur form is triangular
+#[math]
+fn stable_deflating_subspace<T: ComplexField>(
+	X: MatMut<'_, T>,
+	closed_loop_eigenvalues: Option<DiagMut<'_, Complex<T::Real>>>,
+	S: MatMut<'_, T>,
+	P: MatMut<'_, T>,
+	Z: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), RiccatiError> {
+	let n = X.nrows();
+	let mut S = S;
+	let mut P = P;
+	let mut Z = Z;
+
+	hessenberg_triangular_in_place(S.rb_mut(), P.rb_mut(), None, Some(Z.rb_mut()), par, stack);
+	{
+		let (mut alpha, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 1, stack) };
+		let (mut beta, _) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 1, stack) };
+		linalg::gevd::qz::complex_qz(
+			true,
+			S.rb_mut(),
+			P.rb_mut(),
+			None,
+			Some(Z.rb_mut()),
+			alpha.as_mat_mut().col_mut(0),
+			beta.as_mat_mut().col_mut(0),
+		)
+		.map_err(|_| RiccatiError::NoConvergence)?;
+	}
+
+	// an eigenvalue $\alpha / \beta$ is stable if $|\alpha| < |\beta|$, which excludes the
+	// infinite eigenvalues
+	let mut dim = 0;
+	for k in 0..2 * n {
+		if abs2(S[(k, k)]) < abs2(P[(k, k)]) {
+			for j in (dim..k).rev() {
+				pencil_swap(S.rb_mut(), P.rb_mut(), Z.rb_mut(), j);
+			}
+			dim += 1;
+		}
+	}
+	if dim != n {
+		return Err(RiccatiError::NoStabilizingSolution);
+	}
+
+	if let Some(eigenvalues) = closed_loop_eigenvalues {
+		let mut eigenvalues = eigenvalues.column_vector_mut();
+		for i in 0..n {
+			let s = S[(i, i)] * recip(P[(i, i)]);
+			eigenvalues[i] = Complex::new(real(s), imag(s));
+		}
+	}
+
+	solution_from_basis(X, Z.rb(), par, stack)
+}
+
+/// computes the size and alignment of the workspace required to solve a discrete algebraic
+/// riccati equation
+pub fn dare_scratch<T: ComplexField>(state_dim: usize, input_dim: usize, par: Par) -> StackReq {
+	let n = state_dim;
+	let pencil = if try_const! { T::IS_REAL } {
+		temp_mat_scratch::<Complex<T::Real>>(2 * n, 2 * n)
+			.array(3)
+			.and(temp_mat_scratch::<Complex<T::Real>>(n, n))
+			.and(stable_deflating_subspace_scratch::<Complex<T::Real>>(n, par))
+	} else {
+		temp_mat_scratch::<T>(2 * n, 2 * n)
+			.array(3)
+			.and(stable_deflating_subspace_scratch::<T>(n, par))
+	};
+	temp_mat_scratch::<T>(n, n).and(StackReq::any_of(&[gain_matrix_scratch::<T>(n, input_dim, par), pencil]))
+}
+
+/// fills the symplectic pencil
+/// $$\left(\begin{bmatrix} A & 0 \\ -Q & I \end{bmatrix},
+/// \begin{bmatrix} I & G \\ 0 & A^H \end{bmatrix}\right)$$
+/// converting the entries with `f`
+#[math]
+fn symplectic_pencil<T: ComplexField, C: ComplexField>(
+	S: MatMut<'_, C>,
+	P: MatMut<'_, C>,
+	A: MatRef<'_, T>,
+	G: MatRef<'_, T>,
+	Q: MatRef<'_, T>,
+	f: impl Fn(T) -> C,
+) {
+	let n = A.nrows();
+	let (mut S11, mut S12, mut S21, mut S22) = S.split_at_mut(n, n);
+	let (mut P11, mut P12, mut P21, mut P22) = P.split_at_mut(n, n);
+	for j in 0..n {
+		for i in 0..n {
+			S11[(i, j)] = f(copy(A[(i, j)]));
+			S12[(i, j)] = zero();
+			S21[(i, j)] = f(-Q[(i, j)]);
+			S22[(i, j)] = zero();
+
+			P11[(i, j)] = zero();
+			P12[(i, j)] = f(copy(G[(i, j)]));
+			P21[(i, j)] = zero();
+			P22[(i, j)] = f(conj(A[(j, i)]));
+		}
+		S22[(j, j)] = one();
+		P11[(j, j)] = one();
+	}
+}
+
+/// computes the stabilizing solution $X$ of the discrete algebraic riccati equation
+/// $A^H X A - X - A^H X B (R + B^H X B)^{-1} B^H X A + Q = 0$
+///
+/// the eigenvalues of the closed-loop matrix $A - B (R + B^H X B)^{-1} B^H X A$, which all lie
+/// inside the unit circle, are stored in `closed_loop_eigenvalues` if it is provided
+///
+/// the equation is solved with the generalized schur form of the associated symplectic pencil, so
+/// $A$ may be singular. only the lower triangular half of $R$ is accessed
+#[math]
+#[track_caller]
+pub fn dare<T: ComplexField>(
+	X: MatMut<'_, T>,
+	closed_loop_eigenvalues: Option<DiagMut<'_, Complex<T::Real>>>,
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	Q: MatRef<'_, T>,
+	R: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), RiccatiError> {
+	let n = A.nrows();
+	let m = B.ncols();
+	assert!(all(
+		A.ncols() == n,
+		B.nrows() == n,
+		Q.nrows() == n,
+		Q.ncols() == n,
+		R.nrows() == m,
+		R.ncols() == m,
+		X.nrows() == n,
+		X.ncols() == n,
+	));
+	if let Some(s) = closed_loop_eigenvalues.rb() {
+		assert!(s.dim() == n);
+	}
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	let mut X = X;
+	let (mut G, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut G = G.as_mat_mut();
+	gain_matrix(G.rb_mut(), B, R, par, stack)?;
+	let G = G.rb();
+
+	// the generalized schur form of a real pencil is only quasi-triangular, so it is computed in
+	// complex arithmetic instead
+	if try_const! { T::IS_REAL } {
+		let (mut S, stack) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(2 * n, 2 * n, stack) };
+		let (mut P, stack) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(2 * n, 2 * n, stack) };
+		let (mut Z, stack) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(2 * n, 2 * n, stack) };
+		let (mut Y, stack) = unsafe { temp_mat_uninit::<Complex<T::Real>, _, _>(n, n, stack) };
+		let mut Y = Y.as_mat_mut();
+
+		symplectic_pencil(S.as_mat_mut(), P.as_mat_mut(), A, G, Q, |x| Complex::new(real(x), zero()));
+		stable_deflating_subspace(
+			Y.rb_mut(),
+			closed_loop_eigenvalues,
+			S.as_mat_mut(),
+			P.as_mat_mut(),
+			Z.as_mat_mut(),
+			par,
+			stack,
+		)?;
+		z!(X.rb_mut(), Y.rb()).for_each(|uz!(x, y)| *x = from_real(real(*y)));
+		Ok(())
+	} else {
+		let (mut S, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+		let (mut P, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+		let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(2 * n, 2 * n, stack) };
+
+		symplectic_pencil(S.as_mat_mut(), P.as_mat_mut(), A, G, Q, |x| x);
+		stable_deflating_subspace(X, closed_loop_eigenvalues, S.as_mat_mut(), P.as_mat_mut(), Z.as_mat_mut(), par, stack)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	fn random_real(m: usize, n: usize, rng: &mut StdRng) -> Mat<f64> {
+		CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng)
+	}
+
+	fn random_cplx(m: usize, n: usize, rng: &mut StdRng) -> Mat<c64> {
+		CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng)
+	}
+
+	#[test]
+	fn test_care_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (n, m) in [(0, 1), (1, 1), (2, 1), (4, 2), (10, 3), (33, 5)] {
+			let A = random_real(n, n, rng);
+			let B = random_real(n, m, rng);
+			let C = random_real(n, n, rng);
+			let Q = &C * C.transpose() + Mat::<f64>::identity(n, n);
+			let D = random_real(m, m, rng);
+			let R = &D * D.transpose() + Mat::<f64>::identity(m, m);
+
+			let mut X = Mat::zeros(n, n);
+			let mut s = Diag::<c64>::zeros(n);
+			care(
+				X.as_mut(),
+				Some(s.as_mut()),
+				A.as_ref(),
+				B.as_ref(),
+				Q.as_ref(),
+				R.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(care_scratch::<f64>(n, m, Par::Seq))),
+			)
+			.unwrap();
+
+			let K = R.partial_piv_lu().solve(B.transpose()) * &X;
+			let res = A.transpose() * &X + &X * &A - &X * &B * &K + &Q;
+
+			let scale = (X.norm_l2() + 1.0) * (A.norm_l2() + B.norm_l2() * K.norm_l2() + 1.0);
+			let approx_eq = CwiseMat(ApproxEq {
+				abs_tol: 1e-10 * scale,
+				rel_tol: 1e-10,
+			});
+			assert!(res ~ Mat::<f64>::zeros(n, n));
+			assert!(X.transpose().to_owned() ~ X);
+
+			// the closed-loop eigenvalues are stable, and match those of $A - B K$
+			let mut expected = (&A - &B * &K).eigenvalues().unwrap();
+			let mut actual = s.column_vector().iter().copied().collect::<Vec<_>>();
+			for s in &actual {
+				assert!(s.re < 0.0);
+			}
+			let cmp = |a: &c64, b: &c64| a.re.partial_cmp(&b.re).unwrap().then(a.im.partial_cmp(&b.im).unwrap());
+			expected.sort_by(cmp);
+			actual.sort_by(cmp);
+			for (a, e) in core::iter::zip(&actual, &expected) {
+				assert!((a - e).norm() < 1e-8 * scale);
+			}
+		}
+	}
+
+	#[test]
+	fn test_care_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (n, m) in [(1, 1), (2, 1), (4, 2), (10, 3), (33, 5)] {
+			let A = random_cplx(n, n, rng);
+			let B = random_cplx(n, m, rng);
+			let C = random_cplx(n, n, rng);
+			let Q = &C * C.adjoint() + Mat::<c64>::identity(n, n);
+			let R = Mat::<c64>::identity(m, m);
+
+			let mut X = Mat::zeros(n, n);
+			care(
+				X.as_mut(),
+				None,
+				A.as_ref(),
+				B.as_ref(),
+				Q.as_ref(),
+				R.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(care_scratch::<c64>(n, m, Par::Seq))),
+			)
+			.unwrap();
+
+			let res = A.adjoint() * &X + &X * &A - &X * &B * B.adjoint() * &X + &Q;
+
+			let scale = (X.norm_l2() + 1.0) * (A.norm_l2() + B.norm_l2() * B.norm_l2() * X.norm_l2() + 1.0);
+			let approx_eq = CwiseMat(ApproxEq {
+				abs_tol: 1e-10 * scale,
+				rel_tol: 1e-10,
+			});
+			assert!(res ~ Mat::<c64>::zeros(n, n));
+			assert!(X.adjoint().to_owned() ~ X);
+
+			for s in (&A - &B * B.adjoint() * &X).eigenvalues().unwrap() {
+				assert!(s.re < 0.0);
+			}
+		}
+	}
+
+	#[test]
+	fn test_dare() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (n, m) in [(0, 1), (1, 1), (2, 1), (4, 2), (10, 3), (33, 5)] {
+			let A = random_real(n, n, rng) * Scale(1.0 / (n as f64 + 1.0).sqrt());
+			let B = random_real(n, m, rng);
+			let C = random_real(n, n, rng);
+			let Q = &C * C.transpose() + Mat::<f64>::identity(n, n);
+			let D = random_real(m, m, rng);
+			let R = &D * D.transpose() + Mat::<f64>::identity(m, m);
+
+			let mut X = Mat::zeros(n, n);
+			let mut s = Diag::<c64>::zeros(n);
+			dare(
+				X.as_mut(),
+				Some(s.as_mut()),
+				A.as_ref(),
+				B.as_ref(),
+				Q.as_ref(),
+				R.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(dare_scratch::<f64>(n, m, Par::Seq))),
+			)
+			.unwrap();
+
+			let K = (&R + B.transpose() * &X * &B).partial_piv_lu().solve(B.transpose() * &X * &A);
+			let res = A.transpose() * &X * &A - &X - A.transpose() * &X * &B * &K + &Q;
+
+			let scale = (X.norm_l2() + 1.0) * (A.norm_l2() + B.norm_l2() * K.norm_l2() + 1.0).powi(2);
+			let approx_eq = CwiseMat(ApproxEq {
+				abs_tol: 1e-10 * scale,
+				rel_tol: 1e-10,
+			});
+			assert!(res ~ Mat::<f64>::zeros(n, n));
+			assert!(X.transpose().to_owned() ~ X);
+
+			for s in s.column_vector().iter() {
+				assert!(s.norm() < 1.0);
+			}
+			for s in (&A - &B * &K).eigenvalues().unwrap() {
+				assert!(s.norm() < 1.0);
+			}
+
+			let A = random_cplx(n, n, rng) * Scale(c64::new(1.0 / (n as f64 + 1.0).sqrt(), 0.0));
+			let B = random_cplx(n, m, rng);
+			let C = random_cplx(n, n, rng);
+			let Q = &C * C.adjoint() + Mat::<c64>::identity(n, n);
+			let R = Mat::<c64>::identity(m, m);
+
+			let mut X = Mat::zeros(n, n);
+			dare(
+				X.as_mut(),
+				None,
+				A.as_ref(),
+				B.as_ref(),
+				Q.as_ref(),
+				R.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(dare_scratch::<c64>(n, m, Par::Seq))),
+			)
+			.unwrap();
+
+			let K = (&R + B.adjoint() * &X * &B).partial_piv_lu().solve(B.adjoint() * &X * &A);
+			let res = A.adjoint() * &X * &A - &X - A.adjoint() * &X * &B * &K + &Q;
+
+			let scale = (X.norm_l2() + 1.0) * (A.norm_l2() + B.norm_l2() * K.norm_l2() + 1.0).powi(2);
+			let approx_eq = CwiseMat(ApproxEq {
+				abs_tol: 1e-10 * scale,
+				rel_tol: 1e-10,
+			});
+			assert!(res ~ Mat::<c64>::zeros(n, n));
+			assert!(X.adjoint().to_owned() ~ X);
+		}
+	}
+
+	#[test]
+	fn test_dare_singular() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (n, m) in [(1, 1), (2, 1), (5, 2), (12, 3)] {
+			// $A$ has a zero eigenvalue, so the symplectic pencil has an infinite eigenvalue
+			let mut A = random_real(n, n, rng) * Scale(1.0 / (n as f64 + 1.0).sqrt());
+			A.col_mut(0).fill(0.0);
+			let B = random_real(n, m, rng);
+			let Q = Mat::<f64>::identity(n, n);
+			let R = Mat::<f64>::identity(m, m);
+
+			let mut X = Mat::zeros(n, n);
+			let mut s = Diag::<c64>::zeros(n);
+			dare(
+				X.as_mut(),
+				Some(s.as_mut()),
+				A.as_ref(),
+				B.as_ref(),
+				Q.as_ref(),
+				R.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(dare_scratch::<f64>(n, m, Par::Seq))),
+			)
+			.unwrap();
+
+			let K = (&R + B.transpose() * &X * &B).partial_piv_lu().solve(B.transpose() * &X * &A);
+			let res = A.transpose() * &X * &A - &X - A.transpose() * &X * &B * &K + &Q;
+
+			let scale = (X.norm_l2() + 1.0) * (A.norm_l2() + B.norm_l2() * K.norm_l2() + 1.0).powi(2);
+			let approx_eq = CwiseMat(ApproxEq {
+				abs_tol: 1e-10 * scale,
+				rel_tol: 1e-10,
+			});
+			assert!(res ~ Mat::<f64>::zeros(n, n));
+			assert!(X.transpose().to_owned() ~ X);
+
+			// the closed-loop eigenvalues are stable, and match those of $A - B K$
+			let expected = (&A - &B * &K).eigenvalues().unwrap();
+			for a in s.column_vector().iter() {
+				assert!(a.norm() < 1.0);
+				let dist = expected.iter().map(|e| (a - e).norm()).fold(f64::INFINITY, f64::min);
+				assert!(dist < 1e-8 * scale);
+			}
+		}
+
+		// nilpotent $A$
+		let A = crate::mat![[0.0, 1.0], [0.0, 0.0f64]];
+		let B = crate::mat![[0.0], [1.0f64]];
+		let Q = Mat::<f64>::identity(2, 2);
+		let R = crate::mat![[1.0f64]];
+
+		let mut X = Mat::zeros(2, 2);
+		dare(
+			X.as_mut(),
+			None,
+			A.as_ref(),
+			B.as_ref(),
+			Q.as_ref(),
+			R.as_ref(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(dare_scratch::<f64>(2, 1, Par::Seq))),
+		)
+		.unwrap();
+		let K = (&R + B.transpose() * &X * &B).partial_piv_lu().solve(B.transpose() * &X * &A);
+		let res = A.transpose() * &X * &A - &X - A.transpose() * &X * &B * &K + &Q;
+		assert!(res.norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_care_no_stabilizing_solution() {
+		// the unstable mode is neither controllable nor damped
+		let A = crate::mat![[1.0, 0.0], [0.0, -1.0f64]];
+		let B = crate::mat![[0.0], [1.0f64]];
+		let Q = Mat::<f64>::zeros(2, 2);
+		let R = crate::mat![[1.0f64]];
+
+		let mut X = Mat::zeros(2, 2);
+		let res = care(
+			X.as_mut(),
+			None,
+			A.as_ref(),
+			B.as_ref(),
+			Q.as_ref(),
+			R.as_ref(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(care_scratch::<f64>(2, 1, Par::Seq))),
+		);
+		assert!(matches!(res, Err(RiccatiError::NoStabilizingSolution)));
+	}
+}
@@ -1,9 +1,10 @@
 use crate::internal_prelude::*;
-use crate::{assert, get_global_parallelism};
+use crate::{assert, fx128, get_global_parallelism};
 use alloc::vec;
 use alloc::vec::Vec;
 use dyn_stack::MemBuffer;
 use faer_traits::{ComplexConj, math_utils};
+use linalg::equilibrate::{EquilibrationInfo, EquilibrationMethod};
 use linalg::svd::ComputeSvdVectors;
 
 pub use linalg::cholesky::ldlt::factor::LdltError;
@@ -32,6 +33,10 @@ pub trait SolveCore<T: ComplexField>: ShapeCore {
 pub trait SolveLstsqCore<T: ComplexField>: ShapeCore {
 	/// solves the equation `self × x = rhs` in the sense of least squares, implicitly conjugating
 	/// `self` if needed, and stores the result in the top rows of `rhs`
+	///
+	/// solvers that support underdetermined systems compute the minimum norm solution when `self`
+	/// has fewer rows than columns. in that case `rhs` must have as many rows as `self` has
+	/// columns, with the right-hand side stored in its top rows
 	fn solve_lstsq_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>);
 }
 /// dense linear system solver
@@ -43,6 +48,23 @@ pub trait DenseSolveCore<T: ComplexField>: SolveCore<T> {
 	fn inverse(&self) -> Mat<T>;
 }
 
+/// conversion between scalar types of different precision, used for mixed precision iterative
+/// refinement
+pub trait PrecisionCast<To> {
+	/// converts `self` to the target type, rounding if needed
+	fn precision_cast(&self) -> To;
+}
+/// matrix that can compute the residuals needed for iterative refinement
+pub trait RefinementMatrix<T: ComplexField> {
+	/// returns the number of rows of the matrix
+	fn nrows(&self) -> usize;
+	/// returns the number of columns of the matrix
+	fn ncols(&self) -> usize;
+	/// computes the residual $r = b - A x$ and the componentwise bound
+	/// $w = |A| |x| + |b|$
+	fn residual(&self, r: ColMut<'_, T>, w: ColMut<'_, T::Real>, x: ColRef<'_, T>, b: ColRef<'_, T>);
+}
+
 impl<S: ?Sized + ShapeCore> ShapeCore for &S {
 	#[inline]
 	fn nrows(&self) -> usize {
@@ -86,6 +108,100 @@ impl<T: ComplexField, S: ?Sized + DenseSolveCore<T>> DenseSolveCore<T> for &S {
 	}
 }
 
+impl<T: ComplexField> PrecisionCast<T> for T {
+	#[inline]
+	fn precision_cast(&self) -> T {
+		self.clone()
+	}
+}
+
+macro_rules! impl_precision_cast {
+	($from: ty, $to: ty, |$x: ident| $body: expr) => {
+		impl PrecisionCast<$to> for $from {
+			#[inline]
+			fn precision_cast(&self) -> $to {
+				let $x = *self;
+				$body
+			}
+		}
+
+		impl PrecisionCast<Complex<$to>> for Complex<$from> {
+			#[inline]
+			fn precision_cast(&self) -> Complex<$to> {
+				Complex::new(self.re.precision_cast(), self.im.precision_cast())
+			}
+		}
+	};
+}
+
+impl_precision_cast!(f32, f64, |x| x as f64);
+impl_precision_cast!(f64, f32, |x| x as f32);
+impl_precision_cast!(f64, fx128, |x| fx128::from(x));
+impl_precision_cast!(fx128, f64, |x| x.0);
+impl_precision_cast!(f32, fx128, |x| fx128::from(x as f64));
+impl_precision_cast!(fx128, f32, |x| x.0 as f32);
+
+impl<T: ComplexField> RefinementMatrix<T> for MatRef<'_, T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		(*self).nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		(*self).ncols()
+	}
+
+	#[math]
+	fn residual(&self, r: ColMut<'_, T>, w: ColMut<'_, T::Real>, x: ColRef<'_, T>, b: ColRef<'_, T>) {
+		let A = *self;
+		let mut r = r;
+		let mut w = w;
+
+		r.copy_from(b);
+		linalg::matmul::matmul(r.rb_mut().as_mat_mut(), Accum::Add, A, x.as_mat(), -one::<T>(), Par::Seq);
+
+		z!(w.rb_mut(), b).for_each(|uz!(w, b)| *w = abs(*b));
+		for j in 0..A.ncols() {
+			let xj = abs(x[j]);
+			z!(w.rb_mut(), A.col(j)).for_each(|uz!(w, a)| *w = *w + abs(*a) * xj);
+		}
+	}
+}
+
+/// iterative refinement parameters
+#[derive(Copy, Clone, Debug)]
+pub struct RefinementParams {
+	/// maximum number of refinement steps for each column of the right-hand side
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for RefinementParams {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			max_iters: 10,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// iterative refinement result
+#[derive(Clone, Debug)]
+pub struct RefinementInfo<T> {
+	/// componentwise relative backward error of each column of the solution,
+	/// $\max_i |b - A x|_i / (|A| |x| + |b|)_i$
+	pub backward_error: Vec<T>,
+	/// estimated bound on the relative forward error of each column of the solution,
+	/// $\|x - x_\text{true}\|_\infty / \|x\|_\infty$
+	pub forward_error: Vec<T>,
+	/// largest number of refinement steps performed for any column
+	pub iter_count: usize,
+}
+
 /// [`SolveCore`] extension trait
 pub trait Solve<T: ComplexField>: SolveCore<T> {
 	#[track_caller]
@@ -223,6 +339,46 @@ pub trait Solve<T: ComplexField>: SolveCore<T> {
 		self.rsolve_adjoint_in_place(&mut out);
 		out
 	}
+
+	#[track_caller]
+	/// solves $A x = b$ using the factorization stored in `self`, and improves the solution with
+	/// iterative refinement, where the residuals are computed in the (possibly higher) precision
+	/// of $A$ and $b$
+	///
+	/// `self` may be a factorization of $A$ in a lower precision, e.g. `f32` when $A$ is stored as
+	/// `f64`, or `f64` when $A$ is stored as [`fx128`](crate::fx128)
+	fn solve_refined_in_place<E: ComplexField + PrecisionCast<T>>(
+		&self,
+		A: impl RefinementMatrix<E>,
+		rhs: impl AsMatMut<T = E, Rows = usize>,
+		params: RefinementParams,
+	) -> RefinementInfo<E::Real>
+	where
+		T: PrecisionCast<E>,
+	{
+		solve_refined_imp(&self, &A, { rhs }.as_mat_mut().as_dyn_cols_mut(), params)
+	}
+
+	#[track_caller]
+	/// solves $A x = b$ using the factorization stored in `self`, and improves the solution with
+	/// iterative refinement
+	///
+	/// see [`Solve::solve_refined_in_place`]
+	fn solve_refined<E: ComplexField + PrecisionCast<T>, Rhs: AsMatRef<T = E, Rows = usize>>(
+		&self,
+		A: impl RefinementMatrix<E>,
+		rhs: Rhs,
+		params: RefinementParams,
+	) -> (Rhs::Owned, RefinementInfo<E::Real>)
+	where
+		T: PrecisionCast<E>,
+	{
+		let rhs = rhs.as_mat_ref();
+		let mut out = Rhs::Owned::zeros(rhs.nrows(), rhs.ncols());
+		out.as_mat_mut().copy_from(rhs);
+		let info = self.solve_refined_in_place(A, &mut out, params);
+		(out, info)
+	}
 }
 
 impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short, C>>> mat::generic::Mat<Inner> {
@@ -250,6 +406,12 @@ impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 		ColPivQr::new(self.rb())
 	}
 
+	#[track_caller]
+	/// returns the complete orthogonal decomposition of `self`
+	pub fn complete_orthogonal_decomposition(&self) -> CompleteOrthogonalDecomposition<C::Canonical> {
+		CompleteOrthogonalDecomposition::new(self.rb())
+	}
+
 	#[track_caller]
 	/// returns the svd of `self`
 	///
@@ -292,6 +454,20 @@ impl<C: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 		SelfAdjointEigen::new(self.rb(), side)
 	}
 
+	#[track_caller]
+	/// returns the eigendecomposition of the generalized problem given by `problem`, where $A$ is
+	/// `self`, assuming $A$ is self-adjoint and $B$ is self-adjoint positive definite
+	///
+	/// eigenvalues sorted in nondecreasing order
+	pub fn generalized_self_adjoint_eigen(
+		&self,
+		B: impl AsMatRef<T = C, Rows = usize, Cols = usize>,
+		side: Side,
+		problem: GeneralizedProblem,
+	) -> Result<GeneralizedSelfAdjointEigen<C::Canonical>, GeneralizedSelfAdjointEigenError> {
+		GeneralizedSelfAdjointEigen::new(self.rb(), B.as_mat_ref(), side, problem)
+	}
+
 	#[track_caller]
 	/// returns the eigenvalues of `self`, assuming it is self-adjoint
 	///
@@ -376,6 +552,21 @@ impl<C: Conjugate> MatRef<'_, C> {
 		}
 	}
 
+	#[track_caller]
+	fn generalized_eigen_imp(&self, B: MatRef<'_, C>) -> Result<GeneralizedEigen<Real<C>>, EvdError> {
+		if const { C::Canonical::IS_REAL } {
+			GeneralizedEigen::new_from_real(unsafe { crate::hacks::coerce(*self) }, unsafe { crate::hacks::coerce(B) })
+		} else if const { C::IS_CANONICAL } {
+			GeneralizedEigen::new(unsafe { crate::hacks::coerce::<_, MatRef<'_, Complex<Real<C>>>>(*self) }, unsafe {
+				crate::hacks::coerce::<_, MatRef<'_, Complex<Real<C>>>>(B)
+			})
+		} else {
+			GeneralizedEigen::new(unsafe { crate::hacks::coerce::<_, MatRef<'_, ComplexConj<Real<C>>>>(*self) }, unsafe {
+				crate::hacks::coerce::<_, MatRef<'_, ComplexConj<Real<C>>>>(B)
+			})
+		}
+	}
+
 	#[track_caller]
 	fn eigenvalues_imp(&self) -> Result<Vec<Complex<Real<C>>>, EvdError> {
 		let par = get_global_parallelism();
@@ -455,6 +646,12 @@ impl<T: Conjugate, Inner: for<'short> Reborrow<'short, Target = mat::Ref<'short,
 	pub fn eigenvalues(&self) -> Result<Vec<Complex<Real<T>>>, EvdError> {
 		self.rb().eigenvalues_imp()
 	}
+
+	/// returns the generalized eigendecomposition of the pencil $(A, B)$, where $A$ is `self`
+	#[track_caller]
+	pub fn generalized_eigen(&self, B: impl AsMatRef<T = T, Rows = usize, Cols = usize>) -> Result<GeneralizedEigen<Real<T>>, EvdError> {
+		self.rb().generalized_eigen_imp(B.as_mat_ref())
+	}
 }
 
 /// [`SolveLstsqCore`] extension trait
@@ -478,8 +675,8 @@ pub trait SolveLstsq<T: ComplexField>: SolveLstsqCore<T> {
 	/// solves $A x = b$ in the sense of least squares.
 	fn solve_lstsq<Rhs: AsMatRef<T = T, Rows = usize>>(&self, rhs: Rhs) -> Rhs::Owned {
 		let rhs = rhs.as_mat_ref();
-		let mut out = Rhs::Owned::zeros(rhs.nrows(), rhs.ncols());
-		out.as_mat_mut().copy_from(rhs);
+		let mut out = Rhs::Owned::zeros(Ord::max(rhs.nrows(), self.ncols()), rhs.ncols());
+		out.as_mat_mut().get_mut(..rhs.nrows(), ..).copy_from(rhs);
 		self.solve_lstsq_in_place(&mut out);
 		out.truncate(self.ncols(), rhs.ncols());
 		out
@@ -489,8 +686,8 @@ pub trait SolveLstsq<T: ComplexField>: SolveLstsqCore<T> {
 	/// solves $\bar A x = b$ in the sense of least squares.
 	fn solve_conjugate_lstsq<Rhs: AsMatRef<T = T, Rows = usize>>(&self, rhs: Rhs) -> Rhs::Owned {
 		let rhs = rhs.as_mat_ref();
-		let mut out = Rhs::Owned::zeros(rhs.nrows(), rhs.ncols());
-		out.as_mat_mut().copy_from(rhs);
+		let mut out = Rhs::Owned::zeros(Ord::max(rhs.nrows(), self.ncols()), rhs.ncols());
+		out.as_mat_mut().get_mut(..rhs.nrows(), ..).copy_from(rhs);
 		self.solve_conjugate_lstsq_in_place(&mut out);
 		out.truncate(self.ncols(), rhs.ncols());
 		out
@@ -503,6 +700,34 @@ impl<T: ComplexField, S: ?Sized + SolveCore<T>> Solve<T> for S {}
 impl<T: ComplexField, S: ?Sized + SolveLstsqCore<T>> SolveLstsq<T> for S {}
 impl<T: ComplexField, S: ?Sized + DenseSolveCore<T>> DenseSolve<T> for S {}
 
+/// self-adjoint-definite generalized eigenvalue problem type
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GeneralizedProblem {
+	/// $A x = \lambda B x$
+	Pencil,
+	/// $A B x = \lambda x$
+	AB,
+	/// $B A x = \lambda x$
+	BA,
+}
+
+/// error in the self-adjoint-definite generalized eigendecomposition
+#[derive(Copy, Clone, Debug)]
+pub enum GeneralizedSelfAdjointEigenError {
+	/// the matrix $B$ is not positive definite
+	Llt(LltError),
+	/// the eigendecomposition of the reduced problem did not converge
+	Evd(EvdError),
+}
+
+impl core::fmt::Display for GeneralizedSelfAdjointEigenError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for GeneralizedSelfAdjointEigenError {}
+
 /// $L L^\top$ decomposition
 #[derive(Clone, Debug)]
 pub struct Llt<T> {
@@ -542,6 +767,22 @@ pub struct FullPivLu<T> {
 	Q: Perm<usize>,
 }
 
+/// wrapper around an $LU$ decomposition of the equilibrated square matrix $R A C$, where $R$ and
+/// $C$ are diagonal scaling matrices
+///
+/// solutions are computed with respect to the original matrix $A$. it is returned by
+/// [`PartialPivLu::new_equilibrated`], [`FullPivLu::new_equilibrated`] and
+/// [`sparse::linalg::solvers::Lu::try_new_with_symbolic_equilibrated`](crate::sparse::linalg::solvers::Lu::try_new_with_symbolic_equilibrated).
+/// the $QR$ based solvers don't have an equilibrated variant, since the row scaling changes the
+/// solution of the least squares problem
+#[derive(Clone, Debug)]
+pub struct Equilibrated<T: ComplexField, S> {
+	inner: S,
+	row_scale: Col<T::Real>,
+	col_scale: Col<T::Real>,
+	info: EquilibrationInfo<T::Real>,
+}
+
 /// $QR$ decomposition
 #[derive(Clone, Debug)]
 pub struct Qr<T> {
@@ -559,6 +800,19 @@ pub struct ColPivQr<T> {
 	P: Perm<usize>,
 }
 
+/// complete orthogonal decomposition
+/// $$AP^\top = Q \begin{bmatrix} L & 0 \\ 0 & 0 \end{bmatrix} Z^H$$
+/// where $L$ is a lower triangular matrix whose dimension is the numerical rank of $A$
+#[derive(Clone, Debug)]
+pub struct CompleteOrthogonalDecomposition<T> {
+	Q_basis: Mat<T>,
+	Q_coeff: Mat<T>,
+	L: Mat<T>,
+	Z_basis: Mat<T>,
+	Z_coeff: Mat<T>,
+	P: Perm<usize>,
+}
+
 /// svd decomposition (either full or thin)
 #[derive(Clone, Debug)]
 pub struct Svd<T> {
@@ -574,6 +828,13 @@ pub struct SelfAdjointEigen<T> {
 	S: Diag<T>,
 }
 
+/// self-adjoint-definite generalized eigendecomposition
+#[derive(Clone, Debug)]
+pub struct GeneralizedSelfAdjointEigen<T> {
+	U: Mat<T>,
+	S: Diag<T>,
+}
+
 /// eigendecomposition
 #[derive(Clone, Debug)]
 pub struct Eigen<T> {
@@ -581,6 +842,15 @@ pub struct Eigen<T> {
 	S: Diag<Complex<T>>,
 }
 
+/// generalized eigendecomposition
+#[derive(Clone, Debug)]
+pub struct GeneralizedEigen<T> {
+	U_left: Mat<Complex<T>>,
+	U_right: Mat<Complex<T>>,
+	alpha: Diag<Complex<T>>,
+	beta: Diag<Complex<T>>,
+}
+
 impl<T: ComplexField> Llt<T> {
 	/// returns the $L L^\top$ decomposition of $A$
 	#[track_caller]
@@ -616,6 +886,12 @@ impl<T: ComplexField> Llt<T> {
 	pub fn L(&self) -> MatRef<'_, T> {
 		self.L.as_ref()
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		rcond_l1_est(self, anorm)
+	}
 }
 
 impl<T: ComplexField> Ldlt<T> {
@@ -662,6 +938,12 @@ impl<T: ComplexField> Ldlt<T> {
 	pub fn D(&self) -> DiagRef<'_, T> {
 		self.D.as_ref()
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		rcond_l1_est(self, anorm)
+	}
 }
 
 impl<T: ComplexField> Lblt<T> {
@@ -726,6 +1008,12 @@ impl<T: ComplexField> Lblt<T> {
 	pub fn P(&self) -> PermRef<'_, usize> {
 		self.P.as_ref()
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		rcond_l1_est(self, anorm)
+	}
 }
 
 fn split_LU<T: ComplexField>(LU: Mat<T>) -> (Mat<T>, Mat<T>) {
@@ -806,6 +1094,18 @@ impl<T: ComplexField> PartialPivLu<T> {
 	pub fn P(&self) -> PermRef<'_, usize> {
 		self.P.as_ref()
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		rcond_l1_est(self, anorm)
+	}
+
+	/// returns the $LU$ decomposition of the equilibrated matrix $R A C$ with partial pivoting
+	#[track_caller]
+	pub fn new_equilibrated<C: Conjugate<Canonical = T>>(A: MatRef<'_, C>, method: EquilibrationMethod<T::Real>) -> Equilibrated<T, Self> {
+		Equilibrated::new_imp(A.to_owned(), method, Self::new_imp)
+	}
 }
 
 impl<T: ComplexField> FullPivLu<T> {
@@ -816,6 +1116,12 @@ impl<T: ComplexField> FullPivLu<T> {
 		Self::new_imp(LU)
 	}
 
+	/// returns the $LU$ decomposition of the equilibrated matrix $R A C$ with full pivoting
+	#[track_caller]
+	pub fn new_equilibrated<C: Conjugate<Canonical = T>>(A: MatRef<'_, C>, method: EquilibrationMethod<T::Real>) -> Equilibrated<T, Self> {
+		Equilibrated::new_imp(A.to_owned(), method, Self::new_imp)
+	}
+
 	#[track_caller]
 	fn new_imp(mut LU: Mat<T>) -> Self {
 		let par = get_global_parallelism();
@@ -873,6 +1179,54 @@ impl<T: ComplexField> FullPivLu<T> {
 	}
 }
 
+impl<T: ComplexField, S> Equilibrated<T, S> {
+	#[track_caller]
+	fn new_imp(mut A: Mat<T>, method: EquilibrationMethod<T::Real>, factor: impl FnOnce(Mat<T>) -> S) -> Self {
+		let mut row_scale = Col::zeros(A.nrows());
+		let mut col_scale = Col::zeros(A.ncols());
+		let info = linalg::equilibrate::equilibrate(row_scale.as_mut(), col_scale.as_mut(), A.as_ref(), method);
+		linalg::equilibrate::scale_in_place(A.as_mut(), row_scale.as_ref(), col_scale.as_ref());
+
+		Self {
+			inner: factor(A),
+			row_scale,
+			col_scale,
+			info,
+		}
+	}
+
+	/// wraps a decomposition `inner` of $R A C$, given the diagonals of $R$ and $C$
+	#[track_caller]
+	pub fn from_parts(inner: S, row_scale: Col<T::Real>, col_scale: Col<T::Real>, info: EquilibrationInfo<T::Real>) -> Self {
+		Self {
+			inner,
+			row_scale,
+			col_scale,
+			info,
+		}
+	}
+
+	/// returns the decomposition of the equilibrated matrix
+	pub fn inner(&self) -> &S {
+		&self.inner
+	}
+
+	/// returns the diagonal of the row scaling matrix $R$
+	pub fn row_scale(&self) -> ColRef<'_, T::Real> {
+		self.row_scale.as_ref()
+	}
+
+	/// returns the diagonal of the column scaling matrix $C$
+	pub fn col_scale(&self) -> ColRef<'_, T::Real> {
+		self.col_scale.as_ref()
+	}
+
+	/// returns the equilibration info
+	pub fn info(&self) -> &EquilibrationInfo<T::Real> {
+		&self.info
+	}
+}
+
 impl<T: ComplexField> Qr<T> {
 	/// returns the $QR$ decomposition of $A$
 	#[track_caller]
@@ -969,6 +1323,12 @@ impl<T: ComplexField> Qr<T> {
 		);
 		Q
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		rcond_l1_est(self, anorm)
+	}
 }
 
 impl<T: ComplexField> ColPivQr<T> {
@@ -1084,6 +1444,199 @@ impl<T: ComplexField> ColPivQr<T> {
 	}
 }
 
+#[math]
+fn default_rank_threshold<T: ComplexField>(nrows: usize, ncols: usize) -> T::Real {
+	eps::<T::Real>() * from_f64::<T::Real>(Ord::max(nrows, ncols) as f64)
+}
+
+#[math]
+fn numerical_rank<T: ComplexField>(R: MatRef<'_, T>, threshold: T::Real) -> usize {
+	let size = Ord::min(R.nrows(), R.ncols());
+	if size == 0 {
+		return 0;
+	}
+	let tol = threshold * abs(R[(0, 0)]);
+	let mut rank = 0;
+	while rank < size && abs(R[(rank, rank)]) > tol {
+		rank += 1;
+	}
+	rank
+}
+
+impl<T: ComplexField> CompleteOrthogonalDecomposition<T> {
+	/// returns the complete orthogonal decomposition of $A$
+	///
+	/// the numerical rank is determined with a relative threshold of $\varepsilon \max(m, n)$
+	#[track_caller]
+	pub fn new<C: Conjugate<Canonical = T>>(A: MatRef<'_, C>) -> Self {
+		Self::new_with_threshold(A, default_rank_threshold::<T>(A.nrows(), A.ncols()))
+	}
+
+	/// returns the complete orthogonal decomposition of $A$
+	///
+	/// the numerical rank is the number of diagonal entries of the column pivoted $R$ factor
+	/// whose magnitude exceeds `threshold` times the magnitude of the first one
+	#[track_caller]
+	pub fn new_with_threshold<C: Conjugate<Canonical = T>>(A: MatRef<'_, C>, threshold: T::Real) -> Self {
+		Self::new_imp(ColPivQr::new(A), threshold)
+	}
+
+	#[track_caller]
+	fn new_imp(qr: ColPivQr<T>, threshold: T::Real) -> Self {
+		let par = get_global_parallelism();
+
+		let n = qr.ncols();
+		let ColPivQr { Q_basis, Q_coeff, R, P } = qr;
+		let rank = numerical_rank(R.as_ref(), threshold);
+
+		// $[R_{11} \; R_{12}]^H = Z \begin{bmatrix} L^H \\ 0 \end{bmatrix}$
+		let mut Z_basis = R.get(..rank, ..).adjoint().to_owned();
+		let blocksize = Ord::max(1, linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(n, rank));
+		let mut Z_coeff = Mat::zeros(blocksize, rank);
+
+		linalg::qr::no_pivoting::factor::qr_in_place(
+			Z_basis.as_mut(),
+			Z_coeff.as_mut(),
+			par,
+			MemStack::new(&mut MemBuffer::new(linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(
+				n,
+				rank,
+				blocksize,
+				par,
+				default(),
+			))),
+			default(),
+		);
+
+		let mut L = Mat::zeros(rank, rank);
+		L.copy_from_triangular_lower(Z_basis.get(..rank, ..rank).adjoint());
+
+		z!(&mut Z_basis).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero());
+		Z_basis.diagonal_mut().fill(one());
+
+		Self {
+			Q_basis,
+			Q_coeff,
+			L,
+			Z_basis,
+			Z_coeff,
+			P,
+		}
+	}
+
+	/// returns the numerical rank of the original matrix $A$
+	pub fn rank(&self) -> usize {
+		self.L.nrows()
+	}
+
+	/// returns the householder basis of $Q$
+	pub fn Q_basis(&self) -> MatRef<'_, T> {
+		self.Q_basis.as_ref()
+	}
+
+	/// returns the householder coefficients of $Q$
+	pub fn Q_coeff(&self) -> MatRef<'_, T> {
+		self.Q_coeff.as_ref()
+	}
+
+	/// returns the factor $L$
+	pub fn L(&self) -> MatRef<'_, T> {
+		self.L.as_ref()
+	}
+
+	/// returns the householder basis of $Z$
+	pub fn Z_basis(&self) -> MatRef<'_, T> {
+		self.Z_basis.as_ref()
+	}
+
+	/// returns the householder coefficients of $Z$
+	pub fn Z_coeff(&self) -> MatRef<'_, T> {
+		self.Z_coeff.as_ref()
+	}
+
+	/// returns the column pivoting permutation $P$
+	pub fn P(&self) -> PermRef<'_, usize> {
+		self.P.as_ref()
+	}
+
+	/// computes an orthonormal basis of the null space of the original matrix $A$
+	pub fn null_space(&self) -> Mat<T> {
+		let par = get_global_parallelism();
+		let n = self.ncols();
+		let rank = self.rank();
+
+		let mut N = Mat::zeros(n, n - rank);
+		N.get_mut(rank.., ..).diagonal_mut().fill(one());
+		self.apply_Z_P_in_place(Conj::No, N.as_mut(), par);
+		N
+	}
+
+	/// returns the pseudoinverse of the original matrix $A$
+	pub fn pseudoinverse(&self) -> Mat<T> {
+		let m = self.nrows();
+		let n = self.ncols();
+
+		let mut X = Mat::zeros(Ord::max(m, n), m);
+		X.get_mut(..m, ..).diagonal_mut().fill(one());
+		self.solve_min_norm_in_place_with_conj(Conj::No, X.as_mut());
+		X.get(..n, ..).to_owned()
+	}
+
+	/// computes $P^\top Z \, x$, where $x$ is stored in the top rows of `rhs`
+	fn apply_Z_P_in_place(&self, conj: Conj, rhs: MatMut<'_, T>, par: Par) {
+		let n = self.ncols();
+		let k = rhs.ncols();
+		let mut rhs = rhs;
+
+		let mut mem = MemBuffer::new(StackReq::any_of(&[
+			linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(n, self.Z_coeff.nrows(), k),
+			crate::perm::permute_rows_in_place_scratch::<usize, T>(n, k),
+		]));
+		let stack = MemStack::new(&mut mem);
+
+		linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
+			self.Z_basis(),
+			self.Z_coeff(),
+			conj,
+			rhs.rb_mut().get_mut(..n, ..),
+			par,
+			stack,
+		);
+		crate::perm::permute_rows_in_place(rhs.get_mut(..n, ..), self.P().inverse(), stack);
+	}
+
+	/// computes the minimum norm least squares solution, where `rhs` has $\max(m, n)$ rows and
+	/// the right-hand side is stored in its top $m$ rows
+	#[track_caller]
+	fn solve_min_norm_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		let par = get_global_parallelism();
+
+		let m = self.nrows();
+		let n = self.ncols();
+		let rank = self.rank();
+		let k = rhs.ncols();
+		assert!(rhs.nrows() == Ord::max(m, n));
+
+		let mut rhs = rhs;
+
+		linalg::householder::apply_block_householder_sequence_transpose_on_the_left_in_place_with_conj(
+			self.Q_basis(),
+			self.Q_coeff(),
+			conj.compose(Conj::Yes),
+			rhs.rb_mut().get_mut(..m, ..),
+			par,
+			MemStack::new(&mut MemBuffer::new(
+				linalg::householder::apply_block_householder_sequence_transpose_on_the_left_in_place_scratch::<T>(m, self.Q_coeff.nrows(), k),
+			)),
+		);
+
+		linalg::triangular_solve::solve_lower_triangular_in_place_with_conj(self.L(), conj, rhs.rb_mut().get_mut(..rank, ..), par);
+		rhs.rb_mut().get_mut(rank..n, ..).fill(zero());
+
+		self.apply_Z_P_in_place(conj, rhs, par);
+	}
+}
+
 impl<T: ComplexField> Svd<T> {
 	/// returns the svd of $A$
 	#[track_caller]
@@ -1221,11 +1774,88 @@ impl<T: ComplexField> SelfAdjointEigen<T> {
 	}
 }
 
-impl<T: RealField> Eigen<T> {
-	/// returns the eigendecomposition of $A$
+impl<T: ComplexField> GeneralizedSelfAdjointEigen<T> {
+	/// returns the eigendecomposition of the generalized problem given by `problem`, assuming $A$
+	/// is self-adjoint and $B$ is self-adjoint positive definite
 	#[track_caller]
-	pub fn new<C: Conjugate<Canonical = Complex<T>>>(A: MatRef<'_, C>) -> Result<Self, EvdError> {
-		assert!(A.nrows() == A.ncols());
+	pub fn new<C: Conjugate<Canonical = T>>(
+		A: MatRef<'_, C>,
+		B: MatRef<'_, C>,
+		side: Side,
+		problem: GeneralizedProblem,
+	) -> Result<Self, GeneralizedSelfAdjointEigenError> {
+		assert!(all(A.nrows() == A.ncols(), B.nrows() == A.nrows(), B.ncols() == A.ncols()));
+
+		let llt = Llt::new(B, side).map_err(GeneralizedSelfAdjointEigenError::Llt)?;
+
+		let n = A.nrows();
+		let mut A_full = Mat::zeros(n, n);
+		match side {
+			Side::Lower => A_full.copy_from_triangular_lower(A),
+			Side::Upper => A_full.copy_from_triangular_lower(A.adjoint()),
+		}
+		for j in 0..n {
+			for i in 0..j {
+				A_full[(i, j)] = math_utils::conj(&A_full[(j, i)]);
+			}
+		}
+
+		Self::new_imp(A_full.as_ref(), llt.L(), problem).map_err(GeneralizedSelfAdjointEigenError::Evd)
+	}
+
+	#[track_caller]
+	fn new_imp(A: MatRef<'_, T>, L: MatRef<'_, T>, problem: GeneralizedProblem) -> Result<Self, EvdError> {
+		let par = get_global_parallelism();
+
+		// reduce to the standard problem $C y = \lambda y$
+		let C = match problem {
+			GeneralizedProblem::Pencil => {
+				// $C = L^{-1} A L^{-H}$
+				let mut W = A.to_owned();
+				linalg::triangular_solve::solve_lower_triangular_in_place(L, W.as_mut(), par);
+				let mut C = W.adjoint().to_owned();
+				linalg::triangular_solve::solve_lower_triangular_in_place(L, C.as_mut(), par);
+				C
+			},
+			// $C = L^H A L$
+			GeneralizedProblem::AB | GeneralizedProblem::BA => L.adjoint() * A * L,
+		};
+
+		let SelfAdjointEigen { mut U, S } = SelfAdjointEigen::new_imp(C.as_ref(), Conj::No)?;
+
+		match problem {
+			// $x = L^{-H} y$
+			GeneralizedProblem::Pencil | GeneralizedProblem::AB => {
+				linalg::triangular_solve::solve_upper_triangular_in_place(L.adjoint(), U.as_mut(), par);
+			},
+			// $x = L y$
+			GeneralizedProblem::BA => {
+				U = L * &U;
+			},
+		}
+
+		Ok(Self { U, S })
+	}
+
+	/// returns the eigenvectors $U$
+	///
+	/// the eigenvectors are normalized such that $U^H B U = I$ for [`GeneralizedProblem::Pencil`]
+	/// and [`GeneralizedProblem::AB`], and $U^H B^{-1} U = I$ for [`GeneralizedProblem::BA`]
+	pub fn U(&self) -> MatRef<'_, T> {
+		self.U.as_ref()
+	}
+
+	/// returns the eigenvalues $S$, sorted in nondecreasing order
+	pub fn S(&self) -> DiagRef<'_, T> {
+		self.S.as_ref()
+	}
+}
+
+impl<T: RealField> Eigen<T> {
+	/// returns the eigendecomposition of $A$
+	#[track_caller]
+	pub fn new<C: Conjugate<Canonical = Complex<T>>>(A: MatRef<'_, C>) -> Result<Self, EvdError> {
+		assert!(A.nrows() == A.ncols());
 		Self::new_imp(A.canonical(), Conj::get::<C>())
 	}
 
@@ -1334,6 +1964,158 @@ impl<T: RealField> Eigen<T> {
 	}
 }
 
+impl<T: RealField> GeneralizedEigen<T> {
+	/// returns the generalized eigendecomposition of the pencil $(A, B)$
+	#[track_caller]
+	pub fn new<C: Conjugate<Canonical = Complex<T>>>(A: MatRef<'_, C>, B: MatRef<'_, C>) -> Result<Self, EvdError> {
+		assert!(all(A.nrows() == A.ncols(), B.nrows() == A.nrows(), B.ncols() == A.ncols()));
+		Self::new_imp(A.canonical(), B.canonical(), Conj::get::<C>())
+	}
+
+	/// returns the generalized eigendecomposition of the pencil $(A, B)$
+	#[track_caller]
+	pub fn new_from_real(A: MatRef<'_, T>, B: MatRef<'_, T>) -> Result<Self, EvdError> {
+		assert!(all(A.nrows() == A.ncols(), B.nrows() == A.nrows(), B.ncols() == A.ncols()));
+
+		let par = get_global_parallelism();
+
+		let n = A.nrows();
+
+		let mut U_left_real = Mat::zeros(n, n);
+		let mut U_right_real = Mat::zeros(n, n);
+		let mut alpha_re = Diag::zeros(n);
+		let mut alpha_im = Diag::zeros(n);
+		let mut beta_re = Diag::zeros(n);
+
+		linalg::gevd::gevd_real(
+			A,
+			B,
+			alpha_re.as_mut(),
+			alpha_im.as_mut(),
+			beta_re.as_mut(),
+			Some(U_left_real.as_mut()),
+			Some(U_right_real.as_mut()),
+			par,
+			MemStack::new(&mut MemBuffer::new(linalg::gevd::gevd_scratch::<T>(
+				n,
+				linalg::evd::ComputeEigenvectors::Yes,
+				linalg::evd::ComputeEigenvectors::Yes,
+				par,
+			))),
+		)?;
+
+		let mut U_left = Mat::zeros(n, n);
+		let mut U_right = Mat::zeros(n, n);
+		let mut alpha = Diag::zeros(n);
+		let mut beta = Diag::zeros(n);
+
+		let mut j = 0;
+		while j < n {
+			if alpha_im[j] == zero() {
+				alpha[j] = Complex::new(alpha_re[j].clone(), zero());
+				beta[j] = Complex::new(beta_re[j].clone(), zero());
+
+				for i in 0..n {
+					U_left[(i, j)] = Complex::new(U_left_real[(i, j)].clone(), zero());
+					U_right[(i, j)] = Complex::new(U_right_real[(i, j)].clone(), zero());
+				}
+
+				j += 1;
+			} else {
+				alpha[j] = Complex::new(alpha_re[j].clone(), alpha_im[j].clone());
+				alpha[j + 1] = Complex::new(alpha_re[j + 1].clone(), alpha_im[j + 1].clone());
+				beta[j] = Complex::new(beta_re[j].clone(), zero());
+				beta[j + 1] = Complex::new(beta_re[j + 1].clone(), zero());
+
+				for (U, U_real) in [(&mut U_left, &U_left_real), (&mut U_right, &U_right_real)] {
+					for i in 0..n {
+						U[(i, j)] = Complex::new(U_real[(i, j)].clone(), U_real[(i, j + 1)].clone());
+						U[(i, j + 1)] = Complex::new(U_real[(i, j)].clone(), neg(&U_real[(i, j + 1)]));
+					}
+				}
+
+				j += 2;
+			}
+		}
+
+		Ok(Self {
+			U_left,
+			U_right,
+			alpha,
+			beta,
+		})
+	}
+
+	fn new_imp(A: MatRef<'_, Complex<T>>, B: MatRef<'_, Complex<T>>, conj: Conj) -> Result<Self, EvdError> {
+		let par = get_global_parallelism();
+
+		let n = A.nrows();
+
+		let mut U_left = Mat::zeros(n, n);
+		let mut U_right = Mat::zeros(n, n);
+		let mut alpha = Diag::zeros(n);
+		let mut beta = Diag::zeros(n);
+
+		linalg::gevd::gevd_cplx(
+			A,
+			B,
+			alpha.as_mut(),
+			beta.as_mut(),
+			Some(U_left.as_mut()),
+			Some(U_right.as_mut()),
+			par,
+			MemStack::new(&mut MemBuffer::new(linalg::gevd::gevd_scratch::<Complex<T>>(
+				n,
+				linalg::evd::ComputeEigenvectors::Yes,
+				linalg::evd::ComputeEigenvectors::Yes,
+				par,
+			))),
+		)?;
+
+		if conj == Conj::Yes {
+			for U in [&mut U_left, &mut U_right] {
+				for c in U.col_iter_mut() {
+					for x in c.iter_mut() {
+						*x = math_utils::conj(x);
+					}
+				}
+			}
+			for x in alpha.column_vector_mut().iter_mut().chain(beta.column_vector_mut().iter_mut()) {
+				*x = math_utils::conj(x);
+			}
+		}
+
+		Ok(Self {
+			U_left,
+			U_right,
+			alpha,
+			beta,
+		})
+	}
+
+	/// returns the left eigenvectors $U_L$, such that $\beta_j u_j^H A = \alpha_j u_j^H B$
+	pub fn U_left(&self) -> MatRef<'_, Complex<T>> {
+		self.U_left.as_ref()
+	}
+
+	/// returns the right eigenvectors $U_R$, such that $\beta_j A u_j = \alpha_j B u_j$
+	pub fn U_right(&self) -> MatRef<'_, Complex<T>> {
+		self.U_right.as_ref()
+	}
+
+	/// returns the eigenvalue numerators $\alpha$
+	pub fn alpha(&self) -> DiagRef<'_, Complex<T>> {
+		self.alpha.as_ref()
+	}
+
+	/// returns the eigenvalue denominators $\beta$
+	///
+	/// a zero entry corresponds to an infinite eigenvalue
+	pub fn beta(&self) -> DiagRef<'_, Complex<T>> {
+		self.beta.as_ref()
+	}
+}
+
 impl<T: ComplexField> ShapeCore for Llt<T> {
 	#[inline]
 	fn nrows(&self) -> usize {
@@ -1411,6 +2193,17 @@ impl<T: ComplexField> ShapeCore for ColPivQr<T> {
 		self.R().ncols()
 	}
 }
+impl<T: ComplexField> ShapeCore for CompleteOrthogonalDecomposition<T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.Q_basis().nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.Z_basis().nrows()
+	}
+}
 impl<T: ComplexField> ShapeCore for Svd<T> {
 	#[inline]
 	fn nrows(&self) -> usize {
@@ -1433,6 +2226,17 @@ impl<T: ComplexField> ShapeCore for SelfAdjointEigen<T> {
 		self.U().nrows()
 	}
 }
+impl<T: ComplexField> ShapeCore for GeneralizedSelfAdjointEigen<T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.U().nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.U().nrows()
+	}
+}
 impl<T: RealField> ShapeCore for Eigen<T> {
 	#[inline]
 	fn nrows(&self) -> usize {
@@ -1444,6 +2248,17 @@ impl<T: RealField> ShapeCore for Eigen<T> {
 		self.U().nrows()
 	}
 }
+impl<T: RealField> ShapeCore for GeneralizedEigen<T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.U_right().nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.U_right().nrows()
+	}
+}
 
 impl<T: ComplexField> SolveCore<T> for Llt<T> {
 	#[track_caller]
@@ -1487,6 +2302,262 @@ fn make_self_adjoint<T: ComplexField>(mut A: MatMut<'_, T>) {
 	}
 }
 
+#[math]
+fn sum_abs<T: ComplexField>(x: ColRef<'_, T>) -> T::Real {
+	let mut acc = zero::<T::Real>();
+	for i in 0..x.nrows() {
+		acc = acc + abs(x[i]);
+	}
+	acc
+}
+
+#[math]
+fn set_sign<T: ComplexField>(dst: ColMut<'_, T>, src: ColRef<'_, T>) {
+	z!(dst, src).for_each(|uz!(dst, src)| {
+		let a = abs(*src);
+		*dst = if a == zero() { one() } else { mul_real(*src, recip(a)) };
+	});
+}
+
+#[math]
+fn argmax_abs<T: ComplexField>(x: ColRef<'_, T>) -> usize {
+	let mut j = 0;
+	let mut max = zero::<T::Real>();
+	for i in 0..x.nrows() {
+		let a = abs(x[i]);
+		if a > max {
+			max = a;
+			j = i;
+		}
+	}
+	j
+}
+
+/// estimates $\|B\|_1$ with hager and higham's method, given the products with $B$ and $B^H$
+#[math]
+fn norm_l1_est<T: ComplexField>(n: usize, apply: &mut dyn FnMut(ColMut<'_, T>), apply_adjoint: &mut dyn FnMut(ColMut<'_, T>)) -> T::Real {
+	const MAX_ITERS: usize = 5;
+
+	if n == 0 {
+		return zero();
+	}
+
+	let mut x = Col::<T>::zeros(n);
+	let mut sign = Col::<T>::zeros(n);
+
+	x.fill(from_real(recip(from_f64::<T::Real>(n as f64))));
+	apply(x.as_mut());
+	if n == 1 {
+		return abs(x[0]);
+	}
+
+	let mut est = sum_abs(x.as_ref());
+	set_sign(sign.as_mut(), x.as_ref());
+	x.copy_from(&sign);
+	apply_adjoint(x.as_mut());
+	let mut j = argmax_abs(x.as_ref());
+
+	let mut iter = 2;
+	loop {
+		x.fill(zero());
+		x[j] = one();
+		apply(x.as_mut());
+
+		let est_old = copy(est);
+		est = sum_abs(x.as_ref());
+
+		// in the real case, a repeated sign vector means that the iteration has converged
+		if const { T::IS_REAL } {
+			let mut repeated = true;
+			for i in 0..n {
+				let s = if real(x[i]) >= zero() { one::<T::Real>() } else { -one::<T::Real>() };
+				if s != real(sign[i]) {
+					repeated = false;
+					break;
+				}
+			}
+			if repeated {
+				break;
+			}
+		}
+		if est <= est_old {
+			est = est_old;
+			break;
+		}
+
+		set_sign(sign.as_mut(), x.as_ref());
+		x.copy_from(&sign);
+		apply_adjoint(x.as_mut());
+
+		let j_last = j;
+		j = argmax_abs(x.as_ref());
+		if abs(x[j_last]) == abs(x[j]) || iter >= MAX_ITERS {
+			break;
+		}
+		iter += 1;
+	}
+
+	// alternative estimate, which guards against the cases where the power iteration is misled
+	let scale = recip(from_f64::<T::Real>((n - 1) as f64));
+	for i in 0..n {
+		let v = one::<T::Real>() + from_f64::<T::Real>(i as f64) * scale;
+		x[i] = from_real(if i % 2 == 0 { v } else { -v });
+	}
+	apply(x.as_mut());
+	let alt = from_f64::<T::Real>(2.0) * sum_abs(x.as_ref()) * recip(from_f64::<T::Real>((3 * n) as f64));
+
+	max(est, alt)
+}
+
+/// estimates $\|A^{-1}\|_1$, using only solves with $A$ and $A^H$
+fn inverse_norm_l1_est<T: ComplexField>(solver: &dyn SolveCore<T>) -> T::Real {
+	let n = solver.nrows();
+	assert!(solver.ncols() == n);
+
+	norm_l1_est(n, &mut |x| solver.solve_in_place_with_conj(Conj::No, x.as_mat_mut()), &mut |x| {
+		solver.solve_transpose_in_place_with_conj(Conj::Yes, x.as_mat_mut())
+	})
+}
+
+/// estimates the reciprocal condition number $1 / (\|A\|_1 \|A^{-1}\|_1)$, given
+/// `anorm` $= \|A\|_1$ (the maximum absolute column sum)
+///
+/// since $\|A^{-1}\|_1$ is underestimated, the result is never smaller than the exact value, and
+/// is usually within a small factor of it
+#[math]
+pub(crate) fn rcond_l1_est<T: ComplexField>(solver: &dyn SolveCore<T>, anorm: T::Real) -> T::Real {
+	if solver.nrows() == 0 {
+		return one();
+	}
+	if anorm == zero() {
+		return zero();
+	}
+
+	let ainv_norm = inverse_norm_l1_est(solver);
+	if !is_finite(ainv_norm) || ainv_norm == zero() {
+		return zero();
+	}
+	recip(anorm * ainv_norm)
+}
+
+fn cast_col<From: PrecisionCast<To>, To: ComplexField>(dst: ColMut<'_, To>, src: ColRef<'_, From>) {
+	z!(dst, src).for_each(|uz!(dst, src)| *dst = src.precision_cast());
+}
+
+#[math]
+#[track_caller]
+fn solve_refined_imp<T: ComplexField + PrecisionCast<E>, E: ComplexField + PrecisionCast<T>>(
+	solver: &dyn SolveCore<T>,
+	A: &dyn RefinementMatrix<E>,
+	rhs: MatMut<'_, E>,
+	params: RefinementParams,
+) -> RefinementInfo<E::Real> {
+	let n = solver.nrows();
+	let k = rhs.ncols();
+	assert!(all(solver.ncols() == n, A.nrows() == n, A.ncols() == n, rhs.nrows() == n));
+
+	let mut rhs = rhs;
+	let mut info = RefinementInfo {
+		backward_error: Vec::with_capacity(k),
+		forward_error: Vec::with_capacity(k),
+		iter_count: 0,
+	};
+
+	let eps = eps::<E::Real>();
+	let mut b = Col::<E>::zeros(n);
+	let mut r = Col::<E>::zeros(n);
+	let mut w = Col::<E::Real>::zeros(n);
+	let mut f = Col::<E::Real>::zeros(n);
+	let mut d = Col::<T>::zeros(n);
+
+	for j in 0..k {
+		let mut x = rhs.rb_mut().col_mut(j);
+		b.copy_from(&x);
+
+		cast_col(d.as_mut(), b.as_ref());
+		solver.solve_in_place_with_conj(Conj::No, d.as_mat_mut());
+		cast_col(x.rb_mut(), d.as_ref());
+
+		let mut berr_last = infinity::<E::Real>();
+		let mut berr;
+		let mut iter = 0;
+		loop {
+			A.residual(r.as_mut(), w.as_mut(), x.rb(), b.as_ref());
+
+			berr = zero::<E::Real>();
+			for i in 0..n {
+				let ri = abs(r[i]);
+				let ratio = if w[i] > zero() {
+					ri / w[i]
+				} else if ri == zero() {
+					zero()
+				} else {
+					infinity()
+				};
+				berr = max(berr, ratio);
+			}
+
+			// stop when the backward error is at the working precision, or when it stops decreasing
+			// fast enough
+			if berr <= eps || !is_finite(berr) || berr * from_f64::<E::Real>(2.0) > berr_last || iter == params.max_iters {
+				break;
+			}
+
+			cast_col(d.as_mut(), r.as_ref());
+			solver.solve_in_place_with_conj(Conj::No, d.as_mat_mut());
+			for i in 0..n {
+				x[i] = x[i] + d[i].precision_cast();
+			}
+
+			berr_last = copy(berr);
+			iter += 1;
+		}
+
+		// $\| |A^{-1}| (|r| + (n + 1) \varepsilon w) \|_\infty = \|\text{diag}(f) A^{-H}\|_1$, which is
+		// estimated with the factorization. $f$ is normalized to avoid underflow in low precision
+		let c = from_f64::<E::Real>((n + 1) as f64) * eps;
+		let mut f_max = zero::<E::Real>();
+		for i in 0..n {
+			f[i] = abs(r[i]) + c * w[i];
+			f_max = max(f_max, f[i]);
+		}
+		let f_scale = if f_max > zero() { recip(f_max) } else { zero() };
+		for i in 0..n {
+			f[i] = f[i] * f_scale;
+		}
+
+		let f = f.as_ref();
+		let ainv_norm = norm_l1_est::<E>(
+			n,
+			&mut |mut y: ColMut<'_, E>| {
+				let mut d = Col::<T>::zeros(n);
+				cast_col(d.as_mut(), y.rb());
+				solver.solve_transpose_in_place_with_conj(Conj::Yes, d.as_mat_mut());
+				for i in 0..n {
+					y[i] = mul_real(d[i].precision_cast(), f[i]);
+				}
+			},
+			&mut |mut y: ColMut<'_, E>| {
+				let mut d = Col::<T>::zeros(n);
+				for i in 0..n {
+					d[i] = mul_real(y[i], f[i]).precision_cast();
+				}
+				solver.solve_in_place_with_conj(Conj::No, d.as_mat_mut());
+				cast_col(y.rb_mut(), d.as_ref());
+			},
+		);
+
+		let x_norm = x.rb().norm_max();
+		let ferr = if x_norm > zero() { ainv_norm * f_max / x_norm } else { zero() };
+
+		info.backward_error.push(berr);
+		info.forward_error.push(min(ferr, one()));
+		info.iter_count = Ord::max(info.iter_count, iter);
+	}
+
+	info
+}
+
 impl<T: ComplexField> DenseSolveCore<T> for Llt<T> {
 	#[track_caller]
 	fn reconstruct(&self) -> Mat<T> {
@@ -1848,6 +2919,66 @@ impl<T: ComplexField> DenseSolveCore<T> for FullPivLu<T> {
 	}
 }
 
+#[math]
+pub(crate) fn scale_rows<T: ComplexField>(mat: MatMut<'_, T>, scale: ColRef<'_, T::Real>) {
+	let mut mat = mat;
+	for j in 0..mat.ncols() {
+		z!(mat.rb_mut().col_mut(j), scale).for_each(|uz!(x, s)| *x = mul_real(*x, *s));
+	}
+}
+
+impl<T: ComplexField, S: ShapeCore> ShapeCore for Equilibrated<T, S> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.inner.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.inner.ncols()
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T>> SolveCore<T> for Equilibrated<T, S> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(all(self.nrows() == self.ncols(), self.nrows() == rhs.nrows()));
+
+		let mut rhs = rhs;
+		scale_rows(rhs.rb_mut(), self.row_scale());
+		self.inner.solve_in_place_with_conj(conj, rhs.rb_mut());
+		scale_rows(rhs, self.col_scale());
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(all(self.nrows() == self.ncols(), self.ncols() == rhs.nrows()));
+
+		let mut rhs = rhs;
+		scale_rows(rhs.rb_mut(), self.col_scale());
+		self.inner.solve_transpose_in_place_with_conj(conj, rhs.rb_mut());
+		scale_rows(rhs, self.row_scale());
+	}
+}
+
+impl<T: ComplexField, S: DenseSolveCore<T>> DenseSolveCore<T> for Equilibrated<T, S> {
+	#[math]
+	fn reconstruct(&self) -> Mat<T> {
+		let mut out = self.inner.reconstruct();
+		let row_scale = self.row_scale().iter().map(|s| recip(*s)).collect::<Col<T::Real>>();
+		let col_scale = self.col_scale().iter().map(|s| recip(*s)).collect::<Col<T::Real>>();
+		linalg::equilibrate::scale_in_place(out.as_mut(), row_scale.as_ref(), col_scale.as_ref());
+		out
+	}
+
+	#[track_caller]
+	fn inverse(&self) -> Mat<T> {
+		let mut out = self.inner.inverse();
+		linalg::equilibrate::scale_in_place(out.as_mut(), self.col_scale(), self.row_scale());
+		out
+	}
+}
+
 impl<T: ComplexField> SolveCore<T> for Qr<T> {
 	#[track_caller]
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
@@ -2097,6 +3228,15 @@ impl<T: ComplexField> DenseSolveCore<T> for ColPivQr<T> {
 	}
 }
 
+impl<T: ComplexField> SolveLstsqCore<T> for CompleteOrthogonalDecomposition<T> {
+	#[track_caller]
+	fn solve_lstsq_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == Ord::max(self.nrows(), self.ncols()));
+
+		self.solve_min_norm_in_place_with_conj(conj, rhs);
+	}
+}
+
 impl<T: ComplexField> SolveCore<T> for Svd<T> {
 	#[track_caller]
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
@@ -2461,6 +3601,8 @@ mod tests {
 		test_solver(A, A.qr());
 		test_solver(A, A.col_piv_qr());
 		test_solver(A, A.svd().unwrap());
+		test_solver(A, PartialPivLu::new_equilibrated(A, default()));
+		test_solver(A, FullPivLu::new_equilibrated(A, EquilibrationMethod::Ruiz(default())));
 
 		{
 			let ref A = A * A.adjoint();
@@ -2522,6 +3664,122 @@ mod tests {
 		assert!(evd.S().column_vector() ~ ColRef::from_slice(&e));
 	}
 
+	#[test]
+	fn test_generalized_eigen_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64));
+
+		let gevd = A.generalized_eigen(&B).unwrap();
+		let (alpha, beta) = (gevd.alpha(), gevd.beta());
+		assert!(&A * gevd.U_right() * beta ~ &B * gevd.U_right() * alpha);
+		assert!(beta * gevd.U_left().adjoint() * &A ~ alpha * gevd.U_left().adjoint() * &B);
+
+		let gevd = A.conjugate().generalized_eigen(B.conjugate()).unwrap();
+		let (alpha, beta) = (gevd.alpha(), gevd.beta());
+		assert!(A.conjugate() * gevd.U_right() * beta ~ B.conjugate() * gevd.U_right() * alpha);
+	}
+
+	#[test]
+	fn test_generalized_self_adjoint_eigen() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+
+		let A = &A + A.adjoint();
+		let B = &B * B.adjoint() + Mat::<c64>::identity(n, n);
+		let B_inv = B.llt(Side::Lower).unwrap().inverse();
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64));
+
+		for side in [Side::Lower, Side::Upper] {
+			let evd = A.generalized_self_adjoint_eigen(&B, side, GeneralizedProblem::Pencil).unwrap();
+			let (U, S) = (evd.U(), evd.S());
+			assert!(&A * U ~ &B * U * S);
+			assert!(U.adjoint() * &B * U ~ Mat::<c64>::identity(n, n));
+
+			let evd = A.generalized_self_adjoint_eigen(&B, side, GeneralizedProblem::AB).unwrap();
+			let (U, S) = (evd.U(), evd.S());
+			assert!(&A * &B * U ~ U * S);
+			assert!(U.adjoint() * &B * U ~ Mat::<c64>::identity(n, n));
+
+			let evd = A.generalized_self_adjoint_eigen(&B, side, GeneralizedProblem::BA).unwrap();
+			let (U, S) = (evd.U(), evd.S());
+			assert!(&B * &A * U ~ U * S);
+			assert!(U.adjoint() * &B_inv * U ~ Mat::<c64>::identity(n, n));
+		}
+
+		let evd = A
+			.conjugate()
+			.generalized_self_adjoint_eigen(B.conjugate(), Side::Lower, GeneralizedProblem::Pencil)
+			.unwrap();
+		let (U, S) = (evd.U(), evd.S());
+		assert!(A.conjugate() * U ~ B.conjugate() * U * S);
+
+		let mut B = B;
+		B[(0, 0)] = c64::new(-1.0, 0.0);
+		assert!(matches!(
+			A.generalized_self_adjoint_eigen(&B, Side::Lower, GeneralizedProblem::Pencil),
+			Err(GeneralizedSelfAdjointEigenError::Llt(_))
+		));
+	}
+
+	#[test]
+	fn test_generalized_eigen_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let B = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n as f64));
+
+		let gevd = A.generalized_eigen(&B).unwrap();
+		let (alpha, beta) = (gevd.alpha(), gevd.beta());
+
+		let A = Mat::from_fn(n, n, |i, j| c64::from(A[(i, j)]));
+		let B = Mat::from_fn(n, n, |i, j| c64::from(B[(i, j)]));
+
+		assert!(&A * gevd.U_right() * beta ~ &B * gevd.U_right() * alpha);
+		assert!(beta * gevd.U_left().adjoint() * &A ~ alpha * gevd.U_left().adjoint() * &B);
+	}
+
 	#[test]
 	fn test_svd_solver_for_rectangular_matrix() {
 		#[rustfmt::skip]
@@ -2554,4 +3812,236 @@ mod tests {
 		svd.solve_lstsq_in_place_with_conj(crate::Conj::No, X.as_mat_mut());
 		assert!(X.get(..X_true.nrows(),..) ~ X_true);
 	}
+
+	fn norm_l1_induced<T: ComplexField>(A: MatRef<'_, T>) -> T::Real {
+		let mut max = zero::<T::Real>();
+		for j in 0..A.ncols() {
+			let mut sum = zero::<T::Real>();
+			for i in 0..A.nrows() {
+				sum = add(&sum, &abs(&A[(i, j)]));
+			}
+			if sum > max {
+				max = sum;
+			}
+		}
+		max
+	}
+
+	#[test]
+	fn test_rcond() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 40;
+
+		let Q: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng)
+		.qr()
+		.compute_Q();
+
+		// self-adjoint positive definite, with a 2-norm condition number of 1e8
+		let D = Col::<c64>::from_fn(n, |i| c64::new(10.0f64.powf(-8.0 * i as f64 / (n - 1) as f64), 0.0));
+		let H = &Q * D.as_diagonal() * Q.adjoint();
+		// non self-adjoint
+		let N = &Q * D.as_diagonal() * Q.transpose();
+
+		let check = |A: MatRef<'_, c64>, est: f64| {
+			let exact = 1.0 / (norm_l1_induced(A) * norm_l1_induced(A.partial_piv_lu().inverse().as_ref()));
+			assert!(est >= exact * (1.0 - 1e-6));
+			assert!(est <= exact * 10.0);
+		};
+
+		let anorm = norm_l1_induced(H.as_ref());
+		check(H.as_ref(), H.llt(Side::Lower).unwrap().rcond(anorm));
+		check(H.as_ref(), H.ldlt(Side::Lower).unwrap().rcond(anorm));
+		check(H.as_ref(), H.lblt(Side::Lower).rcond(anorm));
+		check(H.as_ref(), H.partial_piv_lu().rcond(anorm));
+		check(H.as_ref(), H.qr().rcond(anorm));
+
+		let anorm = norm_l1_induced(N.as_ref());
+		check(N.as_ref(), N.partial_piv_lu().rcond(anorm));
+		check(N.as_ref(), N.qr().rcond(anorm));
+
+		let A: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let anorm = norm_l1_induced(A.as_ref());
+		let est = A.partial_piv_lu().rcond(anorm);
+		let exact = 1.0 / (anorm * norm_l1_induced(A.partial_piv_lu().inverse().as_ref()));
+		assert!(est >= exact * (1.0 - 1e-6));
+		assert!(est <= exact * 10.0);
+
+		let I = Mat::<f64>::identity(n, n);
+		assert!(I.partial_piv_lu().rcond(1.0) == 1.0);
+		assert!(Mat::<f64>::zeros(n, n).partial_piv_lu().rcond(0.0) == 0.0);
+	}
+
+	#[test]
+	fn test_solve_refined() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 50;
+
+		let A: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let X_true: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let B = &A * &X_true;
+
+		// factor in f32, refine in f64
+		let A32 = Mat::from_fn(n, n, |i, j| A[(i, j)] as f32);
+		let lu = A32.partial_piv_lu();
+		let (X, info) = lu.solve_refined(A.as_ref(), &B, default());
+		for j in 0..2 {
+			let err = (X.col(j) - X_true.col(j)).norm_max() / X.col(j).norm_max();
+			assert!(info.backward_error[j] < 1e-15);
+			assert!(err < 1e-10);
+			assert!(err <= info.forward_error[j]);
+			assert!(info.forward_error[j] < 1e-8);
+		}
+		assert!(info.iter_count > 0);
+
+		// factor in f64, refine in fx128
+		let A128 = Mat::from_fn(n, n, |i, j| fx128::from(A[(i, j)]));
+		let X128_true = Mat::from_fn(n, 2, |i, j| fx128::from(X_true[(i, j)]));
+		let B128 = &A128 * &X128_true;
+		let lu = A.partial_piv_lu();
+		let (X, info) = lu.solve_refined(A128.as_ref(), &B128, default());
+		for j in 0..2 {
+			let mut err = fx128::from(0.0);
+			for i in 0..n {
+				let e = (X[(i, j)] - X128_true[(i, j)]).abs();
+				if e > err {
+					err = e;
+				}
+			}
+			assert!(info.backward_error[j] < fx128::from(1e-28));
+			assert!(err < fx128::from(1e-25));
+		}
+
+		// complex, self-adjoint positive definite, refined in the same precision
+		let M: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let H = &M * M.adjoint() + Scale(c64::new(n as f64, 0.0)) * Mat::<c64>::identity(n, n);
+		let H32 = Mat::from_fn(n, n, |i, j| c32::new(H[(i, j)].re as f32, H[(i, j)].im as f32));
+		let b = Col::<c64>::from_fn(n, |i| c64::new(i as f64, 1.0));
+
+		let (x, info) = H32.llt(Side::Lower).unwrap().solve_refined(H.as_ref(), &b, default());
+		assert!(info.backward_error[0] < 1e-15);
+		assert!((&H * &x - &b).norm_max() < 1e-10);
+
+		let (x, info) = H.llt(Side::Lower).unwrap().solve_refined(H.as_ref(), &b, default());
+		assert!(info.backward_error[0] < 1e-15);
+		assert!((&H * &x - &b).norm_max() < 1e-10);
+	}
+
+	#[test]
+	fn test_complete_orthogonal_decomposition() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e5);
+
+		for (m, n, rank) in [(8, 6, 3), (6, 6, 6), (5, 9, 2), (7, 4, 0)] {
+			let distr = CwiseMatDistribution {
+				nrows: m,
+				ncols: rank,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			};
+			let U: Mat<c64> = distr.sample(rng);
+			let distr = CwiseMatDistribution {
+				nrows: rank,
+				ncols: n,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			};
+			let V: Mat<c64> = distr.sample(rng);
+			let A = &U * &V;
+
+			let cod = A.complete_orthogonal_decomposition();
+			assert!(cod.rank() == rank);
+
+			// moore-penrose conditions
+			let X = cod.pseudoinverse();
+			assert!(&A * &X * &A ~ A);
+			assert!(&X * &A * &X ~ X);
+			assert!(&A * &X ~ (&A * &X).adjoint().to_owned());
+			assert!(&X * &A ~ (&X * &A).adjoint().to_owned());
+
+			let N = cod.null_space();
+			assert!(N.ncols() == n - rank);
+			assert!(&A * &N ~ Mat::zeros(m, n - rank));
+			assert!(N.adjoint() * &N ~ Mat::identity(n - rank, n - rank));
+
+			// the solution has minimum norm, including for wide matrices
+			let distr = CwiseMatDistribution {
+				nrows: m,
+				ncols: 2,
+				dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+			};
+			let B: Mat<c64> = distr.sample(rng);
+			let sol = cod.solve_lstsq(&B);
+			assert!(sol ~ &X * &B);
+
+			let sol = cod.solve_conjugate_lstsq(&B);
+			assert!(sol ~ X.conjugate() * &B);
+
+			let mut sol = Mat::zeros(Ord::max(m, n), 2);
+			sol.get_mut(..m, ..).copy_from(&B);
+			cod.solve_lstsq_in_place(&mut sol);
+			assert!(sol.get(..n, ..) ~ &X * &B);
+		}
+	}
+
+	#[test]
+	fn test_equilibrated_lu() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 30;
+
+		let B: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		// A = D1 B D2, with rows and columns scaled over twelve orders of magnitude
+		let d1 = Col::from_fn(n, |i| 10.0f64.powi(i as i32 % 13 - 6));
+		let d2 = Col::from_fn(n, |j| 10.0f64.powi(6 - j as i32 % 13));
+		let A = Mat::from_fn(n, n, |i, j| d1[i] * B[(i, j)] * d2[j]);
+		let b = Col::from_fn(n, |i| (i as f64).cos());
+
+		// x = D2^-1 B^-1 D1^-1 b
+		let y = B.partial_piv_lu().solve(Col::from_fn(n, |i| b[i] / d1[i]));
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+		for method in [EquilibrationMethod::PowerOfTwo, EquilibrationMethod::Ruiz(default())] {
+			let lu = PartialPivLu::new_equilibrated(A.as_ref(), method);
+			assert!(lu.info().row_ratio < 1e-6);
+
+			let x = lu.solve(&b);
+			let x = Col::from_fn(n, |j| x[j] * d2[j]);
+			assert!(x.as_mat() ~ y.as_mat());
+
+			let A_rec = lu.reconstruct();
+			let B_rec = Mat::from_fn(n, n, |i, j| A_rec[(i, j)] / (d1[i] * d2[j]));
+			assert!(B_rec ~ B);
+
+			let A_inv = lu.inverse();
+			let B_inv = Mat::from_fn(n, n, |i, j| A_inv[(i, j)] * d2[i] * d1[j]);
+			assert!(B_inv * &B ~ Mat::<f64>::identity(n, n));
+		}
+	}
 }
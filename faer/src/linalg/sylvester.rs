@@ -0,0 +1,953 @@
+//! low level implementation of solvers for the sylvester, stein and lyapunov matrix equations.
+//!
+//! the sylvester equation is
+//!
+//! $$A X + X B = C$$
+//!
+//! and the stein equation (its discrete-time counterpart) is
+//!
+//! $$X - A X B = C$$
+//!
+//! the continuous and discrete lyapunov equations are the special cases
+//! $A X + X A^H + Q = 0$ and $A X A^H - X + Q = 0$
+//!
+//! the equations are solved with the bartels-stewart algorithm: $A$ and $B$ are reduced to
+//! (quasi-)upper triangular schur form, the transformed equation is solved by block
+//! back-substitution, and the solution is transformed back
+//!
+//! large quasi-triangular equations are split recursively. for the sylvester equation, once both
+//! dimensions are split, the two diagonal subproblems are independent and are solved in parallel
+
+use crate::assert;
+use crate::internal_prelude::*;
+use crate::utils::thread::join_raw;
+use linalg::evd::schur::{block_size_at, schur_in_place, schur_in_place_scratch};
+
+/// sylvester equation solver parameters
+#[derive(Clone, Copy, Debug)]
+pub struct SylvesterParams {
+	/// threshold at which the quasi-triangular sylvester and stein solvers switch from the
+	/// recursive blocked algorithm to the unblocked one
+	pub blocking_threshold: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: ComplexField> Auto<T> for SylvesterParams {
+	fn auto() -> Self {
+		Self {
+			blocking_threshold: 32,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// sylvester equation error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SylvesterError {
+	/// the schur decomposition did not converge
+	NoConvergence,
+	/// the equation is singular, i.e. it does not have a unique solution
+	Singular,
+}
+
+impl core::fmt::Display for SylvesterError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for SylvesterError {}
+
+/// solves the system $K x = b$ of size at most $4$ with gaussian elimination with partial
+/// pivoting, and overwrites `x` (which initially holds $b$) with the solution
+#[math]
+#[allow(clippy::needless_range_loop)]
+fn solve_small_system<T: ComplexField>(K: &mut [[T; 4]; 4], x: &mut [T; 4], len: usize) -> Result<(), SylvesterError> {
+	for k in 0..len {
+		let mut pivot = k;
+		let mut pivot_abs = abs(K[k][k]);
+		for r in k + 1..len {
+			let val = abs(K[r][k]);
+			if val > pivot_abs {
+				pivot = r;
+				pivot_abs = val;
+			}
+		}
+		if pivot_abs == zero() {
+			return Err(SylvesterError::Singular);
+		}
+		K.swap(k, pivot);
+		x.swap(k, pivot);
+
+		let inv = recip(K[k][k]);
+		for r in k + 1..len {
+			let factor = K[r][k] * inv;
+			for c in k..len {
+				K[r][c] = K[r][c] - factor * K[k][c];
+			}
+			x[r] = x[r] - factor * x[k];
+		}
+	}
+	for k in (0..len).rev() {
+		let mut acc = copy(x[k]);
+		for c in k + 1..len {
+			acc = acc - K[k][c] * x[c];
+		}
+		x[k] = acc * recip(K[k][k]);
+	}
+
+	Ok(())
+}
+
+/// solves $A X + X B = C$ for $1 \times 1$ or $2 \times 2$ matrices $A$ and $B$, and overwrites
+/// `X` (which initially holds $C$) with the solution
+#[math]
+pub(crate) fn solve_small_sylvester<T: ComplexField>(A: MatRef<'_, T>, B: MatRef<'_, T>, X: MatMut<'_, T>) -> Result<(), SylvesterError> {
+	let p = A.nrows();
+	let q = B.nrows();
+	let mut X = X;
+
+	// kronecker form of the system, $(I \otimes A + B^\top \otimes I) \text{vec}(X) = \text{vec}(C)$
+	let mut K: [[T; 4]; 4] = core::array::from_fn(|_| core::array::from_fn(|_| zero()));
+	let mut x: [T; 4] = core::array::from_fn(|_| zero());
+
+	for c in 0..q {
+		for r in 0..p {
+			let row = r + c * p;
+			x[row] = copy(X[(r, c)]);
+			for k in 0..p {
+				K[row][k + c * p] = K[row][k + c * p] + A[(r, k)];
+			}
+			for k in 0..q {
+				K[row][r + k * p] = K[row][r + k * p] + B[(k, c)];
+			}
+		}
+	}
+
+	solve_small_system(&mut K, &mut x, p * q)?;
+
+	for c in 0..q {
+		for r in 0..p {
+			X[(r, c)] = copy(x[r + c * p]);
+		}
+	}
+
+	Ok(())
+}
+
+/// solves $X - A X B = C$ for $1 \times 1$ or $2 \times 2$ matrices $A$ and $B$, and overwrites
+/// `X` (which initially holds $C$) with the solution
+#[math]
+fn solve_small_stein<T: ComplexField>(A: MatRef<'_, T>, B: MatRef<'_, T>, X: MatMut<'_, T>) -> Result<(), SylvesterError> {
+	let p = A.nrows();
+	let q = B.nrows();
+	let mut X = X;
+
+	// kronecker form of the system, $(I - B^\top \otimes A) \text{vec}(X) = \text{vec}(C)$
+	let mut K: [[T; 4]; 4] = core::array::from_fn(|_| core::array::from_fn(|_| zero()));
+	let mut x: [T; 4] = core::array::from_fn(|_| zero());
+
+	for c in 0..q {
+		for r in 0..p {
+			let row = r + c * p;
+			x[row] = copy(X[(r, c)]);
+			K[row][row] = one();
+			for j in 0..q {
+				for i in 0..p {
+					K[row][i + j * p] = K[row][i + j * p] - A[(r, i)] * B[(j, c)];
+				}
+			}
+		}
+	}
+
+	solve_small_system(&mut K, &mut x, p * q)?;
+
+	for c in 0..q {
+		for r in 0..p {
+			X[(r, c)] = copy(x[r + c * p]);
+		}
+	}
+
+	Ok(())
+}
+
+/// returns the size of the diagonal block of the quasi-triangular matrix $T$ ending right before
+/// `end`
+#[inline]
+#[math]
+fn block_size_before<T: ComplexField>(T: MatRef<'_, T>, end: usize) -> usize {
+	if end >= 2 && T[(end - 1, end - 2)] != zero() { 2 } else { 1 }
+}
+
+/// returns an index close to `mid` that does not split a diagonal block of the quasi-triangular
+/// matrix $T$
+#[inline]
+#[math]
+fn split_point<T: ComplexField>(T: MatRef<'_, T>, mid: usize) -> usize {
+	if mid > 0 && mid < T.nrows() && T[(mid, mid - 1)] != zero() {
+		mid + 1
+	} else {
+		mid
+	}
+}
+
+#[math]
+fn sylvester_unblocked<T: ComplexField>(A: MatRef<'_, T>, B: MatRef<'_, T>, C: MatMut<'_, T>, par: Par) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+	let mut C = C;
+
+	let mut l = 0;
+	while l < n {
+		let q = block_size_at(B, l);
+
+		let (Y, rest) = C.rb_mut().split_at_col_mut(l);
+		let mut Cl = rest.subcols_mut(0, q);
+		// $C_l = C_l - Y_{:, :l} B_{:l, l}$
+		linalg::matmul::matmul(Cl.rb_mut(), Accum::Add, Y.rb(), B.submatrix(0, l, l, q), -one::<T>(), par);
+
+		let mut k_end = m;
+		while k_end > 0 {
+			let p = block_size_before(A, k_end);
+			let k = k_end - p;
+
+			let (top, bot) = Cl.rb_mut().split_at_row_mut(k_end);
+			let mut X = top.subrows_mut(k, p);
+			linalg::matmul::matmul(X.rb_mut(), Accum::Add, A.submatrix(k, k_end, p, m - k_end), bot.rb(), -one::<T>(), par);
+			solve_small_sylvester(A.submatrix(k, k, p, p), B.submatrix(l, l, q, q), X)?;
+
+			k_end = k;
+		}
+
+		l += q;
+	}
+
+	Ok(())
+}
+
+/// solves the sylvester equation $A X + X B = C$, where $A$ and $B$ are upper quasi-triangular,
+/// and overwrites $C$ with the solution
+///
+/// the diagonal blocks of $A$ and $B$ must be of size $1 \times 1$ or $2 \times 2$, as produced by
+/// the real or complex schur decomposition
+#[math]
+#[track_caller]
+pub fn solve_quasi_triangular_sylvester_in_place<T: ComplexField>(
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	C: MatMut<'_, T>,
+	par: Par,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+	assert!(all(A.ncols() == m, B.ncols() == n, C.nrows() == m, C.ncols() == n));
+
+	let params = params.config;
+	let threshold = Ord::max(params.blocking_threshold, 2);
+
+	if m == 0 || n == 0 {
+		return Ok(());
+	}
+	if m <= threshold && n <= threshold {
+		return sylvester_unblocked(A, B, C, par);
+	}
+
+	let mut C = C;
+	if m > threshold && n > threshold {
+		// $A = [A_{11} A_{12}; 0 A_{22}]$ and $B = [B_{11} B_{12}; 0 B_{22}]$. the bottom left block
+		// is solved first, then the diagonal blocks which are independent of each other, and the top
+		// right block last
+		let mid_m = split_point(A, m / 2);
+		let mid_n = split_point(B, n / 2);
+
+		let A11 = A.submatrix(0, 0, mid_m, mid_m);
+		let A12 = A.submatrix(0, mid_m, mid_m, m - mid_m);
+		let A22 = A.submatrix(mid_m, mid_m, m - mid_m, m - mid_m);
+		let B11 = B.submatrix(0, 0, mid_n, mid_n);
+		let B12 = B.submatrix(0, mid_n, mid_n, n - mid_n);
+		let B22 = B.submatrix(mid_n, mid_n, n - mid_n, n - mid_n);
+
+		let (mut C11, mut C12, mut C21, mut C22) = C.rb_mut().split_at_mut(mid_m, mid_n);
+
+		solve_quasi_triangular_sylvester_in_place(A22, B11, C21.rb_mut(), par, params.into())?;
+
+		let mut r11 = Ok(());
+		let mut r22 = Ok(());
+		join_raw(
+			|par| {
+				linalg::matmul::matmul(C11.rb_mut(), Accum::Add, A12, C21.rb(), -one::<T>(), par);
+				r11 = solve_quasi_triangular_sylvester_in_place(A11, B11, C11.rb_mut(), par, params.into());
+			},
+			|par| {
+				linalg::matmul::matmul(C22.rb_mut(), Accum::Add, C21.rb(), B12, -one::<T>(), par);
+				r22 = solve_quasi_triangular_sylvester_in_place(A22, B22, C22.rb_mut(), par, params.into());
+			},
+			par,
+		);
+		r11?;
+		r22?;
+
+		linalg::matmul::matmul(C12.rb_mut(), Accum::Add, A12, C22.rb(), -one::<T>(), par);
+		linalg::matmul::matmul(C12.rb_mut(), Accum::Add, C11.rb(), B12, -one::<T>(), par);
+		solve_quasi_triangular_sylvester_in_place(A11, B22, C12, par, params.into())
+	} else if m >= n {
+		// $A = [A_{11} A_{12}; 0 A_{22}]$, solve for the bottom rows first
+		let mid = split_point(A, m / 2);
+		let (mut C1, mut C2) = C.rb_mut().split_at_row_mut(mid);
+
+		solve_quasi_triangular_sylvester_in_place(A.submatrix(mid, mid, m - mid, m - mid), B, C2.rb_mut(), par, params.into())?;
+		linalg::matmul::matmul(C1.rb_mut(), Accum::Add, A.submatrix(0, mid, mid, m - mid), C2.rb(), -one::<T>(), par);
+		solve_quasi_triangular_sylvester_in_place(A.submatrix(0, 0, mid, mid), B, C1, par, params.into())
+	} else {
+		// $B = [B_{11} B_{12}; 0 B_{22}]$, solve for the left columns first
+		let mid = split_point(B, n / 2);
+		let (mut C1, mut C2) = C.rb_mut().split_at_col_mut(mid);
+
+		solve_quasi_triangular_sylvester_in_place(A, B.submatrix(0, 0, mid, mid), C1.rb_mut(), par, params.into())?;
+		linalg::matmul::matmul(C2.rb_mut(), Accum::Add, C1.rb(), B.submatrix(0, mid, mid, n - mid), -one::<T>(), par);
+		solve_quasi_triangular_sylvester_in_place(A, B.submatrix(mid, mid, n - mid, n - mid), C2, par, params.into())
+	}
+}
+
+/// computes the size and alignment of the workspace required to solve a quasi-triangular stein
+/// equation
+pub fn solve_quasi_triangular_stein_in_place_scratch<T: ComplexField>(
+	A_dim: usize,
+	B_dim: usize,
+	par: Par,
+	params: Spec<SylvesterParams, T>,
+) -> StackReq {
+	_ = par;
+	let threshold = Ord::max(params.config.blocking_threshold, 2);
+	if A_dim <= threshold && B_dim <= threshold {
+		temp_mat_scratch::<T>(A_dim, 2)
+	} else {
+		temp_mat_scratch::<T>(A_dim, B_dim).or(temp_mat_scratch::<T>(A_dim, 2))
+	}
+}
+
+#[math]
+fn stein_unblocked<T: ComplexField>(
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	C: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+	let mut C = C;
+
+	let (mut W, _) = unsafe { temp_mat_uninit::<T, _, _>(m, 2, stack) };
+	let mut W = W.as_mat_mut();
+
+	let mut l = 0;
+	while l < n {
+		let q = block_size_at(B, l);
+
+		let (Y, rest) = C.rb_mut().split_at_col_mut(l);
+		let mut Cl = rest.subcols_mut(0, q);
+
+		// $W = (Y B)_{:, l}$, which initially only contains the contribution of the columns that
+		// are already solved, $Y_{:, :l} B_{:l, l}$
+		let mut W = W.rb_mut().subcols_mut(0, q);
+		linalg::matmul::matmul(W.rb_mut(), Accum::Replace, Y.rb(), B.submatrix(0, l, l, q), one::<T>(), par);
+
+		let mut k_end = m;
+		while k_end > 0 {
+			let p = block_size_before(A, k_end);
+			let k = k_end - p;
+
+			// $Y_{kl} - A_{kk} Y_{kl} B_{ll} = C_{kl} + A_{k, k:} W_{k:}$
+			let mut X = Cl.rb_mut().subrows_mut(k, p);
+			linalg::matmul::matmul(
+				X.rb_mut(),
+				Accum::Add,
+				A.submatrix(k, k, p, m - k),
+				W.rb().subrows(k, m - k),
+				one::<T>(),
+				par,
+			);
+			solve_small_stein(A.submatrix(k, k, p, p), B.submatrix(l, l, q, q), X.rb_mut())?;
+
+			linalg::matmul::matmul(W.rb_mut().subrows_mut(k, p), Accum::Add, X.rb(), B.submatrix(l, l, q, q), one::<T>(), par);
+
+			k_end = k;
+		}
+
+		l += q;
+	}
+
+	Ok(())
+}
+
+/// solves the stein equation $X - A X B = C$, where $A$ and $B$ are upper quasi-triangular, and
+/// overwrites $C$ with the solution
+///
+/// the diagonal blocks of $A$ and $B$ must be of size $1 \times 1$ or $2 \times 2$, as produced by
+/// the real or complex schur decomposition
+#[math]
+#[track_caller]
+pub fn solve_quasi_triangular_stein_in_place<T: ComplexField>(
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	C: MatMut<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+	assert!(all(A.ncols() == m, B.ncols() == n, C.nrows() == m, C.ncols() == n));
+
+	let params = params.config;
+	let threshold = Ord::max(params.blocking_threshold, 2);
+
+	if m == 0 || n == 0 {
+		return Ok(());
+	}
+	if m <= threshold && n <= threshold {
+		return stein_unblocked(A, B, C, par, stack);
+	}
+
+	let mut C = C;
+	if m >= n {
+		// $A = [A_{11} A_{12}; 0 A_{22}]$, solve for the bottom rows first, then
+		// $X_1 - A_{11} X_1 B = C_1 + A_{12} X_2 B$
+		let mid = split_point(A, m / 2);
+		let (mut C1, mut C2) = C.rb_mut().split_at_row_mut(mid);
+
+		solve_quasi_triangular_stein_in_place(A.submatrix(mid, mid, m - mid, m - mid), B, C2.rb_mut(), par, stack, params.into())?;
+		{
+			let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(mid, n, stack) };
+			let mut tmp = tmp.as_mat_mut();
+			linalg::matmul::matmul(tmp.rb_mut(), Accum::Replace, A.submatrix(0, mid, mid, m - mid), C2.rb(), one::<T>(), par);
+			linalg::matmul::matmul(C1.rb_mut(), Accum::Add, tmp.rb(), B, one::<T>(), par);
+		}
+		solve_quasi_triangular_stein_in_place(A.submatrix(0, 0, mid, mid), B, C1, par, stack, params.into())
+	} else {
+		// $B = [B_{11} B_{12}; 0 B_{22}]$, solve for the left columns first, then
+		// $X_2 - A X_2 B_{22} = C_2 + A X_1 B_{12}$
+		let mid = split_point(B, n / 2);
+		let (mut C1, mut C2) = C.rb_mut().split_at_col_mut(mid);
+
+		solve_quasi_triangular_stein_in_place(A, B.submatrix(0, 0, mid, mid), C1.rb_mut(), par, stack, params.into())?;
+		{
+			let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(m, n - mid, stack) };
+			let mut tmp = tmp.as_mat_mut();
+			linalg::matmul::matmul(tmp.rb_mut(), Accum::Replace, C1.rb(), B.submatrix(0, mid, mid, n - mid), one::<T>(), par);
+			linalg::matmul::matmul(C2.rb_mut(), Accum::Add, A, tmp.rb(), one::<T>(), par);
+		}
+		solve_quasi_triangular_stein_in_place(A, B.submatrix(mid, mid, n - mid, n - mid), C2, par, stack, params.into())
+	}
+}
+
+/// computes $X = U Y V^H$
+#[math]
+fn back_transform<T: ComplexField>(X: MatMut<'_, T>, U: MatRef<'_, T>, Y: MatRef<'_, T>, V: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+	let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(Y.nrows(), Y.ncols(), stack) };
+	let mut tmp = tmp.as_mat_mut();
+	linalg::matmul::matmul(tmp.rb_mut(), Accum::Replace, U, Y, one(), par);
+	linalg::matmul::matmul(X, Accum::Replace, tmp.rb(), V.adjoint(), one(), par);
+}
+
+/// computes $F = U^H C V$, scaled by `alpha`
+#[math]
+fn transform<T: ComplexField>(F: MatMut<'_, T>, U: MatRef<'_, T>, C: MatRef<'_, T>, V: MatRef<'_, T>, alpha: T, par: Par, stack: &mut MemStack) {
+	let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(C.nrows(), C.ncols(), stack) };
+	let mut tmp = tmp.as_mat_mut();
+	linalg::matmul::matmul(tmp.rb_mut(), Accum::Replace, C, V, one(), par);
+	linalg::matmul::matmul(F, Accum::Replace, U.adjoint(), tmp.rb(), alpha, par);
+}
+
+fn two_sided_scratch<T: ComplexField>(m: usize, n: usize, par: Par, stein: bool, params: Spec<SylvesterParams, T>) -> StackReq {
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(m, m).array(2),
+		temp_mat_scratch::<T>(n, n).array(2),
+		temp_mat_scratch::<T>(m, n),
+		StackReq::any_of(&[
+			schur_in_place_scratch::<T>(m, par),
+			schur_in_place_scratch::<T>(n, par),
+			temp_mat_scratch::<T>(m, n),
+			if stein {
+				solve_quasi_triangular_stein_in_place_scratch::<T>(m, n, par, params)
+			} else {
+				StackReq::EMPTY
+			},
+		]),
+	])
+}
+
+#[math]
+fn two_sided_imp<T: ComplexField>(
+	X: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	C: MatRef<'_, T>,
+	stein: bool,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+
+	if m == 0 || n == 0 {
+		return Ok(());
+	}
+
+	let (mut R, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, m, stack) };
+	let (mut U, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, m, stack) };
+	let (mut S, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut V, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut F, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, n, stack) };
+	let mut R = R.as_mat_mut();
+	let mut U = U.as_mat_mut();
+	let mut S = S.as_mat_mut();
+	let mut V = V.as_mat_mut();
+	let mut F = F.as_mat_mut();
+
+	R.copy_from(A);
+	S.copy_from(B);
+	schur_in_place(R.rb_mut(), U.rb_mut(), par, stack).map_err(|_| SylvesterError::NoConvergence)?;
+	schur_in_place(S.rb_mut(), V.rb_mut(), par, stack).map_err(|_| SylvesterError::NoConvergence)?;
+
+	transform(F.rb_mut(), U.rb(), C, V.rb(), one(), par, stack);
+	if stein {
+		solve_quasi_triangular_stein_in_place(R.rb(), S.rb(), F.rb_mut(), par, stack, params)?;
+	} else {
+		solve_quasi_triangular_sylvester_in_place(R.rb(), S.rb(), F.rb_mut(), par, params)?;
+	}
+	back_transform(X, U.rb(), F.rb(), V.rb(), par, stack);
+
+	Ok(())
+}
+
+fn one_sided_scratch<T: ComplexField>(n: usize, par: Par, stein: bool, params: Spec<SylvesterParams, T>) -> StackReq {
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(n, n).array(4),
+		StackReq::any_of(&[
+			schur_in_place_scratch::<T>(n, par),
+			temp_mat_scratch::<T>(n, n),
+			if stein {
+				solve_quasi_triangular_stein_in_place_scratch::<T>(n, n, par, params)
+			} else {
+				StackReq::EMPTY
+			},
+		]),
+	])
+}
+
+#[math]
+fn one_sided_imp<T: ComplexField>(
+	X: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	Q: MatRef<'_, T>,
+	stein: bool,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let n = A.nrows();
+
+	if n == 0 {
+		return Ok(());
+	}
+
+	let (mut R, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut U, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut S, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let (mut F, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, n, stack) };
+	let mut R = R.as_mat_mut();
+	let mut U = U.as_mat_mut();
+	let mut S = S.as_mat_mut();
+	let mut F = F.as_mat_mut();
+
+	R.copy_from(A);
+	schur_in_place(R.rb_mut(), U.rb_mut(), par, stack).map_err(|_| SylvesterError::NoConvergence)?;
+
+	// with $A = U R U^H$, the right hand side coefficient is $R^H$, which is lower
+	// quasi-triangular. reversing the order of its rows and columns makes it upper
+	// quasi-triangular, so the columns of the unknown are reversed as well
+	S.copy_from(R.rb().adjoint().reverse_rows_and_cols());
+
+	if stein {
+		transform(F.rb_mut(), U.rb(), Q, U.rb(), one(), par, stack);
+		solve_quasi_triangular_stein_in_place(R.rb(), S.rb(), F.rb_mut().reverse_cols_mut(), par, stack, params)?;
+	} else {
+		transform(F.rb_mut(), U.rb(), Q, U.rb(), -one::<T>(), par, stack);
+		solve_quasi_triangular_sylvester_in_place(R.rb(), S.rb(), F.rb_mut().reverse_cols_mut(), par, params)?;
+	}
+	back_transform(X, U.rb(), F.rb(), U.rb(), par, stack);
+
+	Ok(())
+}
+
+/// computes the size and alignment of the workspace required to solve a sylvester equation
+pub fn sylvester_scratch<T: ComplexField>(A_dim: usize, B_dim: usize, par: Par, params: Spec<SylvesterParams, T>) -> StackReq {
+	two_sided_scratch::<T>(A_dim, B_dim, par, false, params)
+}
+
+/// solves the sylvester equation $A X + X B = C$, and stores the solution in $X$
+///
+/// the equation has a unique solution if and only if $A$ and $-B$ have no common eigenvalues
+#[track_caller]
+pub fn sylvester<T: ComplexField>(
+	X: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	C: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+	assert!(all(
+		A.ncols() == m,
+		B.ncols() == n,
+		C.nrows() == m,
+		C.ncols() == n,
+		X.nrows() == m,
+		X.ncols() == n,
+	));
+
+	two_sided_imp(X, A, B, C, false, par, stack, params)
+}
+
+/// computes the size and alignment of the workspace required to solve a stein equation
+pub fn stein_scratch<T: ComplexField>(A_dim: usize, B_dim: usize, par: Par, params: Spec<SylvesterParams, T>) -> StackReq {
+	two_sided_scratch::<T>(A_dim, B_dim, par, true, params)
+}
+
+/// solves the stein equation $X - A X B = C$, and stores the solution in $X$
+///
+/// the equation has a unique solution if and only if $\lambda \mu \neq 1$ for all eigenvalues
+/// $\lambda$ of $A$ and $\mu$ of $B$
+#[track_caller]
+pub fn stein<T: ComplexField>(
+	X: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	B: MatRef<'_, T>,
+	C: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let m = A.nrows();
+	let n = B.nrows();
+	assert!(all(
+		A.ncols() == m,
+		B.ncols() == n,
+		C.nrows() == m,
+		C.ncols() == n,
+		X.nrows() == m,
+		X.ncols() == n,
+	));
+
+	two_sided_imp(X, A, B, C, true, par, stack, params)
+}
+
+/// computes the size and alignment of the workspace required to solve a continuous lyapunov
+/// equation
+pub fn lyapunov_scratch<T: ComplexField>(dim: usize, par: Par, params: Spec<SylvesterParams, T>) -> StackReq {
+	one_sided_scratch::<T>(dim, par, false, params)
+}
+
+/// solves the continuous lyapunov equation $A X + X A^H + Q = 0$, and stores the solution in $X$
+///
+/// the equation has a unique solution if and only if $\lambda + \bar{\mu} \neq 0$ for all
+/// eigenvalues $\lambda$, $\mu$ of $A$, which is the case if $A$ is stable. if $Q$ is
+/// self-adjoint, so is $X$
+#[track_caller]
+pub fn lyapunov<T: ComplexField>(
+	X: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	Q: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, Q.nrows() == n, Q.ncols() == n, X.nrows() == n, X.ncols() == n));
+
+	one_sided_imp(X, A, Q, false, par, stack, params)
+}
+
+/// computes the size and alignment of the workspace required to solve a discrete lyapunov
+/// equation
+pub fn discrete_lyapunov_scratch<T: ComplexField>(dim: usize, par: Par, params: Spec<SylvesterParams, T>) -> StackReq {
+	one_sided_scratch::<T>(dim, par, true, params)
+}
+
+/// solves the discrete lyapunov equation $A X A^H - X + Q = 0$, and stores the solution in $X$
+///
+/// the equation has a unique solution if and only if $\lambda \bar{\mu} \neq 1$ for all
+/// eigenvalues $\lambda$, $\mu$ of $A$, which is the case if $A$ is stable. if $Q$ is
+/// self-adjoint, so is $X$
+#[track_caller]
+pub fn discrete_lyapunov<T: ComplexField>(
+	X: MatMut<'_, T>,
+	A: MatRef<'_, T>,
+	Q: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+	params: Spec<SylvesterParams, T>,
+) -> Result<(), SylvesterError> {
+	let n = A.nrows();
+	assert!(all(A.ncols() == n, Q.nrows() == n, Q.ncols() == n, X.nrows() == n, X.ncols() == n));
+
+	one_sided_imp(X, A, Q, true, par, stack, params)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	fn random_real(m: usize, n: usize, rng: &mut StdRng) -> Mat<f64> {
+		CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng)
+	}
+
+	fn random_cplx(m: usize, n: usize, rng: &mut StdRng) -> Mat<c64> {
+		CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng)
+	}
+
+	#[test]
+	fn test_sylvester_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(0, 3), (3, 0), (1, 1), (2, 3), (5, 4), (10, 10), (33, 17), (64, 80)] {
+			for threshold in [2, 8, 32] {
+				let params = SylvesterParams {
+					blocking_threshold: threshold,
+					..auto!(f64)
+				};
+
+				// shift the spectra so that $A$ and $-B$ have no eigenvalues in common
+				let A = random_real(m, m, rng) + Mat::<f64>::identity(m, m) * Scale(2.0 * (m as f64).sqrt() + 1.0);
+				let B = random_real(n, n, rng) + Mat::<f64>::identity(n, n) * Scale(2.0 * (n as f64).sqrt() + 1.0);
+				let C = random_real(m, n, rng);
+
+				let mut X = Mat::zeros(m, n);
+				sylvester(
+					X.as_mut(),
+					A.as_ref(),
+					B.as_ref(),
+					C.as_ref(),
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(sylvester_scratch::<f64>(m, n, Par::Seq, params.into()))),
+					params.into(),
+				)
+				.unwrap();
+
+				let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (m + n + 1) as f64);
+				assert!(&A * &X + &X * &B ~ C);
+			}
+		}
+	}
+
+	#[test]
+	fn test_sylvester_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(1, 1), (2, 3), (5, 4), (10, 10), (33, 17), (64, 80)] {
+			let A = random_cplx(m, m, rng) + Mat::<c64>::identity(m, m) * Scale(c64::new(2.0 * (m as f64).sqrt() + 1.0, 0.0));
+			let B = random_cplx(n, n, rng) + Mat::<c64>::identity(n, n) * Scale(c64::new(2.0 * (n as f64).sqrt() + 1.0, 0.0));
+			let C = random_cplx(m, n, rng);
+
+			let params = SylvesterParams {
+				blocking_threshold: 8,
+				..auto!(c64)
+			};
+
+			let mut X = Mat::zeros(m, n);
+			sylvester(
+				X.as_mut(),
+				A.as_ref(),
+				B.as_ref(),
+				C.as_ref(),
+				Par::rayon(4),
+				MemStack::new(&mut MemBuffer::new(sylvester_scratch::<c64>(m, n, Par::rayon(4), params.into()))),
+				params.into(),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (m + n + 1) as f64);
+			assert!(&A * &X + &X * &B ~ C);
+		}
+	}
+
+	#[test]
+	fn test_stein() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(0, 3), (1, 1), (2, 3), (5, 4), (10, 10), (33, 17), (64, 80)] {
+			let params = SylvesterParams {
+				blocking_threshold: 8,
+				..auto!(f64)
+			};
+
+			// scale the spectra inside the unit disk so that $\lambda \mu \neq 1$
+			let A = random_real(m, m, rng) * Scale(0.5 / ((m as f64).sqrt() + 1.0));
+			let B = random_real(n, n, rng) * Scale(0.5 / ((n as f64).sqrt() + 1.0));
+			let C = random_real(m, n, rng);
+
+			let mut X = Mat::zeros(m, n);
+			stein(
+				X.as_mut(),
+				A.as_ref(),
+				B.as_ref(),
+				C.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(stein_scratch::<f64>(m, n, Par::Seq, params.into()))),
+				params.into(),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (m + n + 1) as f64);
+			assert!(&X - &A * &X * &B ~ C);
+
+			let A = random_cplx(m, m, rng) * Scale(c64::new(0.5 / ((m as f64).sqrt() + 1.0), 0.0));
+			let B = random_cplx(n, n, rng) * Scale(c64::new(0.5 / ((n as f64).sqrt() + 1.0), 0.0));
+			let C = random_cplx(m, n, rng);
+
+			let mut X = Mat::zeros(m, n);
+			stein(
+				X.as_mut(),
+				A.as_ref(),
+				B.as_ref(),
+				C.as_ref(),
+				Par::rayon(4),
+				MemStack::new(&mut MemBuffer::new(stein_scratch::<c64>(m, n, Par::rayon(4), default()))),
+				default(),
+			)
+			.unwrap();
+
+			assert!(&X - &A * &X * &B ~ C);
+		}
+	}
+
+	#[test]
+	fn test_lyapunov() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			// stable matrix, with all eigenvalues in the left half plane
+			let A = random_real(n, n, rng) - Mat::<f64>::identity(n, n) * Scale(2.0 * (n as f64).sqrt() + 1.0);
+			let B = random_real(n, n, rng);
+			let Q = &B * B.transpose();
+
+			let mut X = Mat::zeros(n, n);
+			lyapunov(
+				X.as_mut(),
+				A.as_ref(),
+				Q.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(lyapunov_scratch::<f64>(n, Par::Seq, default()))),
+				default(),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n + 1) as f64);
+			assert!(&A * &X + &X * A.transpose() + &Q ~ Mat::<f64>::zeros(n, n));
+			assert!(X.transpose().to_owned() ~ X);
+
+			let A = random_cplx(n, n, rng) - Mat::<c64>::identity(n, n) * Scale(c64::new(2.0 * (n as f64).sqrt() + 1.0, 0.0));
+			let B = random_cplx(n, n, rng);
+			let Q = &B * B.adjoint();
+
+			let mut X = Mat::zeros(n, n);
+			lyapunov(
+				X.as_mut(),
+				A.as_ref(),
+				Q.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(lyapunov_scratch::<c64>(n, Par::Seq, default()))),
+				default(),
+			)
+			.unwrap();
+
+			assert!(&A * &X + &X * A.adjoint() + &Q ~ Mat::<c64>::zeros(n, n));
+			assert!(X.adjoint().to_owned() ~ X);
+		}
+	}
+
+	#[test]
+	fn test_discrete_lyapunov() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for n in [0, 1, 2, 3, 5, 10, 33, 64] {
+			let A = random_real(n, n, rng) * Scale(0.5 / ((n as f64).sqrt() + 1.0));
+			let B = random_real(n, n, rng);
+			let Q = &B * B.transpose();
+
+			let mut X = Mat::zeros(n, n);
+			discrete_lyapunov(
+				X.as_mut(),
+				A.as_ref(),
+				Q.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(discrete_lyapunov_scratch::<f64>(n, Par::Seq, default()))),
+				default(),
+			)
+			.unwrap();
+
+			let approx_eq = CwiseMat(ApproxEq::eps() * 128.0 * (n + 1) as f64);
+			assert!(&A * &X * A.transpose() - &X + &Q ~ Mat::<f64>::zeros(n, n));
+
+			let A = random_cplx(n, n, rng) * Scale(c64::new(0.5 / ((n as f64).sqrt() + 1.0), 0.0));
+			let B = random_cplx(n, n, rng);
+			let Q = &B * B.adjoint();
+
+			let mut X = Mat::zeros(n, n);
+			discrete_lyapunov(
+				X.as_mut(),
+				A.as_ref(),
+				Q.as_ref(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(discrete_lyapunov_scratch::<c64>(n, Par::Seq, default()))),
+				default(),
+			)
+			.unwrap();
+
+			assert!(&A * &X * A.adjoint() - &X + &Q ~ Mat::<c64>::zeros(n, n));
+		}
+	}
+
+	#[test]
+	fn test_sylvester_singular() {
+		// $A$ and $-B$ share the eigenvalue $1$
+		let A = crate::mat![[1.0, 2.0], [0.0, 3.0f64]];
+		let B = crate::mat![[-1.0f64]];
+		let C = crate::mat![[1.0], [1.0f64]];
+
+		let mut X = Mat::zeros(2, 1);
+		let res = sylvester(
+			X.as_mut(),
+			A.as_ref(),
+			B.as_ref(),
+			C.as_ref(),
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(sylvester_scratch::<f64>(2, 1, Par::Seq, default()))),
+			default(),
+		);
+		assert!(res == Err(SylvesterError::Singular));
+	}
+}
@@ -1,10 +1,32 @@
 use super::*;
 use crate::assert;
 use crate::linalg::matmul::matmul;
+use crate::linalg::solvers::SolveCore;
 use linalg::evd::schur;
 
 const MIN_DIM: usize = 32;
 
+/// part of the spectrum targeted by the partial eigendecomposition.
+///
+/// for real operators, complex conjugate eigenvalue pairs are ranked by the magnitude of their
+/// imaginary part.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum EigenTarget {
+	/// eigenvalues with the largest magnitude.
+	#[default]
+	LargestMagnitude,
+	/// eigenvalues with the smallest magnitude.
+	SmallestMagnitude,
+	/// eigenvalues with the largest real part.
+	LargestRealPart,
+	/// eigenvalues with the smallest real part.
+	SmallestRealPart,
+	/// eigenvalues with the largest imaginary part.
+	LargestImaginaryPart,
+	/// eigenvalues with the smallest imaginary part.
+	SmallestImaginaryPart,
+}
+
 /// partial eigendecomposition tuning parameters.
 #[derive(Debug, Copy, Clone)]
 pub struct PartialEigenParams {
@@ -14,6 +36,8 @@ pub struct PartialEigenParams {
 	pub max_dim: usize,
 	/// maximum number of algorithm restarts.
 	pub max_restarts: usize,
+	/// part of the spectrum that should be computed.
+	pub which: EigenTarget,
 
 	#[doc(hidden)]
 	pub non_exhaustive: NonExhaustive,
@@ -29,17 +53,38 @@ pub struct PartialEigenInfo {
 	pub non_exhaustive: NonExhaustive,
 }
 
+/// partial eigendecomposition error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PartialEigenError {
+	/// the targeted part of the spectrum is not supported by the algorithm.
+	UnsupportedTarget,
+}
+
 impl Default for PartialEigenParams {
 	fn default() -> Self {
 		Self {
 			min_dim: 0,
 			max_dim: 0,
 			max_restarts: 1000,
+			which: EigenTarget::LargestMagnitude,
 			non_exhaustive: NonExhaustive(()),
 		}
 	}
 }
 
+/// returns a key such that the targeted eigenvalues have the largest keys.
+#[math]
+fn target_priority<T: RealField>(which: EigenTarget, re: &T, im: &T) -> T {
+	match which {
+		EigenTarget::LargestMagnitude => hypot(*re, *im),
+		EigenTarget::SmallestMagnitude => -hypot(*re, *im),
+		EigenTarget::LargestRealPart => copy(*re),
+		EigenTarget::SmallestRealPart => -*re,
+		EigenTarget::LargestImaginaryPart => copy(*im),
+		EigenTarget::SmallestImaginaryPart => -*im,
+	}
+}
+
 #[math]
 fn iterate_arnoldi<T: ComplexField>(A: &dyn LinOp<T>, H: MatMut<'_, T>, V: MatMut<'_, T>, start: usize, end: usize, par: Par, stack: &mut MemStack) {
 	let mut V = V;
@@ -301,6 +346,7 @@ fn partial_schur_real_imp<T: RealField>(
 	n_eigval: usize,
 	tol: T,
 	restarts: usize,
+	which: EigenTarget,
 	par: Par,
 	stack: &mut MemStack,
 ) -> usize {
@@ -407,9 +453,10 @@ fn partial_schur_real_imp<T: RealField>(
 				let mut max = zero::<T>();
 				while i < n {
 					let cplx = i + 1 < n && H_slice[(i + 1, i)] != zero::<T>();
-					let (v, bs) = if cplx { (hypot(w_re[i], w_im[i]), 2) } else { (abs(w_re[i]), 1) };
+					let bs = if cplx { 2 } else { 1 };
+					let v = target_priority(which, &w_re[i], &abs(w_im[i]));
 
-					if v > max {
+					if i == j || v > max {
 						max = v;
 						idx = i;
 					}
@@ -472,29 +519,53 @@ fn partial_schur_real_imp<T: RealField>(
 
 			let mut groups = alloc::vec![Group::Purge; max_dim];
 
-			let mut nev = n_eigval;
-			if w_im[nev - active - 1] > zero::<T>() {
-				nev += 1;
+			// rank all the ritz values against the target, including the ones that were locked
+			// previously, so that a locked ritz value is purged once better ones converge
+			let mut blocks = alloc::vec::Vec::new();
+			let mut i = 0usize;
+			while i < max_dim {
+				let cplx = i + 1 < max_dim && H[(i + 1, i)] != zero::<T>();
+				let bs = if cplx { 2 } else { 1 };
+				let im = if cplx {
+					sqrt(abs(H[(i + 1, i)])) * sqrt(abs(H[(i, i + 1)]))
+				} else {
+					zero::<T>()
+				};
+				blocks.push((i, bs, target_priority(which, &H[(i, i)], &im)));
+				i += bs;
 			}
+			blocks.sort_by(|x, y| {
+				if x.2 > y.2 {
+					core::cmp::Ordering::Less
+				} else if x.2 < y.2 {
+					core::cmp::Ordering::Greater
+				} else {
+					core::cmp::Ordering::Equal
+				}
+			});
 
+			let mut nev = 0usize;
 			let mut nlock = 0usize;
-			for j in 0..nev {
-				if residual[j] <= tol {
-					groups[j] = Group::Lock;
-					nlock += 1;
+			let mut b = 0usize;
+			while nev < n_eigval {
+				let (i, bs, _) = blocks[b];
+				let group = if residual[i] <= tol {
+					nlock += bs;
+					Group::Lock
 				} else {
-					groups[j] = Group::Retain;
+					Group::Retain
+				};
+				for k in 0..bs {
+					groups[i + k] = group;
 				}
+				nev += bs;
+				b += 1;
 			}
 
 			let ideal_size = Ord::min(nlock + min_dim, (min_dim + max_dim) / 2);
 			k = nev;
 
-			let mut i = nev;
-			while i < max_dim {
-				let cplx = w[(i, 1)] != zero::<T>();
-				let bs = if cplx { 2 } else { 1 };
-
+			for &(i, bs, _) in &blocks[b..] {
 				let group;
 				if k < ideal_size && residual[i] > tol {
 					group = Group::Retain;
@@ -506,7 +577,6 @@ fn partial_schur_real_imp<T: RealField>(
 				for k in 0..bs {
 					groups[i + k] = group;
 				}
-				i += bs;
 			}
 
 			let mut purge = 0usize;
@@ -558,6 +628,9 @@ fn partial_schur_real_imp<T: RealField>(
 			let mut b_tmp = tmp.rb_mut().get_mut(0, ..);
 			matmul(b_tmp.rb_mut(), Accum::Replace, H.rb().get(max_dim, ..), Q.rb(), one(), par);
 			H.rb_mut().get_mut(max_dim, ..).copy_from(b_tmp);
+			// the locked ritz vectors are deflated, which keeps the leading block upper
+			// quasi-triangular if one of them is purged later
+			H.rb_mut().get_mut(max_dim, ..nlock).fill(zero());
 
 			let (mut x, y) = V.rb_mut().two_cols_mut(k, max_dim);
 			x.copy_from(&y);
@@ -643,13 +716,13 @@ fn partial_schur_real_imp<T: RealField>(
 
 		if cplx {
 			let im = sqrt(abs(H[(j + 1, j)])) * sqrt(abs(H[(j, j + 1)]));
-			norms[j] = hypot(*re, im);
+			norms[j] = target_priority(which, re, &im);
 			norms[j + 1] = copy(norms[j]);
 
 			perm[j] = j;
 			perm[j + 1] = j;
 		} else {
-			norms[j] = abs(*re);
+			norms[j] = target_priority(which, re, &zero());
 			perm[j] = j;
 		}
 		j += bs;
@@ -718,6 +791,7 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 	n_eigval: usize,
 	tol: T::Real,
 	restarts: usize,
+	which: EigenTarget,
 	par: Par,
 	stack: &mut MemStack,
 ) -> usize {
@@ -810,9 +884,9 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 				let mut idx = j;
 				let mut max = zero::<T::Real>();
 				for i in j..n {
-					let v = abs(w[i]);
+					let v = target_priority(which, &real(w[i]), &imag(w[i]));
 
-					if v > max {
+					if i == j || v > max {
 						max = v;
 						idx = i;
 					}
@@ -852,10 +926,26 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 
 			let mut groups = alloc::vec![Group::Purge; max_dim];
 
+			// rank all the ritz values against the target, including the ones that were locked
+			// previously, so that a locked ritz value is purged once better ones converge
+			let mut order = (0..max_dim).collect::<alloc::vec::Vec<_>>();
+			let priority = (0..max_dim)
+				.map(|i| target_priority(which, &real(H[(i, i)]), &imag(H[(i, i)])))
+				.collect::<alloc::vec::Vec<_>>();
+			order.sort_by(|&i, &j| {
+				if priority[i] > priority[j] {
+					core::cmp::Ordering::Less
+				} else if priority[i] < priority[j] {
+					core::cmp::Ordering::Greater
+				} else {
+					core::cmp::Ordering::Equal
+				}
+			});
+
 			let nev = n_eigval;
 
 			let mut nlock = 0usize;
-			for j in 0..nev {
+			for &j in &order[..nev] {
 				if residual[j] <= tol {
 					groups[j] = Group::Lock;
 					nlock += 1;
@@ -867,7 +957,7 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 			let ideal_size = Ord::min(nlock + min_dim, (min_dim + max_dim) / 2);
 			k = nev;
 
-			for i in nev..max_dim {
+			for &i in &order[nev..] {
 				let group;
 				if k < ideal_size && residual[i] > tol {
 					group = Group::Retain;
@@ -923,6 +1013,9 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 			let mut b_tmp = tmp.rb_mut().get_mut(0, ..);
 			matmul(b_tmp.rb_mut(), Accum::Replace, H.rb().get(max_dim, ..), Q.rb(), one(), par);
 			H.rb_mut().get_mut(max_dim, ..).copy_from(b_tmp);
+			// the locked ritz vectors are deflated, which keeps the leading block upper
+			// quasi-triangular if one of them is purged later
+			H.rb_mut().get_mut(max_dim, ..nlock).fill(zero());
 
 			let (mut x, y) = V.rb_mut().two_cols_mut(k, max_dim);
 			x.copy_from(&y);
@@ -983,7 +1076,7 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 
 	let V = vecs.rb();
 
-	let (mut norms, stack) = stack.make_with(n, |j| abs(H[(j, j)]));
+	let (mut norms, stack) = stack.make_with(n, |j| target_priority(which, &real(H[(j, j)]), &imag(H[(j, j)])));
 	let (mut perm, stack) = stack.make_with(n, |j| j);
 	let _ = stack;
 
@@ -1015,7 +1108,7 @@ fn partial_schur_cplx_imp<T: ComplexField>(
 }
 
 /// computes the size and alignment of required workspace for computing the `n_eigval` eigenvalues
-/// (and corresponding eigenvectors) of $A$ with the largest magnitude.
+/// (and corresponding eigenvectors) of $A$ targeted by `params.which`.
 pub fn partial_eigen_scratch<T: ComplexField>(A: &dyn LinOp<T>, n_eigval: usize, par: Par, params: PartialEigenParams) -> StackReq {
 	let n = A.nrows();
 	assert!(A.ncols() == n);
@@ -1058,9 +1151,9 @@ pub fn partial_eigen_scratch<T: ComplexField>(A: &dyn LinOp<T>, n_eigval: usize,
 	])
 }
 
-/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of $A$ with the largest
-/// magnitude until the provided outputs are full or the maximum number of algorithm restarts is
-/// reached.
+/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of $A$ targeted by
+/// `params.which` until the provided outputs are full or the maximum number of algorithm restarts
+/// is reached.
 pub fn partial_eigen<T: ComplexField>(
 	eigvecs: MatMut<'_, Complex<T::Real>>,
 	eigvals: &mut [Complex<T::Real>],
@@ -1102,6 +1195,7 @@ pub fn partial_eigen<T: ComplexField>(
 			n_eigval,
 			tolerance,
 			params.max_restarts,
+			params.which,
 			par,
 			stack,
 		)
@@ -1116,6 +1210,7 @@ pub fn partial_eigen<T: ComplexField>(
 			n_eigval,
 			tolerance,
 			params.max_restarts,
+			params.which,
 			par,
 			stack,
 		)
@@ -1127,6 +1222,473 @@ pub fn partial_eigen<T: ComplexField>(
 	}
 }
 
+/// shift-invert spectral transformation $(A - \sigma I)^{-1}$, where the inverse is applied with a
+/// user-provided factorization of $A - \sigma I$ (e.g. a dense or sparse $LU$ or cholesky
+/// decomposition).
+///
+/// the eigenvalues of $A$ closest to $\sigma$ are the eigenvalues of the transformed operator
+/// with the largest magnitude.
+#[derive(Debug)]
+pub struct ShiftInvert<'a, T: ComplexField, S: SolveCore<T>> {
+	solver: &'a S,
+	shift: T,
+}
+
+impl<'a, T: ComplexField, S: SolveCore<T>> ShiftInvert<'a, T, S> {
+	/// creates a shift-invert operator from a factorization of $A - \sigma I$ and the shift
+	/// $\sigma$.
+	#[track_caller]
+	pub fn new(solver: &'a S, shift: T) -> Self {
+		assert!(solver.nrows() == solver.ncols());
+		Self { solver, shift }
+	}
+
+	/// returns the shift $\sigma$.
+	#[inline]
+	pub fn shift(&self) -> &T {
+		&self.shift
+	}
+}
+
+impl<T: ComplexField, S: SolveCore<T> + Sync + core::fmt::Debug> LinOp<T> for ShiftInvert<'_, T, S> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.solver.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.solver.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solver.solve_in_place_with_conj(Conj::No, out);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		let mut out = out;
+		out.copy_from(rhs);
+		self.solver.solve_in_place_with_conj(Conj::Yes, out);
+	}
+}
+
+/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of $A$ closest to the
+/// shift $\sigma$, using the shift-invert operator `op`.
+///
+/// `params.which` applies to the eigenvalues $\mu$ of the transformed operator, and the returned
+/// eigenvalues are mapped back to $\lambda = \sigma + 1 / \mu$. the required workspace is given by
+/// [`partial_eigen_scratch`] called with `op`.
+#[math]
+pub fn partial_eigen_shift_invert<T: ComplexField, S: SolveCore<T> + Sync + core::fmt::Debug>(
+	eigvecs: MatMut<'_, Complex<T::Real>>,
+	eigvals: &mut [Complex<T::Real>],
+	op: &ShiftInvert<'_, T, S>,
+	v0: ColRef<'_, T>,
+	tolerance: T::Real,
+	par: Par,
+	stack: &mut MemStack,
+	params: PartialEigenParams,
+) -> PartialEigenInfo {
+	let info = partial_eigen(eigvecs, eigvals, op, v0, tolerance, par, stack, params);
+
+	let shift = Complex::new(real(op.shift), imag(op.shift));
+	for w in &mut eigvals[..info.n_converged_eigen] {
+		*w = shift + recip(*w);
+	}
+	info
+}
+
+/// runs the lanczos iteration with full reorthogonalization, extending the basis $V$ from the
+/// `start`-th column up to the `end`-th one, and stores the diagonal and off-diagonal of the
+/// projected tridiagonal matrix in `alpha` and `beta`.
+#[math]
+fn iterate_lanczos<T: ComplexField>(
+	A: &dyn LinOp<T>,
+	alpha: ColMut<'_, T::Real>,
+	beta: ColMut<'_, T::Real>,
+	V: MatMut<'_, T>,
+	start: usize,
+	end: usize,
+	par: Par,
+	stack: &mut MemStack,
+) {
+	let n = A.nrows();
+	let mut V = V;
+	let mut alpha = alpha;
+	let mut beta = beta;
+
+	for j in start..end {
+		let (V, Vnext) = V.rb_mut().split_at_col_mut(j + 1);
+		let V = V.rb();
+
+		let mut Vnext = Vnext.col_mut(0);
+		A.apply(Vnext.rb_mut().as_mat_mut(), V.col(j).as_mat(), par, stack);
+
+		let (mut converged, _) = stack.collect(core::iter::repeat_n(false, j + 1));
+		let f = from_f64::<T::Real>(Ord::max(j + 1, 8) as f64) * eps::<T::Real>();
+		let prev = if j > 0 { abs(beta[j - 1]) } else { zero() };
+
+		// the projections onto the basis vectors other than the last two vanish in exact
+		// arithmetic, so they are only used to maintain orthogonality
+		let mut a = zero::<T>();
+		for i in 0..j + 1 {
+			let r = V.col(i).adjoint() * Vnext.rb();
+			zip!(Vnext.rb_mut(), V.col(i)).for_each(|unzip!(y, x)| *y = *y - r * *x);
+			if i == j {
+				a = r;
+			}
+		}
+		loop {
+			// stop early if the iteration broke down, since the remaining noise can't be
+			// orthogonalized
+			if Vnext.norm_l2() <= eps::<T::Real>() * (abs(a) + prev) {
+				break;
+			}
+
+			let mut all_true = true;
+			for i in 0..j + 1 {
+				if !converged[i] {
+					all_true = false;
+
+					let r = V.col(i).adjoint() * Vnext.rb();
+					zip!(Vnext.rb_mut(), V.col(i)).for_each(|unzip!(y, x)| *y = *y - r * *x);
+					if i == j {
+						a = a + r;
+					}
+
+					converged[i] = abs(r) < f * Vnext.norm_l2();
+				}
+			}
+			if all_true {
+				break;
+			}
+		}
+		alpha[j] = real(a);
+
+		let norm = Vnext.norm_l2();
+
+		if norm > eps::<T::Real>() * (abs(alpha[j]) + prev) {
+			let norm_inv = recip(norm);
+			zip!(&mut Vnext).for_each(|unzip!(v)| *v = mul_real(*v, norm_inv));
+			beta[j] = norm;
+		} else {
+			// the krylov subspace is invariant, so we continue with an arbitrary vector orthogonal
+			// to the current basis
+			beta[j] = zero();
+			Vnext.fill(zero());
+
+			if j + 1 < n {
+				// at least one of the unit vectors has a component of norm at least $1/\sqrt{n}$
+				// orthogonal to the basis
+				let threshold = from_f64::<T::Real>(0.5) * recip(sqrt(from_f64::<T::Real>(n as f64)));
+				for t in 0..n {
+					Vnext.fill(zero());
+					Vnext[(j + 1 + t) % n] = one();
+					for _ in 0..2 {
+						for i in 0..j + 1 {
+							let r = V.col(i).adjoint() * Vnext.rb();
+							zip!(Vnext.rb_mut(), V.col(i)).for_each(|unzip!(y, x)| *y = *y - r * *x);
+						}
+					}
+
+					let norm = Vnext.norm_l2();
+					if norm >= threshold {
+						let norm_inv = recip(norm);
+						zip!(&mut Vnext).for_each(|unzip!(v)| *v = mul_real(*v, norm_inv));
+						break;
+					}
+				}
+			}
+		}
+	}
+}
+
+/// computes the size and alignment of required workspace for computing the `n_eigval` eigenvalues
+/// (and corresponding eigenvectors) of the self-adjoint operator $A$ targeted by `params.which`.
+pub fn partial_self_adjoint_eigen_scratch<T: ComplexField>(A: &dyn LinOp<T>, n_eigval: usize, par: Par, params: PartialEigenParams) -> StackReq {
+	let n = A.nrows();
+	assert!(A.ncols() == n);
+	if n == 0 {
+		return StackReq::EMPTY;
+	}
+
+	let n_eigval = Ord::min(n_eigval, n);
+
+	let max_dim = Ord::min(Ord::max(params.max_dim, Ord::max(2 * MIN_DIM, 2 * n_eigval)), n);
+
+	let V = temp_mat_scratch::<T>(n, max_dim + 1);
+	let tmp = temp_mat_scratch::<T>(n, max_dim);
+	let Z = temp_mat_scratch::<T>(max_dim, max_dim);
+	let alpha = temp_mat_scratch::<T::Real>(max_dim, 1);
+	let beta = alpha;
+	let diag = alpha;
+	let offdiag = alpha;
+	let residual = alpha;
+	let Y = temp_mat_scratch::<T::Real>(max_dim, max_dim);
+	let C = Y;
+	let M = temp_mat_scratch::<T::Real>(max_dim + 1, max_dim + 1);
+	let key = StackReq::new::<T::Real>(max_dim);
+	let perm = StackReq::new::<usize>(max_dim);
+
+	let blocksize = linalg::qr::no_pivoting::factor::recommended_blocksize::<T::Real>(max_dim + 1, max_dim + 1);
+	let householder = temp_mat_scratch::<T::Real>(blocksize, max_dim);
+	let lanczos = A.apply_scratch(1, par).or(StackReq::new::<bool>(max_dim));
+
+	let tridiag = linalg::evd::tridiag::tridiag_in_place_scratch::<T::Real>(max_dim + 1, par, default());
+	let apply_house = linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T::Real>(max_dim, blocksize, max_dim + 1);
+
+	StackReq::all_of(&[
+		V,
+		tmp,
+		Z,
+		alpha,
+		beta,
+		diag,
+		offdiag,
+		residual,
+		Y,
+		C,
+		M,
+		householder,
+		key,
+		perm,
+		StackReq::any_of(&[lanczos, tridiag, apply_house]),
+	])
+}
+
+/// computes an estimate of the eigenvalues (and corresponding eigenvectors) of the self-adjoint
+/// operator $A$ targeted by `params.which` until the provided outputs are full or the maximum
+/// number of algorithm restarts is reached, using the thick-restart lanczos algorithm.
+///
+/// the eigenvalues are sorted so that the most wanted ones come first.
+///
+/// returns [`PartialEigenError::UnsupportedTarget`] if `params.which` targets the imaginary part of
+/// the eigenvalues.
+#[math]
+pub fn partial_self_adjoint_eigen<T: ComplexField>(
+	eigvecs: MatMut<'_, T>,
+	eigvals: &mut [T::Real],
+	A: &dyn LinOp<T>,
+	v0: ColRef<'_, T>,
+	tolerance: T::Real,
+	par: Par,
+	stack: &mut MemStack,
+	params: PartialEigenParams,
+) -> Result<PartialEigenInfo, PartialEigenError> {
+	let n = v0.nrows();
+	assert!(all(
+		eigvals.len() == eigvecs.ncols(),
+		A.nrows() == n,
+		A.ncols() == n,
+		eigvecs.nrows() == n,
+	));
+	if matches!(params.which, EigenTarget::LargestImaginaryPart | EigenTarget::SmallestImaginaryPart) {
+		return Err(PartialEigenError::UnsupportedTarget);
+	}
+	let n_eigval = eigvals.len();
+	let n_eigval = Ord::min(n_eigval, n);
+
+	if n_eigval == 0 {
+		return Ok(PartialEigenInfo {
+			n_converged_eigen: 0,
+			non_exhaustive: NonExhaustive(()),
+		});
+	}
+
+	let min_dim = Ord::min(Ord::max(params.min_dim, Ord::max(MIN_DIM, n_eigval)), n);
+	let max_dim = Ord::min(Ord::max(params.max_dim, Ord::max(2 * MIN_DIM, 2 * n_eigval)), n);
+	let which = params.which;
+	let tol = tolerance;
+
+	let (mut V, stack) = temp_mat_zeroed::<T, _, _>(n, max_dim + 1, stack);
+	let mut V = V.as_mat_mut();
+	let (mut tmp, stack) = temp_mat_zeroed::<T, _, _>(n, max_dim, stack);
+	let mut tmp = tmp.as_mat_mut();
+	let (mut Z, stack) = temp_mat_zeroed::<T, _, _>(max_dim, max_dim, stack);
+	let mut Z = Z.as_mat_mut();
+
+	let (mut alpha, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, 1, stack);
+	let mut alpha = alpha.as_mat_mut().col_mut(0);
+	let (mut beta, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, 1, stack);
+	let mut beta = beta.as_mat_mut().col_mut(0);
+	let (mut diag, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, 1, stack);
+	let mut diag = diag.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap();
+	let (mut offdiag, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, 1, stack);
+	let mut offdiag = offdiag.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap();
+	let (mut residual, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, 1, stack);
+	let mut residual = residual.as_mat_mut().col_mut(0);
+
+	let (mut Y, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, max_dim, stack);
+	let mut Y = Y.as_mat_mut();
+	let (mut C, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim, max_dim, stack);
+	let mut C = C.as_mat_mut();
+	let (mut M, stack) = temp_mat_zeroed::<T::Real, _, _>(max_dim + 1, max_dim + 1, stack);
+	let mut M = M.as_mat_mut();
+
+	let blocksize = linalg::qr::no_pivoting::factor::recommended_blocksize::<T::Real>(max_dim + 1, max_dim + 1);
+	let (mut householder, stack) = temp_mat_zeroed::<T::Real, _, _>(blocksize, max_dim, stack);
+	let mut householder = householder.as_mat_mut();
+
+	let (mut key, stack) = stack.make_with(max_dim, |_| zero::<T::Real>());
+	let (mut perm, stack) = stack.make_with(max_dim, |j| j);
+	let key = &mut *key;
+	let perm = &mut *perm;
+
+	let f = v0.norm_l2();
+	if f > min_positive() {
+		let f = recip(f);
+		zip!(V.rb_mut().col_mut(0), v0).for_each(|unzip!(y, x)| *y = mul_real(*x, f));
+	} else {
+		let n0 = n as u32;
+		let n1 = (n >> 32) as u32;
+
+		let n = from_f64::<T>(n0 as f64) + from_f64::<T>(n1 as f64);
+		let f = recip(sqrt(n));
+
+		zip!(V.rb_mut().col_mut(0)).for_each(|unzip!(y)| *y = copy(f));
+	}
+
+	let m = max_dim;
+	let mut k = 0usize;
+	let mut iter = 0usize;
+	let mut n_conv;
+
+	loop {
+		iterate_lanczos(A, alpha.rb_mut(), beta.rb_mut(), V.rb_mut(), k, m, par, stack);
+
+		// eigendecomposition of the projected tridiagonal matrix
+		diag.copy_from(&alpha);
+		offdiag.copy_from(&beta);
+		offdiag[m - 1] = zero();
+		if linalg::evd::tridiag_evd::qr_algorithm(diag.rb_mut(), offdiag.rb_mut(), Some(Y.rb_mut())).is_err() {
+			return Ok(PartialEigenInfo {
+				n_converged_eigen: 0,
+				non_exhaustive: NonExhaustive(()),
+			});
+		}
+
+		// AV = VT + f e*
+		// x = V y
+		// Ax = theta x + f * y[-1]
+		let beta_m = abs(beta[m - 1]);
+		for j in 0..m {
+			residual[j] = beta_m * abs(Y[(m - 1, j)]);
+			key[j] = target_priority(which, &diag[j], &zero());
+			perm[j] = j;
+		}
+		perm.sort_unstable_by(|&i, &j| {
+			if key[i] > key[j] {
+				core::cmp::Ordering::Less
+			} else if key[i] < key[j] {
+				core::cmp::Ordering::Greater
+			} else {
+				core::cmp::Ordering::Equal
+			}
+		});
+
+		n_conv = perm[..n_eigval].iter().filter(|&&j| residual[j] <= tol).count();
+		if n_conv == n_eigval || iter == params.max_restarts {
+			break;
+		}
+		iter += 1;
+
+		// keep the most wanted ritz vectors
+		k = Ord::min(n_conv + min_dim, (min_dim + max_dim) / 2);
+		k = Ord::min(Ord::max(k, n_eigval), m - 1);
+
+		for (i, &j) in perm[..k].iter().enumerate() {
+			C.rb_mut().col_mut(i).get_mut(..m).copy_from(Y.rb().col(j).get(..m));
+		}
+
+		// A [U v] = [U v] [diag(theta) s; s* alpha] + ...
+		//
+		// reduce the leading block to tridiagonal form while keeping the residual vector $v$
+		// fixed, by tridiagonalizing it with the indices reversed
+		let mut Mk = M.rb_mut().get_mut(..k + 1, ..k + 1);
+		Mk.fill(zero());
+		for p in 1..k + 1 {
+			let j = perm[k - p];
+			Mk[(p, p)] = copy(diag[j]);
+			Mk[(p, 0)] = beta[m - 1] * Y[(m - 1, j)];
+			Mk[(0, p)] = copy(Mk[(p, 0)]);
+		}
+
+		let mut householder = householder.rb_mut().get_mut(.., ..k);
+		linalg::evd::tridiag::tridiag_in_place(Mk.rb_mut(), householder.rb_mut(), par, stack, default());
+
+		for i in 0..k {
+			alpha[i] = copy(Mk[(k - i, k - i)]);
+			beta[i] = copy(Mk[(k - i, k - i - 1)]);
+		}
+
+		let mut Q = Y.rb_mut().get_mut(..k + 1, ..k + 1);
+		Q.fill(zero());
+		Q.rb_mut().diagonal_mut().fill(one());
+		linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(
+			Mk.rb().submatrix(1, 0, k, k),
+			householder.rb(),
+			Conj::No,
+			Q.rb_mut().subrows_mut(1, k),
+			par,
+			stack,
+		);
+
+		let mut G = M.rb_mut().get_mut(..k, ..k);
+		for i in 0..k {
+			for j in 0..k {
+				G[(j, i)] = copy(Q[(k - j, k - i)]);
+			}
+		}
+
+		let mut CG = Y.rb_mut().get_mut(..m, ..k);
+		matmul(CG.rb_mut(), Accum::Replace, C.rb().get(..m, ..k), G.rb(), one(), par);
+		zip!(Z.rb_mut().get_mut(..m, ..k), CG.rb()).for_each(|unzip!(z, x)| *z = from_real(*x));
+
+		let mut V_tmp = tmp.rb_mut().get_mut(.., ..k);
+		matmul(V_tmp.rb_mut(), Accum::Replace, V.rb().get(.., ..m), Z.rb().get(..m, ..k), one(), par);
+		V.rb_mut().get_mut(.., ..k).copy_from(&V_tmp);
+
+		let (mut x, y) = V.rb_mut().two_cols_mut(k, m);
+		x.copy_from(&y);
+	}
+
+	let mut idx = 0usize;
+	for &j in perm[..n_eigval].iter() {
+		if residual[j] <= tol {
+			eigvals[idx] = copy(diag[j]);
+			zip!(Z.rb_mut().col_mut(idx).get_mut(..m), Y.rb().col(j).get(..m)).for_each(|unzip!(z, x)| *z = from_real(*x));
+			idx += 1;
+		}
+	}
+
+	let mut eigvecs = eigvecs;
+	matmul(
+		eigvecs.rb_mut().get_mut(.., ..n_conv),
+		Accum::Replace,
+		V.rb().get(.., ..m),
+		Z.rb().get(..m, ..n_conv),
+		one(),
+		par,
+	);
+
+	Ok(PartialEigenInfo {
+		n_converged_eigen: n_conv,
+		non_exhaustive: NonExhaustive(()),
+	})
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1398,4 +1960,291 @@ mod tests {
 			assert!((A * V.col(j) - Scale(w[j]) * V.col(j)).norm_l2() < 1e-10);
 		}
 	}
+
+	#[test]
+	fn test_targets_real() {
+		let rng = &mut StdRng::seed_from_u64(1);
+		let n = 100;
+		let n_eigval = 8;
+
+		let A: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let v0: Col<f64> = CwiseColDistribution {
+			nrows: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let all = A.eigenvalues().unwrap();
+		let A_cplx = &zip!(A.as_ref()).map(|unzip!(x)| Complex::from(*x));
+
+		for which in [
+			EigenTarget::LargestRealPart,
+			EigenTarget::SmallestRealPart,
+			EigenTarget::LargestImaginaryPart,
+		] {
+			let key = |w: &c64| match which {
+				EigenTarget::LargestRealPart => w.re,
+				EigenTarget::SmallestRealPart => -w.re,
+				_ => w.im.abs(),
+			};
+			let params = PartialEigenParams {
+				min_dim: 30,
+				max_dim: 60,
+				which,
+				..Default::default()
+			};
+
+			let mem = &mut MemBuffer::new(partial_eigen_scratch(&A.as_ref(), n_eigval, Par::Seq, params));
+			let mut V = Mat::zeros(n, n_eigval);
+			let mut w = vec![c64::ZERO; n_eigval];
+			let info = partial_eigen(
+				V.rb_mut(),
+				&mut w,
+				&A.as_ref(),
+				v0.as_ref(),
+				f64::EPSILON * 128.0,
+				Par::Seq,
+				MemStack::new(mem),
+				params,
+			);
+			assert!(info.n_converged_eigen == n_eigval);
+			assert!(w.iter().map(key).is_sorted_by(|x, y| x >= y));
+
+			let mut expected = all.iter().map(key).collect::<Vec<_>>();
+			expected.sort_unstable_by(|x, y| y.partial_cmp(x).unwrap());
+			assert!((key(&w[0]) - expected[0]).abs() < 1e-8);
+			for (j, wj) in w.iter().enumerate() {
+				assert!(key(wj) >= expected[n_eigval - 1] - 1e-8);
+				assert!((A_cplx * V.col(j) - Scale(*wj) * V.col(j)).norm_l2() < 1e-10);
+			}
+		}
+	}
+
+	#[test]
+	fn test_smallest_magnitude_cplx() {
+		let rng = &mut StdRng::seed_from_u64(1);
+		let n = 20;
+		let n_eigval = 4;
+
+		let A: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let v0: Col<c64> = CwiseColDistribution {
+			nrows: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+
+		let params = PartialEigenParams {
+			which: EigenTarget::SmallestMagnitude,
+			..Default::default()
+		};
+		let mem = &mut MemBuffer::new(partial_eigen_scratch(&A.as_ref(), n_eigval, Par::Seq, params));
+		let mut V = Mat::zeros(n, n_eigval);
+		let mut w = vec![c64::ZERO; n_eigval];
+		let info = partial_eigen(
+			V.rb_mut(),
+			&mut w,
+			&A.as_ref(),
+			v0.as_ref(),
+			f64::EPSILON * 128.0,
+			Par::Seq,
+			MemStack::new(mem),
+			params,
+		);
+		assert!(info.n_converged_eigen == n_eigval);
+		assert!(w.iter().map(|x| x.norm()).is_sorted_by(|x, y| x <= y));
+
+		let mut expected = A.eigenvalues().unwrap().iter().map(|x| x.norm()).collect::<Vec<_>>();
+		expected.sort_unstable_by(|x, y| x.partial_cmp(y).unwrap());
+		for j in 0..n_eigval {
+			assert!((w[j].norm() - expected[j]).abs() < 1e-10);
+			assert!((&A * V.col(j) - Scale(w[j]) * V.col(j)).norm_l2() < 1e-10);
+		}
+	}
+
+	#[test]
+	fn test_shift_invert() {
+		// 1d laplacian, with eigenvalues $2 - 2 \cos(\frac{k \pi}{n + 1})$
+		let n = 200;
+		let n_eigval = 4;
+		let shift = 1.05;
+
+		let mut triplets = vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 2.0 - shift));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -1.0));
+			}
+		}
+		let shifted = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let lu = shifted.sp_lu().unwrap();
+		let op = ShiftInvert::new(&lu, shift);
+
+		let rng = &mut StdRng::seed_from_u64(1);
+		let v0: Col<f64> = CwiseColDistribution {
+			nrows: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+
+		let params = PartialEigenParams::default();
+		let mem = &mut MemBuffer::new(partial_eigen_scratch(&op, n_eigval, Par::Seq, params));
+		let mut V = Mat::zeros(n, n_eigval);
+		let mut w = vec![c64::ZERO; n_eigval];
+		let info = partial_eigen_shift_invert(
+			V.rb_mut(),
+			&mut w,
+			&op,
+			v0.as_ref(),
+			f64::EPSILON * 128.0,
+			Par::Seq,
+			MemStack::new(mem),
+			params,
+		);
+		assert!(info.n_converged_eigen == n_eigval);
+
+		let mut expected = (1..n + 1)
+			.map(|k| 2.0 - 2.0 * (k as f64 * core::f64::consts::PI / (n + 1) as f64).cos())
+			.collect::<Vec<_>>();
+		expected.sort_unstable_by(|x, y| (x - shift).abs().partial_cmp(&(y - shift).abs()).unwrap());
+
+		let mut A = Mat::<c64>::zeros(n, n);
+		for i in 0..n {
+			A[(i, i)] = c64::new(2.0, 0.0);
+			if i + 1 < n {
+				A[(i, i + 1)] = c64::new(-1.0, 0.0);
+				A[(i + 1, i)] = c64::new(-1.0, 0.0);
+			}
+		}
+		for j in 0..n_eigval {
+			assert!(w[j].im.abs() < 1e-10);
+			assert!((w[j].re - expected[j]).abs() < 1e-10);
+			assert!((&A * V.col(j) - Scale(w[j]) * V.col(j)).norm_l2() < 1e-10);
+		}
+	}
+
+	#[test]
+	fn test_lanczos_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 300;
+		let n_eigval = 8;
+
+		let B: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+		let A = &B + B.transpose();
+		let v0: Col<f64> = CwiseColDistribution {
+			nrows: n,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+
+		let expected = A.self_adjoint_eigenvalues(Side::Lower).unwrap();
+
+		for which in [EigenTarget::LargestRealPart, EigenTarget::SmallestRealPart] {
+			let params = PartialEigenParams { which, ..Default::default() };
+			let mem = &mut MemBuffer::new(partial_self_adjoint_eigen_scratch(&A, n_eigval, Par::Seq, params));
+			let mut V = Mat::zeros(n, n_eigval);
+			let mut w = vec![0.0; n_eigval];
+
+			let info = partial_self_adjoint_eigen(V.rb_mut(), &mut w, &A, v0.as_ref(), 1e-10, Par::Seq, MemStack::new(mem), params).unwrap();
+			assert!(info.n_converged_eigen == n_eigval);
+
+			for j in 0..n_eigval {
+				let expected = if which == EigenTarget::LargestRealPart {
+					expected[n - 1 - j]
+				} else {
+					expected[j]
+				};
+				assert!((w[j] - expected).abs() < 1e-8);
+				assert!((&A * V.col(j) - Scale(w[j]) * V.col(j)).norm_l2() < 1e-9);
+			}
+			assert!((V.adjoint() * &V - Mat::<f64>::identity(n_eigval, n_eigval)).norm_max() < 1e-10);
+		}
+
+		for which in [EigenTarget::LargestImaginaryPart, EigenTarget::SmallestImaginaryPart] {
+			let params = PartialEigenParams { which, ..Default::default() };
+			let mem = &mut MemBuffer::new(partial_self_adjoint_eigen_scratch(&A, n_eigval, Par::Seq, params));
+			let mut V = Mat::zeros(n, n_eigval);
+			let mut w = vec![0.0; n_eigval];
+
+			let result = partial_self_adjoint_eigen(V.rb_mut(), &mut w, &A, v0.as_ref(), 1e-10, Par::Seq, MemStack::new(mem), params);
+			assert!(result.unwrap_err() == PartialEigenError::UnsupportedTarget);
+		}
+	}
+
+	#[test]
+	fn test_lanczos_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 200;
+		let n_eigval = 6;
+
+		let B: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let A = &B + B.adjoint();
+		let v0: Col<c64> = CwiseColDistribution {
+			nrows: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+
+		let mut expected = A.self_adjoint_eigenvalues(Side::Lower).unwrap();
+		expected.sort_unstable_by(|x, y| y.abs().partial_cmp(&x.abs()).unwrap());
+
+		let params = PartialEigenParams::default();
+		let mem = &mut MemBuffer::new(partial_self_adjoint_eigen_scratch(&A, n_eigval, Par::Seq, params));
+		let mut V = Mat::zeros(n, n_eigval);
+		let mut w = vec![0.0; n_eigval];
+
+		let info = partial_self_adjoint_eigen(V.rb_mut(), &mut w, &A, v0.as_ref(), 1e-10, Par::Seq, MemStack::new(mem), params).unwrap();
+		assert!(info.n_converged_eigen == n_eigval);
+
+		for j in 0..n_eigval {
+			assert!((w[j] - expected[j]).abs() < 1e-8);
+			assert!((&A * V.col(j) - Scale(c64::new(w[j], 0.0)) * V.col(j)).norm_l2() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn test_lanczos_small() {
+		// the whole space fits in the projection subspace, and the lanczos iteration breaks down
+		// on an invariant subspace of the diagonal matrix
+		let n = 20;
+		let n_eigval = 5;
+
+		let A = Mat::<f64>::from_fn(n, n, |i, j| if i == j { (i as f64) - 9.5 } else { 0.0 });
+		let v0 = Col::<f64>::from_fn(n, |i| if i < 4 { 1.0 } else { 0.0 });
+
+		let params = PartialEigenParams {
+			which: EigenTarget::SmallestMagnitude,
+			..Default::default()
+		};
+		let mem = &mut MemBuffer::new(partial_self_adjoint_eigen_scratch(&A, n_eigval, Par::Seq, params));
+		let mut V = Mat::zeros(n, n_eigval);
+		let mut w = vec![0.0; n_eigval];
+
+		let info = partial_self_adjoint_eigen(V.rb_mut(), &mut w, &A, v0.as_ref(), 1e-12, Par::Seq, MemStack::new(mem), params).unwrap();
+		assert!(info.n_converged_eigen == n_eigval);
+
+		for j in 0..n_eigval {
+			assert!(w[j].abs() == [0.5, 0.5, 1.5, 1.5, 2.5][j]);
+			assert!((&A * V.col(j) - Scale(w[j]) * V.col(j)).norm_l2() < 1e-12);
+		}
+	}
 }
@@ -0,0 +1,559 @@
+use super::*;
+use crate::assert;
+use linalg::matmul::matmul;
+
+/// algorithm parameters
+#[derive(Copy, Clone, Debug)]
+pub struct GmresParams<T> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+	/// dimension of the krylov subspace after which the algorithm is restarted
+	pub restart: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for GmresParams<T> {
+	#[inline]
+	#[math]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			max_iters: usize::MAX,
+			restart: 30,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// algorithm result
+#[derive(Copy, Clone, Debug)]
+pub struct GmresInfo<T> {
+	/// absolute residual at the final step
+	pub abs_residual: T,
+	/// relative residual at the final step
+	pub rel_residual: T,
+	/// number of iterations executed by the algorithm
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum GmresError<T> {
+	/// convergence failure
+	NoConvergence {
+		/// absolute residual at the final step
+		abs_residual: T,
+		/// relative residual at the final step
+		rel_residual: T,
+	},
+}
+
+fn scratch_imp<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, restart: usize, flexible: bool, par: Par) -> StackReq {
+	let n = A.nrows();
+	let m = Ord::max(Ord::min(restart, n), 1);
+
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(n, m + 1),                        // V
+		temp_mat_scratch::<T>(n, if flexible { m } else { 0 }), // Z
+		temp_mat_scratch::<T>(m + 1, m),                        // H
+		temp_mat_scratch::<T>(m, 2),                            // givens rotations
+		temp_mat_scratch::<T>(m + 1, 1),                        // g
+		temp_mat_scratch::<T>(n, 1),                            // w
+		StackReq::any_of(&[
+			A.apply_scratch(1, par),
+			temp_mat_scratch::<T>(n, 1).and(M.apply_scratch(1, par)),
+			temp_mat_scratch::<T>(n, 1).and(A.apply_scratch(1, par)),
+			temp_mat_scratch::<T>(m + 1, 1),
+			StackReq::all_of(&[temp_mat_scratch::<T>(m, 1), temp_mat_scratch::<T>(n, 1), M.apply_scratch(1, par)]),
+		]),
+	])
+}
+
+/// computes the size and alignment of required workspace for executing the restarted gmres
+/// algorithm
+pub fn gmres_scratch<T: ComplexField>(
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs_ncols: usize,
+	params: GmresParams<T::Real>,
+	par: Par,
+) -> StackReq {
+	_ = rhs_ncols;
+	scratch_imp(&precond, &mat, params.restart, false, par)
+}
+
+/// computes the size and alignment of required workspace for executing the restarted flexible
+/// gmres algorithm
+pub fn fgmres_scratch<T: ComplexField>(
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs_ncols: usize,
+	params: GmresParams<T::Real>,
+	par: Par,
+) -> StackReq {
+	_ = rhs_ncols;
+	scratch_imp(&precond, &mat, params.restart, true, par)
+}
+
+/// computes a givens rotation $G$ such that $G \begin{bmatrix} a \\ b \end{bmatrix} =
+/// \begin{bmatrix} r \\ 0 \end{bmatrix}$, and returns $(c, s, r)$ with
+/// $G = \begin{bmatrix} c & s \\ -\bar{s} & c \end{bmatrix}$
+#[math]
+fn make_givens<T: ComplexField>(a: T, b: T) -> (T::Real, T, T) {
+	let abs_a = abs(a);
+	let abs_b = abs(b);
+	if abs_b == zero() {
+		return (one(), zero(), a);
+	}
+	if abs_a == zero() {
+		return (zero(), conj(b) * from_real(recip(abs_b)), from_real(abs_b));
+	}
+
+	let r = hypot(abs_a, abs_b);
+	let phase = mul_real(a, recip(abs_a));
+	let c = abs_a * recip(r);
+	let s = phase * mul_real(conj(b), recip(r));
+	(c, s, mul_real(phase, r))
+}
+
+#[track_caller]
+#[math]
+fn gmres_imp<T: ComplexField>(
+	mut x: MatMut<'_, T>,
+	M: &dyn Precond<T>,
+	A: &dyn LinOp<T>,
+	b: MatRef<'_, T>,
+	params: GmresParams<T::Real>,
+	flexible: bool,
+	callback: &mut dyn FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<GmresInfo<T::Real>, GmresError<T::Real>> {
+	assert!(all(A.nrows() == A.ncols(), M.nrows() == A.nrows(), M.ncols() == A.ncols()));
+
+	let n = A.nrows();
+	let k = b.ncols();
+	let m = Ord::max(Ord::min(params.restart, n), 1);
+	assert!(all(x.nrows() == n, x.ncols() == k, b.nrows() == n));
+
+	let b_norm = b.norm_l2();
+	if b_norm == zero::<T::Real>() {
+		x.fill(zero());
+		return Ok(GmresInfo {
+			abs_residual: zero::<T::Real>(),
+			rel_residual: zero::<T::Real>(),
+			iter_count: 0,
+			non_exhaustive: NonExhaustive(()),
+		});
+	}
+
+	if params.initial_guess == InitialGuessStatus::Zero {
+		x.fill(zero());
+	}
+
+	let (mut V, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, m + 1, stack) };
+	let mut V = V.as_mat_mut();
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, if flexible { m } else { 0 }, stack) };
+	let mut Z = Z.as_mat_mut();
+	let (mut H, stack) = unsafe { temp_mat_uninit::<T, _, _>(m + 1, m, stack) };
+	let mut H = H.as_mat_mut();
+	let (mut rot, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, 2, stack) };
+	let mut rot = rot.as_mat_mut();
+	let (mut g, stack) = unsafe { temp_mat_uninit::<T, _, _>(m + 1, 1, stack) };
+	let mut g = g.as_mat_mut();
+	let mut g = g.rb_mut().col_mut(0);
+	let (mut w, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+	let mut w = w.as_mat_mut();
+
+	let mut iter_count = 0usize;
+	let mut total_residual = zero::<T::Real>();
+	let mut converged = true;
+
+	for j in 0..k {
+		let bj = b.col(j);
+		let bj_norm = bj.norm_l2();
+
+		// the relative threshold vanishes for a zero right-hand side, whose solution is zero
+		if bj_norm == zero::<T::Real>() {
+			x.rb_mut().col_mut(j).fill(zero());
+			continue;
+		}
+
+		let rel_threshold = params.rel_tolerance * bj_norm;
+		let abs_threshold = copy(params.abs_tolerance);
+		let threshold = if abs_threshold > rel_threshold { abs_threshold } else { rel_threshold };
+
+		let mut iter = 0usize;
+		let mut residual;
+
+		loop {
+			// $r = b - A x$, stored in the first krylov vector
+			{
+				let mut r = V.rb_mut().col_mut(0);
+				if iter == 0 && params.initial_guess == InitialGuessStatus::Zero {
+					r.copy_from(bj);
+				} else {
+					A.apply(w.rb_mut(), x.rb().col(j).as_mat(), par, stack);
+					z!(r.rb_mut(), bj, w.rb().col(0)).for_each(|uz!(r, b, ax)| *r = *b - *ax);
+				}
+			}
+			let beta = V.rb().col(0).norm_l2();
+			residual = copy(beta);
+			if beta < threshold || beta == zero() || iter >= params.max_iters {
+				break;
+			}
+
+			{
+				let inv: T = from_real(recip(beta));
+				z!(V.rb_mut().col_mut(0)).for_each(|uz!(v)| *v = *v * inv);
+			}
+			g.fill(zero());
+			g[0] = from_real(beta);
+
+			let mut dim = 0;
+			while dim < m && iter < params.max_iters {
+				let i = dim;
+
+				// $w = A M v_i$
+				{
+					let (mut z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+					let mut z = z.as_mat_mut();
+					M.apply(z.rb_mut(), V.rb().col(i).as_mat(), par, stack);
+					A.apply(w.rb_mut(), z.rb(), par, stack);
+					if flexible {
+						Z.rb_mut().col_mut(i).copy_from(z.rb().col(0));
+					}
+				}
+
+				// classical gram-schmidt with reorthogonalization
+				let mut h = H.rb_mut().col_mut(i);
+				h.fill(zero());
+				for _ in 0..2 {
+					let (mut c, _) = unsafe { temp_mat_uninit::<T, _, _>(i + 1, 1, stack) };
+					let mut c = c.as_mat_mut();
+					matmul(c.rb_mut(), Accum::Replace, V.rb().get(.., ..i + 1).adjoint(), w.rb(), one::<T>(), par);
+					matmul(w.rb_mut(), Accum::Add, V.rb().get(.., ..i + 1), c.rb(), -one::<T>(), par);
+					z!(h.rb_mut().get_mut(..i + 1), c.rb().col(0)).for_each(|uz!(h, c)| *h = *h + *c);
+				}
+				let w_norm = w.norm_l2();
+				h[i + 1] = from_real(copy(w_norm));
+				if w_norm > zero() {
+					let inv: T = from_real(recip(w_norm));
+					z!(V.rb_mut().col_mut(i + 1), w.rb().col(0)).for_each(|uz!(v, w)| *v = *w * inv);
+				}
+
+				// apply the previous rotations to the new column of the hessenberg matrix
+				for p in 0..i {
+					let c = copy(rot[(p, 0)]);
+					let s = copy(rot[(p, 1)]);
+					let a = copy(h[p]);
+					let b = copy(h[p + 1]);
+					h[p] = c * a + s * b;
+					h[p + 1] = c * b - conj(s) * a;
+				}
+				let (c, s, r) = make_givens(copy(h[i]), copy(h[i + 1]));
+				h[i] = r;
+				h[i + 1] = zero();
+				rot[(i, 0)] = from_real(copy(c));
+				rot[(i, 1)] = copy(s);
+
+				let gi = copy(g[i]);
+				g[i] = mul_real(gi, c);
+				g[i + 1] = -conj(s) * gi;
+
+				dim += 1;
+				iter += 1;
+
+				residual = abs(g[i + 1]);
+				if residual < threshold || w_norm == zero() {
+					break;
+				}
+			}
+
+			// solve the triangular least squares problem, and update the solution
+			{
+				let (mut y, stack) = unsafe { temp_mat_uninit::<T, _, _>(dim, 1, stack) };
+				let mut y = y.as_mat_mut();
+				y.rb_mut().col_mut(0).copy_from(g.rb().get(..dim));
+				linalg::triangular_solve::solve_upper_triangular_in_place(H.rb().get(..dim, ..dim), y.rb_mut(), par);
+
+				let xj = x.rb_mut().col_mut(j).as_mat_mut();
+				if flexible {
+					matmul(xj, Accum::Add, Z.rb().get(.., ..dim), y.rb(), one::<T>(), par);
+				} else {
+					matmul(w.rb_mut(), Accum::Replace, V.rb().get(.., ..dim), y.rb(), one::<T>(), par);
+					let (mut z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+					let mut z = z.as_mat_mut();
+					M.apply(z.rb_mut(), w.rb(), par, stack);
+					z!(xj, z.rb()).for_each(|uz!(x, z)| *x = *x + *z);
+				}
+			}
+
+			callback(x.rb());
+
+			if residual < threshold {
+				break;
+			}
+		}
+
+		if residual >= threshold {
+			converged = false;
+		}
+		total_residual = total_residual + residual * residual;
+		iter_count = Ord::max(iter_count, iter);
+	}
+
+	let abs_residual = sqrt(total_residual);
+	let rel_residual = abs_residual / b_norm;
+
+	if converged {
+		Ok(GmresInfo {
+			abs_residual,
+			rel_residual,
+			iter_count,
+			non_exhaustive: NonExhaustive(()),
+		})
+	} else {
+		Err(GmresError::NoConvergence { abs_residual, rel_residual })
+	}
+}
+
+/// executes the restarted gmres algorithm, using the provided preconditioner as a right
+/// preconditioner
+///
+/// the columns of the right-hand side are solved independently, and `params.max_iters` limits
+/// the number of iterations for each of them
+#[track_caller]
+pub fn gmres<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: GmresParams<T::Real>,
+	callback: impl FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<GmresInfo<T::Real>, GmresError<T::Real>> {
+	gmres_imp(out, &precond, &mat, rhs, params, false, &mut { callback }, par, stack)
+}
+
+/// executes the restarted flexible gmres algorithm, using the provided preconditioner as a right
+/// preconditioner
+///
+/// unlike [`gmres`], the preconditioner is allowed to vary between iterations, e.g. if it is
+/// itself an inexact iterative solver. this requires storing the preconditioned krylov basis,
+/// which doubles the memory usage
+///
+/// the columns of the right-hand side are solved independently, and `params.max_iters` limits
+/// the number of iterations for each of them
+#[track_caller]
+pub fn fgmres<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: GmresParams<T::Real>,
+	callback: impl FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<GmresInfo<T::Real>, GmresError<T::Real>> {
+	gmres_imp(out, &precond, &mat, rhs, params, true, &mut { callback }, par, stack)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::matrix_free;
+	use crate::stats::prelude::*;
+	use dyn_stack::MemBuffer;
+	use equator::assert;
+
+	#[test]
+	fn test_gmres() {
+		let A = mat![[2.5, -1.0, 0.3], [1.0, 3.1, -0.7], [0.2, 0.5, 1.9]];
+		let sol = mat![[2.1, 2.1], [4.1, 3.2], [-1.0, 0.5]];
+		let rhs = &A * &sol;
+
+		for restart in [1, 2, 30] {
+			let mut out = Mat::<f64>::zeros(3, sol.ncols());
+			let params = GmresParams {
+				restart,
+				max_iters: 100,
+				..Default::default()
+			};
+			let precond = matrix_free::IdentityPrecond { dim: 3 };
+			let result = gmres(
+				out.as_mut(),
+				precond,
+				A.as_ref(),
+				rhs.as_ref(),
+				params,
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(gmres_scratch(precond, A.as_ref(), sol.ncols(), params, Par::Seq))),
+			);
+
+			assert!(result.is_ok());
+			assert!((&A * &out - &rhs).norm_l2() <= params.rel_tolerance * rhs.norm_l2());
+			if restart >= 3 {
+				assert!(result.unwrap().iter_count <= 3);
+			}
+		}
+	}
+
+	#[test]
+	fn test_gmres_cplx_precond() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 60;
+		let k = 3;
+
+		let mut A: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand(rng);
+		for i in 0..n {
+			A[(i, i)] += c64::new(4.0 * (n as f64).sqrt() * (1.0 + i as f64), 0.0);
+		}
+		let rhs: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: k,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand(rng);
+		let mut precond = Diag::<c64>::zeros(n);
+		for i in 0..n {
+			precond.column_vector_mut()[i] = c64::new(1.0, 0.0) / A[(i, i)];
+		}
+
+		let params = GmresParams {
+			restart: 10,
+			..Default::default()
+		};
+
+		for flexible in [false, true] {
+			let mut out = Mat::<c64>::zeros(n, k);
+			let stack = &mut MemBuffer::new(if flexible {
+				fgmres_scratch(precond.as_ref(), A.as_ref(), k, params, Par::Seq)
+			} else {
+				gmres_scratch(precond.as_ref(), A.as_ref(), k, params, Par::Seq)
+			});
+			let result = if flexible {
+				fgmres(
+					out.as_mut(),
+					precond.as_ref(),
+					A.as_ref(),
+					rhs.as_ref(),
+					params,
+					|_| {},
+					Par::Seq,
+					MemStack::new(stack),
+				)
+			} else {
+				gmres(
+					out.as_mut(),
+					precond.as_ref(),
+					A.as_ref(),
+					rhs.as_ref(),
+					params,
+					|_| {},
+					Par::Seq,
+					MemStack::new(stack),
+				)
+			};
+
+			assert!(result.is_ok());
+			assert!((&A * &out - &rhs).norm_l2() <= 2.0 * params.rel_tolerance * rhs.norm_l2());
+		}
+	}
+
+	#[test]
+	fn test_gmres_zero_rhs_col() {
+		let A = mat![[2.5, -1.0, 0.3], [1.0, 3.1, -0.7], [0.2, 0.5, 1.9]];
+		let rhs = mat![[0.0, 1.0], [0.0, 2.0], [0.0, -1.0f64]];
+
+		let params = GmresParams {
+			restart: 2,
+			max_iters: 100,
+			..Default::default()
+		};
+		let precond = matrix_free::IdentityPrecond { dim: 3 };
+
+		for flexible in [false, true] {
+			// nonzero initial guess for the zero column
+			let mut out = Mat::<f64>::ones(3, 2);
+			let stack = &mut MemBuffer::new(if flexible {
+				fgmres_scratch(precond, A.as_ref(), 2, params, Par::Seq)
+			} else {
+				gmres_scratch(precond, A.as_ref(), 2, params, Par::Seq)
+			});
+			let result = if flexible {
+				fgmres(
+					out.as_mut(),
+					precond,
+					A.as_ref(),
+					rhs.as_ref(),
+					params,
+					|_| {},
+					Par::Seq,
+					MemStack::new(stack),
+				)
+			} else {
+				gmres(
+					out.as_mut(),
+					precond,
+					A.as_ref(),
+					rhs.as_ref(),
+					params,
+					|_| {},
+					Par::Seq,
+					MemStack::new(stack),
+				)
+			};
+
+			let info = result.unwrap();
+			assert!(info.iter_count < params.max_iters);
+			assert!(out.col(0).norm_l2() == 0.0);
+			assert!((&A * &out - &rhs).norm_l2() <= params.rel_tolerance * rhs.norm_l2());
+		}
+	}
+
+	#[test]
+	fn test_gmres_no_convergence() {
+		// a rotation is not reduced by a single iteration of gmres(1)
+		let A = mat![[0.0, 1.0], [-1.0, 0.0f64]];
+		let rhs = mat![[1.0], [0.0f64]];
+		let mut out = Mat::<f64>::zeros(2, 1);
+		let params = GmresParams {
+			restart: 1,
+			max_iters: 10,
+			..Default::default()
+		};
+		let precond = matrix_free::IdentityPrecond { dim: 2 };
+		let result = gmres(
+			out.as_mut(),
+			precond,
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(gmres_scratch(precond, A.as_ref(), 1, params, Par::Seq))),
+		);
+		assert!(matches!(result, Err(GmresError::NoConvergence { .. })));
+	}
+}
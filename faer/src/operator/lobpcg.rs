@@ -0,0 +1,550 @@
+use crate::assert;
+use crate::matrix_free::eigen::EigenTarget;
+use crate::matrix_free::*;
+use linalg::evd::{ComputeEigenvectors, EvdError};
+use linalg::matmul::matmul;
+
+/// algorithm parameters
+#[derive(Copy, Clone, Debug)]
+pub struct LobpcgParams<T: RealField> {
+	/// part of the spectrum that should be computed, either [`EigenTarget::SmallestRealPart`] or
+	/// [`EigenTarget::LargestRealPart`]
+	pub which: EigenTarget,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm result
+#[derive(Copy, Clone, Debug)]
+pub struct LobpcgInfo<T: RealField> {
+	/// largest residual norm of the eigenpairs at the final step
+	pub abs_residual: T,
+	/// number of iterations executed by the algorithm
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum LobpcgError<T: RealField> {
+	/// `params.which` is neither [`EigenTarget::SmallestRealPart`] nor
+	/// [`EigenTarget::LargestRealPart`]
+	UnsupportedTarget,
+	/// mass operator was detected to not be positive definite, or the initial guess was detected
+	/// to be rank deficient
+	NonPositiveDefiniteOperator,
+	/// dense eigensolver used for the rayleigh-ritz procedure failed to converge
+	EvdNoConvergence,
+	/// convergence failure
+	NoConvergence {
+		/// number of eigenpairs that converged
+		n_converged: usize,
+		/// largest residual norm of the eigenpairs at the final step
+		abs_residual: T,
+	},
+}
+
+impl<T: RealField> Default for LobpcgParams<T> {
+	#[inline]
+	#[math]
+	fn default() -> Self {
+		Self {
+			which: EigenTarget::SmallestRealPart,
+			abs_tolerance: zero::<T>(),
+			rel_tolerance: sqrt(eps::<T>()),
+			max_iters: 1000,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+fn apply_mass<T: ComplexField>(B: Option<&dyn LinOp<T>>, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+	match B {
+		Some(B) => B.apply(out, rhs, par, stack),
+		None => { out }.copy_from(rhs),
+	}
+}
+
+fn rayleigh_ritz_scratch<T: ComplexField>(dim: usize, par: Par) -> StackReq {
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(dim, dim).array(5),
+		temp_mat_scratch::<T>(dim, 1).array(2),
+		StackReq::new::<T::Real>(dim),
+		linalg::evd::self_adjoint_evd_scratch::<T>(dim, ComputeEigenvectors::Yes, par, default()),
+	])
+}
+
+/// performs the rayleigh-ritz procedure on the basis $S$, storing the wanted ritz values in
+/// `theta` and the corresponding coefficients in $C$.
+///
+/// returns the number of linearly independent directions in $S$, with respect to the inner
+/// product induced by $B$.
+#[math]
+fn rayleigh_ritz<T: ComplexField>(
+	C: MatMut<'_, T>,
+	theta: &mut [T::Real],
+	S: MatRef<'_, T>,
+	AS: MatRef<'_, T>,
+	BS: MatRef<'_, T>,
+	which: EigenTarget,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<usize, EvdError> {
+	let s = S.ncols();
+	let k = C.ncols();
+	let mut C = C;
+
+	let (mut GA, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack) };
+	let mut GA = GA.as_mat_mut();
+	let (mut GB, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack) };
+	let mut GB = GB.as_mat_mut();
+	let (mut U, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack) };
+	let mut U = U.as_mat_mut();
+	let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack) };
+	let mut tmp = tmp.as_mat_mut();
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, s, stack) };
+	let mut Z = Z.as_mat_mut();
+	let (mut w, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, 1, stack) };
+	let mut w = w.as_mat_mut().col_mut(0);
+	let (mut w2, stack) = unsafe { temp_mat_uninit::<T, _, _>(s, 1, stack) };
+	let mut w2 = w2.as_mat_mut().col_mut(0);
+	let (mut d, stack) = stack.make_with(s, |_| zero::<T::Real>());
+	let d = &mut *d;
+
+	matmul(GA.rb_mut(), Accum::Replace, S.adjoint(), AS, one(), par);
+	matmul(GB.rb_mut(), Accum::Replace, S.adjoint(), BS, one(), par);
+
+	// the basis vectors are scaled to unit $B$-norm, so that the rank decision only depends on
+	// their linear dependence
+	for i in 0..s {
+		let g = real(GB[(i, i)]);
+		d[i] = if g > zero() { recip(sqrt(g)) } else { zero() };
+	}
+	for j in 0..s {
+		for i in 0..s {
+			let f = d[i] * d[j];
+			GA[(i, j)] = mul_real(GA[(i, j)], f);
+			GB[(i, j)] = mul_real(GB[(i, j)], f);
+		}
+	}
+
+	// $G_B = U W U^H$, and the directions with tiny eigenvalues are dropped
+	linalg::evd::self_adjoint_evd(GB.rb(), w.rb_mut().as_diagonal_mut(), Some(U.rb_mut()), par, stack, default())?;
+
+	let max_w = real(w[s - 1]);
+	let threshold = max_w * eps::<T::Real>() * from_f64::<T::Real>((16 * s) as f64);
+	let mut r = 0usize;
+	while r < s && real(w[s - 1 - r]) > threshold {
+		r += 1;
+	}
+	if r < k {
+		return Ok(r);
+	}
+
+	// $T = U_r W_r^{-1/2}$ is a $B$-orthonormal basis of the retained directions
+	let mut T = U.rb_mut().get_mut(.., s - r..);
+	for c in 0..r {
+		let f = recip(sqrt(real(w[s - r + c])));
+		zip!(T.rb_mut().col_mut(c)).for_each(|unzip!(t)| *t = mul_real(*t, f));
+	}
+	let T = T.rb();
+
+	let mut GAT = tmp.rb_mut().get_mut(.., ..r);
+	matmul(GAT.rb_mut(), Accum::Replace, GA.rb(), T, one(), par);
+	let mut GA2 = GB.rb_mut().get_mut(..r, ..r);
+	matmul(GA2.rb_mut(), Accum::Replace, T.adjoint(), GAT.rb(), one(), par);
+
+	let mut Z = Z.rb_mut().get_mut(..r, ..r);
+	let mut w2 = w2.rb_mut().get_mut(..r);
+	linalg::evd::self_adjoint_evd(GA2.rb(), w2.rb_mut().as_diagonal_mut(), Some(Z.rb_mut()), par, stack, default())?;
+
+	for j in 0..k {
+		let idx = match which {
+			EigenTarget::LargestRealPart => r - 1 - j,
+			_ => j,
+		};
+		theta[j] = real(w2[idx]);
+		matmul(
+			C.rb_mut().col_mut(j).as_mat_mut(),
+			Accum::Replace,
+			T,
+			Z.rb().col(idx).as_mat(),
+			one(),
+			par,
+		);
+		for i in 0..s {
+			C[(i, j)] = mul_real(C[(i, j)], d[i]);
+		}
+	}
+
+	Ok(r)
+}
+
+/// computes the size and alignment of required workspace for executing the lobpcg algorithm
+pub fn lobpcg_scratch<T: ComplexField>(precond: impl Precond<T>, A: impl LinOp<T>, B: Option<&dyn LinOp<T>>, n_eigval: usize, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, B: Option<&dyn LinOp<T>>, k: usize, par: Par) -> StackReq {
+		let n = A.nrows();
+		let s = 3 * k;
+
+		let nk = temp_mat_scratch::<T>(n, k);
+		let ns = temp_mat_scratch::<T>(n, s);
+		let sk = temp_mat_scratch::<T>(s, k);
+		let k_usize = StackReq::new::<usize>(k);
+		let k_real = StackReq::new::<T::Real>(k);
+
+		StackReq::all_of(&[
+			ns,      // S
+			ns,      // AS
+			ns,      // BS
+			nk,      // P
+			nk,      // AP
+			nk,      // BP
+			nk,      // R
+			nk,      // tmp
+			sk,      // C
+			k_usize, // active
+			k_real,  // lambda
+			StackReq::any_of(&[
+				A.apply_scratch(k, par),
+				match B {
+					Some(B) => B.apply_scratch(k, par),
+					None => StackReq::EMPTY,
+				},
+				M.apply_scratch(k, par),
+				rayleigh_ritz_scratch::<T>(s, par),
+			]),
+		])
+	}
+	implementation(&precond, &A, B, n_eigval, par)
+}
+
+/// computes the extreme eigenvalues (and corresponding eigenvectors) of the generalized
+/// self-adjoint eigenproblem $A x = \lambda B x$ with the locally optimal block preconditioned
+/// conjugate gradient method, where $B$ is positive definite and defaults to the identity.
+///
+/// on entry, `eigvecs` contains the initial guess, which must have full rank (e.g. a random
+/// matrix). on exit, it contains the $B$-orthonormal eigenvectors, and `eigvals` contains the
+/// eigenvalues, sorted so that the most wanted ones come first.
+///
+/// eigenpairs whose residual norm $\|A x - \lambda B x\|$ falls below the tolerance are soft
+/// locked: they stay in the rayleigh-ritz basis, but no new search directions are computed for
+/// them.
+///
+/// `precond` should approximate the inverse of $A$ (or of $A - \sigma B$ for a shift $\sigma$
+/// close to the wanted eigenvalues), and be self-adjoint positive definite.
+#[track_caller]
+pub fn lobpcg<T: ComplexField>(
+	eigvecs: MatMut<'_, T>,
+	eigvals: &mut [T::Real],
+	precond: impl Precond<T>,
+	A: impl LinOp<T>,
+	B: Option<&dyn LinOp<T>>,
+	params: LobpcgParams<T::Real>,
+	callback: impl FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<LobpcgInfo<T::Real>, LobpcgError<T::Real>> {
+	#[track_caller]
+	#[math]
+	fn implementation<T: ComplexField>(
+		X: MatMut<'_, T>,
+		eigvals: &mut [T::Real],
+		M: &dyn Precond<T>,
+		A: &dyn LinOp<T>,
+		B: Option<&dyn LinOp<T>>,
+		params: LobpcgParams<T::Real>,
+		callback: &mut dyn FnMut(MatRef<'_, T>),
+		par: Par,
+		stack: &mut MemStack,
+	) -> Result<LobpcgInfo<T::Real>, LobpcgError<T::Real>> {
+		let n = A.nrows();
+		let k = X.ncols();
+		assert!(all(A.ncols() == n, X.nrows() == n, eigvals.len() == k,));
+		if let Some(B) = B {
+			assert!(all(B.nrows() == n, B.ncols() == n));
+		}
+		if !matches!(params.which, EigenTarget::SmallestRealPart | EigenTarget::LargestRealPart) {
+			return Err(LobpcgError::UnsupportedTarget);
+		}
+
+		let mut X = X;
+		if k == 0 {
+			return Ok(LobpcgInfo {
+				abs_residual: zero(),
+				iter_count: 0,
+				non_exhaustive: NonExhaustive(()),
+			});
+		}
+
+		let (mut S, stack) = temp_mat_zeroed::<T, _, _>(n, 3 * k, stack);
+		let mut S = S.as_mat_mut();
+		let (mut AS, stack) = temp_mat_zeroed::<T, _, _>(n, 3 * k, stack);
+		let mut AS = AS.as_mat_mut();
+		let (mut BS, stack) = temp_mat_zeroed::<T, _, _>(n, 3 * k, stack);
+		let mut BS = BS.as_mat_mut();
+		let (mut P, stack) = temp_mat_zeroed::<T, _, _>(n, k, stack);
+		let mut P = P.as_mat_mut();
+		let (mut AP, stack) = temp_mat_zeroed::<T, _, _>(n, k, stack);
+		let mut AP = AP.as_mat_mut();
+		let (mut BP, stack) = temp_mat_zeroed::<T, _, _>(n, k, stack);
+		let mut BP = BP.as_mat_mut();
+		let (mut R, stack) = temp_mat_zeroed::<T, _, _>(n, k, stack);
+		let mut R = R.as_mat_mut();
+		let (mut tmp, stack) = temp_mat_zeroed::<T, _, _>(n, k, stack);
+		let mut tmp = tmp.as_mat_mut();
+		let (mut C, stack) = temp_mat_zeroed::<T, _, _>(3 * k, k, stack);
+		let mut C = C.as_mat_mut();
+		let (mut active, stack) = stack.make_with(k, |_| 0usize);
+		let active = &mut *active;
+		let (mut lambda, stack) = stack.make_with(k, |_| zero::<T::Real>());
+		let lambda = &mut *lambda;
+
+		// rayleigh-ritz on the initial guess
+		S.rb_mut().get_mut(.., ..k).copy_from(&X);
+		A.apply(AS.rb_mut().get_mut(.., ..k), X.rb(), par, stack);
+		apply_mass(B, BS.rb_mut().get_mut(.., ..k), X.rb(), par, stack);
+
+		let mut s = k;
+		let mut has_p = false;
+		let mut iter = 0usize;
+
+		loop {
+			let rank = match rayleigh_ritz(
+				C.rb_mut().get_mut(..s, ..),
+				lambda,
+				S.rb().get(.., ..s),
+				AS.rb().get(.., ..s),
+				BS.rb().get(.., ..s),
+				params.which,
+				par,
+				stack,
+			) {
+				Ok(rank) => rank,
+				Err(_) => return Err(LobpcgError::EvdNoConvergence),
+			};
+			// the current ritz vectors are $B$-orthonormal, so the basis can only lose rank if $B$
+			// is not positive definite
+			if rank < k {
+				return Err(LobpcgError::NonPositiveDefiniteOperator);
+			}
+			let C = C.rb().get(..s, ..);
+
+			// the new search directions are the components of the new ritz vectors along $W$ and
+			// $P$
+			if s > k {
+				matmul(P.rb_mut(), Accum::Replace, S.rb().get(.., k..s), C.get(k..s, ..), one(), par);
+				matmul(AP.rb_mut(), Accum::Replace, AS.rb().get(.., k..s), C.get(k..s, ..), one(), par);
+				matmul(BP.rb_mut(), Accum::Replace, BS.rb().get(.., k..s), C.get(k..s, ..), one(), par);
+				has_p = true;
+			}
+			for Y in [S.rb_mut(), AS.rb_mut(), BS.rb_mut()] {
+				matmul(tmp.rb_mut(), Accum::Replace, Y.rb().get(.., ..s), C, one(), par);
+				{ Y }.get_mut(.., ..k).copy_from(&tmp);
+			}
+
+			// residuals
+			let mut n_active = 0usize;
+			let mut abs_residual = zero::<T::Real>();
+			for (j, lambda_j) in lambda.iter().enumerate() {
+				let mut r = R.rb_mut().col_mut(j);
+				let lambda_j = copy(*lambda_j);
+				zip!(r.rb_mut(), AS.rb().col(j), BS.rb().col(j)).for_each(|unzip!(r, ax, bx)| *r = *ax - mul_real(*bx, lambda_j));
+
+				let norm = r.norm_l2();
+				let threshold = max(params.abs_tolerance, params.rel_tolerance * abs(lambda_j) * BS.rb().col(j).norm_l2());
+				if norm > threshold {
+					active[n_active] = j;
+					n_active += 1;
+				}
+				abs_residual = max(abs_residual, norm);
+			}
+
+			X.copy_from(S.rb().get(.., ..k));
+			eigvals.clone_from_slice(lambda);
+			callback(X.rb());
+
+			if n_active == 0 {
+				return Ok(LobpcgInfo {
+					abs_residual,
+					iter_count: iter,
+					non_exhaustive: NonExhaustive(()),
+				});
+			}
+			if iter == params.max_iters {
+				return Err(LobpcgError::NoConvergence {
+					n_converged: k - n_active,
+					abs_residual,
+				});
+			}
+			iter += 1;
+
+			// $W = M R$, only for the active eigenpairs
+			let a = n_active;
+			for (i, &j) in active[..a].iter().enumerate() {
+				tmp.rb_mut().col_mut(i).copy_from(R.rb().col(j));
+			}
+			M.apply(S.rb_mut().get_mut(.., k..k + a), tmp.rb().get(.., ..a), par, stack);
+			{
+				let (S_, mut AS_) = (S.rb().get(.., k..k + a), AS.rb_mut().get_mut(.., k..k + a));
+				A.apply(AS_.rb_mut(), S_, par, stack);
+				apply_mass(B, BS.rb_mut().get_mut(.., k..k + a), S_, par, stack);
+			}
+			s = k + a;
+
+			if has_p {
+				for (i, &j) in active[..a].iter().enumerate() {
+					S.rb_mut().col_mut(s + i).copy_from(P.rb().col(j));
+					AS.rb_mut().col_mut(s + i).copy_from(AP.rb().col(j));
+					BS.rb_mut().col_mut(s + i).copy_from(BP.rb().col(j));
+				}
+				s += a;
+			}
+		}
+	}
+
+	implementation(eigvecs, eigvals, &precond, &A, B, params, &mut { callback }, par, stack)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::matrix_free::precond::ic::IncompleteCholesky;
+	use crate::stats::prelude::*;
+	use crate::{Scale, assert};
+	use dyn_stack::MemBuffer;
+
+	fn laplacian(n: usize) -> SparseColMat<usize, f64> {
+		let mut triplets = vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 2.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -1.0));
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	#[test]
+	fn test_lobpcg_generalized() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 200;
+		let k = 4;
+
+		// 1d finite elements: stiffness and mass matrices
+		let A = laplacian(n);
+		let mut triplets = vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 4.0 / 6.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, 1.0 / 6.0));
+				triplets.push(Triplet::new(i + 1, i, 1.0 / 6.0));
+			}
+		}
+		let B = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		// eigenvalues of the generalized problem
+		let expected = (1..k + 1)
+			.map(|j| {
+				let c = (j as f64 * core::f64::consts::PI / (n + 1) as f64).cos();
+				(2.0 - 2.0 * c) / ((4.0 + 2.0 * c) / 6.0)
+			})
+			.collect::<Vec<_>>();
+
+		let X0: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: k,
+			dist: StandardNormal,
+		}
+		.sample(rng);
+
+		// incomplete cholesky of the stiffness matrix as a preconditioner
+		let precond = IncompleteCholesky::try_new_ic0(A.as_ref(), Side::Lower).unwrap();
+
+		let params = LobpcgParams {
+			rel_tolerance: 1e-10,
+			..Default::default()
+		};
+		let B_op: &dyn LinOp<f64> = &B;
+		let mem = &mut MemBuffer::new(lobpcg_scratch(&precond, &A, Some(B_op), k, Par::Seq));
+
+		let mut X = X0.clone();
+		let mut w = vec![0.0; k];
+		let info = lobpcg(X.rb_mut(), &mut w, &precond, &A, Some(B_op), params, |_| {}, Par::Seq, MemStack::new(mem)).unwrap();
+		assert!(info.iter_count < 50);
+
+		let A = A.to_dense();
+		let B = B.to_dense();
+		for j in 0..k {
+			assert!((w[j] - expected[j]).abs() < 1e-10);
+			assert!((&A * X.col(j) - Scale(w[j]) * (&B * X.col(j))).norm_l2() < 1e-9);
+		}
+		assert!((X.transpose() * &B * &X - Mat::<f64>::identity(k, k)).norm_max() < 1e-10);
+
+		let params = LobpcgParams {
+			which: EigenTarget::LargestMagnitude,
+			..params
+		};
+		let result = lobpcg(X.rb_mut(), &mut w, &precond, &A, Some(B_op), params, |_| {}, Par::Seq, MemStack::new(mem));
+		assert!(matches!(result, Err(LobpcgError::UnsupportedTarget)));
+	}
+
+	#[test]
+	fn test_lobpcg_largest_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 150;
+		let k = 3;
+
+		let M: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let A = &M + M.adjoint();
+		let mut expected = A.self_adjoint_eigenvalues(Side::Lower).unwrap();
+		expected.reverse();
+
+		let mut X: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: k,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let mut w = vec![0.0; k];
+
+		let params = LobpcgParams {
+			which: EigenTarget::LargestRealPart,
+			rel_tolerance: 1e-10,
+			..Default::default()
+		};
+		let precond = IdentityPrecond { dim: n };
+		let mem = &mut MemBuffer::new(lobpcg_scratch(precond, A.as_ref(), None, k, Par::Seq));
+		lobpcg(
+			X.rb_mut(),
+			&mut w,
+			precond,
+			A.as_ref(),
+			None,
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(mem),
+		)
+		.unwrap();
+
+		for j in 0..k {
+			assert!((w[j] - expected[j]).abs() < 1e-8);
+			assert!((&A * X.col(j) - Scale(c64::new(w[j], 0.0)) * X.col(j)).norm_l2() < 1e-8);
+		}
+	}
+}
@@ -0,0 +1,463 @@
+use super::*;
+use crate::assert;
+
+/// algorithm parameters
+#[derive(Copy, Clone, Debug)]
+pub struct MinresParams<T> {
+	/// whether the initial guess is implicitly zero or not
+	pub initial_guess: InitialGuessStatus,
+	/// absolute tolerance for convergence testing
+	pub abs_tolerance: T,
+	/// relative tolerance for convergence testing
+	pub rel_tolerance: T,
+	/// maximum number of iterations
+	pub max_iters: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for MinresParams<T> {
+	#[inline]
+	#[math]
+	fn default() -> Self {
+		Self {
+			initial_guess: InitialGuessStatus::MaybeNonZero,
+			abs_tolerance: zero(),
+			rel_tolerance: eps::<T>() * from_f64::<T>(128.0),
+			max_iters: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// algorithm result
+#[derive(Copy, Clone, Debug)]
+pub struct MinresInfo<T> {
+	/// absolute residual at the final step
+	pub abs_residual: T,
+	/// relative residual at the final step
+	pub rel_residual: T,
+	/// estimate of $\|A r\|$ at the final step, where $r$ is the residual. this quantity goes to
+	/// zero for least squares solutions of singular systems
+	pub ar_residual: T,
+	/// estimate of the frobenius norm of the preconditioned operator
+	pub operator_norm: T,
+	/// number of iterations executed by the algorithm
+	pub iter_count: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// algorithm error
+#[derive(Copy, Clone, Debug)]
+pub enum MinresError<T> {
+	/// preconditioner was detected to not be positive definite
+	NonPositiveDefinitePreconditioner,
+	/// convergence failure
+	NoConvergence {
+		/// absolute residual at the final step
+		abs_residual: T,
+		/// relative residual at the final step
+		rel_residual: T,
+	},
+}
+
+/// computes the size and alignment of required workspace for executing the minres algorithm
+pub fn minres_scratch<T: ComplexField>(precond: impl Precond<T>, mat: impl LinOp<T>, rhs_ncols: usize, par: Par) -> StackReq {
+	fn implementation<T: ComplexField>(M: &dyn Precond<T>, A: &dyn LinOp<T>, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = rhs_ncols;
+		let n = A.nrows();
+		let n1 = temp_mat_scratch::<T>(n, 1);
+
+		StackReq::all_of(&[
+			n1, // r1
+			n1, // r2
+			n1, // y
+			n1, // v
+			n1, // w
+			n1, // w1
+			n1, // w2
+			StackReq::any_of(&[A.apply_scratch(1, par), M.apply_in_place_scratch(1, par)]),
+		])
+	}
+	implementation(&precond, &mat, rhs_ncols, par)
+}
+
+/// executes the minres algorithm using the provided preconditioner
+///
+/// the operator must be self-adjoint, and may be indefinite. the preconditioner must be
+/// self-adjoint positive definite
+///
+/// the columns of the right-hand side are solved independently, and `params.max_iters` limits
+/// the number of iterations for each of them. convergence is tested with the residual estimate in
+/// the norm induced by the preconditioner, which is the euclidean norm if no preconditioner is used
+#[track_caller]
+pub fn minres<T: ComplexField>(
+	out: MatMut<'_, T>,
+	precond: impl Precond<T>,
+	mat: impl LinOp<T>,
+	rhs: MatRef<'_, T>,
+	params: MinresParams<T::Real>,
+	callback: impl FnMut(MatRef<'_, T>),
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<MinresInfo<T::Real>, MinresError<T::Real>> {
+	#[track_caller]
+	#[math]
+	fn implementation<T: ComplexField>(
+		mut x: MatMut<'_, T>,
+		M: &dyn Precond<T>,
+		A: &dyn LinOp<T>,
+		b: MatRef<'_, T>,
+		params: MinresParams<T::Real>,
+		callback: &mut dyn FnMut(MatRef<'_, T>),
+		par: Par,
+		stack: &mut MemStack,
+	) -> Result<MinresInfo<T::Real>, MinresError<T::Real>> {
+		assert!(all(A.nrows() == A.ncols(), M.nrows() == A.nrows(), M.ncols() == A.ncols()));
+
+		let n = A.nrows();
+		let k = b.ncols();
+		assert!(all(x.nrows() == n, x.ncols() == k, b.nrows() == n));
+
+		let b_norm = b.norm_l2();
+		if b_norm == zero::<T::Real>() {
+			x.fill(zero());
+			return Ok(MinresInfo {
+				abs_residual: zero::<T::Real>(),
+				rel_residual: zero::<T::Real>(),
+				ar_residual: zero::<T::Real>(),
+				operator_norm: zero::<T::Real>(),
+				iter_count: 0,
+				non_exhaustive: NonExhaustive(()),
+			});
+		}
+
+		if params.initial_guess == InitialGuessStatus::Zero {
+			x.fill(zero());
+		}
+
+		let (mut r1, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut r1 = r1.as_mat_mut();
+		let (mut r2, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut r2 = r2.as_mat_mut();
+		let (mut y, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut y = y.as_mat_mut();
+		let (mut v, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut v = v.as_mat_mut();
+		let (mut w, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut w = w.as_mat_mut();
+		let (mut w1, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut w1 = w1.as_mat_mut();
+		let (mut w2, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, 1, stack) };
+		let mut w2 = w2.as_mat_mut();
+
+		let dot = |a: MatRef<'_, T>, b: MatRef<'_, T>| -> T::Real { real(a.col(0).adjoint() * b.col(0)) };
+
+		let mut iter_count = 0usize;
+		let mut total_residual = zero::<T::Real>();
+		let mut total_ar_residual = zero::<T::Real>();
+		let mut operator_norm = zero::<T::Real>();
+		let mut converged = true;
+
+		for j in 0..k {
+			let bj = b.col(j);
+
+			// norm of the right-hand side in the norm induced by the preconditioner
+			y.rb_mut().col_mut(0).copy_from(bj);
+			M.apply_in_place(y.rb_mut(), par, stack);
+			let bj_dot = dot(bj.as_mat(), y.rb());
+			if bj_dot < zero() {
+				return Err(MinresError::NonPositiveDefinitePreconditioner);
+			}
+			let bj_norm = sqrt(bj_dot);
+
+			let rel_threshold = params.rel_tolerance * bj_norm;
+			let abs_threshold = copy(params.abs_tolerance);
+			let threshold = if abs_threshold > rel_threshold { abs_threshold } else { rel_threshold };
+
+			// $r_1 = b - A x$, $y = M r_1$
+			if params.initial_guess == InitialGuessStatus::Zero {
+				r1.rb_mut().col_mut(0).copy_from(bj);
+			} else {
+				A.apply(r1.rb_mut(), x.rb().col(j).as_mat(), par, stack);
+				z!(r1.rb_mut().col_mut(0), bj).for_each(|uz!(r, b)| *r = *b - *r);
+				y.copy_from(&r1);
+				M.apply_in_place(y.rb_mut(), par, stack);
+			}
+			let beta1 = dot(r1.rb(), y.rb());
+			if beta1 < zero() {
+				return Err(MinresError::NonPositiveDefinitePreconditioner);
+			}
+			let beta1 = sqrt(beta1);
+
+			r2.copy_from(&r1);
+			w.fill(zero());
+			w2.fill(zero());
+
+			let mut old_beta = zero::<T::Real>();
+			let mut beta = copy(beta1);
+			let mut dbar = zero::<T::Real>();
+			let mut epsln = zero::<T::Real>();
+			let mut phibar = copy(beta1);
+			let mut tnorm2 = zero::<T::Real>();
+			let mut cs = -one::<T::Real>();
+			let mut sn = zero::<T::Real>();
+
+			let mut residual = copy(beta1);
+			let mut ar_residual = zero::<T::Real>();
+
+			let mut iter = 0usize;
+			while residual >= threshold && beta != zero() && iter < params.max_iters {
+				// lanczos step
+				let s = recip(beta);
+				z!(v.rb_mut(), y.rb()).for_each(|uz!(v, y)| *v = mul_real(*y, s));
+				A.apply(y.rb_mut(), v.rb(), par, stack);
+				if iter > 0 {
+					let c = beta / old_beta;
+					z!(y.rb_mut(), r1.rb()).for_each(|uz!(y, r)| *y = *y - mul_real(*r, c));
+				}
+				let alpha = dot(v.rb(), y.rb());
+				{
+					let c = alpha / beta;
+					z!(y.rb_mut(), r2.rb()).for_each(|uz!(y, r)| *y = *y - mul_real(*r, c));
+				}
+				core::mem::swap(&mut r1, &mut r2);
+				core::mem::swap(&mut r2, &mut y);
+				y.copy_from(&r2);
+				M.apply_in_place(y.rb_mut(), par, stack);
+
+				old_beta = beta;
+				let beta2 = dot(r2.rb(), y.rb());
+				if beta2 < zero() {
+					return Err(MinresError::NonPositiveDefinitePreconditioner);
+				}
+				beta = sqrt(beta2);
+				tnorm2 = tnorm2 + alpha * alpha + old_beta * old_beta + beta * beta;
+
+				// apply the previous rotation, and compute the next one
+				let old_epsln = epsln;
+				let delta = cs * dbar + sn * alpha;
+				let gbar = sn * dbar - cs * alpha;
+				epsln = sn * beta;
+				dbar = -cs * beta;
+				let root = hypot(gbar, dbar);
+				ar_residual = phibar * root;
+
+				let mut gamma = hypot(gbar, beta);
+				if gamma == zero() {
+					gamma = eps::<T::Real>();
+				}
+				cs = gbar / gamma;
+				sn = beta / gamma;
+				let phi = cs * phibar;
+				phibar = sn * phibar;
+
+				// update the solution
+				let denom = recip(gamma);
+				core::mem::swap(&mut w1, &mut w2);
+				core::mem::swap(&mut w2, &mut w);
+				z!(w.rb_mut(), v.rb(), w1.rb(), w2.rb())
+					.for_each(|uz!(w, v, w1, w2)| *w = mul_real(*v - mul_real(*w1, old_epsln) - mul_real(*w2, delta), denom));
+				z!(x.rb_mut().col_mut(j), w.rb().col(0)).for_each(|uz!(x, w)| *x = *x + mul_real(*w, phi));
+
+				residual = copy(phibar);
+				iter += 1;
+
+				callback(x.rb());
+			}
+
+			if residual >= threshold {
+				converged = false;
+			}
+
+			// the true residual is reported, since the estimate is measured in the norm induced by
+			// the preconditioner
+			A.apply(y.rb_mut(), x.rb().col(j).as_mat(), par, stack);
+			z!(y.rb_mut().col_mut(0), bj).for_each(|uz!(y, b)| *y = *b - *y);
+			let true_residual = y.norm_l2();
+
+			total_residual = total_residual + true_residual * true_residual;
+			total_ar_residual = total_ar_residual + ar_residual * ar_residual;
+			let a_norm = sqrt(tnorm2);
+			if a_norm > operator_norm {
+				operator_norm = a_norm;
+			}
+			iter_count = Ord::max(iter_count, iter);
+		}
+
+		let abs_residual = sqrt(total_residual);
+		let rel_residual = abs_residual / b_norm;
+
+		if converged {
+			Ok(MinresInfo {
+				abs_residual,
+				rel_residual,
+				ar_residual: sqrt(total_ar_residual),
+				operator_norm,
+				iter_count,
+				non_exhaustive: NonExhaustive(()),
+			})
+		} else {
+			Err(MinresError::NoConvergence { abs_residual, rel_residual })
+		}
+	}
+
+	implementation(out, &precond, &mat, rhs, params, &mut { callback }, par, stack)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::matrix_free;
+	use crate::stats::prelude::*;
+	use dyn_stack::MemBuffer;
+	use equator::assert;
+
+	#[test]
+	fn test_minres_saddle_point() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 30;
+		let m = 10;
+		let k = 2;
+
+		// $\begin{bmatrix} K & B^\top \\ B & 0 \end{bmatrix}$ with $K$ positive definite
+		let C: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+		let B: Mat<f64> = CwiseMatDistribution {
+			nrows: m,
+			ncols: n,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+		let K = &C * C.transpose() + Mat::<f64>::identity(n, n) * Scale(n as f64);
+		let mut A = Mat::<f64>::zeros(n + m, n + m);
+		A.as_mut().submatrix_mut(0, 0, n, n).copy_from(&K);
+		A.as_mut().submatrix_mut(0, n, n, m).copy_from(B.transpose());
+		A.as_mut().submatrix_mut(n, 0, m, n).copy_from(&B);
+
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n + m,
+			ncols: k,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		let mut precond = Diag::<f64>::zeros(n + m);
+		for i in 0..n {
+			precond.column_vector_mut()[i] = 1.0 / K[(i, i)];
+		}
+		for i in n..n + m {
+			precond.column_vector_mut()[i] = 1.0 / n as f64;
+		}
+
+		let params = MinresParams {
+			max_iters: 200,
+			..Default::default()
+		};
+
+		let mut out = Mat::<f64>::zeros(n + m, k);
+		let result = minres(
+			out.as_mut(),
+			matrix_free::IdentityPrecond { dim: n + m },
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(minres_scratch(
+				matrix_free::IdentityPrecond { dim: n + m },
+				A.as_ref(),
+				k,
+				Par::Seq,
+			))),
+		);
+		assert!(result.is_ok());
+		assert!((&A * &out - &rhs).norm_l2() <= 10.0 * params.rel_tolerance * rhs.norm_l2());
+
+		let mut out = Mat::<f64>::zeros(n + m, k);
+		let result = minres(
+			out.as_mut(),
+			precond.as_ref(),
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(minres_scratch(precond.as_ref(), A.as_ref(), k, Par::Seq))),
+		);
+		let result = result.unwrap();
+		assert!((&A * &out - &rhs).norm_l2() <= 100.0 * params.rel_tolerance * rhs.norm_l2());
+		assert!(result.abs_residual <= 100.0 * params.rel_tolerance * rhs.norm_l2());
+	}
+
+	#[test]
+	fn test_minres_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 40;
+
+		// self-adjoint indefinite matrix with eigenvalues on both sides of zero
+		let Q: Mat<c64> = UnitaryMat {
+			dim: n,
+			standard_normal: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+		let mut d = Col::<c64>::zeros(n);
+		for i in 0..n {
+			let s = if i % 2 == 0 { 1.0 } else { -1.0 };
+			d[i] = c64::new(s * (1.0 + i as f64), 0.0);
+		}
+		let A = &Q * d.as_diagonal() * Q.adjoint();
+		let rhs: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand(rng);
+
+		let params = MinresParams {
+			max_iters: 200,
+			..Default::default()
+		};
+		let precond = matrix_free::IdentityPrecond { dim: n };
+		let mut out = Mat::<c64>::zeros(n, 1);
+		let result = minres(
+			out.as_mut(),
+			precond,
+			A.as_ref(),
+			rhs.as_ref(),
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(minres_scratch(precond, A.as_ref(), 1, Par::Seq))),
+		);
+		let result = result.unwrap();
+		assert!((&A * &out - &rhs).norm_l2() <= 100.0 * params.rel_tolerance * rhs.norm_l2());
+		assert!(result.iter_count <= 2 * n);
+	}
+
+	#[test]
+	fn test_minres_indefinite_precond() {
+		let A = crate::mat![[2.0, 1.0], [1.0, -3.0f64]];
+		let rhs = crate::mat![[1.0], [1.0f64]];
+		let precond = Mat::<f64>::identity(2, 2) * Scale(-1.0);
+		let mut out = Mat::<f64>::zeros(2, 1);
+		let result = minres(
+			out.as_mut(),
+			precond.as_ref(),
+			A.as_ref(),
+			rhs.as_ref(),
+			Default::default(),
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(minres_scratch(precond.as_ref(), A.as_ref(), 1, Par::Seq))),
+		);
+		assert!(matches!(result, Err(MinresError::NonPositiveDefinitePreconditioner)));
+	}
+}
@@ -6,11 +6,22 @@ use crate::internal_prelude_sp::*;
 pub mod bicgstab;
 /// conjugate gradient method.
 pub mod conjugate_gradient;
+/// generalized minimal residual method.
+pub mod gmres;
 /// least squares minimal residual.
 pub mod lsmr;
+/// minimal residual method for self-adjoint operators.
+pub mod minres;
 
 /// krylov-schur eigensolvers.
 pub mod eigen;
+/// locally optimal block preconditioned conjugate gradient eigensolver.
+pub mod lobpcg;
+/// partial singular value decomposition.
+pub mod svd;
+
+/// preconditioners for the iterative solvers.
+pub mod precond;
 
 mod operator_impl;
 
@@ -0,0 +1,437 @@
+use super::*;
+use crate::assert;
+use linalg_sp::triangular_solve;
+
+/// incomplete cholesky factorization error
+#[derive(Copy, Clone, Debug)]
+pub enum IncompleteCholeskyError {
+	/// a non-positive pivot was encountered
+	NonPositivePivot {
+		/// column at which the non-positive pivot was encountered
+		index: usize,
+	},
+	/// non algorithmic error
+	Generic(FaerError),
+}
+
+impl core::fmt::Display for IncompleteCholeskyError {
+	#[inline]
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for IncompleteCholeskyError {}
+
+impl<T: Into<FaerError>> From<T> for IncompleteCholeskyError {
+	fn from(value: T) -> Self {
+		Self::Generic(value.into())
+	}
+}
+
+/// incomplete cholesky factorization $A \approx LL^H$ of a sparse self-adjoint matrix, where $L$
+/// is lower triangular
+///
+/// applying the preconditioner solves a system with $LL^H$
+#[derive(Clone, Debug)]
+pub struct IncompleteCholesky<I: Index, T> {
+	L: SparseColMat<I, T>,
+}
+
+impl<I: Index, T: ComplexField> IncompleteCholesky<I, T> {
+	/// computes the $IC(0)$ factorization of $A$, where the sparsity pattern of the factor is
+	/// restricted to that of $A$
+	///
+	/// only the triangular half of $A$ specified by `side` is accessed
+	#[track_caller]
+	pub fn try_new_ic0(A: SparseColMatRef<'_, I, T>, side: Side) -> Result<Self, IncompleteCholeskyError> {
+		ic_imp(A, side, None)
+	}
+
+	/// computes the $ICT$ factorization of $A$, where fill-in is allowed and entries are dropped
+	/// according to `params`
+	///
+	/// only the triangular half of $A$ specified by `side` is accessed
+	#[track_caller]
+	pub fn try_new_ict(A: SparseColMatRef<'_, I, T>, side: Side, params: ThresholdParams<T::Real>) -> Result<Self, IncompleteCholeskyError> {
+		ic_imp(A, side, Some(params))
+	}
+
+	/// returns the lower triangular factor
+	#[inline]
+	pub fn L(&self) -> SparseColMatRef<'_, I, T> {
+		self.L.rb()
+	}
+}
+
+#[track_caller]
+fn ic_imp<I: Index, T: ComplexField>(
+	A: SparseColMatRef<'_, I, T>,
+	side: Side,
+	params: Option<ThresholdParams<T::Real>>,
+) -> Result<IncompleteCholesky<I, T>, IncompleteCholeskyError> {
+	assert!(A.nrows() == A.ncols());
+	match side {
+		Side::Lower => ic_lower_imp(A, params),
+		Side::Upper => {
+			// the upper half of $A$ is the adjoint of its lower half
+			let A = A.adjoint().to_col_major()?;
+			ic_lower_imp(A.rb(), params)
+		},
+	}
+}
+
+#[math]
+fn ic_lower_imp<I: Index, T: ComplexField>(
+	A: SparseColMatRef<'_, I, T>,
+	params: Option<ThresholdParams<T::Real>>,
+) -> Result<IncompleteCholesky<I, T>, IncompleteCholeskyError> {
+	use alloc::vec;
+	use alloc::vec::Vec;
+
+	const NONE: usize = usize::MAX;
+
+	let n = A.nrows();
+
+	let mut col_ptr = Vec::with_capacity(n + 1);
+	let mut row_idx = Vec::<usize>::new();
+	let mut val = Vec::<T>::new();
+	col_ptr.push(0usize);
+
+	let mut work = vec![zero::<T>(); n];
+	let mut mark = vec![NONE; n];
+	let mut pattern = Vec::<usize>::new();
+
+	// columns `k` of $L$ whose next unused entry lies in row `i` form a linked list starting at
+	// `head[i]`. `pos[k]` is the position of that entry
+	let mut head = vec![NONE; n];
+	let mut next = vec![NONE; n];
+	let mut pos = vec![0usize; n];
+
+	for j in 0..n {
+		pattern.clear();
+		let mut col_norm = zero::<T::Real>();
+
+		mark[j] = j;
+		pattern.push(j);
+		for (i, v) in iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+			if i < j {
+				continue;
+			}
+			if mark[i] != j {
+				mark[i] = j;
+				pattern.push(i);
+			}
+			work[i] = work[i] + *v;
+			col_norm = col_norm + abs2(*v);
+		}
+		let col_norm = sqrt(col_norm);
+
+		// left-looking update with the columns $k$ such that $l_{jk} \ne 0$
+		let mut k = head[j];
+		while k != NONE {
+			let k_next = next[k];
+			let p = pos[k];
+			let end = col_ptr[k + 1];
+			let ljk = conj(val[p]);
+
+			for q in p..end {
+				let i = row_idx[q];
+				if mark[i] == j {
+					work[i] = work[i] - val[q] * ljk;
+				} else if params.is_some() {
+					mark[i] = j;
+					pattern.push(i);
+					work[i] = -val[q] * ljk;
+				}
+			}
+
+			pos[k] = p + 1;
+			if p + 1 < end {
+				let i = row_idx[p + 1];
+				next[k] = head[i];
+				head[i] = k;
+			}
+			k = k_next;
+		}
+
+		let d = real(work[j]);
+		if d <= zero() {
+			for &i in &pattern {
+				work[i] = zero();
+			}
+			return Err(IncompleteCholeskyError::NonPositivePivot { index: j });
+		}
+		let d = sqrt(d);
+
+		pattern.retain(|&i| i != j);
+		match &params {
+			Some(params) => drop_entries(&mut pattern, &work, params.drop_tolerance * col_norm, params.max_fill),
+			None => pattern.sort_unstable(),
+		}
+
+		// the diagonal is stored first
+		let start = row_idx.len();
+		row_idx.push(j);
+		val.push(from_real(d));
+		let inv = recip(d);
+		for &i in &pattern {
+			row_idx.push(i);
+			val.push(mul_real(work[i], inv));
+		}
+		col_ptr.push(row_idx.len());
+		check_index_fits::<I>(row_idx.len())?;
+
+		work[j] = zero();
+		for &i in &pattern {
+			work[i] = zero();
+		}
+
+		pos[j] = start + 1;
+		if let Some(&i) = pattern.first() {
+			next[j] = head[i];
+			head[i] = j;
+		}
+	}
+
+	Ok(IncompleteCholesky {
+		L: finish(n, col_ptr, row_idx, val)?,
+	})
+}
+
+impl<I: Index, T: ComplexField> LinOp<T> for IncompleteCholesky<I, T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.L.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.L.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.conj_apply_in_place(out, par, stack);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiLinOp<T> for IncompleteCholesky<I, T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.transpose_apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.adjoint_apply_in_place(out, par, stack);
+	}
+}
+
+impl<I: Index, T: ComplexField> Precond<T> for IncompleteCholesky<I, T> {
+	#[inline]
+	fn apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		let mut rhs = rhs;
+		triangular_solve::solve_lower_triangular_in_place(self.L.rb(), Conj::No, rhs.rb_mut(), par);
+		triangular_solve::solve_lower_triangular_transpose_in_place(self.L.rb(), Conj::Yes, rhs.rb_mut(), par);
+	}
+
+	#[track_caller]
+	fn conj_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		let mut rhs = rhs;
+		triangular_solve::solve_lower_triangular_in_place(self.L.rb(), Conj::Yes, rhs.rb_mut(), par);
+		triangular_solve::solve_lower_triangular_transpose_in_place(self.L.rb(), Conj::No, rhs.rb_mut(), par);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiPrecond<T> for IncompleteCholesky<I, T> {
+	#[inline]
+	fn transpose_apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		// $(LL^H)^\top = \bar L L^\top$
+		self.conj_apply_in_place(rhs, par, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		self.apply_in_place(rhs, par, stack);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::matrix_free::conjugate_gradient::{CgParams, conjugate_gradient, conjugate_gradient_scratch};
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	// 2d laplacian with a complex hermitian perturbation on the grid edges
+	fn laplacian(m: usize) -> SparseColMat<usize, c64> {
+		let n = m * m;
+		let mut triplets = alloc::vec::Vec::new();
+		for x in 0..m {
+			for y in 0..m {
+				let i = x * m + y;
+				triplets.push(Triplet::new(i, i, c64::new(4.0, 0.0)));
+				if x + 1 < m {
+					triplets.push(Triplet::new(i + m, i, c64::new(-1.0, 0.1)));
+					triplets.push(Triplet::new(i, i + m, c64::new(-1.0, -0.1)));
+				}
+				if y + 1 < m {
+					triplets.push(Triplet::new(i + 1, i, c64::new(-1.0, -0.2)));
+					triplets.push(Triplet::new(i, i + 1, c64::new(-1.0, 0.2)));
+				}
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	#[test]
+	fn test_ic0_tridiagonal_is_exact() {
+		let n = 10;
+		let mut triplets = alloc::vec::Vec::new();
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 3.0));
+			if i > 0 {
+				triplets.push(Triplet::new(i, i - 1, -1.0));
+				triplets.push(Triplet::new(i - 1, i, -1.0));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+
+		for side in [Side::Lower, Side::Upper] {
+			let ic = IncompleteCholesky::try_new_ic0(A.rb(), side).unwrap();
+			assert!(ic.L().compute_nnz() == 2 * n - 1);
+
+			let mut x = rhs.clone();
+			ic.apply_in_place(x.as_mut(), Par::Seq, MemStack::new(&mut []));
+			assert!(&A * &x ~ rhs);
+		}
+	}
+
+	#[test]
+	fn test_ic_conjugate_gradient() {
+		let m = 16;
+		let A = laplacian(m);
+		let n = A.nrows();
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let rhs: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand(rng);
+
+		let params = CgParams {
+			max_iters: 400,
+			..Default::default()
+		};
+
+		fn solve(A: SparseColMatRef<'_, usize, c64>, rhs: MatRef<'_, c64>, precond: impl Precond<c64>, params: CgParams<f64>) -> usize {
+			let n = A.nrows();
+			let mut out = Mat::<c64>::zeros(n, 1);
+			let info = conjugate_gradient(
+				out.as_mut(),
+				&precond,
+				A,
+				rhs,
+				params,
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(conjugate_gradient_scratch(&precond, A, 1, Par::Seq))),
+			)
+			.unwrap();
+			assert!((A * &out - rhs).norm_l2() <= 10.0 * params.rel_tolerance * rhs.norm_l2());
+			info.iter_count
+		}
+
+		let unpreconditioned = solve(A.rb(), rhs.as_ref(), IdentityPrecond { dim: n }, params);
+
+		let ic0 = IncompleteCholesky::try_new_ic0(A.rb(), Side::Lower).unwrap();
+		let ict = IncompleteCholesky::try_new_ict(
+			A.rb(),
+			Side::Upper,
+			ThresholdParams {
+				drop_tolerance: 1e-4,
+				max_fill: 20,
+				..Default::default()
+			},
+		)
+		.unwrap();
+		assert!(ict.L().compute_nnz() > ic0.L().compute_nnz());
+
+		let iter0 = solve(A.rb(), rhs.as_ref(), &ic0, params);
+		let iter1 = solve(A.rb(), rhs.as_ref(), &ict, params);
+		assert!(iter0 < unpreconditioned);
+		assert!(iter1 <= iter0);
+	}
+
+	#[test]
+	fn test_ic_non_positive_pivot() {
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(
+			2,
+			2,
+			&[
+				Triplet::new(0, 0, 1.0),
+				Triplet::new(1, 0, 2.0),
+				Triplet::new(0, 1, 2.0),
+				Triplet::new(1, 1, 1.0),
+			],
+		)
+		.unwrap();
+		assert!(matches!(
+			IncompleteCholesky::try_new_ic0(A.rb(), Side::Lower),
+			Err(IncompleteCholeskyError::NonPositivePivot { index: 1 })
+		));
+	}
+}
@@ -0,0 +1,484 @@
+use super::*;
+use crate::assert;
+use linalg_sp::triangular_solve;
+
+/// incomplete $LU$ factorization error
+#[derive(Copy, Clone, Debug)]
+pub enum IncompleteLuError {
+	/// a zero pivot was encountered
+	ZeroPivot {
+		/// column at which the zero pivot was encountered
+		index: usize,
+	},
+	/// non algorithmic error
+	Generic(FaerError),
+}
+
+impl core::fmt::Display for IncompleteLuError {
+	#[inline]
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for IncompleteLuError {}
+
+impl<T: Into<FaerError>> From<T> for IncompleteLuError {
+	fn from(value: T) -> Self {
+		Self::Generic(value.into())
+	}
+}
+
+/// incomplete $LU$ factorization $A \approx LU$ of a sparse matrix, where $L$ is unit lower
+/// triangular and $U$ is upper triangular
+///
+/// applying the preconditioner solves a system with $LU$
+#[derive(Clone, Debug)]
+pub struct IncompleteLu<I: Index, T> {
+	L: SparseColMat<I, T>,
+	U: SparseColMat<I, T>,
+}
+
+impl<I: Index, T: ComplexField> IncompleteLu<I, T> {
+	/// computes the $ILU(0)$ factorization of $A$, where the sparsity pattern of the factors is
+	/// restricted to that of $A$
+	#[track_caller]
+	pub fn try_new_ilu0(A: SparseColMatRef<'_, I, T>) -> Result<Self, IncompleteLuError> {
+		ilu_imp(A, None)
+	}
+
+	/// computes the $ILUT$ factorization of $A$, where fill-in is allowed and entries are dropped
+	/// according to `params`
+	#[track_caller]
+	pub fn try_new_ilut(A: SparseColMatRef<'_, I, T>, params: ThresholdParams<T::Real>) -> Result<Self, IncompleteLuError> {
+		ilu_imp(A, Some(params))
+	}
+
+	/// returns the unit lower triangular factor, with the diagonal stored explicitly
+	#[inline]
+	pub fn L(&self) -> SparseColMatRef<'_, I, T> {
+		self.L.rb()
+	}
+
+	/// returns the upper triangular factor
+	#[inline]
+	pub fn U(&self) -> SparseColMatRef<'_, I, T> {
+		self.U.rb()
+	}
+}
+
+#[track_caller]
+#[math]
+fn ilu_imp<I: Index, T: ComplexField>(
+	A: SparseColMatRef<'_, I, T>,
+	params: Option<ThresholdParams<T::Real>>,
+) -> Result<IncompleteLu<I, T>, IncompleteLuError> {
+	use alloc::collections::BinaryHeap;
+	use alloc::vec;
+	use alloc::vec::Vec;
+	use core::cmp::Reverse;
+
+	assert!(A.nrows() == A.ncols());
+	let n = A.nrows();
+
+	let mut l_col_ptr = Vec::with_capacity(n + 1);
+	let mut l_row_idx = Vec::<usize>::new();
+	let mut l_val = Vec::<T>::new();
+	let mut u_col_ptr = Vec::with_capacity(n + 1);
+	let mut u_row_idx = Vec::<usize>::new();
+	let mut u_val = Vec::<T>::new();
+	l_col_ptr.push(0usize);
+	u_col_ptr.push(0usize);
+
+	let mut work = vec![zero::<T>(); n];
+	let mut mark = vec![usize::MAX; n];
+	let mut pattern = Vec::<usize>::new();
+	let mut lower = Vec::<usize>::new();
+	let mut upper = Vec::<usize>::new();
+	let mut heap = BinaryHeap::<Reverse<usize>>::new();
+
+	for j in 0..n {
+		pattern.clear();
+		let mut col_norm = zero::<T::Real>();
+
+		mark[j] = j;
+		pattern.push(j);
+		for (i, v) in iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+			if mark[i] != j {
+				mark[i] = j;
+				pattern.push(i);
+			}
+			work[i] = work[i] + *v;
+			col_norm = col_norm + abs2(*v);
+		}
+		let col_norm = sqrt(col_norm);
+		let threshold = match &params {
+			Some(params) => params.drop_tolerance * col_norm,
+			None => zero(),
+		};
+
+		for &i in &pattern {
+			if i < j {
+				heap.push(Reverse(i));
+			}
+		}
+
+		// left-looking update of the column, processing the pivots in increasing order
+		while let Some(Reverse(k)) = heap.pop() {
+			let ukj = copy(work[k]);
+			if params.is_some() && abs(ukj) < threshold {
+				continue;
+			}
+
+			// the first stored entry is the unit diagonal
+			let range = l_col_ptr[k] + 1..l_col_ptr[k + 1];
+			for (&i, lik) in iter::zip(&l_row_idx[range.clone()], &l_val[range]) {
+				if mark[i] == j {
+					work[i] = work[i] - *lik * ukj;
+				} else if params.is_some() {
+					mark[i] = j;
+					pattern.push(i);
+					work[i] = -*lik * ukj;
+					if i < j {
+						heap.push(Reverse(i));
+					}
+				}
+			}
+		}
+
+		let pivot = copy(work[j]);
+		if pivot == zero() {
+			for &i in &pattern {
+				work[i] = zero();
+			}
+			return Err(IncompleteLuError::ZeroPivot { index: j });
+		}
+
+		lower.clear();
+		upper.clear();
+		for &i in &pattern {
+			if i < j {
+				upper.push(i);
+			} else if i > j {
+				lower.push(i);
+			}
+		}
+		match &params {
+			Some(params) => {
+				drop_entries(&mut upper, &work, copy(threshold), params.max_fill);
+				drop_entries(&mut lower, &work, copy(threshold), params.max_fill);
+			},
+			None => {
+				upper.sort_unstable();
+				lower.sort_unstable();
+			},
+		}
+
+		// the diagonal of $U$ is stored last, and the unit diagonal of $L$ is stored first
+		for &i in &upper {
+			u_row_idx.push(i);
+			u_val.push(copy(work[i]));
+		}
+		u_row_idx.push(j);
+		u_val.push(copy(pivot));
+
+		let inv = recip(pivot);
+		l_row_idx.push(j);
+		l_val.push(one());
+		for &i in &lower {
+			l_row_idx.push(i);
+			l_val.push(work[i] * inv);
+		}
+
+		u_col_ptr.push(u_row_idx.len());
+		l_col_ptr.push(l_row_idx.len());
+		check_index_fits::<I>(Ord::max(u_row_idx.len(), l_row_idx.len()))?;
+
+		for &i in &pattern {
+			work[i] = zero();
+		}
+	}
+
+	Ok(IncompleteLu {
+		L: finish(n, l_col_ptr, l_row_idx, l_val)?,
+		U: finish(n, u_col_ptr, u_row_idx, u_val)?,
+	})
+}
+
+impl<I: Index, T: ComplexField> LinOp<T> for IncompleteLu<I, T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.L.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.L.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.conj_apply_in_place(out, par, stack);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiLinOp<T> for IncompleteLu<I, T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.transpose_apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.adjoint_apply_in_place(out, par, stack);
+	}
+}
+
+impl<I: Index, T: ComplexField> Precond<T> for IncompleteLu<I, T> {
+	#[inline]
+	fn apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		let mut rhs = rhs;
+		triangular_solve::solve_unit_lower_triangular_in_place(self.L.rb(), Conj::No, rhs.rb_mut(), par);
+		triangular_solve::solve_upper_triangular_in_place(self.U.rb(), Conj::No, rhs.rb_mut(), par);
+	}
+
+	#[track_caller]
+	fn conj_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		let mut rhs = rhs;
+		triangular_solve::solve_unit_lower_triangular_in_place(self.L.rb(), Conj::Yes, rhs.rb_mut(), par);
+		triangular_solve::solve_upper_triangular_in_place(self.U.rb(), Conj::Yes, rhs.rb_mut(), par);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiPrecond<T> for IncompleteLu<I, T> {
+	#[inline]
+	fn transpose_apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		let mut rhs = rhs;
+		triangular_solve::solve_upper_triangular_transpose_in_place(self.U.rb(), Conj::No, rhs.rb_mut(), par);
+		triangular_solve::solve_unit_lower_triangular_transpose_in_place(self.L.rb(), Conj::No, rhs.rb_mut(), par);
+	}
+
+	#[track_caller]
+	fn adjoint_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = stack;
+		let mut rhs = rhs;
+		triangular_solve::solve_upper_triangular_transpose_in_place(self.U.rb(), Conj::Yes, rhs.rb_mut(), par);
+		triangular_solve::solve_unit_lower_triangular_transpose_in_place(self.L.rb(), Conj::Yes, rhs.rb_mut(), par);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::matrix_free::bicgstab::{BicgParams, bicgstab, bicgstab_scratch};
+	use crate::matrix_free::lsmr::{LsmrParams, lsmr, lsmr_scratch};
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	// convection-diffusion operator on a 2d grid, which is not symmetric
+	fn convection_diffusion(m: usize) -> SparseColMat<usize, f64> {
+		let n = m * m;
+		let mut triplets = alloc::vec::Vec::new();
+		for x in 0..m {
+			for y in 0..m {
+				let i = x * m + y;
+				triplets.push(Triplet::new(i, i, 4.0));
+				if x > 0 {
+					triplets.push(Triplet::new(i, i - m, -1.3));
+				}
+				if x + 1 < m {
+					triplets.push(Triplet::new(i, i + m, -0.7));
+				}
+				if y > 0 {
+					triplets.push(Triplet::new(i, i - 1, -1.2));
+				}
+				if y + 1 < m {
+					triplets.push(Triplet::new(i, i + 1, -0.8));
+				}
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	#[test]
+	fn test_ilu0_tridiagonal_is_exact() {
+		// the $LU$ factors of a tridiagonal matrix have no fill-in
+		let n = 10;
+		let mut triplets = alloc::vec::Vec::new();
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 3.0));
+			if i > 0 {
+				triplets.push(Triplet::new(i, i - 1, -1.0));
+				triplets.push(Triplet::new(i - 1, i, -0.5));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let ilu = IncompleteLu::try_new_ilu0(A.rb()).unwrap();
+		assert!(ilu.L().compute_nnz() == 2 * n - 1);
+		assert!(ilu.U().compute_nnz() == 2 * n - 1);
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 128.0);
+
+		let mut x = rhs.clone();
+		ilu.apply_in_place(x.as_mut(), Par::Seq, MemStack::new(&mut []));
+		assert!(&A * &x ~ rhs);
+
+		let mut x = rhs.clone();
+		ilu.transpose_apply_in_place(x.as_mut(), Par::Seq, MemStack::new(&mut []));
+		assert!(A.transpose() * &x ~ rhs);
+	}
+
+	#[test]
+	fn test_ilu_bicgstab() {
+		let m = 16;
+		let A = convection_diffusion(m);
+		let n = A.nrows();
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		let params = BicgParams {
+			max_iters: 400,
+			..Default::default()
+		};
+
+		let solve = |precond: &IncompleteLu<usize, f64>| {
+			let mut out = Mat::<f64>::zeros(n, 1);
+			let info = bicgstab(
+				out.as_mut(),
+				precond,
+				IdentityPrecond { dim: n },
+				A.rb(),
+				rhs.as_ref(),
+				params,
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(bicgstab_scratch(
+					precond,
+					IdentityPrecond { dim: n },
+					A.rb(),
+					1,
+					Par::Seq,
+				))),
+			)
+			.unwrap();
+			assert!((&A * &out - &rhs).norm_l2() <= 10.0 * params.rel_tolerance * rhs.norm_l2());
+			info.iter_count
+		};
+
+		let ilu0 = IncompleteLu::try_new_ilu0(A.rb()).unwrap();
+		let ilut = IncompleteLu::try_new_ilut(
+			A.rb(),
+			ThresholdParams {
+				drop_tolerance: 1e-4,
+				max_fill: 20,
+				..Default::default()
+			},
+		)
+		.unwrap();
+		assert!(ilut.L().compute_nnz() > ilu0.L().compute_nnz());
+
+		let iter0 = solve(&ilu0);
+		let iter1 = solve(&ilut);
+		assert!(iter1 <= iter0);
+	}
+
+	#[test]
+	fn test_ilu_lsmr() {
+		let m = 8;
+		let A = convection_diffusion(m);
+		let n = A.nrows();
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		let ilu = IncompleteLu::try_new_ilut(A.rb(), Default::default()).unwrap();
+		let params = LsmrParams {
+			max_iters: 400,
+			..Default::default()
+		};
+
+		let mut out = Mat::<f64>::zeros(n, 1);
+		lsmr(
+			out.as_mut(),
+			&ilu,
+			A.rb(),
+			rhs.as_ref(),
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(lsmr_scratch(&ilu, A.rb(), 1, Par::Seq))),
+		)
+		.unwrap();
+		assert!((&A * &out - &rhs).norm_l2() <= 1e-6 * rhs.norm_l2());
+	}
+
+	#[test]
+	fn test_ilu_zero_pivot() {
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(2, 2, &[Triplet::new(0, 1, 1.0), Triplet::new(1, 0, 1.0)]).unwrap();
+		assert!(matches!(
+			IncompleteLu::try_new_ilu0(A.rb()),
+			Err(IncompleteLuError::ZeroPivot { index: 0 })
+		));
+	}
+}
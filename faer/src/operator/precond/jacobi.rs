@@ -0,0 +1,467 @@
+use super::*;
+use crate::assert;
+use crate::linalg::solvers::{Llt, LltError, PartialPivLu, SolveCore};
+
+/// jacobi preconditioner, which scales each entry by the inverse of the corresponding diagonal
+/// entry of the matrix
+#[derive(Clone, Debug)]
+pub struct JacobiPrecond<T> {
+	inv_diag: Diag<T>,
+}
+
+impl<T: ComplexField> JacobiPrecond<T> {
+	/// creates a jacobi preconditioner from the diagonal of the matrix
+	///
+	/// zero diagonal entries are treated as ones
+	#[math]
+	pub fn new(diag: DiagRef<'_, T>) -> Self {
+		let n = diag.dim();
+		let mut inv_diag = Diag::<T>::zeros(n);
+		z!(inv_diag.column_vector_mut(), diag.column_vector()).for_each(|uz!(inv, d)| {
+			*inv = if *d == zero() { one() } else { recip(*d) };
+		});
+		Self { inv_diag }
+	}
+
+	/// creates a jacobi preconditioner from the diagonal of a sparse matrix
+	///
+	/// zero diagonal entries are treated as ones
+	#[track_caller]
+	pub fn from_csc<I: Index>(A: SparseColMatRef<'_, I, T>) -> Self {
+		assert!(A.nrows() == A.ncols());
+		Self::new(sparse_diagonal(A).as_ref())
+	}
+
+	/// returns the inverse of the diagonal
+	#[inline]
+	pub fn inv_diag(&self) -> DiagRef<'_, T> {
+		self.inv_diag.as_ref()
+	}
+}
+
+/// computes the diagonal of a sparse matrix, summing duplicate entries
+#[math]
+pub(super) fn sparse_diagonal<I: Index, T: ComplexField>(A: SparseColMatRef<'_, I, T>) -> Diag<T> {
+	let n = A.ncols();
+	let mut diag = Diag::<T>::zeros(n);
+	let mut d = diag.column_vector_mut();
+	for j in 0..n {
+		for (i, v) in iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+			if i == j {
+				d[j] = d[j] + *v;
+			}
+		}
+	}
+	diag
+}
+
+impl<T: ComplexField> LinOp<T> for JacobiPrecond<T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.inv_diag.as_ref().apply_scratch(rhs_ncols, par)
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.inv_diag.dim()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.inv_diag.dim()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().apply(out, rhs, par, stack)
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().conj_apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField> BiLinOp<T> for JacobiPrecond<T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.inv_diag.as_ref().transpose_apply_scratch(rhs_ncols, par)
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().transpose_apply(out, rhs, par, stack)
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().adjoint_apply(out, rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField> Precond<T> for JacobiPrecond<T> {
+	#[inline]
+	fn apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.inv_diag.as_ref().apply_in_place_scratch(rhs_ncols, par)
+	}
+
+	#[track_caller]
+	fn apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().apply_in_place(rhs, par, stack)
+	}
+
+	#[track_caller]
+	fn conj_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().conj_apply_in_place(rhs, par, stack)
+	}
+}
+
+impl<T: ComplexField> BiPrecond<T> for JacobiPrecond<T> {
+	#[inline]
+	fn transpose_apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		self.inv_diag.as_ref().transpose_apply_in_place_scratch(rhs_ncols, par)
+	}
+
+	#[track_caller]
+	fn transpose_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().transpose_apply_in_place(rhs, par, stack)
+	}
+
+	#[track_caller]
+	fn adjoint_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		self.inv_diag.as_ref().adjoint_apply_in_place(rhs, par, stack)
+	}
+}
+
+#[derive(Clone, Debug)]
+enum BlockSolver<T> {
+	Lu(PartialPivLu<T>),
+	Llt(Llt<T>),
+}
+
+/// block jacobi preconditioner, which solves a dense system with each diagonal block of the
+/// matrix, for a user-defined partition of the indices into contiguous blocks
+#[derive(Clone, Debug)]
+pub struct BlockJacobiPrecond<T> {
+	block_ptr: alloc::vec::Vec<usize>,
+	blocks: alloc::vec::Vec<BlockSolver<T>>,
+}
+
+impl<T: ComplexField> BlockJacobiPrecond<T> {
+	/// creates a block jacobi preconditioner, where the diagonal blocks are factorized with a
+	/// partially pivoted $LU$ decomposition
+	///
+	/// block `i` spans the indices `block_ptr[i]..block_ptr[i + 1]`. `block_ptr` must be
+	/// non-decreasing, start at `0` and end at the dimension of the matrix
+	#[track_caller]
+	pub fn new_lu<I: Index>(A: SparseColMatRef<'_, I, T>, block_ptr: &[usize]) -> Self {
+		check_block_ptr(A, block_ptr);
+		let blocks = (0..block_ptr.len() - 1)
+			.map(|b| BlockSolver::Lu(PartialPivLu::new(dense_block(A, block_ptr[b], block_ptr[b + 1]).as_ref())))
+			.collect();
+		Self {
+			block_ptr: block_ptr.to_vec(),
+			blocks,
+		}
+	}
+
+	/// creates a block jacobi preconditioner for a self-adjoint positive definite matrix, where
+	/// the diagonal blocks are factorized with a cholesky decomposition
+	///
+	/// only the triangular half of the diagonal blocks specified by `side` is accessed. block `i`
+	/// spans the indices `block_ptr[i]..block_ptr[i + 1]`. `block_ptr` must be non-decreasing,
+	/// start at `0` and end at the dimension of the matrix
+	#[track_caller]
+	pub fn try_new_llt<I: Index>(A: SparseColMatRef<'_, I, T>, block_ptr: &[usize], side: Side) -> Result<Self, LltError> {
+		check_block_ptr(A, block_ptr);
+		let blocks = (0..block_ptr.len() - 1)
+			.map(|b| {
+				let start = block_ptr[b];
+				Llt::new(dense_block(A, start, block_ptr[b + 1]).as_ref(), side)
+					.map(BlockSolver::Llt)
+					.map_err(|LltError::NonPositivePivot { index }| LltError::NonPositivePivot { index: start + index })
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self {
+			block_ptr: block_ptr.to_vec(),
+			blocks,
+		})
+	}
+
+	fn solve(&self, conj: Conj, transpose: bool, rhs: MatMut<'_, T>) {
+		let mut rhs = rhs;
+		for (b, block) in self.blocks.iter().enumerate() {
+			let start = self.block_ptr[b];
+			let len = self.block_ptr[b + 1] - start;
+			let rhs = rhs.rb_mut().subrows_mut(start, len);
+			let solver: &dyn SolveCore<T> = match block {
+				BlockSolver::Lu(lu) => lu,
+				BlockSolver::Llt(llt) => llt,
+			};
+			if transpose {
+				solver.solve_transpose_in_place_with_conj(conj, rhs);
+			} else {
+				solver.solve_in_place_with_conj(conj, rhs);
+			}
+		}
+	}
+}
+
+#[track_caller]
+fn check_block_ptr<I: Index, T>(A: SparseColMatRef<'_, I, T>, block_ptr: &[usize]) {
+	assert!(all(A.nrows() == A.ncols(), block_ptr.len() > 0));
+	assert!(all(block_ptr[0] == 0, block_ptr[block_ptr.len() - 1] == A.nrows()));
+	for w in block_ptr.windows(2) {
+		assert!(w[0] <= w[1]);
+	}
+}
+
+#[math]
+fn dense_block<I: Index, T: ComplexField>(A: SparseColMatRef<'_, I, T>, start: usize, end: usize) -> Mat<T> {
+	let mut block = Mat::<T>::zeros(end - start, end - start);
+	for j in start..end {
+		for (i, v) in iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+			if i >= start && i < end {
+				block[(i - start, j - start)] = block[(i - start, j - start)] + *v;
+			}
+		}
+	}
+	block
+}
+
+impl<T: ComplexField> LinOp<T> for BlockJacobiPrecond<T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.block_ptr[self.block_ptr.len() - 1]
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.block_ptr[self.block_ptr.len() - 1]
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.conj_apply_in_place(out, par, stack);
+	}
+}
+
+impl<T: ComplexField> BiLinOp<T> for BlockJacobiPrecond<T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.transpose_apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.adjoint_apply_in_place(out, par, stack);
+	}
+}
+
+impl<T: ComplexField> Precond<T> for BlockJacobiPrecond<T> {
+	#[inline]
+	fn apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(Conj::No, false, rhs);
+	}
+
+	#[track_caller]
+	fn conj_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(Conj::Yes, false, rhs);
+	}
+}
+
+impl<T: ComplexField> BiPrecond<T> for BlockJacobiPrecond<T> {
+	#[inline]
+	fn transpose_apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(Conj::No, true, rhs);
+	}
+
+	#[track_caller]
+	fn adjoint_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(Conj::Yes, true, rhs);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::matrix_free::bicgstab::{BicgParams, bicgstab, bicgstab_scratch};
+	use crate::matrix_free::conjugate_gradient::{CgParams, conjugate_gradient, conjugate_gradient_scratch};
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	// block tridiagonal matrix with dense diagonal blocks of size `bs`
+	fn block_matrix(nblocks: usize, bs: usize, rng: &mut StdRng) -> SparseColMat<usize, f64> {
+		let n = nblocks * bs;
+		let mut triplets = alloc::vec::Vec::new();
+		for b in 0..nblocks {
+			let C: Mat<f64> = CwiseMatDistribution {
+				nrows: bs,
+				ncols: bs,
+				dist: StandardNormal,
+			}
+			.rand(rng);
+			let block = &C * C.transpose() + Mat::<f64>::identity(bs, bs) * Scale((b + 1) as f64);
+			for j in 0..bs {
+				for i in 0..bs {
+					triplets.push(Triplet::new(b * bs + i, b * bs + j, block[(i, j)]));
+				}
+			}
+			if b + 1 < nblocks {
+				triplets.push(Triplet::new(b * bs + bs, b * bs, -0.5));
+				triplets.push(Triplet::new(b * bs, b * bs + bs, -0.5));
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	#[test]
+	fn test_jacobi() {
+		let diag = crate::col![2.0, 0.0, -4.0f64];
+		let jacobi = JacobiPrecond::new(diag.as_diagonal());
+		assert!(jacobi.inv_diag().column_vector() == crate::col![0.5, 1.0, -0.25]);
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let A = block_matrix(20, 3, rng);
+		let n = A.nrows();
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		let jacobi = JacobiPrecond::from_csc(A.rb());
+		let params = CgParams {
+			max_iters: 400,
+			..Default::default()
+		};
+		let mut out = Mat::<f64>::zeros(n, 2);
+		conjugate_gradient(
+			out.as_mut(),
+			&jacobi,
+			A.rb(),
+			rhs.as_ref(),
+			params,
+			|_| {},
+			Par::Seq,
+			MemStack::new(&mut MemBuffer::new(conjugate_gradient_scratch(&jacobi, A.rb(), 2, Par::Seq))),
+		)
+		.unwrap();
+		assert!((&A * &out - &rhs).norm_l2() <= 10.0 * params.rel_tolerance * rhs.norm_l2());
+	}
+
+	#[test]
+	fn test_block_jacobi() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let nblocks = 20;
+		let bs = 3;
+		let A = block_matrix(nblocks, bs, rng);
+		let n = A.nrows();
+		let block_ptr = (0..nblocks + 1).map(|b| b * bs).collect::<alloc::vec::Vec<_>>();
+
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		// the preconditioner solves the block diagonal part exactly
+		let mut B = A.to_dense();
+		for j in 0..n {
+			for i in 0..n {
+				if i / bs != j / bs {
+					B[(i, j)] = 0.0;
+				}
+			}
+		}
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+
+		let lu = BlockJacobiPrecond::new_lu(A.rb(), &block_ptr);
+		let llt = BlockJacobiPrecond::try_new_llt(A.rb(), &block_ptr, Side::Lower).unwrap();
+		for precond in [&lu, &llt] {
+			let mut x = rhs.clone();
+			precond.apply_in_place(x.as_mut(), Par::Seq, MemStack::new(&mut []));
+			assert!(&B * &x ~ rhs);
+
+			let mut x = rhs.clone();
+			precond.transpose_apply_in_place(x.as_mut(), Par::Seq, MemStack::new(&mut []));
+			assert!(B.transpose() * &x ~ rhs);
+
+			let params = BicgParams {
+				max_iters: 400,
+				..Default::default()
+			};
+			let mut out = Mat::<f64>::zeros(n, 2);
+			bicgstab(
+				out.as_mut(),
+				precond,
+				IdentityPrecond { dim: n },
+				A.rb(),
+				rhs.as_ref(),
+				params,
+				|_| {},
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(bicgstab_scratch(
+					precond,
+					IdentityPrecond { dim: n },
+					A.rb(),
+					2,
+					Par::Seq,
+				))),
+			)
+			.unwrap();
+			assert!((&A * &out - &rhs).norm_l2() <= 10.0 * params.rel_tolerance * rhs.norm_l2());
+		}
+
+		let neg = A.rb() * Scale(-1.0);
+		assert!(matches!(
+			BlockJacobiPrecond::try_new_llt(neg.rb(), &block_ptr, Side::Lower),
+			Err(LltError::NonPositivePivot { index: 0 })
+		));
+	}
+}
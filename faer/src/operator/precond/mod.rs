@@ -0,0 +1,70 @@
+use super::*;
+
+/// incomplete cholesky preconditioners.
+pub mod ic;
+/// incomplete $LU$ preconditioners.
+pub mod ilu;
+/// jacobi and block jacobi preconditioners.
+pub mod jacobi;
+/// successive over-relaxation and gauss-seidel preconditioners.
+pub mod ssor;
+
+/// dropping strategy for threshold-based incomplete factorizations
+#[derive(Copy, Clone, Debug)]
+pub struct ThresholdParams<T> {
+	/// entries whose magnitude is smaller than this tolerance, relative to the norm of the
+	/// corresponding column of the input matrix, are dropped
+	pub drop_tolerance: T,
+	/// maximum number of off-diagonal entries kept in each column of each triangular factor
+	pub max_fill: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for ThresholdParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			drop_tolerance: from_f64::<T>(1e-3),
+			max_fill: usize::MAX,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// keeps the entries of `pattern` whose magnitude is at least `threshold`, at most `max_fill` of
+/// them, and sorts them by index
+#[math]
+fn drop_entries<T: ComplexField>(pattern: &mut alloc::vec::Vec<usize>, work: &[T], threshold: T::Real, max_fill: usize) {
+	pattern.retain(|&i| abs(work[i]) >= threshold);
+	if pattern.len() > max_fill {
+		pattern.sort_unstable_by(|&i, &j| {
+			let (a, b) = (abs(work[i]), abs(work[j]));
+			b.partial_cmp(&a).unwrap_or(core::cmp::Ordering::Equal)
+		});
+		pattern.truncate(max_fill);
+	}
+	pattern.sort_unstable();
+}
+
+/// converts the column-wise storage built by the factorization into a sparse matrix
+fn finish<I: Index, T>(
+	dim: usize,
+	col_ptr: alloc::vec::Vec<usize>,
+	row_idx: alloc::vec::Vec<usize>,
+	val: alloc::vec::Vec<T>,
+) -> Result<SparseColMat<I, T>, FaerError> {
+	let col_ptr = col_ptr.into_iter().map(I::truncate).collect();
+	let row_idx = row_idx.into_iter().map(I::truncate).collect();
+	let symbolic = unsafe { SymbolicSparseColMat::new_unchecked(dim, dim, col_ptr, None, row_idx) };
+	Ok(SparseColMat::new(symbolic, val))
+}
+
+fn check_index_fits<I: Index>(nnz: usize) -> Result<(), FaerError> {
+	if nnz > I::Signed::MAX.zx() {
+		Err(FaerError::IndexOverflow)
+	} else {
+		Ok(())
+	}
+}
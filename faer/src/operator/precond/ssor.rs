@@ -0,0 +1,476 @@
+use super::*;
+use crate::assert;
+
+/// direction of the relaxation sweep
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SorSweep {
+	/// forward sweep, the preconditioner is $\frac{1}{\omega} D + L$
+	Forward,
+	/// backward sweep, the preconditioner is $\frac{1}{\omega} D + U$
+	Backward,
+	/// forward sweep followed by a backward sweep, the preconditioner is
+	/// $\frac{\omega}{2 - \omega} (\frac{1}{\omega} D + L) (\frac{1}{\omega} D)^{-1}
+	/// (\frac{1}{\omega} D + U)$
+	Symmetric,
+}
+
+/// successive over-relaxation parameters
+#[derive(Copy, Clone, Debug)]
+pub struct SsorParams<T> {
+	/// relaxation factor, must lie in the open interval $(0, 2)$
+	pub omega: T,
+	/// direction of the sweep
+	pub sweep: SorSweep,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl<T: RealField> Default for SsorParams<T> {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			omega: one(),
+			sweep: SorSweep::Symmetric,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// successive over-relaxation error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SsorError {
+	/// a zero diagonal entry was encountered
+	ZeroDiagonal {
+		/// index of the zero diagonal entry
+		index: usize,
+	},
+}
+
+impl core::fmt::Display for SsorError {
+	#[inline]
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self, f)
+	}
+}
+
+impl core::error::Error for SsorError {}
+
+/// successive over-relaxation preconditioner, applying a single relaxation sweep with the lower
+/// triangular part $L$, diagonal $D$ and upper triangular part $U$ of the matrix
+///
+/// with $\omega = 1$, the forward sweep is the gauss-seidel preconditioner
+#[derive(Clone, Debug)]
+pub struct SsorPrecond<'a, I: Index, T: ComplexField> {
+	// the matrix is `mat` if `transposed` is false, otherwise `mat.transpose()`
+	mat: SparseColMatRef<'a, I, T>,
+	transposed: bool,
+	diag: Diag<T>,
+	omega: T::Real,
+	sweep: SorSweep,
+}
+
+impl<'a, I: Index, T: ComplexField> SsorPrecond<'a, I, T> {
+	/// creates a successive over-relaxation preconditioner from a column-major matrix
+	#[track_caller]
+	pub fn try_new_from_csc(A: SparseColMatRef<'a, I, T>, params: SsorParams<T::Real>) -> Result<Self, SsorError> {
+		Self::new_imp(A, false, params)
+	}
+
+	/// creates a successive over-relaxation preconditioner from a row-major matrix
+	#[track_caller]
+	pub fn try_new_from_csr(A: SparseRowMatRef<'a, I, T>, params: SsorParams<T::Real>) -> Result<Self, SsorError> {
+		Self::new_imp(A.transpose(), true, params)
+	}
+
+	#[track_caller]
+	#[math]
+	fn new_imp(mat: SparseColMatRef<'a, I, T>, transposed: bool, params: SsorParams<T::Real>) -> Result<Self, SsorError> {
+		assert!(all(
+			mat.nrows() == mat.ncols(),
+			params.omega > zero::<T::Real>(),
+			params.omega < from_f64::<T::Real>(2.0),
+		));
+
+		let diag = super::jacobi::sparse_diagonal(mat);
+		for (index, d) in diag.column_vector().iter().enumerate() {
+			if *d == zero() {
+				return Err(SsorError::ZeroDiagonal { index });
+			}
+		}
+
+		Ok(Self {
+			mat,
+			transposed,
+			diag,
+			omega: params.omega,
+			sweep: params.sweep,
+		})
+	}
+
+	// solves $(\frac{1}{\omega} D + L) x = b$ for the matrix `mat` if `transposed` is false,
+	// otherwise for `mat.transpose()`
+	#[math]
+	fn lower_solve(&self, transposed: bool, conj: Conj, x: ColMut<'_, T>) {
+		let B = self.mat;
+		let d = self.diag.column_vector();
+		let omega = copy(self.omega);
+		let n = B.ncols();
+		let mut x = x;
+
+		if !transposed {
+			for j in 0..n {
+				let xj = mul_real(x[j] * recip(conj.apply_rt(&d[j])), omega);
+				for (i, b) in iter::zip(B.row_idx_of_col(j), B.val_of_col(j)) {
+					if i > j {
+						x[i] = x[i] - conj.apply_rt(b) * xj;
+					}
+				}
+				x[j] = xj;
+			}
+		} else {
+			for i in 0..n {
+				let mut acc = zero::<T>();
+				for (k, b) in iter::zip(B.row_idx_of_col(i), B.val_of_col(i)) {
+					if k < i {
+						acc = acc + conj.apply_rt(b) * x[k];
+					}
+				}
+				x[i] = mul_real((x[i] - acc) * recip(conj.apply_rt(&d[i])), omega);
+			}
+		}
+	}
+
+	// solves $(\frac{1}{\omega} D + U) x = b$ for the matrix `mat` if `transposed` is false,
+	// otherwise for `mat.transpose()`
+	#[math]
+	fn upper_solve(&self, transposed: bool, conj: Conj, x: ColMut<'_, T>) {
+		let B = self.mat;
+		let d = self.diag.column_vector();
+		let omega = copy(self.omega);
+		let n = B.ncols();
+		let mut x = x;
+
+		if !transposed {
+			for j in (0..n).rev() {
+				let xj = mul_real(x[j] * recip(conj.apply_rt(&d[j])), omega);
+				for (i, b) in iter::zip(B.row_idx_of_col(j), B.val_of_col(j)) {
+					if i < j {
+						x[i] = x[i] - conj.apply_rt(b) * xj;
+					}
+				}
+				x[j] = xj;
+			}
+		} else {
+			for i in (0..n).rev() {
+				let mut acc = zero::<T>();
+				for (k, b) in iter::zip(B.row_idx_of_col(i), B.val_of_col(i)) {
+					if k > i {
+						acc = acc + conj.apply_rt(b) * x[k];
+					}
+				}
+				x[i] = mul_real((x[i] - acc) * recip(conj.apply_rt(&d[i])), omega);
+			}
+		}
+	}
+
+	#[math]
+	fn solve(&self, transpose: bool, conj: Conj, rhs: MatMut<'_, T>) {
+		assert!(rhs.nrows() == self.mat.nrows());
+
+		// the transpose of a sweep over the matrix is the opposite sweep over its transpose
+		let transposed = self.transposed != transpose;
+		let sweep = match (self.sweep, transpose) {
+			(SorSweep::Forward, true) => SorSweep::Backward,
+			(SorSweep::Backward, true) => SorSweep::Forward,
+			(sweep, _) => sweep,
+		};
+
+		let d = self.diag.column_vector();
+		let omega = copy(self.omega);
+		let mut rhs = rhs;
+
+		for x in rhs.rb_mut().col_iter_mut() {
+			let mut x = x;
+			match sweep {
+				SorSweep::Forward => self.lower_solve(transposed, conj, x),
+				SorSweep::Backward => self.upper_solve(transposed, conj, x),
+				SorSweep::Symmetric => {
+					self.lower_solve(transposed, conj, x.rb_mut());
+					let scale = recip(omega);
+					z!(x.rb_mut(), d).for_each(|uz!(x, d)| *x = mul_real(*x * conj.apply_rt(d), scale));
+					self.upper_solve(transposed, conj, x.rb_mut());
+					let scale = (from_f64::<T::Real>(2.0) - omega) * recip(omega);
+					z!(x.rb_mut()).for_each(|uz!(x)| *x = mul_real(*x, scale));
+				},
+			}
+		}
+	}
+}
+
+impl<I: Index, T: ComplexField> LinOp<T> for SsorPrecond<'_, I, T> {
+	#[inline]
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[inline]
+	fn nrows(&self) -> usize {
+		self.mat.nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		self.mat.ncols()
+	}
+
+	#[track_caller]
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.conj_apply_in_place(out, par, stack);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiLinOp<T> for SsorPrecond<'_, I, T> {
+	#[inline]
+	fn transpose_apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.transpose_apply_in_place(out, par, stack);
+	}
+
+	#[track_caller]
+	fn adjoint_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let mut out = out;
+		out.copy_from(rhs);
+		self.adjoint_apply_in_place(out, par, stack);
+	}
+}
+
+impl<I: Index, T: ComplexField> Precond<T> for SsorPrecond<'_, I, T> {
+	#[inline]
+	fn apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(false, Conj::No, rhs);
+	}
+
+	#[track_caller]
+	fn conj_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(false, Conj::Yes, rhs);
+	}
+}
+
+impl<I: Index, T: ComplexField> BiPrecond<T> for SsorPrecond<'_, I, T> {
+	#[inline]
+	fn transpose_apply_in_place_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		_ = (rhs_ncols, par);
+		StackReq::EMPTY
+	}
+
+	#[track_caller]
+	fn transpose_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(true, Conj::No, rhs);
+	}
+
+	#[track_caller]
+	fn adjoint_apply_in_place(&self, rhs: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+		_ = (par, stack);
+		self.solve(true, Conj::Yes, rhs);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::solvers::DenseSolveCore;
+	use crate::matrix_free::conjugate_gradient::{CgParams, conjugate_gradient, conjugate_gradient_scratch};
+	use crate::stats::prelude::*;
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	fn random_sparse(n: usize, rng: &mut StdRng) -> SparseColMat<usize, c64> {
+		let dist = ComplexDistribution::new(StandardNormal, StandardNormal);
+		let mut triplets = alloc::vec::Vec::new();
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, c64::new(n as f64, 1.0)));
+			for j in 0..n {
+				if i != j && (i * 7 + j * 3) % 5 == 0 {
+					triplets.push(Triplet::new(i, j, dist.sample(rng)));
+				}
+			}
+		}
+		SparseColMat::try_new_from_triplets(n, n, &triplets).unwrap()
+	}
+
+	#[test]
+	fn test_ssor_dense_reference() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let n = 12;
+		let A = random_sparse(n, rng);
+		let A_dense = A.to_dense();
+		let A_csr = A.to_row_major().unwrap();
+
+		let rhs: Mat<c64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 3,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand(rng);
+
+		let omega = 1.3;
+		let mut D = Mat::<c64>::zeros(n, n);
+		let mut L = Mat::<c64>::zeros(n, n);
+		let mut U = Mat::<c64>::zeros(n, n);
+		for j in 0..n {
+			for i in 0..n {
+				let a = A_dense[(i, j)];
+				if i == j {
+					D[(i, j)] = a * c64::new(1.0 / omega, 0.0);
+				} else if i > j {
+					L[(i, j)] = a;
+				} else {
+					U[(i, j)] = a;
+				}
+			}
+		}
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1024.0);
+
+		for (sweep, M) in [
+			(SorSweep::Forward, &D + &L),
+			(SorSweep::Backward, &D + &U),
+			(
+				SorSweep::Symmetric,
+				(&D + &L) * D.as_ref().partial_piv_lu().inverse() * (&D + &U) * Scale(c64::new(omega / (2.0 - omega), 0.0)),
+			),
+		] {
+			let params = SsorParams {
+				omega,
+				sweep,
+				..Default::default()
+			};
+			let ssor_csc = SsorPrecond::try_new_from_csc(A.rb(), params).unwrap();
+			let ssor_csr = SsorPrecond::try_new_from_csr(A_csr.rb(), params).unwrap();
+
+			for ssor in [&ssor_csc, &ssor_csr] {
+				let stack = MemStack::new(&mut []);
+
+				let mut x = rhs.clone();
+				ssor.apply_in_place(x.as_mut(), Par::Seq, stack);
+				assert!(&M * &x ~ rhs);
+
+				let mut x = rhs.clone();
+				ssor.conj_apply_in_place(x.as_mut(), Par::Seq, stack);
+				assert!(M.conjugate() * &x ~ rhs);
+
+				let mut x = rhs.clone();
+				ssor.transpose_apply_in_place(x.as_mut(), Par::Seq, stack);
+				assert!(M.transpose() * &x ~ rhs);
+
+				let mut x = rhs.clone();
+				ssor.adjoint_apply_in_place(x.as_mut(), Par::Seq, stack);
+				assert!(M.adjoint() * &x ~ rhs);
+			}
+		}
+	}
+
+	#[test]
+	fn test_ssor_conjugate_gradient() {
+		let m = 12;
+		let n = m * m;
+		let mut triplets = alloc::vec::Vec::new();
+		for x in 0..m {
+			for y in 0..m {
+				let i = x * m + y;
+				triplets.push(Triplet::new(i, i, 4.0));
+				if x + 1 < m {
+					triplets.push(Triplet::new(i + m, i, -1.0));
+					triplets.push(Triplet::new(i, i + m, -1.0));
+				}
+				if y + 1 < m {
+					triplets.push(Triplet::new(i + 1, i, -1.0));
+					triplets.push(Triplet::new(i, i + 1, -1.0));
+				}
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let rhs: Mat<f64> = CwiseMatDistribution {
+			nrows: n,
+			ncols: 1,
+			dist: StandardNormal,
+		}
+		.rand(rng);
+
+		let params = CgParams {
+			max_iters: 400,
+			..Default::default()
+		};
+
+		let solve = |precond: &dyn Fn(MatMut<'_, f64>, &mut MemStack) -> usize, req: StackReq| {
+			let mut out = Mat::<f64>::zeros(n, 1);
+			let iter_count = precond(out.as_mut(), MemStack::new(&mut MemBuffer::new(req)));
+			assert!((&A * &out - &rhs).norm_l2() <= 10.0 * params.rel_tolerance * rhs.norm_l2());
+			iter_count
+		};
+
+		let identity = IdentityPrecond { dim: n };
+		let unpreconditioned = solve(
+			&|out, stack| {
+				conjugate_gradient(out, identity, A.rb(), rhs.as_ref(), params, |_| {}, Par::Seq, stack)
+					.unwrap()
+					.iter_count
+			},
+			conjugate_gradient_scratch(identity, A.rb(), 1, Par::Seq),
+		);
+
+		let ssor = SsorPrecond::try_new_from_csc(
+			A.rb(),
+			SsorParams {
+				omega: 1.5,
+				..Default::default()
+			},
+		)
+		.unwrap();
+		let preconditioned = solve(
+			&|out, stack| {
+				conjugate_gradient(out, &ssor, A.rb(), rhs.as_ref(), params, |_| {}, Par::Seq, stack)
+					.unwrap()
+					.iter_count
+			},
+			conjugate_gradient_scratch(&ssor, A.rb(), 1, Par::Seq),
+		);
+		assert!(preconditioned < unpreconditioned);
+	}
+
+	#[test]
+	fn test_ssor_zero_diagonal() {
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(2, 2, &[Triplet::new(0, 0, 1.0), Triplet::new(0, 1, 1.0)]).unwrap();
+		assert!(SsorPrecond::try_new_from_csc(A.rb(), Default::default()).unwrap_err() == SsorError::ZeroDiagonal { index: 1 });
+	}
+}
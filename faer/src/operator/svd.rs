@@ -0,0 +1,874 @@
+use super::eigen::{EigenTarget, PartialEigenParams, partial_self_adjoint_eigen, partial_self_adjoint_eigen_scratch};
+use super::*;
+use crate::assert;
+use linalg::svd::ComputeSvdVectors;
+#[cfg(feature = "rand")]
+use {linalg::matmul::matmul, linalg::svd::SvdError};
+
+/// part of the singular spectrum targeted by the partial singular value decomposition.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SvdTarget {
+	/// largest singular values.
+	#[default]
+	Largest,
+	/// smallest singular values.
+	Smallest,
+}
+
+/// partial singular value decomposition tuning parameters.
+#[derive(Debug, Copy, Clone)]
+pub struct PartialSvdParams {
+	/// minimum projection subspace dimension.
+	pub min_dim: usize,
+	/// maximum projection subspace dimension.
+	pub max_dim: usize,
+	/// maximum number of algorithm restarts.
+	pub max_restarts: usize,
+	/// part of the singular spectrum that should be computed.
+	pub which: SvdTarget,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+/// partial singular value decomposition information.
+#[derive(Debug, Copy, Clone)]
+pub struct PartialSvdInfo {
+	/// number of converged singular values and singular vectors.
+	pub n_converged_singular: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for PartialSvdParams {
+	fn default() -> Self {
+		Self {
+			min_dim: 0,
+			max_dim: 0,
+			max_restarts: 1000,
+			which: SvdTarget::Largest,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+/// normal operator $A^H A$, or $A A^H$ if `wide` is `true`.
+#[derive(Debug)]
+struct Normal<'a, T: ComplexField> {
+	A: &'a dyn BiLinOp<T>,
+	wide: bool,
+}
+
+impl<T: ComplexField> LinOp<T> for Normal<'_, T> {
+	fn apply_scratch(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		let inner = if self.wide { self.A.ncols() } else { self.A.nrows() };
+		temp_mat_scratch::<T>(inner, rhs_ncols).and(self.A.apply_scratch(rhs_ncols, par).or(self.A.transpose_apply_scratch(rhs_ncols, par)))
+	}
+
+	fn nrows(&self) -> usize {
+		if self.wide { self.A.nrows() } else { self.A.ncols() }
+	}
+
+	fn ncols(&self) -> usize {
+		self.nrows()
+	}
+
+	fn apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let inner = if self.wide { self.A.ncols() } else { self.A.nrows() };
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(inner, rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		if self.wide {
+			self.A.adjoint_apply(tmp.rb_mut(), rhs, par, stack);
+			self.A.apply(out, tmp.rb(), par, stack);
+		} else {
+			self.A.apply(tmp.rb_mut(), rhs, par, stack);
+			self.A.adjoint_apply(out, tmp.rb(), par, stack);
+		}
+	}
+
+	fn conj_apply(&self, out: MatMut<'_, T>, rhs: MatRef<'_, T>, par: Par, stack: &mut MemStack) {
+		let inner = if self.wide { self.A.ncols() } else { self.A.nrows() };
+		let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(inner, rhs.ncols(), stack) };
+		let mut tmp = tmp.as_mat_mut();
+
+		if self.wide {
+			self.A.transpose_apply(tmp.rb_mut(), rhs, par, stack);
+			self.A.conj_apply(out, tmp.rb(), par, stack);
+		} else {
+			self.A.conj_apply(tmp.rb_mut(), rhs, par, stack);
+			self.A.transpose_apply(out, tmp.rb(), par, stack);
+		}
+	}
+}
+
+fn eigen_params(params: PartialSvdParams) -> PartialEigenParams {
+	PartialEigenParams {
+		min_dim: params.min_dim,
+		max_dim: params.max_dim,
+		max_restarts: params.max_restarts,
+		which: match params.which {
+			SvdTarget::Largest => EigenTarget::LargestRealPart,
+			SvdTarget::Smallest => EigenTarget::SmallestRealPart,
+		},
+		..Default::default()
+	}
+}
+
+/// computes the size and alignment of required workspace for computing the `n_singular` singular
+/// values (and corresponding singular vectors) of $A$ targeted by `params.which`.
+pub fn partial_svd_scratch<T: ComplexField>(A: impl BiLinOp<T>, n_singular: usize, par: Par, params: PartialSvdParams) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn BiLinOp<T>, n_singular: usize, par: Par, params: PartialSvdParams) -> StackReq {
+		let op = Normal {
+			A,
+			wide: A.nrows() < A.ncols(),
+		};
+		let n_singular = Ord::min(n_singular, op.nrows());
+		StackReq::any_of(&[
+			partial_self_adjoint_eigen_scratch(&op, n_singular, par, eigen_params(params)),
+			A.apply_scratch(n_singular, par),
+			A.transpose_apply_scratch(n_singular, par),
+		])
+	}
+
+	implementation(&A, n_singular, par, params)
+}
+
+/// computes an estimate of the singular values (and corresponding left and right singular
+/// vectors) of $A$ targeted by `params.which` until the provided outputs are full or the maximum
+/// number of algorithm restarts is reached.
+///
+/// the singular values are computed with the thick-restart lanczos algorithm applied to the
+/// normal operator $A^H A$ if $A$ has at least as many rows as columns, or $A A^H$ otherwise.
+/// `v0` is the starting vector for that operator, so it must have $\min(m, n)$ rows, and
+/// `tolerance` is the threshold on its eigenvalue residuals.
+///
+/// the singular values are sorted so that the most wanted ones come first.
+pub fn partial_svd<T: ComplexField>(
+	U: MatMut<'_, T>,
+	S: &mut [T::Real],
+	V: MatMut<'_, T>,
+	A: impl BiLinOp<T>,
+	v0: ColRef<'_, T>,
+	tolerance: T::Real,
+	par: Par,
+	stack: &mut MemStack,
+	params: PartialSvdParams,
+) -> PartialSvdInfo {
+	#[math]
+	fn implementation<T: ComplexField>(
+		U: MatMut<'_, T>,
+		S: &mut [T::Real],
+		V: MatMut<'_, T>,
+		A: &dyn BiLinOp<T>,
+		v0: ColRef<'_, T>,
+		tolerance: T::Real,
+		par: Par,
+		stack: &mut MemStack,
+		params: PartialSvdParams,
+	) -> PartialSvdInfo {
+		let m = A.nrows();
+		let n = A.ncols();
+		let wide = m < n;
+		let k = S.len();
+		assert!(all(
+			U.nrows() == m,
+			V.nrows() == n,
+			U.ncols() == k,
+			V.ncols() == k,
+			v0.nrows() == Ord::min(m, n),
+		));
+
+		let mut U = U;
+		let mut V = V;
+		let op = Normal { A, wide };
+
+		// the eigenvectors of the normal operator are the singular vectors of the smaller side,
+		// and the other side is recovered by applying $A$ or $A^H$ and normalizing
+		let (mut X, mut Y) = if wide { (U.rb_mut(), V.rb_mut()) } else { (V.rb_mut(), U.rb_mut()) };
+
+		// both targets map to the real part of the spectrum, which is always supported
+		let info = partial_self_adjoint_eigen(X.rb_mut(), S, &op, v0, tolerance, par, stack, eigen_params(params)).unwrap();
+		let k = info.n_converged_eigen;
+
+		let X = X.rb().get(.., ..k);
+		let mut Y = Y.rb_mut().get_mut(.., ..k);
+		if wide {
+			A.adjoint_apply(Y.rb_mut(), X, par, stack);
+		} else {
+			A.apply(Y.rb_mut(), X, par, stack);
+		}
+
+		// $\|A v\|$ is a more accurate estimate of the small singular values than the square root
+		// of the eigenvalue
+		for (j, s) in S[..k].iter_mut().enumerate() {
+			let mut y = Y.rb_mut().col_mut(j);
+			let norm = y.norm_l2();
+			if norm > zero() {
+				let norm_inv = recip(norm);
+				zip!(&mut y).for_each(|unzip!(y)| *y = mul_real(*y, norm_inv));
+			}
+			*s = norm;
+		}
+
+		PartialSvdInfo {
+			n_converged_singular: k,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+
+	implementation(U, S, V, &A, v0, tolerance, par, stack, params)
+}
+
+/// randomized singular value decomposition tuning parameters.
+#[derive(Debug, Copy, Clone)]
+pub struct RandomizedSvdParams {
+	/// number of samples drawn in addition to the target rank.
+	pub oversampling: usize,
+	/// number of power iterations, improving the accuracy when the singular values decay slowly.
+	pub power_iters: usize,
+	/// number of samples drawn at a time by the adaptive range finder.
+	pub block_size: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+impl Default for RandomizedSvdParams {
+	fn default() -> Self {
+		Self {
+			oversampling: 10,
+			power_iters: 2,
+			block_size: 10,
+			non_exhaustive: NonExhaustive(()),
+		}
+	}
+}
+
+#[cfg(feature = "rand")]
+fn sample_gaussian<T: ComplexField>(X: MatMut<'_, T>, rng: &mut (impl ?Sized + rand::Rng)) {
+	use rand::distributions::Distribution;
+
+	let mut X = X;
+	for j in 0..X.ncols() {
+		for i in 0..X.nrows() {
+			let x: f64 = rand_distr::StandardNormal.sample(rng);
+			X[(i, j)] = from_f64::<T>(x);
+		}
+	}
+}
+
+fn orthonormalize_scratch<T: ComplexField>(nrows: usize, ncols: usize, par: Par) -> StackReq {
+	let blocksize = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(nrows, ncols);
+	temp_mat_scratch::<T>(blocksize, ncols).and(
+		linalg::qr::no_pivoting::factor::qr_in_place_scratch::<T>(nrows, ncols, blocksize, par, default()).or(
+			linalg::householder::apply_block_householder_sequence_on_the_left_in_place_scratch::<T>(nrows, blocksize, ncols),
+		),
+	)
+}
+
+#[cfg(feature = "rand")]
+/// stores in $Q$ an orthonormal basis of the column space of $Y$, overwriting $Y$.
+#[math]
+fn orthonormalize<T: ComplexField>(Q: MatMut<'_, T>, Y: MatMut<'_, T>, par: Par, stack: &mut MemStack) {
+	let (m, l) = Y.shape();
+	let mut Q = Q;
+	let mut Y = Y;
+
+	let blocksize = linalg::qr::no_pivoting::factor::recommended_blocksize::<T>(m, l);
+	let (mut householder, stack) = unsafe { temp_mat_uninit::<T, _, _>(blocksize, l, stack) };
+	let mut householder = householder.as_mat_mut();
+
+	linalg::qr::no_pivoting::factor::qr_in_place(Y.rb_mut(), householder.rb_mut(), par, stack, default());
+
+	Q.fill(zero());
+	Q.rb_mut().diagonal_mut().fill(one());
+	linalg::householder::apply_block_householder_sequence_on_the_left_in_place_with_conj(Y.rb(), householder.rb(), Conj::No, Q, par, stack);
+}
+
+fn range_finder_scratch<T: ComplexField>(A: &dyn BiLinOp<T>, l: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+	_ = params;
+	let m = A.nrows();
+	let n = A.ncols();
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(m, l),
+		temp_mat_scratch::<T>(n, l).array(2),
+		StackReq::any_of(&[
+			A.apply_scratch(l, par),
+			A.transpose_apply_scratch(l, par),
+			orthonormalize_scratch::<T>(m, l, par),
+			orthonormalize_scratch::<T>(n, l, par),
+		]),
+	])
+}
+
+/// computes the size and alignment of required workspace for computing an orthonormal basis
+/// with `n_samples` columns of the approximate range of $A$.
+pub fn randomized_range_finder_scratch<T: ComplexField>(A: impl BiLinOp<T>, n_samples: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+	range_finder_scratch(&A, n_samples, par, params)
+}
+
+#[cfg(feature = "rand")]
+#[track_caller]
+fn range_finder<T: ComplexField>(
+	Q: MatMut<'_, T>,
+	A: &dyn BiLinOp<T>,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+	stack: &mut MemStack,
+	params: RandomizedSvdParams,
+) {
+	let m = A.nrows();
+	let n = A.ncols();
+	let l = Q.ncols();
+	assert!(all(Q.nrows() == m, l <= Ord::min(m, n)));
+
+	let mut Q = Q;
+	let (mut Y, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, l, stack) };
+	let mut Y = Y.as_mat_mut();
+	let (mut W, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, l, stack) };
+	let mut W = W.as_mat_mut();
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, l, stack) };
+	let mut Z = Z.as_mat_mut();
+
+	sample_gaussian(Z.rb_mut(), rng);
+	A.apply(Y.rb_mut(), Z.rb(), par, stack);
+	orthonormalize(Q.rb_mut(), Y.rb_mut(), par, stack);
+
+	// the basis is reorthonormalized after every application of the operator, since the
+	// columns would otherwise all converge to the dominant singular vector
+	for _ in 0..params.power_iters {
+		A.adjoint_apply(W.rb_mut(), Q.rb(), par, stack);
+		orthonormalize(Z.rb_mut(), W.rb_mut(), par, stack);
+		A.apply(Y.rb_mut(), Z.rb(), par, stack);
+		orthonormalize(Q.rb_mut(), Y.rb_mut(), par, stack);
+	}
+}
+
+/// computes an orthonormal basis $Q$ of the approximate range of $A$, by applying $A$ to a
+/// gaussian test matrix with as many columns as $Q$, followed by `params.power_iters` power
+/// iterations.
+///
+/// the number of columns of $Q$ must not exceed the number of rows or columns of $A$.
+#[cfg(feature = "rand")]
+#[track_caller]
+pub fn randomized_range_finder<T: ComplexField>(
+	Q: MatMut<'_, T>,
+	A: impl BiLinOp<T>,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+	stack: &mut MemStack,
+	params: RandomizedSvdParams,
+) {
+	range_finder(Q, &A, rng, par, stack, params)
+}
+
+fn svd_from_range_scratch<T: ComplexField>(A: &dyn BiLinOp<T>, l: usize, par: Par) -> StackReq {
+	let n = A.ncols();
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(n, l).array(2),
+		temp_mat_scratch::<T>(l, l),
+		temp_mat_scratch::<T>(l, 1),
+		StackReq::any_of(&[
+			A.transpose_apply_scratch(l, par),
+			linalg::svd::svd_scratch::<T>(n, l, ComputeSvdVectors::Thin, ComputeSvdVectors::Full, par, default()),
+		]),
+	])
+}
+
+#[cfg(feature = "rand")]
+/// computes the leading singular triplets of $A$ from an orthonormal basis $Q$ of its approximate
+/// range, using the svd of the small matrix $B = Q^H A$.
+#[math]
+fn svd_from_range<T: ComplexField>(
+	U: MatMut<'_, T>,
+	S: &mut [T::Real],
+	V: MatMut<'_, T>,
+	A: &dyn BiLinOp<T>,
+	Q: MatRef<'_, T>,
+	par: Par,
+	stack: &mut MemStack,
+) -> Result<(), SvdError> {
+	let n = A.ncols();
+	let l = Q.ncols();
+	let k = S.len();
+
+	let (mut Bt, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, l, stack) };
+	let mut Bt = Bt.as_mat_mut();
+	let (mut Ub, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, l, stack) };
+	let mut Ub = Ub.as_mat_mut();
+	let (mut Vb, stack) = unsafe { temp_mat_uninit::<T, _, _>(l, l, stack) };
+	let mut Vb = Vb.as_mat_mut();
+	let (mut s, stack) = unsafe { temp_mat_uninit::<T, _, _>(l, 1, stack) };
+	let mut s = s.as_mat_mut().col_mut(0);
+
+	// B^H = A^H Q = Ub S Vb^H, so A ≈ Q B = (Q Vb) S Ub^H
+	A.adjoint_apply(Bt.rb_mut(), Q, par, stack);
+	linalg::svd::svd(
+		Bt.rb(),
+		s.rb_mut().as_diagonal_mut(),
+		Some(Ub.rb_mut()),
+		Some(Vb.rb_mut()),
+		par,
+		stack,
+		default(),
+	)?;
+
+	for (j, s_j) in S.iter_mut().enumerate() {
+		*s_j = real(s[j]);
+	}
+	matmul(U, Accum::Replace, Q, Vb.rb().get(.., ..k), one(), par);
+	{ V }.copy_from(Ub.rb().get(.., ..k));
+
+	Ok(())
+}
+
+/// computes the size and alignment of required workspace for computing the `rank` leading
+/// singular values (and corresponding singular vectors) of $A$ with the randomized svd.
+pub fn randomized_svd_scratch<T: ComplexField>(A: impl BiLinOp<T>, rank: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn BiLinOp<T>, rank: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+		let l = Ord::min(rank + params.oversampling, Ord::min(A.nrows(), A.ncols()));
+		temp_mat_scratch::<T>(A.nrows(), l).and(range_finder_scratch(A, l, par, params).or(svd_from_range_scratch(A, l, par)))
+	}
+
+	implementation(&A, rank, par, params)
+}
+
+/// computes an approximation of the leading singular values (and corresponding left and right
+/// singular vectors) of $A$ with the randomized svd of halko, martinsson and tropp, where the
+/// rank is given by the length of `S`.
+///
+/// the range of $A$ is sampled with `params.oversampling` more vectors than the target rank, and
+/// the singular values are sorted in nonincreasing order.
+#[cfg(feature = "rand")]
+#[track_caller]
+pub fn randomized_svd<T: ComplexField>(
+	U: MatMut<'_, T>,
+	S: &mut [T::Real],
+	V: MatMut<'_, T>,
+	A: impl BiLinOp<T>,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+	stack: &mut MemStack,
+	params: RandomizedSvdParams,
+) -> Result<(), SvdError> {
+	#[track_caller]
+	fn implementation<T: ComplexField>(
+		U: MatMut<'_, T>,
+		S: &mut [T::Real],
+		V: MatMut<'_, T>,
+		A: &dyn BiLinOp<T>,
+		rng: &mut (impl ?Sized + rand::Rng),
+		par: Par,
+		stack: &mut MemStack,
+		params: RandomizedSvdParams,
+	) -> Result<(), SvdError> {
+		let m = A.nrows();
+		let n = A.ncols();
+		let k = S.len();
+		assert!(all(U.nrows() == m, V.nrows() == n, U.ncols() == k, V.ncols() == k, k <= Ord::min(m, n),));
+
+		let l = Ord::min(k + params.oversampling, Ord::min(m, n));
+		let (mut Q, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, l, stack) };
+		let mut Q = Q.as_mat_mut();
+
+		range_finder(Q.rb_mut(), A, rng, par, stack, params);
+		svd_from_range(U, S, V, A, Q.rb(), par, stack)
+	}
+
+	implementation(U, S, V, &A, rng, par, stack, params)
+}
+
+fn adaptive_range_finder_scratch_imp<T: ComplexField>(A: &dyn BiLinOp<T>, max_rank: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+	let m = A.nrows();
+	let n = A.ncols();
+	let b = Ord::min(Ord::max(params.block_size, 1), Ord::max(max_rank, 1));
+	StackReq::all_of(&[
+		temp_mat_scratch::<T>(n, b),
+		temp_mat_scratch::<T>(m, b).array(2),
+		temp_mat_scratch::<T>(max_rank, b),
+		StackReq::any_of(&[A.apply_scratch(b, par), orthonormalize_scratch::<T>(m, b, par)]),
+	])
+}
+
+/// computes the size and alignment of required workspace for computing an orthonormal basis of
+/// the approximate range of $A$ with at most `max_rank` columns, up to a given accuracy.
+pub fn adaptive_range_finder_scratch<T: ComplexField>(A: impl BiLinOp<T>, max_rank: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+	adaptive_range_finder_scratch_imp(&A, max_rank, par, params)
+}
+
+#[cfg(feature = "rand")]
+#[track_caller]
+#[math]
+fn adaptive_range_finder_imp<T: ComplexField>(
+	Q: MatMut<'_, T>,
+	A: &dyn BiLinOp<T>,
+	tolerance: T::Real,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+	stack: &mut MemStack,
+	params: RandomizedSvdParams,
+) -> usize {
+	let m = A.nrows();
+	let n = A.ncols();
+	let max_rank = Q.ncols();
+	assert!(all(Q.nrows() == m, max_rank <= Ord::min(m, n)));
+
+	let mut Q = Q;
+	if max_rank == 0 {
+		return 0;
+	}
+
+	let b = Ord::min(Ord::max(params.block_size, 1), max_rank);
+	let (mut Z, stack) = unsafe { temp_mat_uninit::<T, _, _>(n, b, stack) };
+	let mut Z = Z.as_mat_mut();
+	let (mut Y, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, b, stack) };
+	let mut Y = Y.as_mat_mut();
+	let (mut Qb, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, b, stack) };
+	let mut Qb = Qb.as_mat_mut();
+	let (mut C, stack) = unsafe { temp_mat_uninit::<T, _, _>(max_rank, b, stack) };
+	let mut C = C.as_mat_mut();
+
+	// with probability at least $1 - 10^{-b}$, the error is bounded by
+	// $10 \sqrt{2 / \pi}$ times the largest residual norm of the samples
+	let factor = from_f64::<T::Real>(10.0 * (2.0 / core::f64::consts::PI).sqrt());
+
+	let mut l = 0usize;
+	while l < max_rank {
+		let mut Y = Y.rb_mut();
+		sample_gaussian(Z.rb_mut(), rng);
+		A.apply(Y.rb_mut(), Z.rb(), par, stack);
+
+		let Q_l = Q.rb().get(.., ..l);
+		let mut C = C.rb_mut().get_mut(..l, ..);
+		for _ in 0..2 {
+			matmul(C.rb_mut(), Accum::Replace, Q_l.adjoint(), Y.rb(), one(), par);
+			matmul(Y.rb_mut(), Accum::Add, Q_l, C.rb(), -one::<T>(), par);
+		}
+
+		let mut err = zero::<T::Real>();
+		for j in 0..b {
+			err = max(err, Y.rb().col(j).norm_l2());
+		}
+		if factor * err <= tolerance {
+			break;
+		}
+
+		// if the block is numerically rank deficient, the trailing columns of its orthonormal
+		// basis are arbitrary, so they are projected and orthonormalized once more
+		orthonormalize(Qb.rb_mut(), Y.rb_mut(), par, stack);
+		Y.copy_from(&Qb);
+		for _ in 0..2 {
+			matmul(C.rb_mut(), Accum::Replace, Q_l.adjoint(), Y.rb(), one(), par);
+			matmul(Y.rb_mut(), Accum::Add, Q_l, C.rb(), -one::<T>(), par);
+		}
+		orthonormalize(Qb.rb_mut(), Y.rb_mut(), par, stack);
+
+		let bl = Ord::min(b, max_rank - l);
+		Q.rb_mut().get_mut(.., l..l + bl).copy_from(Qb.rb().get(.., ..bl));
+		l += bl;
+	}
+
+	l
+}
+
+/// computes an orthonormal basis of the approximate range of $A$ such that
+/// $\|A - Q Q^H A\|_2 \leq \text{tolerance}$ with high probability, by sampling $A$ with blocks of
+/// `params.block_size` gaussian vectors until the error estimate falls below the tolerance or $Q$
+/// is full.
+///
+/// returns the number of columns of $Q$ that were filled.
+#[cfg(feature = "rand")]
+#[track_caller]
+pub fn adaptive_range_finder<T: ComplexField>(
+	Q: MatMut<'_, T>,
+	A: impl BiLinOp<T>,
+	tolerance: T::Real,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+	stack: &mut MemStack,
+	params: RandomizedSvdParams,
+) -> usize {
+	adaptive_range_finder_imp(Q, &A, tolerance, rng, par, stack, params)
+}
+
+/// computes the size and alignment of required workspace for computing the singular values (and
+/// corresponding singular vectors) of $A$ up to a given accuracy with at most `max_rank` of them.
+pub fn adaptive_randomized_svd_scratch<T: ComplexField>(A: impl BiLinOp<T>, max_rank: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+	fn implementation<T: ComplexField>(A: &dyn BiLinOp<T>, max_rank: usize, par: Par, params: RandomizedSvdParams) -> StackReq {
+		temp_mat_scratch::<T>(A.nrows(), max_rank)
+			.and(adaptive_range_finder_scratch_imp(A, max_rank, par, params).or(svd_from_range_scratch(A, max_rank, par)))
+	}
+
+	implementation(&A, max_rank, par, params)
+}
+
+/// computes an approximate truncated svd of $A$ such that $\|A - U S V^H\|_2 \leq
+/// \text{tolerance}$ with high probability, using [`adaptive_range_finder`] with at most as many
+/// samples as the length of `S`.
+///
+/// returns the computed rank $r$, such that the singular triplets are stored in the first $r$
+/// entries of `S` and columns of `U` and `V`, in nonincreasing order.
+#[cfg(feature = "rand")]
+#[track_caller]
+pub fn adaptive_randomized_svd<T: ComplexField>(
+	U: MatMut<'_, T>,
+	S: &mut [T::Real],
+	V: MatMut<'_, T>,
+	A: impl BiLinOp<T>,
+	tolerance: T::Real,
+	rng: &mut (impl ?Sized + rand::Rng),
+	par: Par,
+	stack: &mut MemStack,
+	params: RandomizedSvdParams,
+) -> Result<usize, SvdError> {
+	#[track_caller]
+	fn implementation<T: ComplexField>(
+		U: MatMut<'_, T>,
+		S: &mut [T::Real],
+		V: MatMut<'_, T>,
+		A: &dyn BiLinOp<T>,
+		tolerance: T::Real,
+		rng: &mut (impl ?Sized + rand::Rng),
+		par: Par,
+		stack: &mut MemStack,
+		params: RandomizedSvdParams,
+	) -> Result<usize, SvdError> {
+		let m = A.nrows();
+		let n = A.ncols();
+		let max_rank = S.len();
+		assert!(all(
+			U.nrows() == m,
+			V.nrows() == n,
+			U.ncols() == max_rank,
+			V.ncols() == max_rank,
+			max_rank <= Ord::min(m, n),
+		));
+
+		let (mut Q, stack) = unsafe { temp_mat_uninit::<T, _, _>(m, max_rank, stack) };
+		let mut Q = Q.as_mat_mut();
+
+		let rank = adaptive_range_finder_imp(Q.rb_mut(), A, tolerance, rng, par, stack, params);
+		svd_from_range(
+			U.get_mut(.., ..rank),
+			&mut S[..rank],
+			V.get_mut(.., ..rank),
+			A,
+			Q.rb().get(.., ..rank),
+			par,
+			stack,
+		)?;
+		Ok(rank)
+	}
+
+	implementation(U, S, V, &A, tolerance, rng, par, stack, params)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::stats::prelude::*;
+	use crate::{Scale, assert};
+	use dyn_stack::MemBuffer;
+	use rand::prelude::*;
+
+	#[test]
+	fn test_partial_svd_real() {
+		let rng = &mut StdRng::seed_from_u64(0);
+
+		for (m, n) in [(300, 150), (150, 300)] {
+			let A: Mat<f64> = CwiseMatDistribution {
+				nrows: m,
+				ncols: n,
+				dist: StandardNormal,
+			}
+			.sample(rng);
+			let v0: Col<f64> = CwiseColDistribution {
+				nrows: Ord::min(m, n),
+				dist: StandardNormal,
+			}
+			.sample(rng);
+
+			let expected = A.singular_values().unwrap();
+			let k = 6;
+
+			for which in [SvdTarget::Largest, SvdTarget::Smallest] {
+				let params = PartialSvdParams { which, ..Default::default() };
+				let mem = &mut MemBuffer::new(partial_svd_scratch(A.as_ref(), k, Par::Seq, params));
+				let mut U = Mat::zeros(m, k);
+				let mut V = Mat::zeros(n, k);
+				let mut S = vec![0.0; k];
+
+				let info = partial_svd(
+					U.rb_mut(),
+					&mut S,
+					V.rb_mut(),
+					A.as_ref(),
+					v0.as_ref(),
+					1e-10,
+					Par::Seq,
+					MemStack::new(mem),
+					params,
+				);
+				assert!(info.n_converged_singular == k);
+
+				for (j, &s) in S.iter().enumerate() {
+					let expected = match which {
+						SvdTarget::Largest => expected[j],
+						SvdTarget::Smallest => expected[expected.len() - 1 - j],
+					};
+					assert!((s - expected).abs() < 1e-8);
+					assert!((&A * V.col(j) - Scale(s) * U.col(j)).norm_l2() < 1e-8);
+					assert!((A.adjoint() * U.col(j) - Scale(s) * V.col(j)).norm_l2() < 1e-8);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_partial_svd_sparse_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let m = 400;
+		let n = 250;
+		let k = 5;
+
+		let mut triplets = vec![];
+		for j in 0..n {
+			for _ in 0..8 {
+				let i = rng.gen_range(0..m);
+				let v = c64::new(rng.sample(StandardNormal), rng.sample(StandardNormal));
+				triplets.push(Triplet::new(i, j, v));
+			}
+		}
+		let A = SparseColMat::<usize, c64>::try_new_from_triplets(m, n, &triplets).unwrap();
+		let dense = A.to_dense();
+		let expected = dense.singular_values().unwrap();
+
+		let v0: Col<c64> = CwiseColDistribution {
+			nrows: n,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.sample(rng);
+
+		let params = PartialSvdParams::default();
+		let mem = &mut MemBuffer::new(partial_svd_scratch(A.as_ref(), k, Par::Seq, params));
+		let mut U = Mat::zeros(m, k);
+		let mut V = Mat::zeros(n, k);
+		let mut S = vec![0.0; k];
+
+		let info = partial_svd(
+			U.rb_mut(),
+			&mut S,
+			V.rb_mut(),
+			A.as_ref(),
+			v0.as_ref(),
+			1e-10,
+			Par::Seq,
+			MemStack::new(mem),
+			params,
+		);
+		assert!(info.n_converged_singular == k);
+
+		for (j, &s) in S.iter().enumerate() {
+			assert!((s - expected[j]).abs() < 1e-8);
+			assert!((&dense * V.col(j) - Scale(c64::new(s, 0.0)) * U.col(j)).norm_l2() < 1e-8);
+		}
+	}
+
+	#[test]
+	fn test_randomized_svd_low_rank() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let m = 300;
+		let n = 200;
+		let r = 15;
+		let k = 8;
+
+		let normal = |nrows, ncols, rng: &mut StdRng| -> Mat<f64> {
+			CwiseMatDistribution {
+				nrows,
+				ncols,
+				dist: StandardNormal,
+			}
+			.sample(rng)
+		};
+		let A = normal(m, r, rng) * normal(r, n, rng);
+		let expected = A.singular_values().unwrap();
+
+		let params = RandomizedSvdParams::default();
+		let mem = &mut MemBuffer::new(randomized_svd_scratch(A.as_ref(), k, Par::Seq, params));
+		let mut U = Mat::zeros(m, k);
+		let mut V = Mat::zeros(n, k);
+		let mut S = vec![0.0; k];
+		randomized_svd(U.rb_mut(), &mut S, V.rb_mut(), A.as_ref(), rng, Par::Seq, MemStack::new(mem), params).unwrap();
+
+		for (j, &s) in S.iter().enumerate() {
+			assert!((s - expected[j]).abs() < 1e-10 * expected[0]);
+			assert!((&A * V.col(j) - Scale(s) * U.col(j)).norm_l2() < 1e-10 * expected[0]);
+		}
+
+		// the range of a rank $r$ matrix is found exactly, after which the sampling stops
+		let max_rank = 40;
+		let params = RandomizedSvdParams {
+			block_size: 4,
+			..Default::default()
+		};
+		let mem = &mut MemBuffer::new(adaptive_randomized_svd_scratch(A.as_ref(), max_rank, Par::Seq, params));
+		let mut U = Mat::zeros(m, max_rank);
+		let mut V = Mat::zeros(n, max_rank);
+		let mut S = vec![0.0; max_rank];
+		let rank = adaptive_randomized_svd(
+			U.rb_mut(),
+			&mut S,
+			V.rb_mut(),
+			A.as_ref(),
+			1e-8,
+			rng,
+			Par::Seq,
+			MemStack::new(mem),
+			params,
+		)
+		.unwrap();
+		assert!(all(rank >= r, rank < r + 4));
+
+		let U = U.get(.., ..rank);
+		let V = V.get(.., ..rank);
+		let S = ColRef::from_slice(&S[..rank]);
+		assert!((&A - U * S.as_diagonal() * V.adjoint()).norm_l2() < 1e-8);
+	}
+
+	#[test]
+	fn test_randomized_svd_sparse_cplx() {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let m = 500;
+		let n = 400;
+		let k = 5;
+
+		// singular values $2^{-i}$, with a random phase and some off-diagonal noise
+		let mut triplets = vec![];
+		for i in 0..n {
+			let phase: f64 = rng.gen_range(0.0..core::f64::consts::TAU);
+			triplets.push(Triplet::new(i + 50, i, c64::cis(phase) * 0.5f64.powi(i as i32)));
+		}
+		for _ in 0..200 {
+			let i = rng.gen_range(0..m);
+			let j = rng.gen_range(20..n);
+			triplets.push(Triplet::new(i, j, c64::new(1e-8, -1e-8)));
+		}
+		let A = SparseColMat::<usize, c64>::try_new_from_triplets(m, n, &triplets).unwrap();
+		let dense = A.to_dense();
+		let expected = dense.singular_values().unwrap();
+
+		let params = RandomizedSvdParams::default();
+		let mem = &mut MemBuffer::new(randomized_svd_scratch(A.as_ref(), k, Par::Seq, params));
+		let mut U = Mat::zeros(m, k);
+		let mut V = Mat::zeros(n, k);
+		let mut S = vec![0.0; k];
+		randomized_svd(U.rb_mut(), &mut S, V.rb_mut(), A.as_ref(), rng, Par::Seq, MemStack::new(mem), params).unwrap();
+
+		for (j, &s) in S.iter().enumerate() {
+			assert!((s - expected[j]).abs() < 1e-10);
+			assert!((&dense * V.col(j) - Scale(c64::new(s, 0.0)) * U.col(j)).norm_l2() < 1e-10);
+		}
+	}
+}
@@ -383,6 +383,7 @@
 //! 			stack,
 //! 			faer::sparse::linalg::SymbolicSupernodalParams {
 //! 				relax: Some(&[(usize::MAX, 1.0)]),
+//! 				..Default::default()
 //! 			},
 //! 		)?
 //! 	};
@@ -503,7 +504,7 @@ use crate::internal_prelude_sp::*;
 use linalg::cholesky::lblt::factor::{LbltInfo, LbltParams};
 use linalg::cholesky::ldlt::factor::{LdltError, LdltInfo, LdltParams, LdltRegularization};
 use linalg::cholesky::llt::factor::{LltError, LltInfo, LltParams, LltRegularization};
-use linalg_sp::{SupernodalThreshold, SymbolicSupernodalParams, amd, triangular_solve};
+use linalg_sp::{SupernodalThreshold, SymbolicSupernodalParams, amd, nested_dissection, triangular_solve};
 
 /// fill reducing ordering to use for the cholesky factorization
 #[derive(Copy, Clone, Debug, Default)]
@@ -515,6 +516,8 @@ pub enum SymmetricOrdering<'a, I: Index> {
 	Identity,
 	/// custom reordering
 	Custom(PermRef<'a, I>),
+	/// multilevel nested dissection ordering
+	NestedDissection(nested_dissection::Control),
 }
 
 /// simplicial factorization module
@@ -1334,6 +1337,255 @@ pub mod simplicial {
 		factorize_simplicial_numeric_ldlt_scratch::<I, T>(n)
 	}
 
+	#[inline]
+	pub(crate) fn column_layout<I: Index>(symbolic: &SymbolicSimplicialCholesky<I>, j: usize) -> ColumnLayout<'_, I> {
+		let p = symbolic.col_ptr[j].zx();
+		(p, j + 1..j + 1, &symbolic.row_idx[p + 1..symbolic.col_ptr[j + 1].zx()])
+	}
+
+	/// returns the size and alignment of the workspace required to compute the symbolic structure
+	/// of the cholesky factor of $A + W W^H$, where $A$ has dimension `n` and $W$ has `rank`
+	/// columns
+	pub fn update_simplicial_symbolic_cholesky_scratch<I: Index>(n: usize, rank: usize) -> StackReq {
+		update_pattern_scratch::<I>(n, rank)
+	}
+
+	/// computes the symbolic structure of the cholesky factor of $A + W W^H$, given that of the
+	/// cholesky factor of $A$
+	///
+	/// returns `None` if the structure of the factor of $A$ already contains it, in which case the
+	/// factor can be updated in place. otherwise, its values can be moved to the new structure with
+	/// [`remap_simplicial_values`]
+	#[track_caller]
+	pub fn update_simplicial_symbolic_cholesky<I: Index>(
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		W: SymbolicSparseColMatRef<'_, I>,
+		stack: &mut MemStack,
+	) -> Result<Option<SymbolicSimplicialCholesky<I>>, FaerError> {
+		assert!(W.nrows() == symbolic.nrows());
+		update_simplicial_symbolic_cholesky_imp(symbolic, W, None, stack)
+	}
+
+	pub(crate) fn update_simplicial_symbolic_cholesky_imp<I: Index>(
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		W: SymbolicSparseColMatRef<'_, I>,
+		row_map: Option<&[I]>,
+		stack: &mut MemStack,
+	) -> Result<Option<SymbolicSimplicialCholesky<I>>, FaerError> {
+		let n = symbolic.nrows();
+		Ok(
+			update_pattern(n, symbolic.len_val(), |j| column_layout(symbolic, j), W, row_map, stack)?.map(|(col_ptr, row_idx, etree)| {
+				SymbolicSimplicialCholesky {
+					dimension: n,
+					col_ptr,
+					row_idx,
+					etree,
+				}
+			}),
+		)
+	}
+
+	/// copies the numerical values `src` of a cholesky factor with the structure `src_symbolic` to
+	/// `dst`, for a factor with the structure `dst_symbolic`, which must contain it
+	#[track_caller]
+	pub fn remap_simplicial_values<I: Index, T: ComplexField>(
+		dst: &mut [T],
+		dst_symbolic: &SymbolicSimplicialCholesky<I>,
+		src: &[T],
+		src_symbolic: &SymbolicSimplicialCholesky<I>,
+	) {
+		let n = src_symbolic.nrows();
+		assert!(all(
+			dst_symbolic.nrows() == n,
+			dst.len() == dst_symbolic.len_val(),
+			src.len() == src_symbolic.len_val(),
+		));
+		remap_values(n, dst, |j| column_layout(dst_symbolic, j), src, |j| column_layout(src_symbolic, j));
+	}
+
+	/// returns the size and alignment of the workspace required to update the cholesky factor of a
+	/// matrix with dimension `n`
+	pub fn rank_r_update_simplicial_numeric_llt_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+		rank_r_update_scratch::<I, T>(n)
+	}
+
+	/// computes the cholesky $LL^H$ factor of $A + W \operatorname{diag}(\alpha) W^H$ in place,
+	/// given the factor of $A$
+	///
+	/// the columns of $W$ with a negative weight are downdates, which fail if the resulting matrix
+	/// is not numerically positive definite. the values of the factor are then unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`update_simplicial_symbolic_cholesky`] beforehand
+	#[track_caller]
+	pub fn rank_r_update_simplicial_numeric_llt<I: Index, T: ComplexField>(
+		L_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		W: SparseColMatRef<'_, I, T>,
+		alpha: DiagRef<'_, T::Real>,
+		stack: &mut MemStack,
+	) -> Result<(), LltError> {
+		assert!(all(
+			L_values.len() == symbolic.len_val(),
+			W.nrows() == symbolic.nrows(),
+			alpha.column_vector().nrows() == W.ncols(),
+		));
+		rank_r_update(L_values, |j| column_layout(symbolic, j), W, None, alpha, stack)
+	}
+
+	/// returns the size and alignment of the workspace required to compute the symbolic structure
+	/// of the cholesky factor of a matrix with dimension `n` after one of its rows and columns is
+	/// modified
+	pub fn row_add_simplicial_symbolic_cholesky_scratch<I: Index>(n: usize) -> StackReq {
+		update_pattern_scratch::<I>(n, n + 1)
+	}
+
+	/// computes the symbolic structure of the cholesky factor of a matrix $A'$ that matches $A$
+	/// outside of its row and column `k`, whose column `k` has the structure `A_col`, given that
+	/// of the cholesky factor of $A$
+	///
+	/// returns `None` if the structure of the factor of $A$ already contains it. otherwise, the
+	/// values of the factor can be moved to the new structure with [`remap_simplicial_values`]
+	#[track_caller]
+	pub fn row_add_simplicial_symbolic_cholesky<I: Index>(
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		k: usize,
+		A_col: SymbolicSparseColMatRef<'_, I>,
+		stack: &mut MemStack,
+	) -> Result<Option<SymbolicSimplicialCholesky<I>>, FaerError> {
+		let n = symbolic.nrows();
+		assert!(all(k < n, A_col.nrows() == n, A_col.ncols() == 1));
+		let (col_ptr, row_idx) = row_add_pattern(k, A_col, None)?;
+		let W = SymbolicSparseColMatRef::new_checked(n, col_ptr.len() - 1, &col_ptr, None, &row_idx);
+		update_simplicial_symbolic_cholesky_imp(symbolic, W, None, stack)
+	}
+
+	/// returns the size and alignment of the workspace required to add a row and column to the
+	/// cholesky factor of a matrix with dimension `n`
+	pub fn row_add_simplicial_numeric_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+		row_add_scratch::<I, T>(n)
+	}
+
+	/// computes the cholesky $LL^H$ factor of the matrix $A'$ obtained by replacing the row and
+	/// column `k` of $A$ by `A_col` (and its adjoint) in place, given the factor of $A$
+	///
+	/// the row and column `k` of $A$ must be those of the identity, e.g., after a call to
+	/// [`row_del_simplicial_numeric_llt`]. this fails if $A'$ is not numerically positive
+	/// definite, in which case the values of the factor are unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`row_add_simplicial_symbolic_cholesky`] beforehand
+	#[track_caller]
+	pub fn row_add_simplicial_numeric_llt<I: Index, T: ComplexField>(
+		L_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		k: usize,
+		A_col: SparseColMatRef<'_, I, T>,
+		stack: &mut MemStack,
+	) -> Result<(), LltError> {
+		let n = symbolic.nrows();
+		assert!(all(L_values.len() == symbolic.len_val(), k < n, A_col.nrows() == n, A_col.ncols() == 1));
+		row_add(L_values, |j| column_layout(symbolic, j), k, A_col, None, false, stack).map_err(|index| LltError::NonPositivePivot { index })
+	}
+
+	/// computes the cholesky $LDL^H$ factor of the matrix $A'$ obtained by replacing the row and
+	/// column `k` of $A$ by `A_col` (and its adjoint) in place, given the factor of $A$
+	///
+	/// the row and column `k` of $A$ must be those of the identity, e.g., after a call to
+	/// [`row_del_simplicial_numeric_ldlt`]. this fails if a zero pivot is encountered, in which
+	/// case the values of the factor are unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`row_add_simplicial_symbolic_cholesky`] beforehand
+	#[track_caller]
+	pub fn row_add_simplicial_numeric_ldlt<I: Index, T: ComplexField>(
+		L_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		k: usize,
+		A_col: SparseColMatRef<'_, I, T>,
+		stack: &mut MemStack,
+	) -> Result<(), LdltError> {
+		let n = symbolic.nrows();
+		assert!(all(L_values.len() == symbolic.len_val(), k < n, A_col.nrows() == n, A_col.ncols() == 1));
+		row_add(L_values, |j| column_layout(symbolic, j), k, A_col, None, true, stack).map_err(|index| LdltError::ZeroPivot { index })
+	}
+
+	/// returns the size and alignment of the workspace required to delete a row and column from
+	/// the cholesky factor of a matrix with dimension `n`
+	pub fn row_del_simplicial_numeric_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+		row_del_scratch::<T>(n)
+	}
+
+	/// computes the cholesky $LL^H$ factor of the matrix obtained by replacing the row and column
+	/// `k` of $A$ by those of the identity in place, given the factor of $A$
+	///
+	/// the structure of the factor is left unchanged
+	#[track_caller]
+	pub fn row_del_simplicial_numeric_llt<I: Index, T: ComplexField>(
+		L_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		k: usize,
+		stack: &mut MemStack,
+	) -> Result<(), LltError> {
+		let n = symbolic.nrows();
+		assert!(all(L_values.len() == symbolic.len_val(), k < n));
+		row_del(L_values, |j| column_layout(symbolic, j), n, k, false, stack).map_err(|index| LltError::NonPositivePivot { index })
+	}
+
+	/// computes the cholesky $LDL^H$ factor of the matrix obtained by replacing the row and column
+	/// `k` of $A$ by those of the identity in place, given the factor of $A$
+	///
+	/// the structure of the factor is left unchanged. this fails if a zero pivot is encountered,
+	/// in which case the values of the factor are unspecified
+	#[track_caller]
+	pub fn row_del_simplicial_numeric_ldlt<I: Index, T: ComplexField>(
+		L_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		k: usize,
+		stack: &mut MemStack,
+	) -> Result<(), LdltError> {
+		let n = symbolic.nrows();
+		assert!(all(L_values.len() == symbolic.len_val(), k < n));
+		row_del(L_values, |j| column_layout(symbolic, j), n, k, true, stack).map_err(|index| LdltError::ZeroPivot { index })
+	}
+
+	/// returns the size and alignment of the workspace required to compute the selected inverse of
+	/// a matrix with dimension `n` from its cholesky factor
+	pub fn selected_inverse_simplicial_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+		selected_inverse_scratch::<I, T>(n)
+	}
+
+	/// computes the entries of $A^{-1}$ on the structure of the cholesky $LL^H$ factor of $A$, and
+	/// stores them in `Z_values`, so that `SparseColMatRef::new(symbolic.factor(), Z_values)` is
+	/// the lower triangular part of the selected inverse
+	#[track_caller]
+	pub fn selected_inverse_simplicial_llt<I: Index, T: ComplexField>(
+		Z_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		L_values: &[T],
+		stack: &mut MemStack,
+	) {
+		assert!(all(Z_values.len() == symbolic.len_val(), L_values.len() == symbolic.len_val()));
+		selected_inverse(Z_values, |j| column_layout(symbolic, j), symbolic.nrows(), L_values, false, stack);
+	}
+
+	/// computes the entries of $A^{-1}$ on the structure of the cholesky $LDL^H$ factor of $A$,
+	/// and stores them in `Z_values`, so that `SparseColMatRef::new(symbolic.factor(), Z_values)`
+	/// is the lower triangular part of the selected inverse
+	#[track_caller]
+	pub fn selected_inverse_simplicial_ldlt<I: Index, T: ComplexField>(
+		Z_values: &mut [T],
+		symbolic: &SymbolicSimplicialCholesky<I>,
+		L_values: &[T],
+		stack: &mut MemStack,
+	) {
+		assert!(all(Z_values.len() == symbolic.len_val(), L_values.len() == symbolic.len_val()));
+		selected_inverse(Z_values, |j| column_layout(symbolic, j), symbolic.nrows(), L_values, true, stack);
+	}
+
 	/// cholesky $LL^H$ factor containing both its symbolic and numeric representations
 	#[derive(Debug)]
 	pub struct SimplicialLltRef<'a, I: Index, T> {
@@ -1549,6 +1801,11 @@ pub mod supernodal {
 		pub(crate) row_idx: alloc::vec::Vec<I>,
 
 		pub(crate) nnz_per_super: Option<alloc::vec::Vec<I>>,
+
+		#[cfg(feature = "rayon")]
+		pub(crate) factorize_schedule: TreeSchedule,
+		#[cfg(feature = "rayon")]
+		pub(crate) solve_schedule: TreeSchedule,
 	}
 
 	impl<I: Index> SymbolicSupernodalCholesky<I> {
@@ -1625,14 +1882,22 @@ pub mod supernodal {
 		/// returns the size and alignment of the workspace required to solve the system
 		/// $A x = rhs$
 		pub fn solve_in_place_scratch<T: ComplexField>(&self, rhs_ncols: usize, par: Par) -> StackReq {
-			_ = par;
 			let mut req = StackReq::EMPTY;
 			let symbolic = self;
 			for s in 0..symbolic.n_supernodes() {
 				let s = self.supernode(s);
 				req = req.or(temp_mat_scratch::<T>(s.pattern.len(), rhs_ncols));
 			}
-			req
+			match par {
+				// the threads each get their own workspace, and the updates of the subtrees to the
+				// top of the tree are accumulated separately
+				#[cfg(feature = "rayon")]
+				Par::Rayon(nthreads) if nthreads.get() > 1 => {
+					let schedule = &self.solve_schedule;
+					temp_mat_scratch::<T>(schedule.ext_ptr[schedule.roots.len()], rhs_ncols).and(req.array(Ord::max(schedule.n_bins(), 1)))
+				},
+				_ => req,
+			}
 		}
 
 		#[doc(hidden)]
@@ -1819,25 +2084,13 @@ pub mod supernodal {
 
 			let mut x = rhs;
 			let k = x.ncols();
-			for s in 0..symbolic.n_supernodes() {
+			forward_supernodes(symbolic, None, x.rb_mut(), par, stack, &|s, mut x_top, mut tmp, par| {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
-				let Ls = s.matrix;
-				let (Ls_top, Ls_bot) = Ls.split_at_row(size);
-				let mut x_top = x.rb_mut().subrows_mut(s.start(), size);
+				let (Ls_top, Ls_bot) = s.matrix.split_at_row(size);
 				linalg::triangular_solve::solve_unit_lower_triangular_in_place_with_conj(Ls_top, conj, x_top.rb_mut(), par);
-
-				let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s.pattern().len(), k, stack) };
-				let mut tmp = tmp.as_mat_mut();
 				linalg::matmul::matmul_with_conj(tmp.rb_mut(), Accum::Replace, Ls_bot, conj, x_top.rb(), Conj::No, one::<T>(), par);
-
-				for j in 0..k {
-					for (idx, i) in s.pattern().iter().enumerate() {
-						let i = i.zx();
-						x[(i, j)] = x[(i, j)] - tmp[(idx, j)]
-					}
-				}
-			}
+			});
 			for s in 0..symbolic.n_supernodes() {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
@@ -1850,28 +2103,16 @@ pub mod supernodal {
 					}
 				}
 			}
-			for s in (0..symbolic.n_supernodes()).rev() {
+			backward_supernodes(symbolic, None, x.rb_mut(), par, stack, &|s, mut x_top, tmp, par| {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
-				let Ls = s.matrix;
-				let (Ls_top, Ls_bot) = Ls.split_at_row(size);
-
-				let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s.pattern().len(), k, stack) };
-				let mut tmp = tmp.as_mat_mut();
-				for j in 0..k {
-					for (idx, i) in s.pattern().iter().enumerate() {
-						let i = i.zx();
-						tmp[(idx, j)] = copy(x[(i, j)]);
-					}
-				}
-
-				let mut x_top = x.rb_mut().subrows_mut(s.start(), size);
+				let (Ls_top, Ls_bot) = s.matrix.split_at_row(size);
 				linalg::matmul::matmul_with_conj(
 					x_top.rb_mut(),
 					Accum::Add,
 					Ls_bot.transpose(),
 					conj.compose(Conj::Yes),
-					tmp.rb(),
+					tmp,
 					Conj::No,
 					-one::<T>(),
 					par,
@@ -1882,7 +2123,7 @@ pub mod supernodal {
 					x_top.rb_mut(),
 					par,
 				);
-			}
+			});
 		}
 	}
 
@@ -2027,54 +2268,29 @@ pub mod supernodal {
 			assert!(rhs.nrows() == n);
 
 			let mut x = rhs;
-			let k = x.ncols();
-			for s in 0..symbolic.n_supernodes() {
+			forward_supernodes(symbolic, None, x.rb_mut(), par, stack, &|s, mut x_top, mut tmp, par| {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
-				let Ls = s.matrix;
-				let (Ls_top, Ls_bot) = Ls.split_at_row(size);
-				let mut x_top = x.rb_mut().subrows_mut(s.start(), size);
+				let (Ls_top, Ls_bot) = s.matrix.split_at_row(size);
 				linalg::triangular_solve::solve_lower_triangular_in_place_with_conj(Ls_top, conj, x_top.rb_mut(), par);
-
-				let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s.pattern().len(), k, stack) };
-				let mut tmp = tmp.as_mat_mut();
 				linalg::matmul::matmul_with_conj(tmp.rb_mut(), Accum::Replace, Ls_bot, conj, x_top.rb(), Conj::No, one::<T>(), par);
-
-				for j in 0..k {
-					for (idx, i) in s.pattern().iter().enumerate() {
-						let i = i.zx();
-						x[(i, j)] = x[(i, j)] - tmp[(idx, j)]
-					}
-				}
-			}
-			for s in (0..symbolic.n_supernodes()).rev() {
+			});
+			backward_supernodes(symbolic, None, x.rb_mut(), par, stack, &|s, mut x_top, tmp, par| {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
-				let Ls = s.matrix;
-				let (Ls_top, Ls_bot) = Ls.split_at_row(size);
-
-				let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s.pattern().len(), k, stack) };
-				let mut tmp = tmp.as_mat_mut();
-				for j in 0..k {
-					for (idx, i) in s.pattern().iter().enumerate() {
-						let i = i.zx();
-						tmp[(idx, j)] = copy(x[(i, j)]);
-					}
-				}
-
-				let mut x_top = x.rb_mut().subrows_mut(s.start(), size);
+				let (Ls_top, Ls_bot) = s.matrix.split_at_row(size);
 				linalg::matmul::matmul_with_conj(
 					x_top.rb_mut(),
 					Accum::Add,
 					Ls_bot.transpose(),
 					conj.compose(Conj::Yes),
-					tmp.rb(),
+					tmp,
 					Conj::No,
 					-one::<T>(),
 					par,
 				);
 				linalg::triangular_solve::solve_upper_triangular_in_place_with_conj(Ls_top.transpose(), conj.compose(Conj::Yes), x_top.rb_mut(), par);
-			}
+			});
 		}
 	}
 
@@ -2167,27 +2383,14 @@ pub mod supernodal {
 			let mut x = rhs;
 
 			let k = x.ncols();
-			for s in 0..symbolic.n_supernodes() {
+			let inv = self.perm.arrays().1;
+			forward_supernodes(symbolic, Some(inv), x.rb_mut(), par, stack, &|s, mut x_top, mut tmp, par| {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
-				let Ls = s.matrix;
-				let (Ls_top, Ls_bot) = Ls.split_at_row(size);
-				let mut x_top = x.rb_mut().subrows_mut(s.start(), size);
+				let (Ls_top, Ls_bot) = s.matrix.split_at_row(size);
 				linalg::triangular_solve::solve_unit_lower_triangular_in_place_with_conj(Ls_top, conj_lb, x_top.rb_mut(), par);
-
-				let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s.pattern().len(), k, stack) };
-				let mut tmp = tmp.as_mat_mut();
 				linalg::matmul::matmul_with_conj(tmp.rb_mut(), Accum::Replace, Ls_bot, conj_lb, x_top.rb(), Conj::No, one::<T>(), par);
-
-				let inv = self.perm.arrays().1;
-				for j in 0..k {
-					for (idx, i) in s.pattern().iter().enumerate() {
-						let i = i.zx();
-						let i = inv[i].zx();
-						x[(i, j)] = x[(i, j)] - tmp[(idx, j)];
-					}
-				}
-			}
+			});
 			for s in 0..symbolic.n_supernodes() {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
@@ -2223,30 +2426,16 @@ pub mod supernodal {
 					}
 				}
 			}
-			for s in (0..symbolic.n_supernodes()).rev() {
+			backward_supernodes(symbolic, Some(inv), x.rb_mut(), par, stack, &|s, mut x_top, tmp, par| {
 				let s = self.supernode(s);
 				let size = s.matrix.ncols();
-				let Ls = s.matrix;
-				let (Ls_top, Ls_bot) = Ls.split_at_row(size);
-
-				let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s.pattern().len(), k, stack) };
-				let mut tmp = tmp.as_mat_mut();
-				let inv = self.perm.arrays().1;
-				for j in 0..k {
-					for (idx, i) in s.pattern().iter().enumerate() {
-						let i = i.zx();
-						let i = inv[i].zx();
-						tmp[(idx, j)] = copy(x[(i, j)]);
-					}
-				}
-
-				let mut x_top = x.rb_mut().subrows_mut(s.start(), size);
+				let (Ls_top, Ls_bot) = s.matrix.split_at_row(size);
 				linalg::matmul::matmul_with_conj(
 					x_top.rb_mut(),
 					Accum::Add,
 					Ls_bot.transpose(),
 					conj_lb.compose(Conj::Yes),
-					tmp.rb(),
+					tmp,
 					Conj::No,
 					-one::<T>(),
 					par,
@@ -2257,7 +2446,7 @@ pub mod supernodal {
 					x_top.rb_mut(),
 					par,
 				);
-			}
+			});
 		}
 	}
 
@@ -2328,7 +2517,7 @@ pub mod supernodal {
 
 		if n == 0 {
 			// would be funny if this allocation failed
-			return Ok(SymbolicSupernodalCholesky {
+			return SymbolicSupernodalCholesky {
 				dimension: n,
 				supernode_postorder: alloc::vec::Vec::new(),
 				supernode_postorder_inv: alloc::vec::Vec::new(),
@@ -2339,7 +2528,13 @@ pub mod supernodal {
 				col_ptr_for_val: try_collect([zero])?,
 				row_idx: alloc::vec::Vec::new(),
 				nnz_per_super: None,
-			});
+
+				#[cfg(feature = "rayon")]
+				factorize_schedule: TreeSchedule::default(),
+				#[cfg(feature = "rayon")]
+				solve_schedule: TreeSchedule::default(),
+			}
+			.with_schedules(params.par);
 		}
 		let original_stack = stack;
 
@@ -2406,10 +2601,18 @@ pub mod supernodal {
 				N_FUNDAMENTAL_SUPERNODES,
 			);
 
-			if let Some(relax) = params.relax {
-				let mut mem = dyn_stack::MemBuffer::try_new(StackReq::all_of(&[StackReq::new::<I>(n_fundamental_supernodes); 5]))
-					.ok()
-					.ok_or(FaerError::OutOfMemory)?;
+			if params.relax.is_some() || params.pivot_pairs.is_some() {
+				let relax = params.relax.unwrap_or(&[]);
+				let mut mem = dyn_stack::MemBuffer::try_new(StackReq::all_of(&[
+					StackReq::new::<I>(n_fundamental_supernodes),
+					StackReq::new::<I>(n_fundamental_supernodes),
+					StackReq::new::<I>(n_fundamental_supernodes),
+					StackReq::new::<I>(n_fundamental_supernodes),
+					StackReq::new::<I>(n_fundamental_supernodes),
+					StackReq::new::<u8>(n_fundamental_supernodes),
+				]))
+				.ok()
+				.ok_or(FaerError::OutOfMemory)?;
 				let stack = MemStack::new(&mut mem);
 
 				let child_lists = bytemuck::cast_slice_mut(&mut child_count.as_mut()[..n_fundamental_supernodes]);
@@ -2417,7 +2620,8 @@ pub mod supernodal {
 				let (last_merged_children, stack) = unsafe { stack.make_raw::<I::Signed>(n_fundamental_supernodes) };
 				let (merge_parents, stack) = unsafe { stack.make_raw::<I::Signed>(n_fundamental_supernodes) };
 				let (fundamental_supernode_degrees, stack) = unsafe { stack.make_raw::<I>(n_fundamental_supernodes) };
-				let (num_zeros, _) = unsafe { stack.make_raw::<I>(n_fundamental_supernodes) };
+				let (num_zeros, stack) = unsafe { stack.make_raw::<I>(n_fundamental_supernodes) };
+				let (tied_to_parent, _) = unsafe { stack.make_raw::<u8>(n_fundamental_supernodes) };
 
 				let child_lists = Array::from_mut(ghost::fill_none::<I>(child_lists, N_FUNDAMENTAL_SUPERNODES), N_FUNDAMENTAL_SUPERNODES);
 				let child_list_heads = Array::from_mut(
@@ -2431,11 +2635,15 @@ pub mod supernodal {
 				let merge_parents = Array::from_mut(ghost::fill_none::<I>(merge_parents, N_FUNDAMENTAL_SUPERNODES), N_FUNDAMENTAL_SUPERNODES);
 				let fundamental_supernode_degrees = Array::from_mut(fundamental_supernode_degrees, N_FUNDAMENTAL_SUPERNODES);
 				let num_zeros = Array::from_mut(num_zeros, N_FUNDAMENTAL_SUPERNODES);
+				let tied_to_parent = Array::from_mut(tied_to_parent, N_FUNDAMENTAL_SUPERNODES);
 
 				let mut supernode_begin = 0usize;
 				for s in N_FUNDAMENTAL_SUPERNODES.indices() {
 					let size = supernode_sizes[s].zx();
-					fundamental_supernode_degrees[s] = col_counts[N.check(supernode_begin + size - 1)] - one;
+					let last = supernode_begin + size - 1;
+					fundamental_supernode_degrees[s] = col_counts[N.check(last)] - one;
+					// the next column is the first one of the parent if `s + 1` is the parent of `s`
+					tied_to_parent[s] = params.pivot_pairs.is_some_and(|pairs| pairs[last]) as u8;
 					supernode_begin += size;
 				}
 
@@ -2468,9 +2676,12 @@ pub mod supernodal {
 								continue;
 							}
 
+							// merging a child that is tied to its parent isn't optional
+							let tied = tied_to_parent[child] != 0;
+
 							let parent_size = supernode_sizes[parent].zx();
 							let child_size = supernode_sizes[child].zx();
-							if child_size < largest_mergable_size {
+							if !tied && child_size < largest_mergable_size {
 								child_ = child_lists[child];
 								continue;
 							}
@@ -2494,6 +2705,9 @@ pub mod supernodal {
 									let num_expanded_entries = (combined_size * (combined_size + 1)) / 2 + parent_degree * combined_size;
 
 									let f = || {
+										if tied {
+											return num_zeros;
+										}
 										for cutoff in relax {
 											let num_zeros_cutoff = num_expanded_entries as f64 * cutoff.1;
 											if cutoff.0 >= combined_size && num_zeros_cutoff >= num_zeros as f64 {
@@ -2511,12 +2725,15 @@ pub mod supernodal {
 							}
 
 							let num_proposed_new_zeros = status_num_merged_zeros - (num_child_zeros + num_parent_zeros);
-							if child_size > largest_mergable_size || num_proposed_new_zeros < num_new_zeros {
+							if tied || child_size > largest_mergable_size || num_proposed_new_zeros < num_new_zeros {
 								merging_child = MaybeIdx::from_index(child);
 								num_new_zeros = num_proposed_new_zeros;
 								num_merged_zeros = status_num_merged_zeros;
 								largest_mergable_size = child_size;
 							}
+							if tied {
+								break;
+							}
 
 							child_ = child_lists[child];
 						}
@@ -2724,7 +2941,7 @@ pub mod supernodal {
 			}
 		};
 
-		Ok(SymbolicSupernodalCholesky {
+		SymbolicSupernodalCholesky {
 			dimension: n,
 			supernode_postorder: supernode_postorder__,
 			supernode_postorder_inv: supernode_etree__,
@@ -2734,7 +2951,39 @@ pub mod supernodal {
 			col_ptr_for_val: col_ptr_for_val__,
 			row_idx: row_idx__,
 			nnz_per_super: None,
-		})
+
+			#[cfg(feature = "rayon")]
+			factorize_schedule: TreeSchedule::default(),
+			#[cfg(feature = "rayon")]
+			solve_schedule: TreeSchedule::default(),
+		}
+		.with_schedules(params.par)
+	}
+
+	impl<I: Index> SymbolicSupernodalCholesky<I> {
+		/// computes the mapping of the elimination tree onto the threads that is used by the
+		/// parallel factorization and solves
+		fn with_schedules(self, par: Par) -> Result<Self, FaerError> {
+			#[cfg(feature = "rayon")]
+			{
+				let mut this = self;
+				let nthreads = Ord::max(par.degree(), 1);
+				let factorize_schedule = TreeSchedule::new(&this, nthreads, |s| {
+					let (_, s_ncols) = supernode_cols(&this, s);
+					let s_nrows = supernode_len(&this, s) / s_ncols;
+					s_ncols as f64 * s_nrows as f64 * s_nrows as f64
+				})?;
+				let solve_schedule = TreeSchedule::new(&this, nthreads, |s| supernode_len(&this, s) as f64)?;
+				this.factorize_schedule = factorize_schedule;
+				this.solve_schedule = solve_schedule;
+				Ok(this)
+			}
+			#[cfg(not(feature = "rayon"))]
+			{
+				_ = par;
+				Ok(self)
+			}
+		}
 	}
 
 	#[inline]
@@ -2743,33 +2992,520 @@ pub mod supernodal {
 		move |&i| i < idx
 	}
 
-	/// returns the size and alignment of the workspace required to compute the numeric
-	/// cholesky $LL^H$ factorization of a matrix $A$ with dimension `n`
-	pub fn factorize_supernodal_numeric_llt_scratch<I: Index, T: ComplexField>(
-		symbolic: &SymbolicSupernodalCholesky<I>,
-		par: Par,
-		params: Spec<LltParams, T>,
-	) -> StackReq {
-		let n_supernodes = symbolic.n_supernodes();
-		let n = symbolic.nrows();
-		let post = &*symbolic.supernode_postorder;
-		let post_inv = &*symbolic.supernode_postorder_inv;
+	/// static mapping of the supernodal elimination tree onto the threads, following the
+	/// subtree-to-thread scheme of geist and ng
+	///
+	/// the heaviest subtrees are split until the remaining ones can be spread evenly over the
+	/// threads. the supernodes that were split off form the top of the tree, which is processed
+	/// once all the subtrees are done, using the parallelism within each supernode instead
+	#[cfg(feature = "rayon")]
+	#[derive(Debug, Default)]
+	pub(crate) struct TreeSchedule {
+		// roots of the independent subtrees, grouped by thread
+		roots: alloc::vec::Vec<usize>,
+		// `roots[bin_ptr[b]..bin_ptr[b + 1]]` are the subtrees assigned to the `b`-th thread
+		bin_ptr: alloc::vec::Vec<usize>,
+		// supernodes at the top of the tree, in increasing order
+		top: alloc::vec::Vec<usize>,
+		// `ext_ptr[idx]..ext_ptr[idx + 1]` are the rows of the workspace of the solves that hold the
+		// updates of the subtree rooted at `roots[idx]` to the pattern of its root
+		ext_ptr: alloc::vec::Vec<usize>,
+	}
 
-		let desc_count = &*symbolic.descendant_count;
+	#[cfg(feature = "rayon")]
+	impl TreeSchedule {
+		fn new<I: Index>(symbolic: &SymbolicSupernodalCholesky<I>, nthreads: usize, weight: impl Fn(usize) -> f64) -> Result<Self, FaerError> {
+			let n_supernodes = symbolic.n_supernodes();
+			let post = &*symbolic.supernode_postorder;
+			let post_inv = &*symbolic.supernode_postorder_inv;
+			let desc_count = &*symbolic.descendant_count;
+
+			// the descendants of a supernode are contiguous in the postorder, so the weight of a
+			// subtree is a difference of prefix sums
+			let mut prefix = try_zeroed::<f64>(n_supernodes + 1)?;
+			for q in 0..n_supernodes {
+				prefix[q + 1] = prefix[q] + weight(post[q].zx());
+			}
+			let subtree_weight = |s: usize| {
+				let q = post_inv[s].zx();
+				prefix[q + 1] - prefix[q - desc_count[s].zx()]
+			};
+			// pushes the roots of the subtrees covering the postorder range `lo..hi`
+			let push_roots = |roots: &mut alloc::vec::Vec<usize>, lo: usize, hi: usize| {
+				let mut q = hi;
+				while q > lo {
+					let s = post[q - 1].zx();
+					roots.push(s);
+					q -= desc_count[s].zx() + 1;
+				}
+			};
 
-		let col_ptr_row = &*symbolic.col_ptr_for_row_idx;
-		let row_idx = &*symbolic.row_idx;
+			// each supernode is pushed at most once to either list, so they are never reallocated
+			let mut roots = alloc::vec::Vec::new();
+			let mut top = alloc::vec::Vec::new();
+			roots.try_reserve_exact(n_supernodes).map_err(|_| FaerError::OutOfMemory)?;
+			top.try_reserve_exact(n_supernodes).map_err(|_| FaerError::OutOfMemory)?;
+			push_roots(&mut roots, 0, n_supernodes);
 
-		let mut req = StackReq::empty();
-		for s in 0..n_supernodes {
-			let s_start = symbolic.supernode_begin[s].zx();
-			let s_end = symbolic.supernode_begin[s + 1].zx();
+			while let Some((idx, &heaviest)) = roots
+				.iter()
+				.enumerate()
+				.max_by(|(_, a), (_, b)| subtree_weight(**a).total_cmp(&subtree_weight(**b)))
+			{
+				let total: f64 = roots.iter().map(|&s| subtree_weight(s)).sum();
+				if roots.len() >= nthreads && 2.0 * nthreads as f64 * subtree_weight(heaviest) <= total {
+					break;
+				}
+				let desc = desc_count[heaviest].zx();
+				if desc == 0 {
+					break;
+				}
+				let q = post_inv[heaviest].zx();
+				roots.swap_remove(idx);
+				top.push(heaviest);
+				push_roots(&mut roots, q - desc, q);
+			}
+			top.sort_unstable();
+
+			// longest processing time first assignment of the subtrees to the threads
+			roots.sort_unstable_by(|&a, &b| subtree_weight(b).total_cmp(&subtree_weight(a)));
+			let n_bins = Ord::min(nthreads, roots.len());
+			let mut load = try_zeroed::<f64>(n_bins)?;
+			let mut bin = try_zeroed::<usize>(roots.len())?;
+			for (&s, bin) in iter::zip(&roots, &mut bin) {
+				let b = (0..n_bins).min_by(|&a, &b| load[a].total_cmp(&load[b])).unwrap();
+				load[b] += subtree_weight(s);
+				*bin = b;
+			}
 
-			let s_ncols = s_end - s_start;
+			let mut bin_ptr = try_zeroed::<usize>(n_bins + 1)?;
+			for &b in &bin {
+				bin_ptr[b + 1] += 1;
+			}
+			for b in 0..n_bins {
+				bin_ptr[b + 1] += bin_ptr[b];
+			}
+			let mut pos = try_collect(bin_ptr[..n_bins].iter().copied())?;
+			let mut grouped = try_zeroed::<usize>(roots.len())?;
+			for (&s, &b) in iter::zip(&roots, &bin) {
+				grouped[pos[b]] = s;
+				pos[b] += 1;
+			}
 
-			let s_postordered = post_inv[s].zx();
-			let desc_count = desc_count[s].zx();
-			for d in &post[s_postordered - desc_count..s_postordered] {
+			let mut ext_ptr = try_zeroed::<usize>(grouped.len() + 1)?;
+			for (idx, &root) in grouped.iter().enumerate() {
+				ext_ptr[idx + 1] = ext_ptr[idx] + symbolic.supernode(root).pattern().len();
+			}
+
+			Ok(Self {
+				roots: grouped,
+				bin_ptr,
+				top,
+				ext_ptr,
+			})
+		}
+
+		#[inline]
+		pub(crate) fn n_bins(&self) -> usize {
+			self.bin_ptr.len() - 1
+		}
+
+		/// returns the roots of the subtrees assigned to the `b`-th thread, along with their
+		/// position in `self.roots`
+		#[inline]
+		fn bin(&self, b: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+			(self.bin_ptr[b]..self.bin_ptr[b + 1]).map(|idx| (idx, self.roots[idx]))
+		}
+
+		/// returns the supernodes of the subtree rooted at `root`, in postorder
+		#[inline]
+		fn subtree<I: Index>(symbolic: &SymbolicSupernodalCholesky<I>, root: usize) -> impl DoubleEndedIterator<Item = usize> + '_ {
+			let q = symbolic.supernode_postorder_inv[root].zx();
+			symbolic.supernode_postorder[q - symbolic.descendant_count[root].zx()..q + 1]
+				.iter()
+				.map(|s| s.zx())
+		}
+	}
+
+	/// runs `f(b, stack)` concurrently for each thread `b` in `bins`, where each call gets its own
+	/// `slot` sized region of `stack`
+	#[cfg(feature = "rayon")]
+	fn for_each_bin<E: Send, F: Sync + Fn(usize, &mut MemStack) -> Result<usize, E>>(
+		bins: core::ops::Range<usize>,
+		slot: StackReq,
+		stack: &mut MemStack,
+		f: &F,
+	) -> Result<usize, E> {
+		match bins.len() {
+			0 => Ok(0),
+			1 => f(bins.start, stack),
+			len => {
+				let mid = bins.start + len / 2;
+				let (left, right) = stack.make_aligned_uninit::<u8>((mid - bins.start) * slot.size_bytes(), slot.align_bytes());
+				let left = MemStack::new(left);
+				let (a, b) = rayon::join(
+					|| for_each_bin(bins.start..mid, slot, left, f),
+					|| for_each_bin(mid..bins.end, slot, right, f),
+				);
+				Ok(a? + b?)
+			},
+		}
+	}
+
+	/// shared view over a slice whose disjoint parts are written by different threads
+	struct SharedSlice<'a, T> {
+		ptr: crate::utils::thread::Ptr<T>,
+		len: usize,
+		__marker: core::marker::PhantomData<&'a mut [T]>,
+	}
+
+	impl<T> Copy for SharedSlice<'_, T> {}
+	impl<T> Clone for SharedSlice<'_, T> {
+		#[inline]
+		fn clone(&self) -> Self {
+			*self
+		}
+	}
+
+	impl<'a, T> SharedSlice<'a, T> {
+		#[inline]
+		fn new(slice: &'a mut [T]) -> Self {
+			Self {
+				ptr: crate::utils::thread::Ptr(slice.as_mut_ptr()),
+				len: slice.len(),
+				__marker: core::marker::PhantomData,
+			}
+		}
+
+		/// # safety
+		/// no thread may write to `range` while the returned slice is alive
+		#[inline]
+		#[track_caller]
+		unsafe fn get(self, range: core::ops::Range<usize>) -> &'a [T] {
+			assert!(all(range.start <= range.end, range.end <= self.len));
+			core::slice::from_raw_parts(self.ptr.0.add(range.start), range.end - range.start)
+		}
+
+		/// # safety
+		/// no other thread may access `range` while the returned slice is alive
+		#[inline]
+		#[track_caller]
+		#[allow(clippy::mut_from_ref)]
+		unsafe fn get_mut(&self, range: core::ops::Range<usize>) -> &'a mut [T] {
+			assert!(all(range.start <= range.end, range.end <= self.len));
+			core::slice::from_raw_parts_mut(self.ptr.0.add(range.start), range.end - range.start)
+		}
+	}
+
+	fn factorize_supernodes_scratch<I: Index>(symbolic: &SymbolicSupernodalCholesky<I>, par: Par, slot: StackReq) -> StackReq {
+		let slot = StackReq::new::<I>(symbolic.nrows()).and(slot);
+		match par {
+			#[cfg(feature = "rayon")]
+			Par::Rayon(nthreads) if nthreads.get() > 1 => slot.array(Ord::max(symbolic.factorize_schedule.n_bins(), 1)),
+			_ => slot,
+		}
+	}
+
+	/// calls `f(s, par, global_to_local, stack)` on each supernode `s`, once it has been called on
+	/// all of its descendants, and returns the sum of the results
+	///
+	/// with [`Par::Rayon`], independent subtrees are processed concurrently, each thread getting a
+	/// region of `stack` of the size returned by `slot`. `global_to_local` is filled with `NONE`,
+	/// and must be left in that state by `f`
+	fn factorize_supernodes<I: Index, E: Send, F: Sync + Fn(usize, Par, &mut [I::Signed], &mut MemStack) -> Result<usize, E>>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		par: Par,
+		stack: &mut MemStack,
+		slot: impl FnOnce() -> StackReq,
+		f: &F,
+	) -> Result<usize, E> {
+		let n = symbolic.nrows();
+		let none = I::Signed::truncate(NONE);
+
+		match par {
+			#[cfg(feature = "rayon")]
+			Par::Rayon(nthreads) if nthreads.get() > 1 => {
+				let schedule = &symbolic.factorize_schedule;
+				let slot = StackReq::new::<I>(n).and(slot());
+				let n_slots = Ord::max(schedule.n_bins(), 1);
+
+				let (stack, _) = stack.make_aligned_uninit::<u8>(n_slots * slot.size_bytes(), slot.align_bytes());
+				let stack = MemStack::new(stack);
+				{
+					let mut rest = &mut *stack;
+					for _ in 0..n_slots {
+						let (slot_stack, next) = { rest }.make_aligned_uninit::<u8>(slot.size_bytes(), slot.align_bytes());
+						let (global_to_local, _) = unsafe { MemStack::new(slot_stack).make_raw::<I::Signed>(n) };
+						global_to_local.fill(none);
+						rest = next;
+					}
+				}
+
+				let mut count = for_each_bin(0..schedule.n_bins(), slot, stack, &|b, stack| {
+					let (global_to_local, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+					let mut count = 0;
+					for (_, root) in schedule.bin(b) {
+						for s in TreeSchedule::subtree(symbolic, root) {
+							count += f(s, Par::Seq, global_to_local, stack)?;
+						}
+					}
+					Ok(count)
+				})?;
+
+				let (global_to_local, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+				for &s in &schedule.top {
+					count += f(s, par, global_to_local, stack)?;
+				}
+				Ok(count)
+			},
+			_ => {
+				_ = slot;
+				let (global_to_local, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+				global_to_local.fill(none);
+
+				let mut count = 0;
+				for s in 0..symbolic.n_supernodes() {
+					count += f(s, par, global_to_local, stack)?;
+				}
+				Ok(count)
+			},
+		}
+	}
+
+	/// forward substitution with a supernodal factor
+	///
+	/// `step(s, x_s, tmp, par)` solves in place with the diagonal block of the supernode `s`, then
+	/// stores in `tmp` the product of its off-diagonal block with the solution. `tmp` is then
+	/// subtracted from the rows of `x` in the pattern of `s`, after mapping them with `row_map`
+	#[math]
+	fn forward_supernodes<I: Index, T: ComplexField, F: Sync + Fn(usize, MatMut<'_, T>, MatMut<'_, T>, Par)>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		row_map: Option<&[I]>,
+		x: MatMut<'_, T>,
+		par: Par,
+		stack: &mut MemStack,
+		step: &F,
+	) {
+		let mut x = x;
+		let k = x.ncols();
+		let map = |i: I| match row_map {
+			Some(row_map) => row_map[i.zx()].zx(),
+			None => i.zx(),
+		};
+
+		let seq = |s: usize, mut x: MatMut<'_, T>, par: Par, stack: &mut MemStack| {
+			let (s_start, s_ncols) = supernode_cols(symbolic, s);
+			let s_pattern = symbolic.supernode(s).pattern();
+			let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s_pattern.len(), k, stack) };
+			let mut tmp = tmp.as_mat_mut();
+			step(s, x.rb_mut().subrows_mut(s_start, s_ncols), tmp.rb_mut(), par);
+
+			for j in 0..k {
+				for (idx, &i) in s_pattern.iter().enumerate() {
+					let i = map(i);
+					x[(i, j)] = x[(i, j)] - tmp[(idx, j)];
+				}
+			}
+		};
+
+		match par {
+			#[cfg(feature = "rayon")]
+			Par::Rayon(nthreads) if nthreads.get() > 1 => {
+				let n = symbolic.nrows();
+				let schedule = &symbolic.solve_schedule;
+				let ext_ptr = &*schedule.ext_ptr;
+				let slot = symbolic.solve_in_place_scratch::<T>(k, Par::Seq);
+
+				// the updates of each subtree to the rows outside of it are accumulated separately,
+				// then applied once all the subtrees are done
+				let (mut ext, stack) = temp_mat_zeroed::<T, _, _>(ext_ptr[schedule.roots.len()], k, stack);
+				let ext = ext.as_mat_mut();
+
+				{
+					let (x_ptr, x_rs, x_cs) = (crate::utils::thread::Ptr(x.as_ptr_mut()), x.row_stride(), x.col_stride());
+					let (ext_ptr_, ext_rs, ext_cs) = (crate::utils::thread::Ptr(ext.as_ptr_mut()), ext.row_stride(), ext.col_stride());
+					let ext_nrows = ext.nrows();
+
+					let Ok(_) = for_each_bin::<core::convert::Infallible, _>(0..schedule.n_bins(), slot, stack, &|b, stack| {
+						// SAFETY: each thread only writes to the rows of `x` in its own subtrees, and
+						// to its own rows of `ext`
+						let mut x = unsafe { MatMut::from_raw_parts_mut({ x_ptr }.0, n, k, x_rs, x_cs) };
+						let mut ext = unsafe { MatMut::from_raw_parts_mut({ ext_ptr_ }.0, ext_nrows, k, ext_rs, ext_cs) };
+
+						for (idx, root) in schedule.bin(b) {
+							let root_end = symbolic.supernode_begin[root + 1].zx();
+							let root_pattern = symbolic.supernode(root).pattern();
+							let mut ext = ext.rb_mut().subrows_mut(ext_ptr[idx], root_pattern.len());
+
+							for s in TreeSchedule::subtree(symbolic, root) {
+								let (s_start, s_ncols) = supernode_cols(symbolic, s);
+								let s_pattern = symbolic.supernode(s).pattern();
+								let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s_pattern.len(), k, stack) };
+								let mut tmp = tmp.as_mat_mut();
+								step(s, x.rb_mut().subrows_mut(s_start, s_ncols), tmp.rb_mut(), Par::Seq);
+
+								// the rows before the end of the root belong to the subtree, and the
+								// remaining ones are in the pattern of the root
+								let mid = s_pattern.partition_point(partition_fn(root_end));
+								for j in 0..k {
+									for (idx, &i) in s_pattern[..mid].iter().enumerate() {
+										let i = map(i);
+										x[(i, j)] = x[(i, j)] - tmp[(idx, j)];
+									}
+									let mut pos = 0;
+									for (idx, &i) in s_pattern.iter().enumerate().skip(mid) {
+										while root_pattern[pos] != i {
+											pos += 1;
+										}
+										ext[(pos, j)] = ext[(pos, j)] + tmp[(idx, j)];
+									}
+								}
+							}
+						}
+						Ok(0)
+					});
+				}
+
+				for (idx, &root) in schedule.roots.iter().enumerate() {
+					let ext = ext.rb().subrows(ext_ptr[idx], ext_ptr[idx + 1] - ext_ptr[idx]);
+					for j in 0..k {
+						for (idx, &i) in symbolic.supernode(root).pattern().iter().enumerate() {
+							let i = map(i);
+							x[(i, j)] = x[(i, j)] - ext[(idx, j)];
+						}
+					}
+				}
+				for &s in &schedule.top {
+					seq(s, x.rb_mut(), par, stack);
+				}
+			},
+			_ => {
+				for s in 0..symbolic.n_supernodes() {
+					seq(s, x.rb_mut(), par, stack);
+				}
+			},
+		}
+	}
+
+	/// backward substitution with a supernodal factor
+	///
+	/// `step(s, x_s, tmp, par)` updates `x_s` using `tmp`, which holds the rows of `x` in the
+	/// pattern of the supernode `s` after mapping them with `row_map`, then solves in place with
+	/// the adjoint of the diagonal block of `s`
+	#[math]
+	fn backward_supernodes<I: Index, T: ComplexField, F: Sync + Fn(usize, MatMut<'_, T>, MatRef<'_, T>, Par)>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		row_map: Option<&[I]>,
+		x: MatMut<'_, T>,
+		par: Par,
+		stack: &mut MemStack,
+		step: &F,
+	) {
+		let mut x = x;
+		let k = x.ncols();
+		let map = |i: I| match row_map {
+			Some(row_map) => row_map[i.zx()].zx(),
+			None => i.zx(),
+		};
+
+		let seq = |s: usize, mut x: MatMut<'_, T>, par: Par, stack: &mut MemStack| {
+			let (s_start, s_ncols) = supernode_cols(symbolic, s);
+			let s_pattern = symbolic.supernode(s).pattern();
+			let (mut tmp, _) = unsafe { temp_mat_uninit::<T, _, _>(s_pattern.len(), k, stack) };
+			let mut tmp = tmp.as_mat_mut();
+			for j in 0..k {
+				for (idx, &i) in s_pattern.iter().enumerate() {
+					tmp[(idx, j)] = copy(x[(map(i), j)]);
+				}
+			}
+			step(s, x.rb_mut().subrows_mut(s_start, s_ncols), tmp.rb(), par);
+		};
+
+		match par {
+			#[cfg(feature = "rayon")]
+			Par::Rayon(nthreads) if nthreads.get() > 1 => {
+				let n = symbolic.nrows();
+				let schedule = &symbolic.solve_schedule;
+				let slot = symbolic.solve_in_place_scratch::<T>(k, Par::Seq);
+
+				for &s in schedule.top.iter().rev() {
+					seq(s, x.rb_mut(), par, stack);
+				}
+
+				let (x_ptr, x_rs, x_cs) = (crate::utils::thread::Ptr(x.as_ptr_mut()), x.row_stride(), x.col_stride());
+				let Ok(_) = for_each_bin::<core::convert::Infallible, _>(0..schedule.n_bins(), slot, stack, &|b, stack| {
+					// SAFETY: each thread only writes to the rows of `x` in its own subtrees, and
+					// reads the rows of their ancestors, which are no longer modified
+					let x = unsafe { MatMut::from_raw_parts_mut({ x_ptr }.0, n, k, x_rs, x_cs) };
+					let mut x = x;
+					for (_, root) in schedule.bin(b) {
+						for s in TreeSchedule::subtree(symbolic, root).rev() {
+							seq(s, x.rb_mut(), Par::Seq, stack);
+						}
+					}
+					Ok(0)
+				});
+			},
+			_ => {
+				for s in (0..symbolic.n_supernodes()).rev() {
+					seq(s, x.rb_mut(), par, stack);
+				}
+			},
+		}
+	}
+
+	/// returns the first column and the number of columns of the supernode `s`
+	#[inline]
+	fn supernode_cols<I: Index>(symbolic: &SymbolicSupernodalCholesky<I>, s: usize) -> (usize, usize) {
+		let start = symbolic.supernode_begin[s].zx();
+		(start, symbolic.supernode_begin[s + 1].zx() - start)
+	}
+
+	/// returns the number of stored values of the supernode `s`
+	#[cfg(feature = "rayon")]
+	#[inline]
+	fn supernode_len<I: Index>(symbolic: &SymbolicSupernodalCholesky<I>, s: usize) -> usize {
+		symbolic.col_ptr_for_val[s + 1].zx() - symbolic.col_ptr_for_val[s].zx()
+	}
+
+	/// returns the size and alignment of the workspace required to compute the numeric
+	/// cholesky $LL^H$ factorization of a matrix $A$ with dimension `n`
+	pub fn factorize_supernodal_numeric_llt_scratch<I: Index, T: ComplexField>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		par: Par,
+		params: Spec<LltParams, T>,
+	) -> StackReq {
+		factorize_supernodes_scratch::<I>(
+			symbolic,
+			par,
+			factorize_supernodal_numeric_llt_slot_scratch::<I, T>(symbolic, par, params),
+		)
+	}
+
+	fn factorize_supernodal_numeric_llt_slot_scratch<I: Index, T: ComplexField>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		par: Par,
+		params: Spec<LltParams, T>,
+	) -> StackReq {
+		let n_supernodes = symbolic.n_supernodes();
+		let post = &*symbolic.supernode_postorder;
+		let post_inv = &*symbolic.supernode_postorder_inv;
+
+		let desc_count = &*symbolic.descendant_count;
+
+		let col_ptr_row = &*symbolic.col_ptr_for_row_idx;
+		let row_idx = &*symbolic.row_idx;
+
+		let mut req = StackReq::empty();
+		for s in 0..n_supernodes {
+			let s_start = symbolic.supernode_begin[s].zx();
+			let s_end = symbolic.supernode_begin[s + 1].zx();
+
+			let s_ncols = s_end - s_start;
+
+			let s_postordered = post_inv[s].zx();
+			let desc_count = desc_count[s].zx();
+			for d in &post[s_postordered - desc_count..s_postordered] {
 				let mut d_scratch = StackReq::empty();
 				let d = d.zx();
 				let d_start = symbolic.supernode_begin[d].zx();
@@ -2796,7 +3532,7 @@ pub mod supernodal {
 			}
 			req = req.or(linalg::cholesky::llt::factor::cholesky_in_place_scratch::<T>(s_ncols, par, params));
 		}
-		req.and(StackReq::new::<I>(n))
+		req
 	}
 
 	/// returns the size and alignment of the workspace required to compute the numeric
@@ -2805,9 +3541,20 @@ pub mod supernodal {
 		symbolic: &SymbolicSupernodalCholesky<I>,
 		par: Par,
 		params: Spec<LdltParams, T>,
+	) -> StackReq {
+		factorize_supernodes_scratch::<I>(
+			symbolic,
+			par,
+			factorize_supernodal_numeric_ldlt_slot_scratch::<I, T>(symbolic, par, params),
+		)
+	}
+
+	fn factorize_supernodal_numeric_ldlt_slot_scratch<I: Index, T: ComplexField>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		par: Par,
+		params: Spec<LdltParams, T>,
 	) -> StackReq {
 		let n_supernodes = symbolic.n_supernodes();
-		let n = symbolic.nrows();
 		let post = &*symbolic.supernode_postorder;
 		let post_inv = &*symbolic.supernode_postorder_inv;
 
@@ -2854,7 +3601,7 @@ pub mod supernodal {
 			}
 			req = req.or(linalg::cholesky::ldlt::factor::cholesky_in_place_scratch::<T>(s_ncols, par, params));
 		}
-		req.and(StackReq::new::<I>(n))
+		req
 	}
 
 	/// returns the size and alignment of the workspace required to compute the numeric
@@ -2864,9 +3611,20 @@ pub mod supernodal {
 		symbolic: &SymbolicSupernodalCholesky<I>,
 		par: Par,
 		params: Spec<LbltParams, T>,
+	) -> StackReq {
+		factorize_supernodes_scratch::<I>(
+			symbolic,
+			par,
+			factorize_supernodal_numeric_intranode_lblt_slot_scratch::<I, T>(symbolic, par, params),
+		)
+	}
+
+	fn factorize_supernodal_numeric_intranode_lblt_slot_scratch<I: Index, T: ComplexField>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		par: Par,
+		params: Spec<LbltParams, T>,
 	) -> StackReq {
 		let n_supernodes = symbolic.n_supernodes();
-		let n = symbolic.nrows();
 		let post = &*symbolic.supernode_postorder;
 		let post_inv = &*symbolic.supernode_postorder_inv;
 
@@ -2909,7 +3667,7 @@ pub mod supernodal {
 				crate::perm::permute_cols_in_place_scratch::<I, T>(s_pattern.len(), s_ncols),
 			]);
 		}
-		req.and(StackReq::new::<I>(n))
+		req
 	}
 
 	/// computes the numeric values of the cholesky $LL^H$ factor of the matrix $A$, and stores them
@@ -2933,9 +3691,7 @@ pub mod supernodal {
 		stack: &mut MemStack,
 		params: Spec<LltParams, T>,
 	) -> Result<LltInfo, LltError> {
-		let n_supernodes = symbolic.n_supernodes();
 		let n = symbolic.nrows();
-		let mut dynamic_regularization_count = 0usize;
 		L_values.fill(zero::<T>());
 
 		assert!(A_lower.nrows() == n);
@@ -2953,105 +3709,114 @@ pub mod supernodal {
 		let col_ptr_val = &*symbolic.col_ptr_for_val;
 		let row_idx = &*symbolic.row_idx;
 
-		// mapping from global indices to local
-		let (global_to_local, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
-		global_to_local.fill(I::Signed::truncate(NONE));
+		let L_values = SharedSlice::new(L_values);
+		let dynamic_regularization_count = factorize_supernodes(
+			symbolic,
+			par,
+			stack,
+			|| factorize_supernodal_numeric_llt_slot_scratch::<I, T>(symbolic, par, params),
+			&|s, par, global_to_local, stack| {
+				let s_start = symbolic.supernode_begin[s].zx();
+				let s_end = symbolic.supernode_begin[s + 1].zx();
 
-		for s in 0..n_supernodes {
-			let s_start = symbolic.supernode_begin[s].zx();
-			let s_end = symbolic.supernode_begin[s + 1].zx();
+				let s_pattern = &row_idx[col_ptr_row[s].zx()..col_ptr_row[s + 1].zx()];
+				let s_ncols = s_end - s_start;
+				let s_nrows = s_pattern.len() + s_ncols;
 
-			let s_pattern = &row_idx[col_ptr_row[s].zx()..col_ptr_row[s + 1].zx()];
-			let s_ncols = s_end - s_start;
-			let s_nrows = s_pattern.len() + s_ncols;
+				for (i, &row) in s_pattern.iter().enumerate() {
+					global_to_local[row.zx()] = I::Signed::truncate(i + s_ncols);
+				}
 
-			for (i, &row) in s_pattern.iter().enumerate() {
-				global_to_local[row.zx()] = I::Signed::truncate(i + s_ncols);
-			}
+				// SAFETY: the values of `s` are only accessed by this task
+				let mut Ls = MatMut::from_column_major_slice_mut(
+					unsafe { L_values.get_mut(col_ptr_val[s].zx()..col_ptr_val[s + 1].zx()) },
+					s_nrows,
+					s_ncols,
+				);
 
-			let (head, tail) = L_values.split_at_mut(col_ptr_val[s].zx());
-			let head = head.rb();
-			let mut Ls = MatMut::from_column_major_slice_mut(&mut tail[..col_ptr_val[s + 1].zx() - col_ptr_val[s].zx()], s_nrows, s_ncols);
+				for j in s_start..s_end {
+					let j_shifted = j - s_start;
+					for (i, val) in iter::zip(A_lower.row_idx_of_col(j), A_lower.val_of_col(j)) {
+						if i < j {
+							continue;
+						}
 
-			for j in s_start..s_end {
-				let j_shifted = j - s_start;
-				for (i, val) in iter::zip(A_lower.row_idx_of_col(j), A_lower.val_of_col(j)) {
-					if i < j {
-						continue;
+						let (ix, iy) = if i >= s_end {
+							(global_to_local[i].sx(), j_shifted)
+						} else {
+							(i - s_start, j_shifted)
+						};
+						Ls[(ix, iy)] = Ls[(ix, iy)] + *val;
 					}
-
-					let (ix, iy) = if i >= s_end {
-						(global_to_local[i].sx(), j_shifted)
-					} else {
-						(i - s_start, j_shifted)
-					};
-					Ls[(ix, iy)] = Ls[(ix, iy)] + *val;
 				}
-			}
 
-			let s_postordered = post_inv[s].zx();
-			let desc_count = desc_count[s].zx();
-			for d in &post[s_postordered - desc_count..s_postordered] {
-				let d = d.zx();
-				let d_start = symbolic.supernode_begin[d].zx();
-				let d_end = symbolic.supernode_begin[d + 1].zx();
+				let s_postordered = post_inv[s].zx();
+				let desc_count = desc_count[s].zx();
+				for d in &post[s_postordered - desc_count..s_postordered] {
+					let d = d.zx();
+					let d_start = symbolic.supernode_begin[d].zx();
+					let d_end = symbolic.supernode_begin[d + 1].zx();
 
-				let d_pattern = &row_idx[col_ptr_row[d].zx()..col_ptr_row[d + 1].zx()];
-				let d_ncols = d_end - d_start;
-				let d_nrows = d_pattern.len() + d_ncols;
+					let d_pattern = &row_idx[col_ptr_row[d].zx()..col_ptr_row[d + 1].zx()];
+					let d_ncols = d_end - d_start;
+					let d_nrows = d_pattern.len() + d_ncols;
 
-				let Ld = MatRef::from_column_major_slice(&head[col_ptr_val[d].zx()..col_ptr_val[d + 1].zx()], d_nrows, d_ncols);
+					// SAFETY: the descendants of `s` are done, and are no longer modified
+					let Ld = MatRef::from_column_major_slice(unsafe { L_values.get(col_ptr_val[d].zx()..col_ptr_val[d + 1].zx()) }, d_nrows, d_ncols);
 
-				let d_pattern_start = d_pattern.partition_point(partition_fn(s_start));
-				let d_pattern_mid_len = d_pattern[d_pattern_start..].partition_point(partition_fn(s_end));
+					let d_pattern_start = d_pattern.partition_point(partition_fn(s_start));
+					let d_pattern_mid_len = d_pattern[d_pattern_start..].partition_point(partition_fn(s_end));
 
-				let (_, Ld_mid_bot) = Ld.split_at_row(d_ncols);
-				let (_, Ld_mid_bot) = Ld_mid_bot.split_at_row(d_pattern_start);
-				let (Ld_mid, _) = Ld_mid_bot.split_at_row(d_pattern_mid_len);
+					let (_, Ld_mid_bot) = Ld.split_at_row(d_ncols);
+					let (_, Ld_mid_bot) = Ld_mid_bot.split_at_row(d_pattern_start);
+					let (Ld_mid, _) = Ld_mid_bot.split_at_row(d_pattern_mid_len);
 
-				use linalg::matmul::triangular;
-				let (row_idx, stack) = stack.make_with(Ld_mid_bot.nrows(), |i| {
-					if i < d_pattern_mid_len {
-						I::truncate(d_pattern[d_pattern_start + i].zx() - s_start)
-					} else {
-						I::from_signed(global_to_local[d_pattern[d_pattern_start + i].zx()])
-					}
-				});
-				let (col_idx, stack) = stack.make_with(d_pattern_mid_len, |j| I::truncate(d_pattern[d_pattern_start + j].zx() - s_start));
+					use linalg::matmul::triangular;
+					let (row_idx, stack) = stack.make_with(Ld_mid_bot.nrows(), |i| {
+						if i < d_pattern_mid_len {
+							I::truncate(d_pattern[d_pattern_start + i].zx() - s_start)
+						} else {
+							I::from_signed(global_to_local[d_pattern[d_pattern_start + i].zx()])
+						}
+					});
+					let (col_idx, stack) = stack.make_with(d_pattern_mid_len, |j| I::truncate(d_pattern[d_pattern_start + j].zx() - s_start));
+
+					spicy_matmul(
+						Ls.rb_mut(),
+						triangular::BlockStructure::TriangularLower,
+						Some(&row_idx),
+						Some(&col_idx),
+						Accum::Add,
+						Ld_mid_bot,
+						Conj::No,
+						Ld_mid.transpose(),
+						Conj::Yes,
+						None,
+						-one::<T>(),
+						par,
+						stack,
+					);
+				}
 
-				spicy_matmul(
-					Ls.rb_mut(),
-					triangular::BlockStructure::TriangularLower,
-					Some(&row_idx),
-					Some(&col_idx),
-					Accum::Add,
-					Ld_mid_bot,
-					Conj::No,
-					Ld_mid.transpose(),
-					Conj::Yes,
-					None,
-					-one::<T>(),
-					par,
-					stack,
-				);
-			}
+				let (mut Ls_top, mut Ls_bot) = Ls.rb_mut().split_at_row_mut(s_ncols);
 
-			let (mut Ls_top, mut Ls_bot) = Ls.rb_mut().split_at_row_mut(s_ncols);
+				let dynamic_regularization_count =
+					match linalg::cholesky::llt::factor::cholesky_in_place(Ls_top.rb_mut(), regularization.clone(), par, stack, params) {
+						Ok(count) => count,
+						Err(LltError::NonPositivePivot { index }) => {
+							return Err(LltError::NonPositivePivot { index: index + s_start });
+						},
+					}
+					.dynamic_regularization_count;
+				linalg::triangular_solve::solve_lower_triangular_in_place(Ls_top.rb().conjugate(), Ls_bot.rb_mut().transpose_mut(), par);
 
-			dynamic_regularization_count +=
-				match linalg::cholesky::llt::factor::cholesky_in_place(Ls_top.rb_mut(), regularization.clone(), par, stack, params) {
-					Ok(count) => count,
-					Err(LltError::NonPositivePivot { index }) => {
-						return Err(LltError::NonPositivePivot { index: index + s_start });
-					},
+				for &row in s_pattern {
+					global_to_local[row.zx()] = none;
 				}
-				.dynamic_regularization_count;
-			linalg::triangular_solve::solve_lower_triangular_in_place(Ls_top.rb().conjugate(), Ls_bot.rb_mut().transpose_mut(), par);
+				Ok(dynamic_regularization_count)
+			},
+		)?;
 
-			for &row in s_pattern {
-				global_to_local[row.zx()] = none;
-			}
-		}
 		Ok(LltInfo {
 			dynamic_regularization_count,
 		})
@@ -3078,9 +3843,7 @@ pub mod supernodal {
 		stack: &mut MemStack,
 		params: Spec<LdltParams, T>,
 	) -> Result<LdltInfo, LdltError> {
-		let n_supernodes = symbolic.n_supernodes();
 		let n = symbolic.nrows();
-		let mut dynamic_regularization_count = 0usize;
 		L_values.fill(zero());
 
 		assert!(A_lower.nrows() == n);
@@ -3098,128 +3861,137 @@ pub mod supernodal {
 		let col_ptr_val = &*symbolic.col_ptr_for_val;
 		let row_idx = &*symbolic.row_idx;
 
-		// mapping from global indices to local
-		let (global_to_local, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
-		global_to_local.fill(I::Signed::truncate(NONE));
+		let L_values = SharedSlice::new(L_values);
+		let dynamic_regularization_count = factorize_supernodes(
+			symbolic,
+			par,
+			stack,
+			|| factorize_supernodal_numeric_ldlt_slot_scratch::<I, T>(symbolic, par, params),
+			&|s, par, global_to_local, stack| {
+				let s_start = symbolic.supernode_begin[s].zx();
+				let s_end = symbolic.supernode_begin[s + 1].zx();
+				let s_pattern = if let Some(nnz_per_super) = symbolic.nnz_per_super.as_deref() {
+					&row_idx[col_ptr_row[s].zx()..][..nnz_per_super[s].zx()]
+				} else {
+					&row_idx[col_ptr_row[s].zx()..col_ptr_row[s + 1].zx()]
+				};
 
-		for s in 0..n_supernodes {
-			let s_start = symbolic.supernode_begin[s].zx();
-			let s_end = symbolic.supernode_begin[s + 1].zx();
-			let s_pattern = if let Some(nnz_per_super) = symbolic.nnz_per_super.as_deref() {
-				&row_idx[col_ptr_row[s].zx()..][..nnz_per_super[s].zx()]
-			} else {
-				&row_idx[col_ptr_row[s].zx()..col_ptr_row[s + 1].zx()]
-			};
+				let s_ncols = s_end - s_start;
+				let s_nrows = s_pattern.len() + s_ncols;
 
-			let s_ncols = s_end - s_start;
-			let s_nrows = s_pattern.len() + s_ncols;
+				for (i, &row) in s_pattern.iter().enumerate() {
+					global_to_local[row.zx()] = I::Signed::truncate(i + s_ncols);
+				}
 
-			for (i, &row) in s_pattern.iter().enumerate() {
-				global_to_local[row.zx()] = I::Signed::truncate(i + s_ncols);
-			}
+				// SAFETY: the values of `s` are only accessed by this task
+				let mut Ls = MatMut::from_column_major_slice_mut(
+					unsafe { L_values.get_mut(col_ptr_val[s].zx()..col_ptr_val[s + 1].zx()) },
+					s_nrows,
+					s_ncols,
+				);
 
-			let (head, tail) = L_values.split_at_mut(col_ptr_val[s].zx());
-			let head = head.rb();
-			let mut Ls = MatMut::from_column_major_slice_mut(&mut tail[..col_ptr_val[s + 1].zx() - col_ptr_val[s].zx()], s_nrows, s_ncols);
+				for j in s_start..s_end {
+					let j_shifted = j - s_start;
+					for (i, val) in iter::zip(A_lower.row_idx_of_col(j), A_lower.val_of_col(j)) {
+						if i < j {
+							continue;
+						}
 
-			for j in s_start..s_end {
-				let j_shifted = j - s_start;
-				for (i, val) in iter::zip(A_lower.row_idx_of_col(j), A_lower.val_of_col(j)) {
-					if i < j {
-						continue;
+						let (ix, iy) = if i >= s_end {
+							(global_to_local[i].sx(), j_shifted)
+						} else {
+							(i - s_start, j_shifted)
+						};
+						Ls[(ix, iy)] = Ls[(ix, iy)] + *val;
 					}
+				}
 
-					let (ix, iy) = if i >= s_end {
-						(global_to_local[i].sx(), j_shifted)
+				let s_postordered = post_inv[s].zx();
+				let desc_count = desc_count[s].zx();
+				for d in &post[s_postordered - desc_count..s_postordered] {
+					let d = d.zx();
+					let d_start = symbolic.supernode_begin[d].zx();
+					let d_end = symbolic.supernode_begin[d + 1].zx();
+					let d_pattern = if let Some(nnz_per_super) = symbolic.nnz_per_super.as_deref() {
+						&row_idx[col_ptr_row[d].zx()..][..nnz_per_super[d].zx()]
 					} else {
-						(i - s_start, j_shifted)
+						&row_idx[col_ptr_row[d].zx()..col_ptr_row[d + 1].zx()]
 					};
-					Ls[(ix, iy)] = Ls[(ix, iy)] + *val;
-				}
-			}
 
-			let s_postordered = post_inv[s].zx();
-			let desc_count = desc_count[s].zx();
-			for d in &post[s_postordered - desc_count..s_postordered] {
-				let d = d.zx();
-				let d_start = symbolic.supernode_begin[d].zx();
-				let d_end = symbolic.supernode_begin[d + 1].zx();
-				let d_pattern = if let Some(nnz_per_super) = symbolic.nnz_per_super.as_deref() {
-					&row_idx[col_ptr_row[d].zx()..][..nnz_per_super[d].zx()]
-				} else {
-					&row_idx[col_ptr_row[d].zx()..col_ptr_row[d + 1].zx()]
-				};
+					let d_ncols = d_end - d_start;
+					let d_nrows = d_pattern.len() + d_ncols;
 
-				let d_ncols = d_end - d_start;
-				let d_nrows = d_pattern.len() + d_ncols;
+					// SAFETY: the descendants of `s` are done, and are no longer modified
+					let Ld = MatRef::from_column_major_slice(unsafe { L_values.get(col_ptr_val[d].zx()..col_ptr_val[d + 1].zx()) }, d_nrows, d_ncols);
 
-				let Ld = MatRef::from_column_major_slice(&head[col_ptr_val[d].zx()..col_ptr_val[d + 1].zx()], d_nrows, d_ncols);
+					let d_pattern_start = d_pattern.partition_point(partition_fn(s_start));
+					let d_pattern_mid_len = d_pattern[d_pattern_start..].partition_point(partition_fn(s_end));
 
-				let d_pattern_start = d_pattern.partition_point(partition_fn(s_start));
-				let d_pattern_mid_len = d_pattern[d_pattern_start..].partition_point(partition_fn(s_end));
+					let (Ld_top, Ld_mid_bot) = Ld.split_at_row(d_ncols);
+					let (_, Ld_mid_bot) = Ld_mid_bot.split_at_row(d_pattern_start);
+					let (Ld_mid, _) = Ld_mid_bot.split_at_row(d_pattern_mid_len);
+					let D = Ld_top.diagonal().column_vector();
 
-				let (Ld_top, Ld_mid_bot) = Ld.split_at_row(d_ncols);
-				let (_, Ld_mid_bot) = Ld_mid_bot.split_at_row(d_pattern_start);
-				let (Ld_mid, _) = Ld_mid_bot.split_at_row(d_pattern_mid_len);
-				let D = Ld_top.diagonal().column_vector();
+					use linalg::matmul::triangular;
+					let (row_idx, stack) = stack.make_with(Ld_mid_bot.nrows(), |i| {
+						if i < d_pattern_mid_len {
+							I::truncate(d_pattern[d_pattern_start + i].zx() - s_start)
+						} else {
+							I::from_signed(global_to_local[d_pattern[d_pattern_start + i].zx()])
+						}
+					});
+					let (col_idx, stack) = stack.make_with(d_pattern_mid_len, |j| I::truncate(d_pattern[d_pattern_start + j].zx() - s_start));
+
+					spicy_matmul(
+						Ls.rb_mut(),
+						triangular::BlockStructure::TriangularLower,
+						Some(&row_idx),
+						Some(&col_idx),
+						Accum::Add,
+						Ld_mid_bot,
+						Conj::No,
+						Ld_mid.transpose(),
+						Conj::Yes,
+						Some(D.as_diagonal()),
+						-one::<T>(),
+						par,
+						stack,
+					);
+				}
 
-				use linalg::matmul::triangular;
-				let (row_idx, stack) = stack.make_with(Ld_mid_bot.nrows(), |i| {
-					if i < d_pattern_mid_len {
-						I::truncate(d_pattern[d_pattern_start + i].zx() - s_start)
-					} else {
-						I::from_signed(global_to_local[d_pattern[d_pattern_start + i].zx()])
-					}
-				});
-				let (col_idx, stack) = stack.make_with(d_pattern_mid_len, |j| I::truncate(d_pattern[d_pattern_start + j].zx() - s_start));
+				let (mut Ls_top, mut Ls_bot) = Ls.rb_mut().split_at_row_mut(s_ncols);
 
-				spicy_matmul(
-					Ls.rb_mut(),
-					triangular::BlockStructure::TriangularLower,
-					Some(&row_idx),
-					Some(&col_idx),
-					Accum::Add,
-					Ld_mid_bot,
-					Conj::No,
-					Ld_mid.transpose(),
-					Conj::Yes,
-					Some(D.as_diagonal()),
-					-one::<T>(),
+				let dynamic_regularization_count = match linalg::cholesky::ldlt::factor::cholesky_in_place(
+					Ls_top.rb_mut(),
+					LdltRegularization {
+						dynamic_regularization_signs: regularization.dynamic_regularization_signs.map(|signs| &signs[s_start..s_end]),
+						..regularization.clone()
+					},
 					par,
 					stack,
-				);
-			}
-
-			let (mut Ls_top, mut Ls_bot) = Ls.rb_mut().split_at_row_mut(s_ncols);
+					params,
+				) {
+					Ok(count) => count.dynamic_regularization_count,
+					Err(LdltError::ZeroPivot { index }) => {
+						return Err(LdltError::ZeroPivot { index: index + s_start });
+					},
+				};
+				z!(Ls_top.rb_mut()).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero::<T>());
+				linalg::triangular_solve::solve_unit_lower_triangular_in_place(Ls_top.rb().conjugate(), Ls_bot.rb_mut().transpose_mut(), par);
+				for j in 0..s_ncols {
+					let d = recip(real(Ls_top[(j, j)]));
+					for i in 0..s_pattern.len() {
+						Ls_bot[(i, j)] = mul_real(Ls_bot[(i, j)], d);
+					}
+				}
 
-			dynamic_regularization_count += match linalg::cholesky::ldlt::factor::cholesky_in_place(
-				Ls_top.rb_mut(),
-				LdltRegularization {
-					dynamic_regularization_signs: regularization.dynamic_regularization_signs.map(|signs| &signs[s_start..s_end]),
-					..regularization.clone()
-				},
-				par,
-				stack,
-				params,
-			) {
-				Ok(count) => count.dynamic_regularization_count,
-				Err(LdltError::ZeroPivot { index }) => {
-					return Err(LdltError::ZeroPivot { index: index + s_start });
-				},
-			};
-			z!(Ls_top.rb_mut()).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero::<T>());
-			linalg::triangular_solve::solve_unit_lower_triangular_in_place(Ls_top.rb().conjugate(), Ls_bot.rb_mut().transpose_mut(), par);
-			for j in 0..s_ncols {
-				let d = recip(real(Ls_top[(j, j)]));
-				for i in 0..s_pattern.len() {
-					Ls_bot[(i, j)] = mul_real(Ls_bot[(i, j)], d);
+				for &row in s_pattern {
+					global_to_local[row.zx()] = none;
 				}
-			}
+				Ok(dynamic_regularization_count)
+			},
+		)?;
 
-			for &row in s_pattern {
-				global_to_local[row.zx()] = none;
-			}
-		}
 		Ok(LdltInfo {
 			dynamic_regularization_count,
 		})
@@ -3248,9 +4020,7 @@ pub mod supernodal {
 		stack: &mut MemStack,
 		params: Spec<LbltParams, T>,
 	) -> LbltInfo {
-		let n_supernodes = symbolic.n_supernodes();
 		let n = symbolic.nrows();
-		let mut transposition_count = 0usize;
 		L_values.fill(zero());
 
 		assert!(A_lower.nrows() == n);
@@ -3271,256 +4041,1061 @@ pub mod supernodal {
 		let col_ptr_val = &*symbolic.col_ptr_for_val;
 		let row_idx = &*symbolic.row_idx;
 
-		// mapping from global indices to local
-		let (global_to_local, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
-		global_to_local.fill(I::Signed::truncate(NONE));
+		let L_values = SharedSlice::new(L_values);
+		let subdiag = SharedSlice::new(subdiag);
+		let perm_forward = SharedSlice::new(perm_forward);
+		let perm_inverse = SharedSlice::new(perm_inverse);
+		let Ok(transposition_count) = factorize_supernodes::<I, core::convert::Infallible, _>(
+			symbolic,
+			par,
+			stack,
+			|| factorize_supernodal_numeric_intranode_lblt_slot_scratch::<I, T>(symbolic, par, params),
+			&|s, par, global_to_local, stack| {
+				let s_start = symbolic.supernode_begin[s].zx();
+				let s_end = symbolic.supernode_begin[s + 1].zx();
 
-		for s in 0..n_supernodes {
-			let s_start = symbolic.supernode_begin[s].zx();
-			let s_end = symbolic.supernode_begin[s + 1].zx();
+				let s_pattern = &row_idx[col_ptr_row[s].zx()..col_ptr_row[s + 1].zx()];
+				let s_ncols = s_end - s_start;
+				let s_nrows = s_pattern.len() + s_ncols;
 
-			let s_pattern = &row_idx[col_ptr_row[s].zx()..col_ptr_row[s + 1].zx()];
-			let s_ncols = s_end - s_start;
-			let s_nrows = s_pattern.len() + s_ncols;
+				for (i, &row) in s_pattern.iter().enumerate() {
+					global_to_local[row.zx()] = I::Signed::truncate(i + s_ncols);
+				}
 
-			for (i, &row) in s_pattern.iter().enumerate() {
-				global_to_local[row.zx()] = I::Signed::truncate(i + s_ncols);
-			}
+				// SAFETY: the values of `s` are only accessed by this task
+				let mut Ls = MatMut::from_column_major_slice_mut(
+					unsafe { L_values.get_mut(col_ptr_val[s].zx()..col_ptr_val[s + 1].zx()) },
+					s_nrows,
+					s_ncols,
+				);
 
-			let (head, tail) = L_values.split_at_mut(col_ptr_val[s].zx());
-			let head = head.rb();
-			let mut Ls = MatMut::from_column_major_slice_mut(&mut tail[..col_ptr_val[s + 1].zx() - col_ptr_val[s].zx()], s_nrows, s_ncols);
+				for j in s_start..s_end {
+					let j_shifted = j - s_start;
+					for (i, val) in iter::zip(A_lower.row_idx_of_col(j), A_lower.val_of_col(j)) {
+						if i < j {
+							continue;
+						}
 
-			for j in s_start..s_end {
-				let j_shifted = j - s_start;
-				for (i, val) in iter::zip(A_lower.row_idx_of_col(j), A_lower.val_of_col(j)) {
-					if i < j {
-						continue;
+						let (ix, iy) = if i >= s_end {
+							(global_to_local[i].sx(), j_shifted)
+						} else {
+							(i - s_start, j_shifted)
+						};
+						Ls[(ix, iy)] = Ls[(ix, iy)] + *val;
 					}
-
-					let (ix, iy) = if i >= s_end {
-						(global_to_local[i].sx(), j_shifted)
-					} else {
-						(i - s_start, j_shifted)
-					};
-					Ls[(ix, iy)] = Ls[(ix, iy)] + *val;
 				}
-			}
 
-			let s_postordered = post_inv[s].zx();
-			let desc_count = desc_count[s].zx();
-			for d in &post[s_postordered - desc_count..s_postordered] {
-				let d = d.zx();
-				let d_start = symbolic.supernode_begin[d].zx();
-				let d_end = symbolic.supernode_begin[d + 1].zx();
+				let s_postordered = post_inv[s].zx();
+				let desc_count = desc_count[s].zx();
+				for d in &post[s_postordered - desc_count..s_postordered] {
+					let d = d.zx();
+					let d_start = symbolic.supernode_begin[d].zx();
+					let d_end = symbolic.supernode_begin[d + 1].zx();
+
+					let d_pattern = &row_idx[col_ptr_row[d].zx()..col_ptr_row[d + 1].zx()];
+					let d_ncols = d_end - d_start;
+					let d_nrows = d_pattern.len() + d_ncols;
+
+					// SAFETY: the descendants of `s` are done, and are no longer modified
+					let Ld = MatRef::from_column_major_slice(unsafe { L_values.get(col_ptr_val[d].zx()..col_ptr_val[d + 1].zx()) }, d_nrows, d_ncols);
+
+					let d_pattern_start = d_pattern.partition_point(partition_fn(s_start));
+					let d_pattern_mid_len = d_pattern[d_pattern_start..].partition_point(partition_fn(s_end));
+					let d_pattern_mid = d_pattern_start + d_pattern_mid_len;
+
+					let (Ld_top, Ld_mid_bot) = Ld.split_at_row(d_ncols);
+					let (_, Ld_mid_bot) = Ld_mid_bot.split_at_row(d_pattern_start);
+					let (Ld_mid, Ld_bot) = Ld_mid_bot.split_at_row(d_pattern_mid_len);
+					let d_subdiag = unsafe { subdiag.get(d_start..d_start + d_ncols) };
+
+					let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(Ld_mid_bot.nrows(), d_pattern_mid_len, stack) };
+					let (mut tmp2, _) = unsafe { temp_mat_uninit::<T, _, _>(Ld_mid.ncols(), Ld_mid.nrows(), stack) };
+					let tmp = tmp.as_mat_mut();
+					let mut Ld_mid_x_D = tmp2.as_mat_mut().transpose_mut();
+
+					let mut j = 0;
+					while j < d_ncols {
+						let subdiag = copy(d_subdiag[j]);
+						if subdiag == zero::<T>() {
+							let d = real(Ld_top[(j, j)]);
+							for i in 0..d_pattern_mid_len {
+								Ld_mid_x_D[(i, j)] = mul_real(Ld_mid[(i, j)], d);
+							}
+							j += 1;
+						} else {
+							let akp1k = subdiag;
+							let ak = real(Ld_top[(j, j)]);
+							let akp1 = real(Ld_top[(j + 1, j + 1)]);
 
-				let d_pattern = &row_idx[col_ptr_row[d].zx()..col_ptr_row[d + 1].zx()];
-				let d_ncols = d_end - d_start;
-				let d_nrows = d_pattern.len() + d_ncols;
+							for i in 0..d_pattern_mid_len {
+								let xk = copy(Ld_mid[(i, j)]);
+								let xkp1 = copy(Ld_mid[(i, j + 1)]);
 
-				let Ld = MatRef::from_column_major_slice(&head[col_ptr_val[d].zx()..col_ptr_val[d + 1].zx()], d_nrows, d_ncols);
+								Ld_mid_x_D[(i, j)] = mul_real(xk, ak) + xkp1 * akp1k;
+								Ld_mid_x_D[(i, j + 1)] = mul_real(xkp1, akp1) + xk * conj(akp1k);
+							}
+							j += 2;
+						}
+					}
 
-				let d_pattern_start = d_pattern.partition_point(partition_fn(s_start));
-				let d_pattern_mid_len = d_pattern[d_pattern_start..].partition_point(partition_fn(s_end));
-				let d_pattern_mid = d_pattern_start + d_pattern_mid_len;
+					let (mut tmp_top, mut tmp_bot) = tmp.split_at_row_mut(d_pattern_mid_len);
+
+					use linalg::matmul;
+					use linalg::matmul::triangular;
+					triangular::matmul(
+						tmp_top.rb_mut(),
+						triangular::BlockStructure::TriangularLower,
+						Accum::Replace,
+						Ld_mid,
+						triangular::BlockStructure::Rectangular,
+						Ld_mid_x_D.rb().adjoint(),
+						triangular::BlockStructure::Rectangular,
+						one::<T>(),
+						par,
+					);
+					matmul::matmul(tmp_bot.rb_mut(), Accum::Replace, Ld_bot, Ld_mid_x_D.rb().adjoint(), one::<T>(), par);
 
-				let (Ld_top, Ld_mid_bot) = Ld.split_at_row(d_ncols);
-				let (_, Ld_mid_bot) = Ld_mid_bot.split_at_row(d_pattern_start);
-				let (Ld_mid, Ld_bot) = Ld_mid_bot.split_at_row(d_pattern_mid_len);
-				let d_subdiag = &subdiag[d_start..d_start + d_ncols];
+					for (j_idx, j) in d_pattern[d_pattern_start..d_pattern_mid].iter().enumerate() {
+						let j = j.zx();
+						let j_s = j - s_start;
+						for (i_idx, i) in d_pattern[d_pattern_start..d_pattern_mid][j_idx..].iter().enumerate() {
+							let i_idx = i_idx + j_idx;
 
-				let (mut tmp, stack) = unsafe { temp_mat_uninit::<T, _, _>(Ld_mid_bot.nrows(), d_pattern_mid_len, stack) };
-				let (mut tmp2, _) = unsafe { temp_mat_uninit::<T, _, _>(Ld_mid.ncols(), Ld_mid.nrows(), stack) };
-				let tmp = tmp.as_mat_mut();
-				let mut Ld_mid_x_D = tmp2.as_mat_mut().transpose_mut();
+							let i = i.zx();
+							let i_s = i - s_start;
 
-				let mut j = 0;
-				while j < d_ncols {
-					let subdiag = copy(d_subdiag[j]);
-					if subdiag == zero::<T>() {
-						let d = real(Ld_top[(j, j)]);
-						for i in 0..d_pattern_mid_len {
-							Ld_mid_x_D[(i, j)] = mul_real(Ld_mid[(i, j)], d);
+							debug_assert!(i_s >= j_s);
+							Ls[(i_s, j_s)] = Ls[(i_s, j_s)] - tmp_top[(i_idx, j_idx)];
 						}
-						j += 1;
-					} else {
-						let akp1k = subdiag;
-						let ak = real(Ld_top[(j, j)]);
-						let akp1 = real(Ld_top[(j + 1, j + 1)]);
-
-						for i in 0..d_pattern_mid_len {
-							let xk = copy(Ld_mid[(i, j)]);
-							let xkp1 = copy(Ld_mid[(i, j + 1)]);
+					}
 
-							Ld_mid_x_D[(i, j)] = mul_real(xk, ak) + xkp1 * akp1k;
-							Ld_mid_x_D[(i, j + 1)] = mul_real(xkp1, akp1) + xk * conj(akp1k);
+					for (j_idx, j) in d_pattern[d_pattern_start..d_pattern_mid].iter().enumerate() {
+						let j = j.zx();
+						let j_s = j - s_start;
+						for (i_idx, i) in d_pattern[d_pattern_mid..].iter().enumerate() {
+							let i = i.zx();
+							let i_s = global_to_local[i].zx();
+							Ls[(i_s, j_s)] = Ls[(i_s, j_s)] - tmp_bot[(i_idx, j_idx)];
 						}
-						j += 2;
 					}
 				}
 
-				let (mut tmp_top, mut tmp_bot) = tmp.split_at_row_mut(d_pattern_mid_len);
-
-				use linalg::matmul;
-				use linalg::matmul::triangular;
-				triangular::matmul(
-					tmp_top.rb_mut(),
-					triangular::BlockStructure::TriangularLower,
-					Accum::Replace,
-					Ld_mid,
-					triangular::BlockStructure::Rectangular,
-					Ld_mid_x_D.rb().adjoint(),
-					triangular::BlockStructure::Rectangular,
-					one::<T>(),
+				let (mut Ls_top, mut Ls_bot) = Ls.rb_mut().split_at_row_mut(s_ncols);
+				let (s_subdiag, s_perm_forward, s_perm_inverse) = unsafe {
+					(
+						subdiag.get_mut(s_start..s_end),
+						perm_forward.get_mut(s_start..s_end),
+						perm_inverse.get_mut(s_start..s_end),
+					)
+				};
+
+				let (info, perm) = linalg::cholesky::lblt::factor::cholesky_in_place(
+					Ls_top.rb_mut(),
+					ColMut::from_slice_mut(s_subdiag).as_diagonal_mut(),
+					s_perm_forward,
+					s_perm_inverse,
 					par,
+					stack,
+					params,
 				);
-				matmul::matmul(tmp_bot.rb_mut(), Accum::Replace, Ld_bot, Ld_mid_x_D.rb().adjoint(), one::<T>(), par);
+				z!(Ls_top.rb_mut()).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero::<T>());
 
-				for (j_idx, j) in d_pattern[d_pattern_start..d_pattern_mid].iter().enumerate() {
-					let j = j.zx();
-					let j_s = j - s_start;
-					for (i_idx, i) in d_pattern[d_pattern_start..d_pattern_mid][j_idx..].iter().enumerate() {
-						let i_idx = i_idx + j_idx;
+				crate::perm::permute_cols_in_place(Ls_bot.rb_mut(), perm.rb(), stack);
 
-						let i = i.zx();
-						let i_s = i - s_start;
+				for p in s_perm_forward {
+					*p += I::truncate(s_start);
+				}
+				for p in s_perm_inverse {
+					*p += I::truncate(s_start);
+				}
+
+				linalg::triangular_solve::solve_unit_lower_triangular_in_place(Ls_top.rb().conjugate(), Ls_bot.rb_mut().transpose_mut(), par);
+
+				let mut j = 0;
+				while j < s_ncols {
+					if s_subdiag[j] == zero::<T>() {
+						let d = recip(real(Ls_top[(j, j)]));
+						for i in 0..s_pattern.len() {
+							Ls_bot[(i, j)] = mul_real(Ls_bot[(i, j)], d);
+						}
+						j += 1;
+					} else {
+						let akp1k = recip(conj(s_subdiag[j]));
+						let ak = mul_real(conj(akp1k), real(Ls_top[(j, j)]));
+						let akp1 = mul_real(akp1k, real(Ls_top[(j + 1, j + 1)]));
+
+						let denom = recip(ak * akp1 - one::<T>());
 
-						debug_assert!(i_s >= j_s);
-						Ls[(i_s, j_s)] = Ls[(i_s, j_s)] - tmp_top[(i_idx, j_idx)];
+						for i in 0..s_pattern.len() {
+							let xk = Ls_bot[(i, j)] * conj(akp1k);
+							let xkp1 = Ls_bot[(i, j + 1)] * akp1k;
+
+							Ls_bot[(i, j)] = (akp1 * xk - xkp1) * denom;
+							Ls_bot[(i, j + 1)] = (ak * xkp1 - xk) * denom;
+						}
+						j += 2;
 					}
 				}
 
-				for (j_idx, j) in d_pattern[d_pattern_start..d_pattern_mid].iter().enumerate() {
-					let j = j.zx();
-					let j_s = j - s_start;
-					for (i_idx, i) in d_pattern[d_pattern_mid..].iter().enumerate() {
-						let i = i.zx();
-						let i_s = global_to_local[i].zx();
-						Ls[(i_s, j_s)] = Ls[(i_s, j_s)] - tmp_bot[(i_idx, j_idx)];
-					}
+				for &row in s_pattern {
+					global_to_local[row.zx()] = none;
 				}
+				Ok(info.transposition_count)
+			},
+		);
+
+		LbltInfo { transposition_count }
+	}
+	#[inline]
+	pub(crate) fn column_layout<I: Index>(symbolic: &SymbolicSupernodalCholesky<I>, j: usize) -> ColumnLayout<'_, I> {
+		let s = symbolic.supernode_begin.partition_point(|&begin| begin.zx() <= j) - 1;
+		let s_start = symbolic.supernode_begin[s].zx();
+		let s_end = symbolic.supernode_begin[s + 1].zx();
+		let s_pattern = symbolic.supernode(s).pattern;
+		let s_nrows = s_end - s_start + s_pattern.len();
+		let j_shifted = j - s_start;
+		(
+			symbolic.col_ptr_for_val[s].zx() + j_shifted * s_nrows + j_shifted,
+			j + 1..s_end,
+			s_pattern,
+		)
+	}
+
+	/// returns the size and alignment of the workspace required to compute the symbolic structure
+	/// of the cholesky factor of $A + W W^H$, where $A$ has dimension `n` and $W$ has `rank`
+	/// columns
+	pub fn update_supernodal_symbolic_cholesky_scratch<I: Index>(n: usize, rank: usize) -> StackReq {
+		StackReq::or(
+			update_pattern_scratch::<I>(n, rank),
+			factorize_supernodal_symbolic_cholesky_scratch::<I>(n),
+		)
+	}
+
+	/// computes the symbolic structure of the cholesky factor of $A + W W^H$, given that of the
+	/// cholesky factor of $A$
+	///
+	/// returns `None` if the structure of the factor of $A$ already contains it, in which case the
+	/// factor can be updated in place. otherwise, the supernodes are recomputed from the new
+	/// structure, and the values of the factor can be moved to it with
+	/// [`remap_supernodal_values`]
+	#[track_caller]
+	pub fn update_supernodal_symbolic_cholesky<I: Index>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		W: SymbolicSparseColMatRef<'_, I>,
+		stack: &mut MemStack,
+		params: SymbolicSupernodalParams<'_>,
+	) -> Result<Option<SymbolicSupernodalCholesky<I>>, FaerError> {
+		assert!(W.nrows() == symbolic.nrows());
+		update_supernodal_symbolic_cholesky_imp(symbolic, W, None, stack, params)
+	}
+
+	pub(crate) fn update_supernodal_symbolic_cholesky_imp<I: Index>(
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		W: SymbolicSparseColMatRef<'_, I>,
+		row_map: Option<&[I]>,
+		stack: &mut MemStack,
+		params: SymbolicSupernodalParams<'_>,
+	) -> Result<Option<SymbolicSupernodalCholesky<I>>, FaerError> {
+		let n = symbolic.nrows();
+
+		// number of entries in the lower triangular part of the factor, including the explicit
+		// zeros of the relaxed supernodes
+		let mut L_nnz = 0usize;
+		for s in 0..symbolic.n_supernodes() {
+			let s_ncols = symbolic.supernode_begin[s + 1].zx() - symbolic.supernode_begin[s].zx();
+			let s_pattern_len = symbolic.supernode(s).pattern.len();
+			L_nnz += s_ncols * (s_ncols + 1) / 2 + s_ncols * s_pattern_len;
+		}
+
+		let Some((L_col_ptr, L_row_idx, etree)) = update_pattern(n, L_nnz, |j| column_layout(symbolic, j), W, row_map, stack)? else {
+			return Ok(None);
+		};
+
+		// the new structure is its own symbolic factor, so the supernodes are computed from the
+		// matrix whose upper triangular part is its transpose
+		let mut col_counts = try_zeroed::<I>(n)?;
+		let mut A_col_ptr = try_zeroed::<I>(n + 1)?;
+		for j in 0..n {
+			col_counts[j] = L_col_ptr[j + 1] - L_col_ptr[j];
+		}
+		for &i in &L_row_idx {
+			A_col_ptr[i.zx() + 1] += I::truncate(1);
+		}
+		for i in 0..n {
+			A_col_ptr[i + 1] = A_col_ptr[i + 1] + A_col_ptr[i];
+		}
+		let mut pos = try_collect(A_col_ptr[..n].iter().copied())?;
+		let mut A_row_idx = try_zeroed::<I>(L_row_idx.len())?;
+		for j in 0..n {
+			for &i in &L_row_idx[L_col_ptr[j].zx()..L_col_ptr[j + 1].zx()] {
+				let p = &mut pos[i.zx()];
+				A_row_idx[p.zx()] = I::truncate(j);
+				*p += I::truncate(1);
 			}
+		}
+		let A = SymbolicSparseColMatRef::new_checked(n, n, &A_col_ptr, None, &A_row_idx);
+
+		factorize_supernodal_symbolic_cholesky(
+			A,
+			// SAFETY: the elimination tree of the new structure is that of the matrix
+			unsafe { simplicial::EliminationTreeRef::from_inner(bytemuck::cast_slice::<I, I::Signed>(&etree)) },
+			&col_counts,
+			stack,
+			params,
+		)
+		.map(Some)
+	}
 
-			let (mut Ls_top, mut Ls_bot) = Ls.rb_mut().split_at_row_mut(s_ncols);
-			let s_subdiag = &mut subdiag[s_start..s_end];
+	/// copies the numerical values `src` of a cholesky factor with the structure `src_symbolic` to
+	/// `dst`, for a factor with the structure `dst_symbolic`, which must contain it
+	#[track_caller]
+	pub fn remap_supernodal_values<I: Index, T: ComplexField>(
+		dst: &mut [T],
+		dst_symbolic: &SymbolicSupernodalCholesky<I>,
+		src: &[T],
+		src_symbolic: &SymbolicSupernodalCholesky<I>,
+	) {
+		let n = src_symbolic.nrows();
+		assert!(all(
+			dst_symbolic.nrows() == n,
+			dst.len() == dst_symbolic.len_val(),
+			src.len() == src_symbolic.len_val(),
+		));
+		remap_values(n, dst, |j| column_layout(dst_symbolic, j), src, |j| column_layout(src_symbolic, j));
+	}
 
-			let (info, perm) = linalg::cholesky::lblt::factor::cholesky_in_place(
-				Ls_top.rb_mut(),
-				ColMut::from_slice_mut(s_subdiag).as_diagonal_mut(),
-				&mut perm_forward[s_start..s_end],
-				&mut perm_inverse[s_start..s_end],
-				par,
-				stack,
-				params,
-			);
-			transposition_count += info.transposition_count;
-			z!(Ls_top.rb_mut()).for_each_triangular_upper(linalg::zip::Diag::Skip, |uz!(x)| *x = zero::<T>());
+	/// returns the size and alignment of the workspace required to update the cholesky factor of a
+	/// matrix with dimension `n`
+	pub fn rank_r_update_supernodal_numeric_llt_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+		rank_r_update_scratch::<I, T>(n)
+	}
+
+	/// computes the cholesky $LL^H$ factor of $A + W \operatorname{diag}(\alpha) W^H$ in place,
+	/// given the factor of $A$
+	///
+	/// the columns of $W$ with a negative weight are downdates, which fail if the resulting matrix
+	/// is not numerically positive definite. the values of the factor are then unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`update_supernodal_symbolic_cholesky`] beforehand
+	#[track_caller]
+	pub fn rank_r_update_supernodal_numeric_llt<I: Index, T: ComplexField>(
+		L_values: &mut [T],
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		W: SparseColMatRef<'_, I, T>,
+		alpha: DiagRef<'_, T::Real>,
+		stack: &mut MemStack,
+	) -> Result<(), LltError> {
+		assert!(all(
+			L_values.len() == symbolic.len_val(),
+			W.nrows() == symbolic.nrows(),
+			alpha.column_vector().nrows() == W.ncols(),
+		));
+		rank_r_update(L_values, |j| column_layout(symbolic, j), W, None, alpha, stack)
+	}
+
+	/// returns the size and alignment of the workspace required to compute the selected inverse of
+	/// a matrix with dimension `n` from its cholesky factor
+	pub fn selected_inverse_supernodal_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+		selected_inverse_scratch::<I, T>(n)
+	}
+
+	/// computes the entries of $A^{-1}$ on the structure of the cholesky $LL^H$ factor of $A$, and
+	/// stores them in `Z_values`, with the same layout as the values of the factor
+	///
+	/// the entries above the diagonal of each supernode are left unchanged
+	#[track_caller]
+	pub fn selected_inverse_supernodal_llt<I: Index, T: ComplexField>(
+		Z_values: &mut [T],
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		L_values: &[T],
+		stack: &mut MemStack,
+	) {
+		assert!(all(Z_values.len() == symbolic.len_val(), L_values.len() == symbolic.len_val()));
+		selected_inverse(Z_values, |j| column_layout(symbolic, j), symbolic.nrows(), L_values, false, stack);
+	}
+
+	/// computes the entries of $A^{-1}$ on the structure of the cholesky $LDL^H$ factor of $A$,
+	/// and stores them in `Z_values`, with the same layout as the values of the factor
+	///
+	/// the entries above the diagonal of each supernode are left unchanged
+	#[track_caller]
+	pub fn selected_inverse_supernodal_ldlt<I: Index, T: ComplexField>(
+		Z_values: &mut [T],
+		symbolic: &SymbolicSupernodalCholesky<I>,
+		L_values: &[T],
+		stack: &mut MemStack,
+	) {
+		assert!(all(Z_values.len() == symbolic.len_val(), L_values.len() == symbolic.len_val()));
+		selected_inverse(Z_values, |j| column_layout(symbolic, j), symbolic.nrows(), L_values, true, stack);
+	}
+}
+
+fn postorder_depth_first_search<'n, I: Index>(
+	post: &mut Array<'n, I>,
+	root: usize,
+	mut start_index: usize,
+	stack: &mut Array<'n, I>,
+	first_child: &mut Array<'n, MaybeIdx<'n, I>>,
+	next_child: &Array<'n, I::Signed>,
+) -> usize {
+	let mut top = 1usize;
+	let N = post.len();
+
+	stack[N.check(0)] = I::truncate(root);
+	while top != 0 {
+		let current_node = stack[N.check(top - 1)].zx();
+		let first_child = &mut first_child[N.check(current_node)];
+		let current_child = (*first_child).sx();
+
+		if let Some(current_child) = current_child.idx() {
+			stack[N.check(top)] = *current_child.truncate::<I>();
+			top += 1;
+			*first_child = MaybeIdx::new_checked(next_child[current_child], N);
+		} else {
+			post[N.check(start_index)] = I::truncate(current_node);
+			start_index += 1;
+			top -= 1;
+		}
+	}
+	start_index
+}
+
+pub(crate) fn ghost_postorder<'n, I: Index>(post: &mut Array<'n, I>, etree: &Array<'n, MaybeIdx<'n, I>>, stack: &mut MemStack) {
+	let N = post.len();
+	let n = *N;
+
+	if n == 0 {
+		return;
+	}
+
+	let (stack_, stack) = unsafe { stack.make_raw::<I>(n) };
+	let (first_child, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (next_child, _) = unsafe { stack.make_raw::<I::Signed>(n) };
+
+	let stack = Array::from_mut(stack_, N);
+	let next_child = Array::from_mut(next_child, N);
+	let first_child = Array::from_mut(ghost::fill_none::<I>(first_child, N), N);
+
+	for j in N.indices().rev() {
+		let parent = etree[j];
+		if let Some(parent) = parent.idx() {
+			let first = &mut first_child[parent.zx()];
+			next_child[j] = **first;
+			*first = MaybeIdx::from_index(j.truncate::<I>());
+		}
+	}
+
+	let mut start_index = 0usize;
+	for (root, &parent) in etree.as_ref().iter().enumerate() {
+		if parent.idx().is_none() {
+			start_index = postorder_depth_first_search(post, root, start_index, stack, first_child, next_child);
+		}
+	}
+}
+
+// layout of a column `j` of a cholesky factor: the position of its diagonal entry in the values,
+// followed by the row indices of its off-diagonal entries, given as a contiguous range, then as a
+// sorted slice. the off-diagonal values are stored right after the diagonal one
+type ColumnLayout<'a, I> = (usize, core::ops::Range<usize>, &'a [I]);
+
+// column pointers, row indices and elimination tree of a simplicial cholesky factor
+type Pattern<I> = (alloc::vec::Vec<I>, alloc::vec::Vec<I>, alloc::vec::Vec<I>);
+
+fn update_pattern_scratch<I: Index>(n: usize, rank: usize) -> StackReq {
+	StackReq::new::<I>(n).array(5).and(StackReq::new::<I>(rank))
+}
+
+// computes the pattern of the cholesky factor of $L L^H + W W^H$, column by column, as the union of
+// the pattern of `L`, the patterns of the columns of `W` (added to the column of their first row),
+// and the patterns of the children in the new elimination tree
+//
+// returns `None` if it is the same as that of `L`
+fn update_pattern<'a, I: Index>(
+	n: usize,
+	L_nnz: usize,
+	column: impl Fn(usize) -> ColumnLayout<'a, I>,
+	W: SymbolicSparseColMatRef<'_, I>,
+	row_map: Option<&[I]>,
+	stack: &mut MemStack,
+) -> Result<Option<Pattern<I>>, FaerError> {
+	let none = I::Signed::truncate(NONE);
+	let map = |i: usize| match row_map {
+		Some(row_map) => row_map[i].zx(),
+		None => i,
+	};
+
+	let (w_head, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (child_head, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (child_next, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (mark, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (rows, stack) = unsafe { stack.make_raw::<I>(n) };
+	let (w_next, _) = unsafe { stack.make_raw::<I::Signed>(W.ncols()) };
+	w_head.fill(none);
+	child_head.fill(none);
+	mark.fill(none);
+
+	// bucket the columns of `W` by their first row
+	for (c, next) in w_next.iter_mut().enumerate() {
+		if let Some(first) = W.row_idx_of_col(c).map(map).min() {
+			*next = w_head[first];
+			w_head[first] = I::Signed::truncate(c);
+		}
+	}
+
+	let mut col_ptr = try_zeroed::<I>(n + 1)?;
+	let mut etree = try_zeroed::<I>(n)?;
+	let mut row_idx = alloc::vec::Vec::<I>::new();
+	row_idx.try_reserve_exact(L_nnz).map_err(|_| FaerError::OutOfMemory)?;
+
+	for j in 0..n {
+		let j_ = I::Signed::truncate(j);
+		mark[j] = j_;
+		let mut len = 0usize;
+		let mut push = |i: usize| {
+			if mark[i] != j_ {
+				mark[i] = j_;
+				rows[len] = I::truncate(i);
+				len += 1;
+			}
+		};
+
+		let (_, dense, sparse) = column(j);
+		dense.for_each(&mut push);
+		sparse.iter().for_each(|&i| push(i.zx()));
+
+		let mut c = w_head[j];
+		while c != none {
+			let c_ = c.zx();
+			W.row_idx_of_col(c_).for_each(|i| push(map(i)));
+			c = w_next[c_];
+		}
+
+		let mut child = child_head[j];
+		while child != none {
+			let child_ = child.zx();
+			row_idx[col_ptr[child_].zx() + 1..col_ptr[child_ + 1].zx()]
+				.iter()
+				.for_each(|&i| push(i.zx()));
+			child = child_next[child_];
+		}
+
+		let rows = &mut rows[..len];
+		rows.sort_unstable();
+
+		row_idx.try_reserve(len + 1).map_err(|_| FaerError::OutOfMemory)?;
+		row_idx.push(I::truncate(j));
+		row_idx.extend_from_slice(rows);
+		col_ptr[j + 1] = I::truncate(row_idx.len());
+
+		if let Some(&parent) = rows.first() {
+			let parent = parent.zx();
+			etree[j] = I::truncate(parent);
+			child_next[j] = child_head[parent];
+			child_head[parent] = j_;
+		} else {
+			etree[j] = I::from_signed(none);
+		}
+	}
+
+	if row_idx.len() == L_nnz {
+		Ok(None)
+	} else {
+		Ok(Some((col_ptr, row_idx, etree)))
+	}
+}
+
+// copies the values of a factor into a factor whose structure contains it
+#[track_caller]
+fn remap_values<'a, 'b, I: Index, T: ComplexField>(
+	n: usize,
+	dst: &mut [T],
+	dst_column: impl Fn(usize) -> ColumnLayout<'a, I>,
+	src: &[T],
+	src_column: impl Fn(usize) -> ColumnLayout<'b, I>,
+) {
+	dst.fill(zero::<T>());
+	for j in 0..n {
+		let (p, src_dense, src_sparse) = src_column(j);
+		let (q, dst_dense, dst_sparse) = dst_column(j);
+
+		dst[q] = src[p].clone();
+		let mut pos = 0usize;
+		for (k, i) in src_dense.chain(src_sparse.iter().map(|i| i.zx())).enumerate() {
+			let idx = if i < dst_dense.end {
+				assert!(i >= dst_dense.start);
+				i - dst_dense.start
+			} else {
+				pos += dst_sparse[pos..].partition_point(|&r| r.zx() < i);
+				assert!(dst_sparse.get(pos).map(|r| r.zx()) == Some(i));
+				dst_dense.len() + pos
+			};
+			dst[q + 1 + idx] = src[p + 1 + k].clone();
+		}
+	}
+}
+
+fn rank_r_update_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+	StackReq::and(temp_mat_scratch::<T>(n, 1), StackReq::new::<I>(n))
+}
+
+// parent of a column in the elimination tree, or `n` if it is a root
+#[inline]
+fn layout_parent<I: Index>(n: usize, (_, dense, sparse): &ColumnLayout<'_, I>) -> usize {
+	if !dense.is_empty() {
+		dense.start
+	} else if let Some(&i) = sparse.first() {
+		i.zx()
+	} else {
+		n
+	}
+}
+
+// position in the values of the entry of row `i` of a column, if it is part of its structure
+#[inline]
+fn layout_find<I: Index>((p, dense, sparse): &ColumnLayout<'_, I>, i: usize) -> Option<usize> {
+	if dense.contains(&i) {
+		Some(p + 1 + (i - dense.start))
+	} else {
+		sparse.binary_search_by(|r| r.zx().cmp(&i)).ok().map(|pos| p + 1 + dense.len() + pos)
+	}
+}
+
+// computes the cholesky factor of $L L^H \pm x x^H$ in place, where the nonzero entries of `x` all
+// lie on the path from `first` to the root of the elimination tree. `x` is overwritten
+//
+// on failure, returns the index of the column whose pivot is not positive
+#[math]
+fn llt_update_path<'a, I: Index, T: ComplexField>(
+	L_values: &mut [T],
+	column: &impl Fn(usize) -> ColumnLayout<'a, I>,
+	x: &mut [T],
+	first: usize,
+	downdate: bool,
+) -> Result<(), usize> {
+	let n = x.len();
+	let mut beta = one::<T::Real>();
+	let mut j = first;
+	while j < n {
+		let (p, dense, sparse) = column(j);
+		let next = layout_parent(n, &(p, dense.clone(), sparse));
+		let (L_jj, L_col) = L_values[p..p + 1 + dense.len() + sparse.len()].split_first_mut().unwrap();
+
+		let d = real(*L_jj);
+		let a = mul_real(x[j], recip(d));
+		let beta_next = if downdate { beta * beta - abs2(a) } else { beta * beta + abs2(a) };
+		if beta_next <= zero::<T::Real>() {
+			return Err(j);
+		}
+		let beta_next = sqrt(beta_next);
+
+		let (delta, gamma) = if downdate {
+			(beta_next / beta, -mul_real(conj(a), recip(beta_next * beta)))
+		} else {
+			(beta / beta_next, mul_real(conj(a), recip(beta_next * beta)))
+		};
+
+		*L_jj = if downdate {
+			from_real(delta * d)
+		} else {
+			from_real(delta * d + real(gamma * x[j]))
+		};
+		for (l, i) in iter::zip(L_col, dense.chain(sparse.iter().map(|i| i.zx()))) {
+			let w1 = copy(x[i]);
+			let w2 = w1 - a * *l;
+			x[i] = copy(w2);
+			let w = if downdate { w2 } else { w1 };
+			*l = mul_real(*l, delta) + gamma * w;
+		}
+
+		x[j] = zero::<T>();
+		beta = beta_next;
+		j = next;
+	}
+	Ok(())
+}
+
+// computes the cholesky factor of $L D L^H + \alpha x x^H$ in place, where the nonzero entries of
+// `x` all lie on the path from `first` to the root of the elimination tree. `x` is overwritten
+//
+// on failure, returns the index of the column whose pivot is zero
+#[math]
+fn ldlt_update_path<'a, I: Index, T: ComplexField>(
+	L_values: &mut [T],
+	column: &impl Fn(usize) -> ColumnLayout<'a, I>,
+	x: &mut [T],
+	first: usize,
+	alpha: T::Real,
+) -> Result<(), usize> {
+	let n = x.len();
+	let mut alpha = alpha;
+	let mut j = first;
+	while j < n {
+		let (p, dense, sparse) = column(j);
+		let next = layout_parent(n, &(p, dense.clone(), sparse));
+		let (D_jj, L_col) = L_values[p..p + 1 + dense.len() + sparse.len()].split_first_mut().unwrap();
+
+		let d = real(*D_jj);
+		let x_j = copy(x[j]);
+		let d_next = d + alpha * abs2(x_j);
+		if d_next == zero::<T::Real>() {
+			return Err(j);
+		}
+		let gamma = mul_real(conj(x_j), alpha / d_next);
+		alpha = alpha * d / d_next;
+		*D_jj = from_real(d_next);
+
+		for (l, i) in iter::zip(L_col, dense.chain(sparse.iter().map(|i| i.zx()))) {
+			let w = x[i] - x_j * *l;
+			x[i] = copy(w);
+			*l = *l + gamma * w;
+		}
+
+		x[j] = zero::<T>();
+		j = next;
+	}
+	Ok(())
+}
+
+// computes the cholesky factor of $L L^H + W \text{diag}(\alpha) W^H$ in place, one column of `W`
+// at a time, following the path from its first row to the root of the elimination tree
+#[math]
+#[track_caller]
+fn rank_r_update<'a, I: Index, T: ComplexField>(
+	L_values: &mut [T],
+	column: impl Fn(usize) -> ColumnLayout<'a, I>,
+	W: SparseColMatRef<'_, I, T>,
+	row_map: Option<&[I]>,
+	alpha: DiagRef<'_, T::Real>,
+	stack: &mut MemStack,
+) -> Result<(), LltError> {
+	let n = W.nrows();
+	let none = I::Signed::truncate(NONE);
+	let map = |i: usize| match row_map {
+		Some(row_map) => row_map[i].zx(),
+		None => i,
+	};
 
-			crate::perm::permute_cols_in_place(Ls_bot.rb_mut(), perm.rb(), stack);
+	let (mut x, stack) = temp_mat_zeroed::<T, _, _>(n, 1, stack);
+	let x = x.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap().as_slice_mut();
+	let (mark, _) = unsafe { stack.make_raw::<I::Signed>(n) };
+	mark.fill(none);
 
-			for p in &mut perm_forward[s_start..s_end] {
-				*p += I::truncate(s_start);
+	for c in 0..W.ncols() {
+		let alpha = copy(alpha.column_vector()[c]);
+		if alpha == zero::<T::Real>() {
+			continue;
+		}
+		let downdate = alpha < zero::<T::Real>();
+		let scale = sqrt(abs(alpha));
+		let c_ = I::Signed::truncate(c);
+
+		let mut first = n;
+		let mut count = 0usize;
+		for i in W.row_idx_of_col(c) {
+			let i = map(i);
+			if mark[i] != c_ {
+				mark[i] = c_;
+				count += 1;
 			}
-			for p in &mut perm_inverse[s_start..s_end] {
-				*p += I::truncate(s_start);
+			first = Ord::min(first, i);
+		}
+
+		// the rows of the column must all lie on the path, so that its entries can be chased down
+		// the tree
+		let mut j = first;
+		while j < n {
+			if mark[j] == c_ {
+				mark[j] = none;
+				count -= 1;
 			}
+			j = layout_parent(n, &column(j));
+		}
+		assert!(count == 0);
 
-			linalg::triangular_solve::solve_unit_lower_triangular_in_place(Ls_top.rb().conjugate(), Ls_bot.rb_mut().transpose_mut(), par);
+		for (i, w) in iter::zip(W.row_idx_of_col(c), W.val_of_col(c)) {
+			let i = map(i);
+			x[i] = x[i] + mul_real(*w, scale);
+		}
 
-			let mut j = 0;
-			while j < s_ncols {
-				if s_subdiag[j] == zero::<T>() {
-					let d = recip(real(Ls_top[(j, j)]));
-					for i in 0..s_pattern.len() {
-						Ls_bot[(i, j)] = mul_real(Ls_bot[(i, j)], d);
-					}
-					j += 1;
-				} else {
-					let akp1k = recip(conj(s_subdiag[j]));
-					let ak = mul_real(conj(akp1k), real(Ls_top[(j, j)]));
-					let akp1 = mul_real(akp1k, real(Ls_top[(j + 1, j + 1)]));
+		llt_update_path(L_values, &column, x, first, downdate).map_err(|index| LltError::NonPositivePivot { index })?;
+	}
+	Ok(())
+}
 
-					let denom = recip(ak * akp1 - one::<T>());
+fn row_add_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+	StackReq::and(temp_mat_scratch::<T>(n, 1), StackReq::new::<I>(n).array(2))
+}
 
-					for i in 0..s_pattern.len() {
-						let xk = Ls_bot[(i, j)] * conj(akp1k);
-						let xkp1 = Ls_bot[(i, j + 1)] * akp1k;
+// replaces the row and column `k` of the factor of the identity at that position by those of
+// `A_col`, then downdates the trailing part of the factor with the new column
+//
+// with `ldlt`, the factor is a unit lower triangular matrix storing the diagonal factor in place of
+// its diagonal. on failure, returns the index of the column whose pivot is not positive (or zero)
+#[math]
+#[track_caller]
+fn row_add<'a, I: Index, T: ComplexField>(
+	L_values: &mut [T],
+	column: impl Fn(usize) -> ColumnLayout<'a, I>,
+	k: usize,
+	A_col: SparseColMatRef<'_, I, T>,
+	row_map: Option<&[I]>,
+	ldlt: bool,
+	stack: &mut MemStack,
+) -> Result<(), usize> {
+	let n = A_col.nrows();
+	let none = I::Signed::truncate(NONE);
+	let k_ = I::Signed::truncate(k);
+	let map = |i: usize| match row_map {
+		Some(row_map) => row_map[i].zx(),
+		None => i,
+	};
 
-						Ls_bot[(i, j)] = (akp1 * xk - xkp1) * denom;
-						Ls_bot[(i, j + 1)] = (ak * xkp1 - xk) * denom;
-					}
-					j += 2;
-				}
-			}
+	let (mut x, stack) = temp_mat_zeroed::<T, _, _>(n, 1, stack);
+	let x = x.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap().as_slice_mut();
+	let (mark, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (reach, _) = unsafe { stack.make_raw::<I>(n) };
+	mark.fill(none);
+
+	let col_k = column(k);
+	col_k.1.clone().chain(col_k.2.iter().map(|i| i.zx())).for_each(|i| mark[i] = k_);
+
+	// the rows of $L_{11}^{-1} a_{12}$ are those reachable from the rows of $a_{12}$ in the
+	// elimination tree, which must all be ancestors of the rows of $a_{12}$
+	let mut a_kk = zero::<T::Real>();
+	let mut len = 0usize;
+	for (i, a) in iter::zip(A_col.row_idx_of_col(0), A_col.val_of_col(0)) {
+		let i = map(i);
+		if i == k {
+			a_kk = a_kk + real(*a);
+			continue;
+		}
+		x[i] = x[i] + *a;
 
-			for &row in s_pattern {
-				global_to_local[row.zx()] = none;
+		if i > k {
+			assert!(mark[i] == k_);
+		} else {
+			let mut j = i;
+			while j < k && mark[j] != k_ {
+				mark[j] = k_;
+				reach[len] = I::truncate(j);
+				len += 1;
+				j = layout_parent(n, &column(j));
 			}
+			assert!(j <= k);
 		}
-		LbltInfo { transposition_count }
 	}
-}
-
-fn postorder_depth_first_search<'n, I: Index>(
-	post: &mut Array<'n, I>,
-	root: usize,
-	mut start_index: usize,
-	stack: &mut Array<'n, I>,
-	first_child: &mut Array<'n, MaybeIdx<'n, I>>,
-	next_child: &Array<'n, I::Signed>,
-) -> usize {
-	let mut top = 1usize;
-	let N = post.len();
-
-	stack[N.check(0)] = I::truncate(root);
-	while top != 0 {
-		let current_node = stack[N.check(top - 1)].zx();
-		let first_child = &mut first_child[N.check(current_node)];
-		let current_child = (*first_child).sx();
+	let reach = &mut reach[..len];
+	reach.sort_unstable();
+
+	// solve $L_{11} y = a_{12}$, and accumulate $a_{32} - L_{31} y$
+	let mut d = a_kk;
+	for &j in &*reach {
+		let j = j.zx();
+		let (p, dense, sparse) = column(j);
+		let L_jj = real(L_values[p]);
+
+		if !ldlt {
+			x[j] = mul_real(x[j], recip(L_jj));
+		}
+		let x_j = copy(x[j]);
+		for (l, i) in iter::zip(&L_values[p + 1..], dense.chain(sparse.iter().map(|i| i.zx()))) {
+			if i != k {
+				assert!(mark[i] == k_);
+				x[i] = x[i] - *l * x_j;
+			}
+		}
 
-		if let Some(current_child) = current_child.idx() {
-			stack[N.check(top)] = *current_child.truncate::<I>();
-			top += 1;
-			*first_child = MaybeIdx::new_checked(next_child[current_child], N);
+		let pos = layout_find(&column(j), k).unwrap();
+		if ldlt {
+			L_values[pos] = mul_real(conj(x_j), recip(L_jj));
+			d = d - abs2(x_j) / L_jj;
 		} else {
-			post[N.check(start_index)] = I::truncate(current_node);
-			start_index += 1;
-			top -= 1;
+			L_values[pos] = conj(x_j);
+			d = d - abs2(x_j);
 		}
+		x[j] = zero::<T>();
+	}
+
+	if ldlt {
+		if d == zero::<T::Real>() {
+			return Err(k);
+		}
+	} else if d <= zero::<T::Real>() {
+		return Err(k);
+	}
+
+	let (p, dense, sparse) = col_k;
+	let scale = if ldlt { recip(d) } else { recip(sqrt(d)) };
+	L_values[p] = if ldlt { from_real(d) } else { from_real(sqrt(d)) };
+	for (l, i) in iter::zip(&mut L_values[p + 1..], dense.chain(sparse.iter().map(|i| i.zx()))) {
+		x[i] = mul_real(x[i], scale);
+		*l = copy(x[i]);
+	}
+
+	let first = layout_parent(n, &column(k));
+	if ldlt {
+		ldlt_update_path(L_values, &column, x, first, -d)
+	} else {
+		llt_update_path(L_values, &column, x, first, true)
 	}
-	start_index
 }
 
-pub(crate) fn ghost_postorder<'n, I: Index>(post: &mut Array<'n, I>, etree: &Array<'n, MaybeIdx<'n, I>>, stack: &mut MemStack) {
-	let N = post.len();
-	let n = *N;
+fn row_del_scratch<T: ComplexField>(n: usize) -> StackReq {
+	temp_mat_scratch::<T>(n, 1)
+}
 
-	if n == 0 {
-		return;
+// replaces the row and column `k` of the factor by those of the identity, then updates the
+// trailing part of the factor with the old column
+//
+// with `ldlt`, the factor is a unit lower triangular matrix storing the diagonal factor in place of
+// its diagonal. on failure, returns the index of the column whose pivot is zero
+#[math]
+fn row_del<'a, I: Index, T: ComplexField>(
+	L_values: &mut [T],
+	column: impl Fn(usize) -> ColumnLayout<'a, I>,
+	n: usize,
+	k: usize,
+	ldlt: bool,
+	stack: &mut MemStack,
+) -> Result<(), usize> {
+	let (mut x, _) = temp_mat_zeroed::<T, _, _>(n, 1, stack);
+	let x = x.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap().as_slice_mut();
+
+	// the row `k` is found by scanning the previous columns
+	for j in 0..k {
+		if let Some(pos) = layout_find(&column(j), k) {
+			L_values[pos] = zero::<T>();
+		}
 	}
 
-	let (stack_, stack) = unsafe { stack.make_raw::<I>(n) };
-	let (first_child, stack) = unsafe { stack.make_raw::<I::Signed>(n) };
-	let (next_child, _) = unsafe { stack.make_raw::<I::Signed>(n) };
+	let (p, dense, sparse) = column(k);
+	let d = real(L_values[p]);
+	L_values[p] = one::<T>();
+	for (l, i) in iter::zip(&mut L_values[p + 1..], dense.chain(sparse.iter().map(|i| i.zx()))) {
+		x[i] = copy(*l);
+		*l = zero::<T>();
+	}
 
-	let stack = Array::from_mut(stack_, N);
-	let next_child = Array::from_mut(next_child, N);
-	let first_child = Array::from_mut(ghost::fill_none::<I>(first_child, N), N);
+	let first = layout_parent(n, &column(k));
+	if ldlt {
+		ldlt_update_path(L_values, &column, x, first, d)
+	} else {
+		llt_update_path(L_values, &column, x, first, false)
+	}
+}
 
-	for j in N.indices().rev() {
-		let parent = etree[j];
-		if let Some(parent) = parent.idx() {
-			let first = &mut first_child[parent.zx()];
-			next_child[j] = **first;
-			*first = MaybeIdx::from_index(j.truncate::<I>());
+// columns of a matrix $W$ such that the structure of $W W^H$ is that of the row and column `k` of
+// a matrix whose column `k` is `A_col`: one column per entry above the diagonal, and one for the
+// diagonal and the entries below it
+fn row_add_pattern<I: Index>(
+	k: usize,
+	A_col: SymbolicSparseColMatRef<'_, I>,
+	row_map: Option<&[I]>,
+) -> Result<(alloc::vec::Vec<I>, alloc::vec::Vec<I>), FaerError> {
+	let map = |i: usize| match row_map {
+		Some(row_map) => row_map[i].zx(),
+		None => i,
+	};
+	let nnz = A_col.row_idx_of_col(0).count();
+
+	let mut col_ptr = alloc::vec::Vec::<I>::new();
+	let mut row_idx = alloc::vec::Vec::<I>::new();
+	col_ptr.try_reserve_exact(nnz + 2).map_err(|_| FaerError::OutOfMemory)?;
+	row_idx.try_reserve_exact(2 * nnz + 1).map_err(|_| FaerError::OutOfMemory)?;
+
+	col_ptr.push(I::truncate(0));
+	for i in A_col.row_idx_of_col(0).map(map) {
+		if i < k {
+			row_idx.push(I::truncate(i));
+			row_idx.push(I::truncate(k));
+			col_ptr.push(I::truncate(row_idx.len()));
+		}
+	}
+	let start = row_idx.len();
+	row_idx.push(I::truncate(k));
+	row_idx.extend(A_col.row_idx_of_col(0).map(map).filter(|&i| i > k).map(I::truncate));
+	row_idx[start..].sort_unstable();
+	// the diagonal entry comes first, and the duplicate entries are merged
+	let mut len = start + 1;
+	for p in start + 1..row_idx.len() {
+		if row_idx[p] != row_idx[len - 1] {
+			row_idx[len] = row_idx[p];
+			len += 1;
 		}
 	}
+	row_idx.truncate(len);
+	col_ptr.push(I::truncate(row_idx.len()));
 
-	let mut start_index = 0usize;
-	for (root, &parent) in etree.as_ref().iter().enumerate() {
-		if parent.idx().is_none() {
-			start_index = postorder_depth_first_search(post, root, start_index, stack, first_child, next_child);
+	Ok((col_ptr, row_idx))
+}
+
+fn selected_inverse_scratch<I: Index, T: ComplexField>(n: usize) -> StackReq {
+	StackReq::all_of(&[temp_mat_scratch::<T>(n, 1), temp_mat_scratch::<T>(n, 1), StackReq::new::<I>(n)])
+}
+
+// computes the entries of $Z = A^{-1}$ on the structure of the cholesky factor of $A$, with the
+// takahashi equations $Z = D^{-1} L^{-1} + (I - L^H) Z$, from the last column to the first. `Z`
+// has the same layout as the factor
+//
+// the structure of each column of the factor below its diagonal is a clique in the structure of
+// the factor, so the entries of `Z` that it needs have already been computed
+#[math]
+fn selected_inverse<'a, I: Index, T: ComplexField>(
+	Z_values: &mut [T],
+	column: impl Fn(usize) -> ColumnLayout<'a, I>,
+	n: usize,
+	L_values: &[T],
+	ldlt: bool,
+	stack: &mut MemStack,
+) {
+	let none = I::Signed::truncate(NONE);
+	let (mut x, stack) = temp_mat_zeroed::<T, _, _>(n, 1, stack);
+	let x = x.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap().as_slice_mut();
+	let (mut y, stack) = temp_mat_zeroed::<T, _, _>(n, 1, stack);
+	let y = y.as_mat_mut().col_mut(0).try_as_col_major_mut().unwrap().as_slice_mut();
+	let (mark, _) = unsafe { stack.make_raw::<I::Signed>(n) };
+	mark.fill(none);
+
+	for j in (0..n).rev() {
+		let j_ = I::Signed::truncate(j);
+		let (p, dense, sparse) = column(j);
+		let rows = || dense.clone().chain(sparse.iter().map(|i| i.zx()));
+
+		// the factor is written as $L D L^H$ with a unit lower triangular $L$
+		let L_jj = real(L_values[p]);
+		let (scale, d) = if ldlt {
+			(one::<T::Real>(), copy(L_jj))
+		} else {
+			(recip(L_jj), L_jj * L_jj)
+		};
+		for (l, i) in iter::zip(&L_values[p + 1..], rows()) {
+			mark[i] = j_;
+			x[i] = mul_real(*l, scale);
+		}
+
+		// $Z_{ij} = -\sum_k Z_{ik} L_{kj}$, where only the lower triangular part of `Z` is stored
+		for k in rows() {
+			let (q, k_dense, k_sparse) = column(k);
+			let L_kj = copy(x[k]);
+			y[k] = y[k] - Z_values[q] * L_kj;
+			for (z, r) in iter::zip(&Z_values[q + 1..], k_dense.chain(k_sparse.iter().map(|i| i.zx()))) {
+				if mark[r] == j_ {
+					y[r] = y[r] - *z * L_kj;
+					y[k] = y[k] - conj(*z) * x[r];
+				}
+			}
+		}
+
+		let mut Z_jj = recip(d);
+		for (z, i) in iter::zip(&mut Z_values[p + 1..], rows()) {
+			*z = copy(y[i]);
+			Z_jj = Z_jj - real(conj(x[i]) * y[i]);
+			x[i] = zero::<T>();
+			y[i] = zero::<T>();
 		}
+		Z_values[p] = from_real(Z_jj);
 	}
 }
 
@@ -3818,64 +5393,396 @@ impl<I: Index> SymbolicCholesky<I> {
 		let (new_col_ptr, stack) = unsafe { stack.make_raw::<I>(n + 1) };
 		let (new_row_idx, stack) = unsafe { stack.make_raw::<I>(A_nnz) };
 
-		let out_side = match &self.raw {
-			SymbolicCholeskyRaw::Simplicial(_) => Side::Upper,
-			SymbolicCholeskyRaw::Supernodal(_) => Side::Lower,
+		let out_side = match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(_) => Side::Upper,
+			SymbolicCholeskyRaw::Supernodal(_) => Side::Lower,
+		};
+
+		let A = match self.perm() {
+			Some(perm) => {
+				let perm = perm.as_shape(N);
+				let A = permute_self_adjoint_to_unsorted(new_values, new_col_ptr, new_row_idx, A, perm, side, out_side, stack).into_const();
+
+				A
+			},
+			None => {
+				if side == out_side {
+					A
+				} else {
+					adjoint(new_values, new_col_ptr, new_row_idx, A, stack).into_const()
+				}
+			},
+		};
+
+		match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(this) => {
+				let regularization = LdltRegularization::default();
+				for (i, p) in perm_forward.iter_mut().enumerate() {
+					*p = I::truncate(i);
+				}
+				for (i, p) in perm_inverse.iter_mut().enumerate() {
+					*p = I::truncate(i);
+				}
+				let _ = simplicial::factorize_simplicial_numeric_ldlt(L_values, A.as_dyn().into_const(), regularization, this, stack);
+			},
+			SymbolicCholeskyRaw::Supernodal(this) => {
+				supernodal::factorize_supernodal_numeric_intranode_lblt(
+					L_values,
+					subdiag,
+					perm_forward,
+					perm_inverse,
+					A.as_dyn().into_const(),
+					this,
+					par,
+					stack,
+					params,
+				);
+			},
+		}
+
+		IntranodeLbltRef::<'out, I, T>::new(self, L_values, subdiag, unsafe {
+			PermRef::<'out, I>::new_unchecked(perm_forward, perm_inverse, n)
+		})
+	}
+
+	/// computes the required workspace size and alignment for a dense solve in place using an
+	/// $LL^H$, $LDL^H$ or intranodal $LBL^\top$ factorization
+	pub fn solve_in_place_scratch<T: ComplexField>(&self, rhs_ncols: usize, par: Par) -> StackReq {
+		temp_mat_scratch::<T>(self.nrows(), rhs_ncols).and(match self.raw() {
+			SymbolicCholeskyRaw::Simplicial(this) => this.solve_in_place_scratch::<T>(rhs_ncols),
+			SymbolicCholeskyRaw::Supernodal(this) => this.solve_in_place_scratch::<T>(rhs_ncols, par),
+		})
+	}
+
+	/// computes the symbolic structure of the cholesky factor of $A + W W^H$, keeping the same
+	/// fill-reducing permutation
+	///
+	/// returns `None` if the structure of the current factor already contains it, in which case
+	/// the factor can be updated in place. otherwise, its values can be moved to the new structure
+	/// with [`Self::remap_values`]
+	#[track_caller]
+	pub fn update_symbolic(&self, W: SymbolicSparseColMatRef<'_, I>) -> Result<Option<Self>, FaerError> {
+		assert!(W.nrows() == self.nrows());
+
+		// room for the new entries of the updated matrix, when it is factorized again
+		let mut A_nnz = self.A_nnz;
+		for j in 0..W.ncols() {
+			let nnz = W.row_idx_of_col(j).count();
+			A_nnz = A_nnz.saturating_add(nnz * nnz);
+		}
+		self.update_symbolic_imp(W, self.perm_inv.as_deref(), A_nnz)
+	}
+
+	/// computes the symbolic structure of the cholesky factor of a matrix $A'$ that matches $A$
+	/// outside of its row and column `k`, whose column `k` has the structure `A_col`, keeping the
+	/// same fill-reducing permutation
+	///
+	/// returns `None` if the structure of the current factor already contains it, in which case
+	/// the factor can be updated in place. otherwise, its values can be moved to the new structure
+	/// with [`Self::remap_values`]
+	#[track_caller]
+	pub fn row_add_symbolic(&self, k: usize, A_col: SymbolicSparseColMatRef<'_, I>) -> Result<Option<Self>, FaerError> {
+		let n = self.nrows();
+		assert!(all(k < n, A_col.nrows() == n, A_col.ncols() == 1));
+
+		let row_map = self.perm_inv.as_deref();
+		let k = match row_map {
+			Some(row_map) => row_map[k].zx(),
+			None => k,
+		};
+		let (col_ptr, row_idx) = row_add_pattern(k, A_col, row_map)?;
+		let W = SymbolicSparseColMatRef::new_checked(n, col_ptr.len() - 1, &col_ptr, None, &row_idx);
+
+		let A_nnz = self.A_nnz.saturating_add(2 * row_idx.len());
+		self.update_symbolic_imp(W, None, A_nnz)
+	}
+
+	fn update_symbolic_imp(&self, W: SymbolicSparseColMatRef<'_, I>, row_map: Option<&[I]>, A_nnz: usize) -> Result<Option<Self>, FaerError> {
+		let n = self.nrows();
+		let rank = W.ncols();
+
+		let req = match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(_) => simplicial::update_simplicial_symbolic_cholesky_scratch::<I>(n, rank),
+			SymbolicCholeskyRaw::Supernodal(_) => supernodal::update_supernodal_symbolic_cholesky_scratch::<I>(n, rank),
+		};
+		let mut mem = dyn_stack::MemBuffer::try_new(req).ok().ok_or(FaerError::OutOfMemory)?;
+		let stack = MemStack::new(&mut mem);
+
+		let raw = match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(this) => {
+				simplicial::update_simplicial_symbolic_cholesky_imp(this, W, row_map, stack)?.map(SymbolicCholeskyRaw::Simplicial)
+			},
+			SymbolicCholeskyRaw::Supernodal(this) => {
+				supernodal::update_supernodal_symbolic_cholesky_imp(this, W, row_map, stack, Default::default())?.map(SymbolicCholeskyRaw::Supernodal)
+			},
+		};
+		let Some(raw) = raw else {
+			return Ok(None);
+		};
+
+		let A_nnz = Ord::min(A_nnz, n.saturating_mul(n));
+		let perm_fwd = match &self.perm_fwd {
+			Some(perm_fwd) => Some(try_collect(perm_fwd.iter().copied())?),
+			None => None,
+		};
+		let perm_inv = match &self.perm_inv {
+			Some(perm_inv) => Some(try_collect(perm_inv.iter().copied())?),
+			None => None,
+		};
+
+		Ok(Some(Self {
+			raw,
+			perm_fwd,
+			perm_inv,
+			A_nnz,
+		}))
+	}
+
+	/// copies the numerical values of a cholesky factor with this structure to `dst_values`, for a
+	/// factor with the structure `dst`, computed by [`Self::update_symbolic`]
+	#[track_caller]
+	pub fn remap_values<T: ComplexField>(&self, values: &[T], dst: &Self, dst_values: &mut [T]) {
+		match (&self.raw, &dst.raw) {
+			(SymbolicCholeskyRaw::Simplicial(src), SymbolicCholeskyRaw::Simplicial(dst)) => {
+				simplicial::remap_simplicial_values(dst_values, dst, values, src)
+			},
+			(SymbolicCholeskyRaw::Supernodal(src), SymbolicCholeskyRaw::Supernodal(dst)) => {
+				supernodal::remap_supernodal_values(dst_values, dst, values, src)
+			},
+			_ => panic!("both factors must be simplicial, or both supernodal"),
+		}
+	}
+
+	/// computes the required workspace size and alignment for a rank update of an $LL^H$
+	/// factorization
+	#[inline]
+	pub fn rank_r_update_numeric_llt_scratch<T: ComplexField>(&self) -> StackReq {
+		rank_r_update_scratch::<I, T>(self.nrows())
+	}
+
+	/// computes the cholesky $LL^H$ factor of $A + W \operatorname{diag}(\alpha) W^H$ in place,
+	/// given the factor of $A$
+	///
+	/// the columns of $W$ with a negative weight are downdates, which fail if the resulting matrix
+	/// is not numerically positive definite. the values of the factor are then unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`Self::update_symbolic`] beforehand
+	#[track_caller]
+	pub fn rank_r_update_numeric_llt<T: ComplexField>(
+		&self,
+		L_values: &mut [T],
+		W: SparseColMatRef<'_, I, T>,
+		alpha: DiagRef<'_, T::Real>,
+		stack: &mut MemStack,
+	) -> Result<(), LltError> {
+		assert!(all(
+			L_values.len() == self.len_val(),
+			W.nrows() == self.nrows(),
+			alpha.column_vector().nrows() == W.ncols(),
+		));
+
+		let row_map = self.perm_inv.as_deref();
+		match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(this) => rank_r_update(L_values, |j| simplicial::column_layout(this, j), W, row_map, alpha, stack),
+			SymbolicCholeskyRaw::Supernodal(this) => rank_r_update(L_values, |j| supernodal::column_layout(this, j), W, row_map, alpha, stack),
+		}
+	}
+
+	/// computes the required workspace size and alignment for adding a row and column to a
+	/// cholesky factorization
+	#[inline]
+	pub fn row_add_numeric_scratch<T: ComplexField>(&self) -> StackReq {
+		row_add_scratch::<I, T>(self.nrows())
+	}
+
+	/// computes the cholesky $LL^H$ factor of the matrix $A'$ obtained by replacing the row and
+	/// column `k` of $A$ by `A_col` (and its adjoint) in place, given the factor of $A$
+	///
+	/// the row and column `k` of $A$ must be those of the identity, e.g., after a call to
+	/// [`Self::row_del_numeric_llt`]. this fails if $A'$ is not numerically positive definite,
+	/// in which case the values of the factor are unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`Self::row_add_symbolic`] beforehand
+	#[track_caller]
+	pub fn row_add_numeric_llt<T: ComplexField>(
+		&self,
+		L_values: &mut [T],
+		k: usize,
+		A_col: SparseColMatRef<'_, I, T>,
+		stack: &mut MemStack,
+	) -> Result<(), LltError> {
+		self.row_add_numeric(L_values, k, A_col, false, stack)
+			.map_err(|index| LltError::NonPositivePivot { index })
+	}
+
+	/// computes the cholesky $LDL^H$ factor of the matrix $A'$ obtained by replacing the row and
+	/// column `k` of $A$ by `A_col` (and its adjoint) in place, given the factor of $A$
+	///
+	/// the row and column `k` of $A$ must be those of the identity, e.g., after a call to
+	/// [`Self::row_del_numeric_ldlt`]. this fails if a zero pivot is encountered, in which case
+	/// the values of the factor are unspecified
+	///
+	/// # panics
+	/// the structure of the factor must contain that of the updated factor, which can be ensured
+	/// by calling [`Self::row_add_symbolic`] beforehand
+	#[track_caller]
+	pub fn row_add_numeric_ldlt<T: ComplexField>(
+		&self,
+		L_values: &mut [T],
+		k: usize,
+		A_col: SparseColMatRef<'_, I, T>,
+		stack: &mut MemStack,
+	) -> Result<(), LdltError> {
+		self.row_add_numeric(L_values, k, A_col, true, stack)
+			.map_err(|index| LdltError::ZeroPivot { index })
+	}
+
+	#[track_caller]
+	fn row_add_numeric<T: ComplexField>(
+		&self,
+		L_values: &mut [T],
+		k: usize,
+		A_col: SparseColMatRef<'_, I, T>,
+		ldlt: bool,
+		stack: &mut MemStack,
+	) -> Result<(), usize> {
+		let n = self.nrows();
+		assert!(all(L_values.len() == self.len_val(), k < n, A_col.nrows() == n, A_col.ncols() == 1));
+
+		let row_map = self.perm_inv.as_deref();
+		let k = match row_map {
+			Some(row_map) => row_map[k].zx(),
+			None => k,
+		};
+		match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(this) => row_add(L_values, |j| simplicial::column_layout(this, j), k, A_col, row_map, ldlt, stack),
+			SymbolicCholeskyRaw::Supernodal(this) => row_add(L_values, |j| supernodal::column_layout(this, j), k, A_col, row_map, ldlt, stack),
+		}
+	}
+
+	/// computes the required workspace size and alignment for deleting a row and column from a
+	/// cholesky factorization
+	#[inline]
+	pub fn row_del_numeric_scratch<T: ComplexField>(&self) -> StackReq {
+		row_del_scratch::<T>(self.nrows())
+	}
+
+	/// computes the cholesky $LL^H$ factor of the matrix obtained by replacing the row and column
+	/// `k` of $A$ by those of the identity in place, given the factor of $A$
+	///
+	/// the structure of the factor is left unchanged
+	#[track_caller]
+	pub fn row_del_numeric_llt<T: ComplexField>(&self, L_values: &mut [T], k: usize, stack: &mut MemStack) -> Result<(), LltError> {
+		self.row_del_numeric(L_values, k, false, stack)
+			.map_err(|index| LltError::NonPositivePivot { index })
+	}
+
+	/// computes the cholesky $LDL^H$ factor of the matrix obtained by replacing the row and column
+	/// `k` of $A$ by those of the identity in place, given the factor of $A$
+	///
+	/// the structure of the factor is left unchanged. this fails if a zero pivot is encountered,
+	/// in which case the values of the factor are unspecified
+	#[track_caller]
+	pub fn row_del_numeric_ldlt<T: ComplexField>(&self, L_values: &mut [T], k: usize, stack: &mut MemStack) -> Result<(), LdltError> {
+		self.row_del_numeric(L_values, k, true, stack)
+			.map_err(|index| LdltError::ZeroPivot { index })
+	}
+
+	#[track_caller]
+	fn row_del_numeric<T: ComplexField>(&self, L_values: &mut [T], k: usize, ldlt: bool, stack: &mut MemStack) -> Result<(), usize> {
+		let n = self.nrows();
+		assert!(all(L_values.len() == self.len_val(), k < n));
+
+		let k = match &self.perm_inv {
+			Some(perm_inv) => perm_inv[k].zx(),
+			None => k,
 		};
+		match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(this) => row_del(L_values, |j| simplicial::column_layout(this, j), n, k, ldlt, stack),
+			SymbolicCholeskyRaw::Supernodal(this) => row_del(L_values, |j| supernodal::column_layout(this, j), n, k, ldlt, stack),
+		}
+	}
 
-		let A = match self.perm() {
-			Some(perm) => {
-				let perm = perm.as_shape(N);
-				let A = permute_self_adjoint_to_unsorted(new_values, new_col_ptr, new_row_idx, A, perm, side, out_side, stack).into_const();
+	/// computes the values of the selected inverse, laid out like those of the factor
+	fn selected_inverse_values<T: ComplexField>(&self, L_values: &[T], ldlt: bool) -> Result<alloc::vec::Vec<T>, FaerError> {
+		let n = self.nrows();
+		let mut mem = dyn_stack::MemBuffer::try_new(selected_inverse_scratch::<I, T>(n))
+			.ok()
+			.ok_or(FaerError::OutOfMemory)?;
+		let stack = MemStack::new(&mut mem);
 
-				A
+		let mut Z_values = alloc::vec::Vec::new();
+		Z_values.try_reserve_exact(L_values.len()).map_err(|_| FaerError::OutOfMemory)?;
+		Z_values.resize(L_values.len(), zero::<T>());
+
+		match &self.raw {
+			SymbolicCholeskyRaw::Simplicial(this) => {
+				selected_inverse(&mut Z_values, |j| simplicial::column_layout(this, j), n, L_values, ldlt, stack)
 			},
-			None => {
-				if side == out_side {
-					A
-				} else {
-					adjoint(new_values, new_col_ptr, new_row_idx, A, stack).into_const()
-				}
+			SymbolicCholeskyRaw::Supernodal(this) => {
+				selected_inverse(&mut Z_values, |j| supernodal::column_layout(this, j), n, L_values, ldlt, stack)
 			},
-		};
+		}
+		Ok(Z_values)
+	}
+
+	fn selected_inverse_imp<T: ComplexField>(&self, L_values: &[T], ldlt: bool) -> Result<SparseColMat<I, T>, FaerError> {
+		let n = self.nrows();
+		let Z_values = self.selected_inverse_values(L_values, ldlt)?;
 
 		match &self.raw {
 			SymbolicCholeskyRaw::Simplicial(this) => {
-				let regularization = LdltRegularization::default();
-				for (i, p) in perm_forward.iter_mut().enumerate() {
-					*p = I::truncate(i);
-				}
-				for (i, p) in perm_inverse.iter_mut().enumerate() {
-					*p = I::truncate(i);
-				}
-				let _ = simplicial::factorize_simplicial_numeric_ldlt(L_values, A.as_dyn().into_const(), regularization, this, stack);
+				let col_ptr = try_collect(this.col_ptr().iter().copied())?;
+				let row_idx = try_collect(this.row_idx().iter().copied())?;
+				// SAFETY: this is the structure of the factor
+				let symbolic = unsafe { SymbolicSparseColMat::new_unchecked(n, n, col_ptr, None, row_idx) };
+				Ok(SparseColMat::new(symbolic, Z_values))
 			},
 			SymbolicCholeskyRaw::Supernodal(this) => {
-				supernodal::factorize_supernodal_numeric_intranode_lblt(
-					L_values,
-					subdiag,
-					perm_forward,
-					perm_inverse,
-					A.as_dyn().into_const(),
-					this,
-					par,
-					stack,
-					params,
-				);
+				// the lower triangular part of the supernodes, column by column
+				let mut col_ptr = try_zeroed::<I>(n + 1)?;
+				let mut row_idx = alloc::vec::Vec::<I>::new();
+				let mut values = alloc::vec::Vec::<T>::new();
+				for j in 0..n {
+					let (p, dense, sparse) = supernodal::column_layout(this, j);
+					let len = 1 + dense.len() + sparse.len();
+					row_idx.try_reserve(len).map_err(|_| FaerError::OutOfMemory)?;
+					values.try_reserve(len).map_err(|_| FaerError::OutOfMemory)?;
+
+					row_idx.push(I::truncate(j));
+					row_idx.extend(dense.map(I::truncate));
+					row_idx.extend_from_slice(sparse);
+					values.extend_from_slice(&Z_values[p..p + len]);
+					col_ptr[j + 1] = I::truncate(row_idx.len());
+				}
+				// SAFETY: this is the structure of the factor
+				let symbolic = unsafe { SymbolicSparseColMat::new_unchecked(n, n, col_ptr, None, row_idx) };
+				Ok(SparseColMat::new(symbolic, values))
 			},
 		}
-
-		IntranodeLbltRef::<'out, I, T>::new(self, L_values, subdiag, unsafe {
-			PermRef::<'out, I>::new_unchecked(perm_forward, perm_inverse, n)
-		})
 	}
 
-	/// computes the required workspace size and alignment for a dense solve in place using an
-	/// $LL^H$, $LDL^H$ or intranodal $LBL^\top$ factorization
-	pub fn solve_in_place_scratch<T: ComplexField>(&self, rhs_ncols: usize, par: Par) -> StackReq {
-		temp_mat_scratch::<T>(self.nrows(), rhs_ncols).and(match self.raw() {
-			SymbolicCholeskyRaw::Simplicial(this) => this.solve_in_place_scratch::<T>(rhs_ncols),
-			SymbolicCholeskyRaw::Supernodal(this) => this.solve_in_place_scratch::<T>(rhs_ncols, par),
+	fn inverse_diagonal_imp<T: ComplexField>(&self, L_values: &[T], ldlt: bool) -> Result<Col<T>, FaerError> {
+		let Z_values = self.selected_inverse_values(L_values, ldlt)?;
+		let diag = |j: usize| {
+			let p = match &self.raw {
+				SymbolicCholeskyRaw::Simplicial(this) => simplicial::column_layout(this, j).0,
+				SymbolicCholeskyRaw::Supernodal(this) => supernodal::column_layout(this, j).0,
+			};
+			copy(&Z_values[p])
+		};
+
+		// the $i$-th diagonal entry of $A^{-1}$ is the $\text{inv}_i$-th one of $(P A P^\top)^{-1}$
+		Ok(match self.perm() {
+			Some(perm) => {
+				let inv = perm.arrays().1;
+				Col::from_fn(self.nrows(), |i| diag(inv[i].zx()))
+			},
+			None => Col::from_fn(self.nrows(), diag),
 		})
 	}
 }
@@ -4121,6 +6028,25 @@ impl<'a, I: Index, T> LltRef<'a, I, T> {
 			}
 		}
 	}
+
+	/// computes the entries of $(P A P^\top)^{-1}$ on the structure of the lower triangular
+	/// cholesky $LL^H$ factor, where $P$ is the fill-reducing permutation
+	///
+	/// in particular, the diagonal of the result is the diagonal of $A^{-1}$, permuted by $P$
+	pub fn selected_inverse(&self) -> Result<SparseColMat<I, T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		self.symbolic.selected_inverse_imp(self.values, false)
+	}
+
+	/// computes the diagonal of $A^{-1}$, in the original order of the rows of $A$
+	pub fn inverse_diagonal(&self) -> Result<Col<T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		self.symbolic.inverse_diagonal_imp(self.values, false)
+	}
 }
 
 impl<'a, I: Index, T> LdltRef<'a, I, T> {
@@ -4185,6 +6111,25 @@ impl<'a, I: Index, T> LdltRef<'a, I, T> {
 			}
 		}
 	}
+
+	/// computes the entries of $(P A P^\top)^{-1}$ on the structure of the lower triangular
+	/// cholesky $LDL^H$ factor, where $P$ is the fill-reducing permutation
+	///
+	/// in particular, the diagonal of the result is the diagonal of $A^{-1}$, permuted by $P$
+	pub fn selected_inverse(&self) -> Result<SparseColMat<I, T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		self.symbolic.selected_inverse_imp(self.values, true)
+	}
+
+	/// computes the diagonal of $A^{-1}$, in the original order of the rows of $A$
+	pub fn inverse_diagonal(&self) -> Result<Col<T>, FaerError>
+	where
+		T: ComplexField,
+	{
+		self.symbolic.inverse_diagonal_imp(self.values, true)
+	}
 }
 
 /// computes the symbolic cholesky factorization of the matrix $A$, or returns an error if the
@@ -4260,6 +6205,10 @@ pub fn factorize_symbolic_cholesky<I: Index>(
 			perm_inv.as_mut().unwrap().copy_from_slice(inv);
 			None
 		},
+		SymmetricOrdering::NestedDissection(control) => {
+			nested_dissection::order(perm_fwd.as_mut().unwrap(), perm_inv.as_mut().unwrap(), A.as_dyn(), control)?;
+			None
+		},
 	};
 
 	let (new_col_ptr, stack) = unsafe { stack.make_raw::<I>(n + 1) };
@@ -4943,6 +6892,7 @@ pub(super) mod tests {
 						SymmetricOrdering::Amd,
 						CholeskySymbolicParams {
 							supernodal_flop_ratio_threshold,
+							supernodal_params: SymbolicSupernodalParams { par, ..Default::default() },
 							..Default::default()
 						},
 					)?;
@@ -4974,7 +6924,7 @@ pub(super) mod tests {
 								conj,
 								x.rb_mut(),
 								par,
-								MemStack::new(&mut MemBuffer::new(llt.solve_in_place_scratch::<c64>(k, Par::Seq))),
+								MemStack::new(&mut MemBuffer::new(llt.solve_in_place_scratch::<c64>(k, par))),
 							);
 
 							let target = rhs.as_ref();
@@ -5019,6 +6969,7 @@ pub(super) mod tests {
 						SymmetricOrdering::Amd,
 						CholeskySymbolicParams {
 							supernodal_flop_ratio_threshold,
+							supernodal_params: SymbolicSupernodalParams { par, ..Default::default() },
 							..Default::default()
 						},
 					)?;
@@ -5050,7 +7001,7 @@ pub(super) mod tests {
 								conj,
 								x.rb_mut(),
 								par,
-								MemStack::new(&mut MemBuffer::new(ldlt.solve_in_place_scratch::<c64>(k, Par::Seq))),
+								MemStack::new(&mut MemBuffer::new(ldlt.solve_in_place_scratch::<c64>(k, par))),
 							);
 
 							let target = rhs.as_ref();
@@ -5095,6 +7046,7 @@ pub(super) mod tests {
 						SymmetricOrdering::Amd,
 						CholeskySymbolicParams {
 							supernodal_flop_ratio_threshold,
+							supernodal_params: SymbolicSupernodalParams { par, ..Default::default() },
 							..Default::default()
 						},
 					)?;
@@ -5131,7 +7083,7 @@ pub(super) mod tests {
 								conj,
 								x.rb_mut(),
 								par,
-								MemStack::new(&mut MemBuffer::new(lblt.solve_in_place_scratch::<c64>(k, Par::Seq))),
+								MemStack::new(&mut MemBuffer::new(lblt.solve_in_place_scratch::<c64>(k, par))),
 							);
 
 							let target = rhs.as_ref();
@@ -5148,4 +7100,467 @@ pub(super) mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_supernodal_par() -> Result {
+		// 5-point stencil on a 2d grid, nested dissection exposes many independent subtrees
+		let nx = 40;
+		let n = nx * nx;
+		let mut triplets = vec![];
+		for y in 0..nx {
+			for x in 0..nx {
+				let i = x + nx * y;
+				triplets.push(Triplet::new(i, i, 4.5 + (i % 3) as f64));
+				if x + 1 < nx {
+					triplets.push(Triplet::new(i + 1, i, -1.0));
+				}
+				if y + 1 < nx {
+					triplets.push(Triplet::new(i + nx, i, -1.0));
+				}
+			}
+		}
+		let A_lower = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets)?;
+		let A_lower = A_lower.rb();
+
+		let symbolic = &factorize_symbolic_cholesky(
+			A_lower.symbolic(),
+			Side::Lower,
+			SymmetricOrdering::NestedDissection(default()),
+			CholeskySymbolicParams {
+				supernodal_flop_ratio_threshold: SupernodalThreshold::FORCE_SUPERNODAL,
+				supernodal_params: SymbolicSupernodalParams {
+					par: Par::rayon(4),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		)?;
+		assert!(matches!(symbolic.raw(), SymbolicCholeskyRaw::Supernodal(_)));
+		let SymbolicCholeskyRaw::Supernodal(supernodal) = symbolic.raw() else {
+			unreachable!()
+		};
+		assert!(supernodal.solve_schedule.n_bins() > 1);
+		assert!(supernodal.factorize_schedule.n_bins() > 1);
+
+		let rng = &mut StdRng::seed_from_u64(0);
+		let k = 3;
+		let rhs = CwiseMatDistribution {
+			nrows: n,
+			ncols: k,
+			dist: StandardNormal,
+		}
+		.rand::<Mat<f64>>(rng);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e2);
+
+		let mut L = vec![];
+		let mut X = vec![];
+		for par in [Par::Seq, Par::rayon(4)] {
+			let L_val = &mut *vec![0.0; symbolic.len_val()];
+			let llt = symbolic.factorize_numeric_llt(
+				L_val,
+				A_lower,
+				Side::Lower,
+				Default::default(),
+				par,
+				MemStack::new(&mut MemBuffer::new(
+					symbolic.factorize_numeric_llt_scratch::<f64>(par, Default::default()),
+				)),
+				Default::default(),
+			)?;
+			let mut x = rhs.clone();
+			llt.solve_in_place_with_conj(
+				Conj::No,
+				x.rb_mut(),
+				par,
+				MemStack::new(&mut MemBuffer::new(llt.solve_in_place_scratch::<f64>(k, par))),
+			);
+			L.push(ColRef::from_slice(L_val).to_owned());
+			X.push(x);
+
+			let L_val = &mut *vec![0.0; symbolic.len_val()];
+			let ldlt = symbolic.factorize_numeric_ldlt(
+				L_val,
+				A_lower,
+				Side::Lower,
+				Default::default(),
+				par,
+				MemStack::new(&mut MemBuffer::new(
+					symbolic.factorize_numeric_ldlt_scratch::<f64>(par, Default::default()),
+				)),
+				Default::default(),
+			)?;
+			let mut x = rhs.clone();
+			ldlt.solve_in_place_with_conj(
+				Conj::No,
+				x.rb_mut(),
+				par,
+				MemStack::new(&mut MemBuffer::new(ldlt.solve_in_place_scratch::<f64>(k, par))),
+			);
+			L.push(ColRef::from_slice(L_val).to_owned());
+			X.push(x);
+
+			let L_val = &mut *vec![0.0; symbolic.len_val()];
+			let subdiag = &mut *vec![0.0; n];
+			let fwd = &mut *vec![0usize; n];
+			let bwd = &mut *vec![0usize; n];
+			let lblt = symbolic.factorize_numeric_intranode_lblt(
+				L_val,
+				subdiag,
+				fwd,
+				bwd,
+				A_lower,
+				Side::Lower,
+				par,
+				MemStack::new(&mut MemBuffer::new(
+					symbolic.factorize_numeric_intranode_lblt_scratch::<f64>(par, Default::default()),
+				)),
+				Default::default(),
+			);
+			let mut x = rhs.clone();
+			lblt.solve_in_place_with_conj(
+				Conj::No,
+				x.rb_mut(),
+				par,
+				MemStack::new(&mut MemBuffer::new(lblt.solve_in_place_scratch::<f64>(k, par))),
+			);
+			L.push(ColRef::from_slice(L_val).to_owned());
+			X.push(x);
+		}
+
+		for i in 0..3 {
+			assert!(L[i].as_mat() ~ L[i + 3].as_mat());
+			assert!(X[i] ~ X[i + 3]);
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_rank_update() -> Result {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let nx = 12;
+		let n = nx * nx;
+
+		let mut triplets = vec![];
+		for y in 0..nx {
+			for x in 0..nx {
+				let i = x + nx * y;
+				triplets.push(Triplet::new(i, i, c64::new(8.0, 0.0)));
+				if x + 1 < nx {
+					triplets.push(Triplet::new(i + 1, i, c64::new(-1.0, 0.5)));
+				}
+				if y + 1 < nx {
+					triplets.push(Triplet::new(i + nx, i, c64::new(-1.0, -0.5)));
+				}
+			}
+		}
+		let A_lower = SparseColMat::<usize, c64>::try_new_from_triplets(n, n, &triplets)?;
+		let A_lower = A_lower.rb();
+		let mut A_full = A_lower.to_dense();
+		let adjoint = A_full.adjoint().to_owned();
+		A_full.copy_from_triangular_upper(adjoint);
+
+		// columns coupling distant nodes of the grid, which introduces new fill
+		let mut triplets = vec![];
+		for (j, rows) in [[0usize, 77, 143], [5, 60, 130], [13, 14, 100]].iter().enumerate() {
+			for &i in rows {
+				triplets.push(Triplet::new(i, j, c64::new(StandardNormal.sample(rng), StandardNormal.sample(rng))));
+			}
+		}
+		let W = SparseColMat::<usize, c64>::try_new_from_triplets(n, 3, &triplets)?;
+		let W = W.rb();
+		let alpha = Col::from_fn(3, |j| [1.0, 2.0, 0.5][j]);
+
+		let mut W_alpha = W.to_dense();
+		for j in 0..3 {
+			zip!(W_alpha.col_mut(j)).for_each(|unzip!(x)| *x *= c64::new(alpha[j], 0.0));
+		}
+		let A_updated = &A_full + &W_alpha * W.to_dense().adjoint();
+
+		let rhs = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+
+		for supernodal_flop_ratio_threshold in [SupernodalThreshold::FORCE_SIMPLICIAL, SupernodalThreshold::FORCE_SUPERNODAL] {
+			let symbolic = factorize_symbolic_cholesky(
+				A_lower.symbolic(),
+				Side::Lower,
+				SymmetricOrdering::Amd,
+				CholeskySymbolicParams {
+					supernodal_flop_ratio_threshold,
+					..Default::default()
+				},
+			)?;
+			let L_val = &mut *vec![zero::<c64>(); symbolic.len_val()];
+			symbolic.factorize_numeric_llt(
+				L_val,
+				A_lower,
+				Side::Lower,
+				Default::default(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(
+					symbolic.factorize_numeric_llt_scratch::<c64>(Par::Seq, Default::default()),
+				)),
+				Default::default(),
+			)?;
+
+			let updated = symbolic.update_symbolic(W.symbolic())?.unwrap();
+			assert!(updated.len_val() > symbolic.len_val());
+			let L_val_updated = &mut *vec![zero::<c64>(); updated.len_val()];
+			symbolic.remap_values(L_val, &updated, L_val_updated);
+
+			let stack = &mut MemBuffer::new(updated.rank_r_update_numeric_llt_scratch::<c64>());
+			let solve_stack = &mut MemBuffer::new(updated.solve_in_place_scratch::<c64>(2, Par::Seq));
+
+			updated.rank_r_update_numeric_llt(L_val_updated, W, alpha.as_diagonal(), MemStack::new(stack))?;
+			let mut x = rhs.clone();
+			LltRef::new(&updated, L_val_updated).solve_in_place_with_conj(Conj::No, x.rb_mut(), Par::Seq, MemStack::new(solve_stack));
+			assert!(&A_updated * &x ~ rhs);
+
+			// downdating brings back the original matrix, without changing the structure
+			assert!(updated.update_symbolic(W.symbolic())?.is_none());
+			let minus_alpha = -&alpha;
+			updated.rank_r_update_numeric_llt(L_val_updated, W, minus_alpha.as_diagonal(), MemStack::new(stack))?;
+			let mut x = rhs.clone();
+			LltRef::new(&updated, L_val_updated).solve_in_place_with_conj(Conj::No, x.rb_mut(), Par::Seq, MemStack::new(solve_stack));
+			assert!(&A_full * &x ~ rhs);
+
+			// downdating past positive definiteness fails
+			let too_much = Col::from_fn(3, |_| -1e6);
+			assert!(
+				updated
+					.rank_r_update_numeric_llt(L_val_updated, W, too_much.as_diagonal(), MemStack::new(stack))
+					.is_err()
+			);
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_row_add_del() -> Result {
+		let rng = &mut StdRng::seed_from_u64(0);
+		let nx = 12;
+		let n = nx * nx;
+		let k = 40;
+
+		let mut triplets = vec![];
+		for y in 0..nx {
+			for x in 0..nx {
+				let i = x + nx * y;
+				triplets.push(Triplet::new(i, i, c64::new(8.0, 0.0)));
+				if x + 1 < nx {
+					triplets.push(Triplet::new(i + 1, i, c64::new(-1.0, 0.5)));
+				}
+				if y + 1 < nx {
+					triplets.push(Triplet::new(i + nx, i, c64::new(-1.0, -0.5)));
+				}
+			}
+		}
+		let A_lower = SparseColMat::<usize, c64>::try_new_from_triplets(n, n, &triplets)?;
+		let A_lower = A_lower.rb();
+		let mut A_full = A_lower.to_dense();
+		let adjoint = A_full.adjoint().to_owned();
+		A_full.copy_from_triangular_upper(adjoint);
+
+		let mut A_deleted = A_full.clone();
+		A_deleted.row_mut(k).fill(zero());
+		A_deleted.col_mut(k).fill(zero());
+		A_deleted[(k, k)] = one();
+
+		let rhs = CwiseMatDistribution {
+			nrows: n,
+			ncols: 2,
+			dist: ComplexDistribution::new(StandardNormal, StandardNormal),
+		}
+		.rand::<Mat<c64>>(rng);
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+
+		for ldlt in [false, true] {
+			// the new column couples distant nodes of the grid, which introduces new fill. the
+			// $LDL^H$ factorization can also handle an indefinite matrix
+			let mut triplets = vec![];
+			for i in [3usize, k - 1, k + 1, 52, 130] {
+				triplets.push(Triplet::new(i, 0, c64::new(StandardNormal.sample(rng), StandardNormal.sample(rng))));
+			}
+			let diag = if ldlt { -10.0 } else { 10.0 };
+			triplets.push(Triplet::new(k, 0, c64::new(diag, 0.0)));
+			let A_col = SparseColMat::<usize, c64>::try_new_from_triplets(n, 1, &triplets)?;
+			let A_col = A_col.rb();
+
+			let mut A_added = A_deleted.clone();
+			for (i, v) in iter::zip(A_col.row_idx_of_col(0), A_col.val_of_col(0)) {
+				A_added[(i, k)] = *v;
+				A_added[(k, i)] = v.conj();
+			}
+
+			for supernodal_flop_ratio_threshold in [SupernodalThreshold::FORCE_SIMPLICIAL, SupernodalThreshold::FORCE_SUPERNODAL] {
+				let symbolic = factorize_symbolic_cholesky(
+					A_lower.symbolic(),
+					Side::Lower,
+					SymmetricOrdering::Amd,
+					CholeskySymbolicParams {
+						supernodal_flop_ratio_threshold,
+						..Default::default()
+					},
+				)?;
+				let L_val = &mut *vec![zero::<c64>(); symbolic.len_val()];
+				if ldlt {
+					symbolic.factorize_numeric_ldlt(
+						L_val,
+						A_lower,
+						Side::Lower,
+						Default::default(),
+						Par::Seq,
+						MemStack::new(&mut MemBuffer::new(
+							symbolic.factorize_numeric_ldlt_scratch::<c64>(Par::Seq, Default::default()),
+						)),
+						Default::default(),
+					)?;
+				} else {
+					symbolic.factorize_numeric_llt(
+						L_val,
+						A_lower,
+						Side::Lower,
+						Default::default(),
+						Par::Seq,
+						MemStack::new(&mut MemBuffer::new(
+							symbolic.factorize_numeric_llt_scratch::<c64>(Par::Seq, Default::default()),
+						)),
+						Default::default(),
+					)?;
+				}
+
+				let solve = |symbolic: &SymbolicCholesky<usize>, L_val: &[c64]| {
+					let mut x = rhs.clone();
+					let mem = &mut MemBuffer::new(symbolic.solve_in_place_scratch::<c64>(2, Par::Seq));
+					let stack = MemStack::new(mem);
+					if ldlt {
+						LdltRef::new(symbolic, L_val).solve_in_place_with_conj(Conj::No, x.rb_mut(), Par::Seq, stack);
+					} else {
+						LltRef::new(symbolic, L_val).solve_in_place_with_conj(Conj::No, x.rb_mut(), Par::Seq, stack);
+					}
+					x
+				};
+
+				let stack = &mut MemBuffer::new(symbolic.row_del_numeric_scratch::<c64>());
+				if ldlt {
+					symbolic.row_del_numeric_ldlt(L_val, k, MemStack::new(stack))?;
+				} else {
+					symbolic.row_del_numeric_llt(L_val, k, MemStack::new(stack))?;
+				}
+				assert!(&A_deleted * solve(&symbolic, L_val) ~ rhs);
+
+				let added = symbolic.row_add_symbolic(k, A_col.symbolic())?.unwrap();
+				assert!(added.len_val() > symbolic.len_val());
+				assert!(added.row_add_symbolic(k, A_col.symbolic())?.is_none());
+				let L_val_added = &mut *vec![zero::<c64>(); added.len_val()];
+				symbolic.remap_values(L_val, &added, L_val_added);
+
+				let stack = &mut MemBuffer::new(added.row_add_numeric_scratch::<c64>());
+				if ldlt {
+					added.row_add_numeric_ldlt(L_val_added, k, A_col, MemStack::new(stack))?;
+				} else {
+					added.row_add_numeric_llt(L_val_added, k, A_col, MemStack::new(stack))?;
+				}
+				assert!(&A_added * solve(&added, L_val_added) ~ rhs);
+			}
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_selected_inverse() -> Result {
+		let nx = 12;
+		let n = nx * nx;
+
+		let mut triplets = vec![];
+		for y in 0..nx {
+			for x in 0..nx {
+				let i = x + nx * y;
+				triplets.push(Triplet::new(i, i, c64::new(8.0, 0.0)));
+				if x + 1 < nx {
+					triplets.push(Triplet::new(i + 1, i, c64::new(-1.0, 0.5)));
+				}
+				if y + 1 < nx {
+					triplets.push(Triplet::new(i + nx, i, c64::new(-1.0, -0.5)));
+				}
+			}
+		}
+		let A_lower = SparseColMat::<usize, c64>::try_new_from_triplets(n, n, &triplets)?;
+		let A_lower = A_lower.rb();
+		let approx_eq = ApproxEq::eps() * 1e4;
+
+		for ldlt in [false, true] {
+			for supernodal_flop_ratio_threshold in [SupernodalThreshold::FORCE_SIMPLICIAL, SupernodalThreshold::FORCE_SUPERNODAL] {
+				let symbolic = factorize_symbolic_cholesky(
+					A_lower.symbolic(),
+					Side::Lower,
+					SymmetricOrdering::Amd,
+					CholeskySymbolicParams {
+						supernodal_flop_ratio_threshold,
+						..Default::default()
+					},
+				)?;
+				let L_val = &mut *vec![zero::<c64>(); symbolic.len_val()];
+				let mut inv = Mat::<c64>::identity(n, n);
+				let mem = &mut MemBuffer::new(symbolic.solve_in_place_scratch::<c64>(n, Par::Seq));
+				let Z = if ldlt {
+					symbolic.factorize_numeric_ldlt(
+						L_val,
+						A_lower,
+						Side::Lower,
+						Default::default(),
+						Par::Seq,
+						MemStack::new(&mut MemBuffer::new(
+							symbolic.factorize_numeric_ldlt_scratch::<c64>(Par::Seq, Default::default()),
+						)),
+						Default::default(),
+					)?;
+					let ldlt = LdltRef::new(&symbolic, L_val);
+					ldlt.solve_in_place_with_conj(Conj::No, inv.rb_mut(), Par::Seq, MemStack::new(mem));
+					ldlt.selected_inverse()?
+				} else {
+					symbolic.factorize_numeric_llt(
+						L_val,
+						A_lower,
+						Side::Lower,
+						Default::default(),
+						Par::Seq,
+						MemStack::new(&mut MemBuffer::new(
+							symbolic.factorize_numeric_llt_scratch::<c64>(Par::Seq, Default::default()),
+						)),
+						Default::default(),
+					)?;
+					let llt = LltRef::new(&symbolic, L_val);
+					llt.solve_in_place_with_conj(Conj::No, inv.rb_mut(), Par::Seq, MemStack::new(mem));
+					llt.selected_inverse()?
+				};
+
+				let fwd = symbolic.perm().unwrap().arrays().0;
+				for j in 0..n {
+					// the diagonal entry comes first, followed by the ones below it
+					assert!(Z.row_idx_of_col(j).next() == Some(j));
+					for (i, z) in iter::zip(Z.row_idx_of_col(j), Z.val_of_col(j)) {
+						assert!(i >= j);
+						assert!(*z ~ inv[(fwd[i], fwd[j])]);
+					}
+				}
+
+				// the diagonal of the inverse is returned in the original order
+				assert!((0..n).any(|i| fwd[i].zx() != i));
+				let diag = if ldlt {
+					LdltRef::new(&symbolic, L_val).inverse_diagonal()?
+				} else {
+					LltRef::new(&symbolic, L_val).inverse_diagonal()?
+				};
+				for i in 0..n {
+					assert!(diag[i] ~ inv[(i, i)]);
+				}
+			}
+		}
+		Ok(())
+	}
 }
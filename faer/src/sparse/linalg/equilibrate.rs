@@ -0,0 +1,96 @@
+//! row and column equilibration of a sparse matrix.
+//!
+//! see [`crate::linalg::equilibrate`] for the dense counterpart. the sparse $LU$ solver does this
+//! transparently when constructed with
+//! [`Lu::try_new_with_symbolic_equilibrated`](crate::sparse::linalg::solvers::Lu::try_new_with_symbolic_equilibrated)
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::linalg::equilibrate::{EquilibrationInfo, EquilibrationMethod, equilibrate_imp};
+
+/// computes the row and column scaling factors $R$ and $C$ that equilibrate $A$, so that the
+/// entries of $R A C$ are better balanced
+#[track_caller]
+#[math]
+pub fn equilibrate<I: Index, T: ComplexField>(
+	row_scale: ColMut<'_, T::Real>,
+	col_scale: ColMut<'_, T::Real>,
+	A: SparseColMatRef<'_, I, T>,
+	method: EquilibrationMethod<T::Real>,
+) -> EquilibrationInfo<T::Real> {
+	assert!(all(row_scale.nrows() == A.nrows(), col_scale.nrows() == A.ncols()));
+
+	equilibrate_imp(
+		row_scale,
+		col_scale,
+		|mut row_max, mut col_max, r, c| {
+			row_max.fill(zero());
+			for j in 0..A.ncols() {
+				let mut cmax = zero::<T::Real>();
+				for (i, a) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+					let v = abs(*a) * r[i] * c[j];
+					row_max[i] = max(row_max[i], v);
+					cmax = max(cmax, v);
+				}
+				col_max[j] = cmax;
+			}
+		},
+		method,
+	)
+}
+
+/// computes $R A C$ in place, given the scaling factors
+#[track_caller]
+#[math]
+pub fn scale_in_place<I: Index, T: ComplexField>(A: SparseColMatMut<'_, I, T>, row_scale: ColRef<'_, T::Real>, col_scale: ColRef<'_, T::Real>) {
+	assert!(all(row_scale.nrows() == A.nrows(), col_scale.nrows() == A.ncols()));
+
+	let mut A = A;
+	for j in 0..A.ncols() {
+		let cj = copy(col_scale[j]);
+		let (row_idx, val) = A.rb_mut().idx_val_of_col_mut(j);
+		for (i, a) in core::iter::zip(row_idx, val) {
+			*a = mul_real(*a, row_scale[i] * cj);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+
+	#[test]
+	fn test_sparse_equilibrate() {
+		let n = 20;
+		let mut triplets = vec![];
+		for i in 0..n {
+			let s = 10.0f64.powi(i as i32 % 7 - 3);
+			triplets.push(Triplet::new(i, i, 4.0 * s));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -s));
+				triplets.push(Triplet::new(i + 1, i, -1e-4 / s));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		for method in [EquilibrationMethod::PowerOfTwo, EquilibrationMethod::Ruiz(default())] {
+			let mut r = Col::zeros(n);
+			let mut c = Col::zeros(n);
+			let info = equilibrate(r.as_mut(), c.as_mut(), A.as_ref(), method);
+			assert!(!info.has_zero_line);
+
+			let mut S = A.clone();
+			scale_in_place(S.rb_mut(), r.as_ref(), c.as_ref());
+
+			let mut D = A.to_dense();
+			crate::linalg::equilibrate::scale_in_place(D.as_mut(), r.as_ref(), c.as_ref());
+			assert!(S.to_dense() == D);
+
+			for j in 0..n {
+				let col_max = D.col(j).iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+				assert!(col_max > 0.5 && col_max <= 2.0);
+			}
+		}
+	}
+}
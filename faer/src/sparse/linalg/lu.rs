@@ -12,7 +12,7 @@ use crate::internal_prelude_sp::*;
 use crate::sparse::utils;
 use linalg::lu::partial_pivoting::factor::PartialPivLuParams;
 use linalg_sp::cholesky::simplicial::EliminationTreeRef;
-use linalg_sp::{LuError, SupernodalThreshold, SymbolicSupernodalParams, colamd};
+use linalg_sp::{LuError, SupernodalThreshold, SymbolicSupernodalParams, colamd, nested_dissection};
 
 #[inline(never)]
 fn resize_vec<T: Clone>(v: &mut alloc::vec::Vec<T>, n: usize, exact: bool, reserve_only: bool, value: T) -> Result<(), FaerError> {
@@ -1466,9 +1466,24 @@ pub mod simplicial {
 	}
 }
 
+/// fill reducing column ordering to use for the $LU$ factorization
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ColumnOrdering {
+	/// column approximate minimum degree ordering, which targets the pattern of $A^\top A$.
+	/// default option
+	#[default]
+	Colamd,
+	/// multilevel nested dissection ordering of the pattern of $A + A^\top$, which is best suited
+	/// to matrices with a nearly symmetric pattern and a large diagonal, e.g. after a
+	/// [`matching`](crate::sparse::linalg::matching) row permutation
+	NestedDissection(nested_dissection::Control),
+}
+
 /// tuning parameters for the $LU$ symbolic factorization
 #[derive(Copy, Clone, Debug, Default)]
 pub struct LuSymbolicParams<'a> {
+	/// fill reducing column ordering
+	pub col_ordering: ColumnOrdering,
 	/// parameters for the fill reducing column permutation
 	pub colamd_params: colamd::Control,
 	/// threshold for selecting the supernodal factorization
@@ -1808,7 +1823,10 @@ pub fn factorize_symbolic_lu<I: Index>(A: SymbolicSparseColMatRef<'_, I>, params
 	let mut col_perm_inv = try_zeroed::<I>(n)?;
 	let mut min_row = try_zeroed::<I>(m)?;
 
-	linalg_sp::colamd::order(&mut col_perm_fwd, &mut col_perm_inv, A.as_dyn(), params.colamd_params, stack)?;
+	match params.col_ordering {
+		ColumnOrdering::Colamd => linalg_sp::colamd::order(&mut col_perm_fwd, &mut col_perm_inv, A.as_dyn(), params.colamd_params, stack)?,
+		ColumnOrdering::NestedDissection(control) => linalg_sp::nested_dissection::order(&mut col_perm_fwd, &mut col_perm_inv, A.as_dyn(), control)?,
+	}
 
 	let col_perm = PermRef::new_checked(&col_perm_fwd, &col_perm_inv, n).as_shape(N);
 
@@ -1907,9 +1925,11 @@ pub fn factorize_symbolic_lu<I: Index>(A: SymbolicSparseColMatRef<'_, I>, params
 mod tests {
 	use super::*;
 	use crate::assert;
+	use crate::linalg::equilibrate::EquilibrationMethod;
 	use crate::stats::prelude::*;
 	use dyn_stack::MemBuffer;
 	use linalg_sp::cholesky::tests::load_mtx;
+	use linalg_sp::equilibrate::{equilibrate, scale_in_place};
 	use matrix_market_rs::MtxData;
 	use std::path::PathBuf;
 
@@ -1985,6 +2005,7 @@ mod tests {
 			))),
 			linalg_sp::SymbolicSupernodalParams {
 				relax: Some(&[(4, 1.0), (16, 0.8), (48, 0.1), (usize::MAX, 0.05)]),
+				..Default::default()
 			},
 		)
 		.unwrap();
@@ -2107,6 +2128,128 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_numeric_lu_equilibrated() {
+		let n = 60usize;
+		let row_scale = |i: usize| 10.0f64.powi(i as i32 % 11 - 5);
+		let col_scale = |j: usize| 10.0f64.powi((3 * j) as i32 % 7 - 3);
+
+		// badly scaled unsymmetric matrix, whose scaled counterpart is diagonally dominant
+		let mut triplets = vec![];
+		for j in 0..n {
+			for i in [j.wrapping_sub(7), j.wrapping_sub(1), j, j + 1, j + 5] {
+				if i < n {
+					let v = if i == j { 6.0 } else { -1.0 };
+					triplets.push(Triplet::new(i, j, row_scale(i) * v * col_scale(j)));
+				}
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let dense = A.to_dense();
+		let rhs = Mat::<f64>::from_fn(n, 2, |i, j| ((i + 3 * j) as f64).sin() * row_scale(i));
+
+		for method in [EquilibrationMethod::PowerOfTwo, EquilibrationMethod::Ruiz(Default::default())] {
+			let mut r = Col::zeros(n);
+			let mut c = Col::zeros(n);
+			equilibrate(r.as_mut(), c.as_mut(), A.as_ref(), method);
+			let mut S = A.clone();
+			scale_in_place(S.rb_mut(), r.as_ref(), c.as_ref());
+
+			for supernodal_flop_ratio_threshold in [SupernodalThreshold::FORCE_SUPERNODAL, SupernodalThreshold::FORCE_SIMPLICIAL] {
+				let symbolic = factorize_symbolic_lu(
+					S.symbolic(),
+					LuSymbolicParams {
+						supernodal_flop_ratio_threshold,
+						..Default::default()
+					},
+				)
+				.unwrap();
+				let mut numeric = NumericLu::<usize, f64>::new();
+				let lu = symbolic
+					.factorize_numeric_lu(
+						&mut numeric,
+						S.rb(),
+						Par::Seq,
+						MemStack::new(&mut MemBuffer::new(
+							symbolic.factorize_numeric_lu_scratch::<f64>(Par::Seq, Default::default()),
+						)),
+						Default::default(),
+					)
+					.unwrap();
+
+				// $A x = b$ is solved through $(R A C) y = R b$, with $x = C y$
+				let mut x = Mat::from_fn(n, 2, |i, j| r[i] * rhs[(i, j)]);
+				lu.solve_in_place_with_conj(
+					crate::Conj::No,
+					x.as_mut(),
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(symbolic.solve_in_place_scratch::<f64>(2, Par::Seq))),
+				);
+				let x = Mat::from_fn(n, 2, |i, j| c[i] * x[(i, j)]);
+
+				// componentwise backward error
+				let res = &dense * &x - &rhs;
+				let w = Mat::from_fn(n, n, |i, j| dense[(i, j)].abs()) * Mat::from_fn(n, 2, |i, j| x[(i, j)].abs());
+				for j in 0..2 {
+					for i in 0..n {
+						assert!(res[(i, j)].abs() <= 1e-13 * (w[(i, j)] + rhs[(i, j)].abs()));
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_solver_lu_nested_dissection() {
+		type T = c64;
+
+		let (m, n, col_ptr, row_idx, val) =
+			load_mtx::<usize>(MtxData::from_file(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/sparse_lu/YAO.mtx")).unwrap());
+
+		let mut rng = StdRng::seed_from_u64(0);
+		let mut gen = || T::new(rng.gen::<f64>(), rng.gen::<f64>());
+
+		let val = val.iter().map(|_| gen()).collect::<alloc::vec::Vec<_>>();
+		let A = SparseColMatRef::<'_, usize, T>::new(SymbolicSparseColMatRef::new_checked(m, n, &col_ptr, None, &row_idx), &val);
+
+		let rhs = Mat::<T>::from_fn(m, 6, |_, _| gen());
+
+		for supernodal_flop_ratio_threshold in [SupernodalThreshold::FORCE_SUPERNODAL, SupernodalThreshold::FORCE_SIMPLICIAL] {
+			let symbolic = factorize_symbolic_lu(
+				A.symbolic(),
+				LuSymbolicParams {
+					col_ordering: ColumnOrdering::NestedDissection(Default::default()),
+					supernodal_flop_ratio_threshold,
+					..Default::default()
+				},
+			)
+			.unwrap();
+			let mut numeric = NumericLu::<usize, T>::new();
+			let lu = symbolic
+				.factorize_numeric_lu(
+					&mut numeric,
+					A,
+					Par::Seq,
+					MemStack::new(&mut MemBuffer::new(
+						symbolic.factorize_numeric_lu_scratch::<T>(Par::Seq, Default::default()),
+					)),
+					Default::default(),
+				)
+				.unwrap();
+
+			let mut x = rhs.clone();
+			lu.solve_in_place_with_conj(
+				crate::Conj::No,
+				x.as_mut(),
+				Par::Seq,
+				MemStack::new(&mut MemBuffer::new(symbolic.solve_in_place_scratch::<T>(rhs.ncols(), Par::Seq))),
+			);
+
+			let linsolve_diff = A * &x - &rhs;
+			assert!(linsolve_diff.norm_max() <= 1e-10);
+		}
+	}
+
 	#[test]
 	fn test_solver_lu_simplicial() {
 		type T = c64;
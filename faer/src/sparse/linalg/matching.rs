@@ -0,0 +1,741 @@
+//! maximum weight bipartite matching of the rows and columns of a sparse matrix.
+//!
+//! the matching is used to find a row permutation $P$ that moves large entries onto the diagonal
+//! of $PA$, similarly to the `mc64` routines. this makes the factorization less dependent on
+//! dynamic pivoting, and is typically applied before
+//! [`factorize_symbolic_lu`](crate::sparse::linalg::lu::factorize_symbolic_lu).
+//!
+//! when the objective is [`MatchingObjective::MaxProduct`], the matching is computed along with
+//! row and column scaling factors $D_r$ and $D_c$ such that every entry of $D_r A D_c$ has
+//! magnitude at most one, and the diagonal entries of $P D_r A D_c$ have magnitude exactly one.
+//!
+//! for self-adjoint matrices, [`symmetric_scaling`] turns these factors into a symmetric scaling,
+//! and [`symmetric_order`] computes a fill-reducing ordering that keeps the matched entries inside
+//! $2\times 2$ diagonal blocks, which can then be used as a custom ordering for the $LDL^H$ and
+//! $LBL^\top$ factorizations.
+//! [`Lblt::try_new_with_matching`](crate::sparse::linalg::solvers::Lblt::try_new_with_matching)
+//! combines these steps
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::sparse::linalg::amd;
+
+/// objective of the matching
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MatchingObjective {
+	/// maximizes the number of nonzero diagonal entries
+	MaxCardinality,
+	/// maximizes the sum of the magnitudes of the diagonal entries
+	MaxSum,
+	/// maximizes the product of the magnitudes of the diagonal entries. default option
+	#[default]
+	MaxProduct,
+}
+
+/// matching result
+#[derive(Copy, Clone, Debug)]
+pub struct MatchingInfo {
+	/// number of matched columns. equal to the dimension of the matrix if and only if it is
+	/// structurally nonsingular
+	pub matched: usize,
+
+	#[doc(hidden)]
+	pub non_exhaustive: NonExhaustive,
+}
+
+const UNSEEN: u8 = 0;
+const IN_HEAP: u8 = 1;
+const DONE: u8 = 2;
+
+fn sift_up<I: Index, R: PartialOrd>(heap: &mut [I], pos: &mut [I], key: &[R], mut idx: usize) {
+	let node = heap[idx];
+	while idx > 0 {
+		let parent = (idx - 1) / 2;
+		if key[heap[parent].zx()] <= key[node.zx()] {
+			break;
+		}
+		heap[idx] = heap[parent];
+		pos[heap[idx].zx()] = I::truncate(idx);
+		idx = parent;
+	}
+	heap[idx] = node;
+	pos[node.zx()] = I::truncate(idx);
+}
+
+fn sift_down<I: Index, R: PartialOrd>(heap: &mut [I], pos: &mut [I], key: &[R], len: usize, mut idx: usize) {
+	let node = heap[idx];
+	loop {
+		let mut child = 2 * idx + 1;
+		if child >= len {
+			break;
+		}
+		if child + 1 < len && key[heap[child + 1].zx()] < key[heap[child].zx()] {
+			child += 1;
+		}
+		if key[node.zx()] <= key[heap[child].zx()] {
+			break;
+		}
+		heap[idx] = heap[child];
+		pos[heap[idx].zx()] = I::truncate(idx);
+		idx = child;
+	}
+	heap[idx] = node;
+	pos[node.zx()] = I::truncate(idx);
+}
+
+/// computes the size and alignment of required workspace for computing a maximum weight matching
+pub fn max_weight_matching_scratch<I: Index, T: ComplexField>(dim: usize) -> StackReq {
+	let n_scratch = StackReq::new::<I>(dim);
+	StackReq::all_of(&[
+		// col_max, row_dual, col_dual, dist
+		temp_mat_scratch::<T::Real>(dim, 4),
+		// mate_row, mate_col, pred, heap, heap_pos, visited
+		n_scratch,
+		n_scratch,
+		n_scratch,
+		n_scratch,
+		n_scratch,
+		n_scratch,
+		// state
+		StackReq::new::<u8>(dim),
+	])
+}
+
+/// computes a row permutation $P$ that maximizes the given objective over the diagonal of $PA$,
+/// where $A$ is a square matrix, using successive shortest augmenting paths
+///
+/// on output, `perm[j]` is the row of $A$ that is moved to the $j$-th row of $PA$, and `perm_inv`
+/// is its inverse. rows that could not be matched are assigned to the remaining positions in
+/// increasing order
+///
+/// if the objective is [`MatchingObjective::MaxProduct`], `row_scale` and `col_scale` are filled
+/// with the diagonals of $D_r$ and $D_c$. otherwise they're filled with ones
+///
+/// # note
+/// explicit zeros are ignored. allows unsorted matrices
+#[track_caller]
+#[math]
+pub fn max_weight_matching<I: Index, T: ComplexField>(
+	perm: &mut [I],
+	perm_inv: &mut [I],
+	row_scale: Option<ColMut<'_, T::Real>>,
+	col_scale: Option<ColMut<'_, T::Real>>,
+	A: SparseColMatRef<'_, I, T>,
+	objective: MatchingObjective,
+	stack: &mut MemStack,
+) -> MatchingInfo {
+	let n = A.ncols();
+	assert!(all(A.nrows() == n, perm.len() == n, perm_inv.len() == n));
+	if let Some(row_scale) = &row_scale {
+		assert!(row_scale.nrows() == n);
+	}
+	if let Some(col_scale) = &col_scale {
+		assert!(col_scale.nrows() == n);
+	}
+
+	let I = I::truncate;
+	let none = I(NONE);
+	let multiplicative = objective == MatchingObjective::MaxProduct;
+
+	// costs are either additive, or multiplicative in the max product case, which avoids
+	// computing logarithms. in both cases, `e` is the identity and all the costs are `>= e`
+	let e = if multiplicative { one::<T::Real>() } else { zero::<T::Real>() };
+	let combine = |a: &T::Real, b: &T::Real| if multiplicative { *a * *b } else { *a + *b };
+	let split = |a: &T::Real, b: &T::Real| if multiplicative { *a / *b } else { *a - *b };
+
+	let (mut work, stack) = temp_mat_zeroed::<T::Real, _, _>(n, 4, stack);
+	let work = work.as_mat_mut();
+	let (col_max, work) = work.split_at_col_mut(1);
+	let (row_dual, work) = work.split_at_col_mut(1);
+	let (col_dual, dist) = work.split_at_col_mut(1);
+	let mut col_max = col_max.col_mut(0);
+	let mut row_dual = row_dual.col_mut(0);
+	let mut col_dual = col_dual.col_mut(0);
+	let dist = dist.col_mut(0).try_as_col_major_mut().unwrap().as_slice_mut();
+
+	let (mut mate_row, stack) = stack.collect(repeat_n!(none, n));
+	let (mut mate_col, stack) = stack.collect(repeat_n!(none, n));
+	let (mut pred, stack) = stack.collect(repeat_n!(none, n));
+	let (mut heap, stack) = stack.collect(repeat_n!(none, n));
+	let (mut heap_pos, stack) = stack.collect(repeat_n!(none, n));
+	let (mut visited, stack) = stack.collect(repeat_n!(none, n));
+	let (mut state, _) = stack.collect(repeat_n!(UNSEEN, n));
+	let mate_row = &mut *mate_row;
+	let mate_col = &mut *mate_col;
+	let pred = &mut *pred;
+	let heap = &mut *heap;
+	let heap_pos = &mut *heap_pos;
+	let visited = &mut *visited;
+	let state = &mut *state;
+
+	for j in 0..n {
+		let mut m = zero::<T::Real>();
+		for a in A.val_of_col(j) {
+			m = max(m, abs(*a));
+		}
+		col_max[j] = m;
+	}
+
+	let cost = |j: usize, a: &T| -> Option<T::Real> {
+		let x = abs(*a);
+		if x == zero() {
+			return None;
+		}
+		Some(match objective {
+			MatchingObjective::MaxCardinality => zero(),
+			MatchingObjective::MaxSum => col_max[j] - x,
+			MatchingObjective::MaxProduct => col_max[j] / x,
+		})
+	};
+
+	// initial duals: the column duals are `e` since the smallest cost of each column is `e`, and
+	// each row dual is the smallest cost of its row
+	col_dual.fill(copy(e));
+	row_dual.fill(infinity());
+	for j in 0..n {
+		for (i, a) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+			if let Some(c) = cost(j, a) {
+				let i = i.zx();
+				row_dual[i] = min(row_dual[i], c);
+			}
+		}
+	}
+	for i in 0..n {
+		if row_dual[i] == infinity() {
+			row_dual[i] = copy(e);
+		}
+	}
+
+	// cheap initial matching using the edges with zero reduced cost
+	let mut matched = 0usize;
+	for j in 0..n {
+		for (i, a) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+			let i = i.zx();
+			if mate_row[i] != none {
+				continue;
+			}
+			if let Some(c) = cost(j, a) {
+				if split(&c, &combine(&row_dual[i], &col_dual[j])) <= e {
+					mate_row[i] = I(j);
+					mate_col[j] = I(i);
+					matched += 1;
+					break;
+				}
+			}
+		}
+	}
+
+	for j0 in 0..n {
+		if mate_col[j0] != none {
+			continue;
+		}
+
+		let mut heap_len = 0usize;
+		let mut visited_len = 0usize;
+		let mut free_row = None;
+
+		let mut j = j0;
+		let mut dj = copy(e);
+		loop {
+			// relax the edges of column j, which is reached at distance dj
+			for (i, a) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+				let i = i.zx();
+				if state[i] == DONE {
+					continue;
+				}
+				let Some(c) = cost(j, a) else {
+					continue;
+				};
+				let reduced = max(split(&c, &combine(&row_dual[i], &col_dual[j])), e);
+				let d = combine(&dj, &reduced);
+
+				if state[i] == UNSEEN {
+					state[i] = IN_HEAP;
+					visited[visited_len] = I(i);
+					visited_len += 1;
+					dist[i] = d;
+					pred[i] = I(j);
+					heap[heap_len] = I(i);
+					heap_len += 1;
+					sift_up(heap, heap_pos, dist, heap_len - 1);
+				} else if d < dist[i] {
+					dist[i] = d;
+					pred[i] = I(j);
+					sift_up(heap, heap_pos, dist, heap_pos[i].zx());
+				}
+			}
+
+			if heap_len == 0 {
+				break;
+			}
+
+			let i = heap[0].zx();
+			heap_len -= 1;
+			if heap_len > 0 {
+				heap[0] = heap[heap_len];
+				sift_down(heap, heap_pos, dist, heap_len, 0);
+			}
+			state[i] = DONE;
+
+			if mate_row[i] == none {
+				free_row = Some(i);
+				break;
+			}
+			j = mate_row[i].zx();
+			dj = copy(dist[i]);
+		}
+
+		if let Some(free_row) = free_row {
+			let d_star = copy(dist[free_row]);
+
+			// update the duals so that the reduced costs stay nonnegative, and vanish along the
+			// augmenting path
+			col_dual[j0] = combine(&col_dual[j0], &d_star);
+			for &k in &visited[..visited_len] {
+				let k = k.zx();
+				if state[k] != DONE {
+					continue;
+				}
+				let delta = split(&d_star, &dist[k]);
+				row_dual[k] = split(&row_dual[k], &delta);
+				if mate_row[k] != none {
+					let jk = mate_row[k].zx();
+					col_dual[jk] = combine(&col_dual[jk], &delta);
+				}
+			}
+
+			let mut i = free_row;
+			loop {
+				let j = pred[i].zx();
+				let next = mate_col[j];
+				mate_col[j] = I(i);
+				mate_row[i] = I(j);
+				if j == j0 {
+					break;
+				}
+				i = next.zx();
+			}
+			matched += 1;
+		}
+
+		for &k in &visited[..visited_len] {
+			state[k.zx()] = UNSEEN;
+		}
+	}
+
+	let mut next_free_row = 0usize;
+	for j in 0..n {
+		if mate_col[j] == none {
+			while mate_row[next_free_row] != none {
+				next_free_row += 1;
+			}
+			mate_row[next_free_row] = I(j);
+			mate_col[j] = I(next_free_row);
+		}
+		perm[j] = mate_col[j];
+		perm_inv[mate_col[j].zx()] = I(j);
+	}
+
+	// since the reduced cost of (i, j) is col_max[j] / (|a_ij| * u_i * v_j) >= 1, with equality
+	// on the matching, the scaling factors are u_i and v_j / col_max[j]
+	if let Some(mut row_scale) = row_scale {
+		if multiplicative {
+			row_scale.copy_from(&row_dual);
+		} else {
+			row_scale.fill(one());
+		}
+	}
+	if let Some(mut col_scale) = col_scale {
+		for j in 0..n {
+			col_scale[j] = if multiplicative && col_max[j] > zero() {
+				col_dual[j] / col_max[j]
+			} else {
+				one()
+			};
+		}
+	}
+
+	MatchingInfo {
+		matched,
+		non_exhaustive: NonExhaustive(()),
+	}
+}
+
+/// computes the symmetric scaling $D = (D_r D_c)^{1/2}$ from the scaling factors returned by
+/// [`max_weight_matching`] for a self-adjoint matrix $A$, so that every entry of $DAD$ has
+/// magnitude at most one
+#[track_caller]
+#[math]
+pub fn symmetric_scaling<T: RealField>(scale: ColMut<'_, T>, row_scale: ColRef<'_, T>, col_scale: ColRef<'_, T>) {
+	assert!(all(scale.nrows() == row_scale.nrows(), scale.nrows() == col_scale.nrows()));
+	z!(scale, row_scale, col_scale).for_each(|uz!(s, r, c)| *s = sqrt(*r * *c));
+}
+
+/// computes the size and alignment of required workspace for computing a symmetric matching
+/// ordering
+pub fn symmetric_order_scratch<I: Index>(dim: usize, A_nnz: usize) -> StackReq {
+	let n_scratch = StackReq::new::<I>(dim);
+	StackReq::all_of(&[
+		// group, members, compressed col_ptr, compressed row_idx
+		n_scratch,
+		StackReq::new::<I>(dim.saturating_mul(2)),
+		StackReq::new::<I>(dim + 1),
+		StackReq::new::<I>(A_nnz),
+		// compressed perm, perm_inv
+		n_scratch,
+		n_scratch,
+		amd::order_maybe_unsorted_scratch::<I>(dim, A_nnz),
+	])
+}
+
+/// computes a fill-reducing ordering of the self-adjoint matrix with the sparsity pattern of $A$
+/// (both triangular halves must be stored), in which the rows and columns that are matched by
+/// `matching` stay adjacent, so that the matched entries fall into $2 \times 2$ diagonal blocks
+///
+/// the cycles of the matching permutation are split into pairs, and an odd cycle leaves a single
+/// $1 \times 1$ block. the graph with each pair collapsed into a single node is then ordered with
+/// the amd ordering, and the pairs are expanded again
+///
+/// on output, `pivot_pairs[k]` is `true` if the columns `k` and `k + 1` of the permuted matrix
+/// form a $2 \times 2$ block. the supernodal factorizations only pivot within a supernode, so it
+/// should be passed to
+/// [`SymbolicSupernodalParams::pivot_pairs`](crate::sparse::linalg::SymbolicSupernodalParams::pivot_pairs)
+/// to keep the blocks from being split across two supernodes
+///
+/// returns the number of $2 \times 2$ blocks
+pub fn symmetric_order<I: Index>(
+	perm: &mut [I],
+	perm_inv: &mut [I],
+	pivot_pairs: &mut [bool],
+	matching: PermRef<'_, I>,
+	A: SymbolicSparseColMatRef<'_, I>,
+	control: amd::Control,
+	stack: &mut MemStack,
+) -> Result<usize, FaerError> {
+	let n = A.ncols();
+	assert!(all(
+		A.nrows() == n,
+		matching.len() == n,
+		perm.len() == n,
+		perm_inv.len() == n,
+		pivot_pairs.len() == n,
+	));
+
+	let I = I::truncate;
+	let none = I(NONE);
+	let (matching, _) = matching.arrays();
+
+	// assign each vertex to a group of one or two vertices
+	let (mut group, stack) = stack.collect(repeat_n!(none, n));
+	let (mut members, stack) = stack.collect(repeat_n!(none, 2 * n));
+	let group = &mut *group;
+	let members = &mut *members;
+
+	let mut n_groups = 0usize;
+	let mut n_pairs = 0usize;
+	for start in 0..n {
+		if group[start] != none {
+			continue;
+		}
+		let mut j = start;
+		loop {
+			let next = matching[j].zx();
+			if next == j || group[next] != none {
+				group[j] = I(n_groups);
+				members[2 * n_groups] = I(j);
+				n_groups += 1;
+				break;
+			}
+			group[j] = I(n_groups);
+			group[next] = I(n_groups);
+			members[2 * n_groups] = I(j);
+			members[2 * n_groups + 1] = I(next);
+			n_groups += 1;
+			n_pairs += 1;
+
+			j = matching[next].zx();
+			if group[j] != none {
+				break;
+			}
+		}
+	}
+
+	// pattern of the compressed graph, possibly with duplicate entries
+	let nnz = A.compute_nnz();
+	let (mut col_ptr, stack) = stack.collect(repeat_n!(I(0), n_groups + 1));
+	let (row_idx, stack) = unsafe { stack.make_raw::<I>(nnz) };
+	let col_ptr = &mut *col_ptr;
+
+	let mut pos = 0usize;
+	for g in 0..n_groups {
+		col_ptr[g] = I(pos);
+		for &j in &members[2 * g..2 * g + 2] {
+			if j == none {
+				continue;
+			}
+			for i in A.row_idx_of_col(j.zx()) {
+				let gi = group[i.zx()];
+				if gi != I(g) {
+					row_idx[pos] = gi;
+					pos += 1;
+				}
+			}
+		}
+	}
+	col_ptr[n_groups] = I(pos);
+	let compressed = unsafe { SymbolicSparseColMatRef::new_unchecked(n_groups, n_groups, &*col_ptr, None, &row_idx[..pos]) };
+
+	let (mut gperm, stack) = stack.collect(repeat_n!(I(0), n_groups));
+	let (mut gperm_inv, stack) = stack.collect(repeat_n!(I(0), n_groups));
+	let gperm = &mut *gperm;
+	amd::order_maybe_unsorted(gperm, &mut gperm_inv, compressed, control, stack)?;
+
+	let mut k = 0usize;
+	for &g in &*gperm {
+		let members = &members[2 * g.zx()..2 * g.zx() + 2];
+		for &j in members {
+			if j == none {
+				continue;
+			}
+			perm[k] = j;
+			perm_inv[j.zx()] = I(k);
+			pivot_pairs[k] = false;
+			k += 1;
+		}
+		if members[1] != none {
+			pivot_pairs[k - 2] = true;
+		}
+	}
+
+	Ok(n_pairs)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::solvers::Solve;
+	use crate::sparse::linalg::cholesky::{CholeskySymbolicParams, SymmetricOrdering, factorize_symbolic_cholesky};
+	use crate::sparse::linalg::{SupernodalThreshold, SymbolicSupernodalParams};
+	use crate::utils::approx::*;
+	use dyn_stack::MemBuffer;
+
+	fn matching(A: SparseColMatRef<'_, usize, f64>, objective: MatchingObjective) -> (MatchingInfo, Perm<usize>, Col<f64>, Col<f64>) {
+		let n = A.nrows();
+		let mut perm = alloc::vec![0usize; n];
+		let mut perm_inv = alloc::vec![0usize; n];
+		let mut r = Col::<f64>::zeros(n);
+		let mut c = Col::<f64>::zeros(n);
+
+		let info = max_weight_matching(
+			&mut perm,
+			&mut perm_inv,
+			Some(r.as_mut()),
+			Some(c.as_mut()),
+			A,
+			objective,
+			MemStack::new(&mut MemBuffer::new(max_weight_matching_scratch::<usize, f64>(n))),
+		);
+		(info, Perm::new_checked(perm.into_boxed_slice(), perm_inv.into_boxed_slice(), n), r, c)
+	}
+
+	#[test]
+	fn test_max_product_matching() {
+		let n = 40;
+		// tiny diagonal entries, with one large entry per column at a permuted position
+		let sigma = (0..n).map(|j| (7 * j + 3) % n).collect::<alloc::vec::Vec<_>>();
+		let mut triplets = alloc::vec![];
+		for (j, &sj) in sigma.iter().enumerate() {
+			triplets.push(Triplet::new(j, j, 1e-12));
+			triplets.push(Triplet::new(sj, j, 10.0f64.powi((j % 5) as i32)));
+			triplets.push(Triplet::new((j + 1) % n, j, 0.5));
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		let (info, P, r, c) = matching(A.as_ref(), MatchingObjective::MaxProduct);
+		assert!(info.matched == n);
+		assert!(P.as_ref().arrays().0 == &*sigma);
+
+		let dense = A.to_dense();
+		let (perm, _) = P.as_ref().arrays();
+		for j in 0..n {
+			for i in 0..n {
+				assert!((dense[(i, j)] * r[i] * c[j]).abs() <= 1.0 + 1e-12);
+			}
+			assert!(((dense[(perm[j], j)] * r[perm[j]] * c[j]).abs() - 1.0).abs() <= 1e-12);
+		}
+
+		// solve A x = b through the permuted and scaled matrix P D_r A D_c
+		let mut triplets = alloc::vec![];
+		for j in 0..n {
+			for (i, v) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+				triplets.push(Triplet::new(P.as_ref().arrays().1[i], j, r[i] * v * c[j]));
+			}
+		}
+		let B = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let B_dense = B.to_dense();
+		for j in 0..n {
+			assert!((B_dense[(j, j)].abs() - 1.0).abs() <= 1e-12);
+		}
+
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).sin());
+		let rb = Col::<f64>::from_fn(n, |i| r[perm[i]] * b[perm[i]]);
+		let y = B.sp_lu().unwrap().solve(&rb);
+		let x = Col::<f64>::from_fn(n, |j| c[j] * y[j]);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+		assert!((&A * &x).as_mat() ~ b.as_mat());
+	}
+
+	#[test]
+	fn test_matching_objectives() {
+		let triplets = [
+			Triplet::new(0, 0, 1.0),
+			Triplet::new(1, 0, 3.0),
+			Triplet::new(0, 1, 3.0),
+			Triplet::new(1, 1, 4.0),
+			Triplet::new(2, 1, 5.0),
+			Triplet::new(1, 2, 0.5),
+			Triplet::new(2, 2, 1.0),
+		];
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(3, 3, &triplets).unwrap();
+
+		// max sum: 3 + 3 + 1 = 7 > 1 + 5 + 0.5, 1 + 4 + 1
+		let (info, P, _, _) = matching(A.as_ref(), MatchingObjective::MaxSum);
+		assert!(info.matched == 3);
+		assert!(P.as_ref().arrays().0 == &[1, 0, 2]);
+
+		// max product: 3 * 3 * 1 = 9 > 1 * 5 * 0.5, 1 * 4 * 1
+		let (info, P, r, c) = matching(A.as_ref(), MatchingObjective::MaxProduct);
+		assert!(info.matched == 3);
+		assert!(P.as_ref().arrays().0 == &[1, 0, 2]);
+		assert!(r.norm_max() > 0.0 && c.norm_max() > 0.0);
+
+		let (info, _, r, c) = matching(A.as_ref(), MatchingObjective::MaxCardinality);
+		assert!(info.matched == 3);
+		assert!(r == Col::<f64>::ones(3));
+		assert!(c == Col::<f64>::ones(3));
+
+		// structurally singular matrix, with an explicit zero
+		let triplets = [
+			Triplet::new(0, 0, 1.0),
+			Triplet::new(0, 1, 2.0),
+			Triplet::new(1, 2, 1.0),
+			Triplet::new(2, 0, 0.0),
+		];
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(3, 3, &triplets).unwrap();
+		let (info, P, _, _) = matching(A.as_ref(), MatchingObjective::MaxProduct);
+		assert!(info.matched == 2);
+		assert!(P.as_ref().arrays().0 == &[0, 2, 1]);
+	}
+
+	#[test]
+	fn test_symmetric_order() {
+		let n = 40;
+		// self-adjoint matrix with a small diagonal, where each row has a single large entry
+		let partner = |j: usize| j ^ 1;
+		let mut triplets = alloc::vec![];
+		for j in 0..n {
+			triplets.push(Triplet::new(j, j, 1e-3));
+			triplets.push(Triplet::new(partner(j), j, 10.0 * (1.0 + j as f64 / 2.0).floor()));
+			let k = (j + 5) % n;
+			triplets.push(Triplet::new(k, j, 1.0));
+			triplets.push(Triplet::new(j, k, 1.0));
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+
+		let (info, P, r, c) = matching(A.as_ref(), MatchingObjective::MaxProduct);
+		assert!(info.matched == n);
+
+		let mut s = Col::<f64>::zeros(n);
+		symmetric_scaling(s.as_mut(), r.as_ref(), c.as_ref());
+		let dense = A.to_dense();
+		for j in 0..n {
+			for i in 0..n {
+				assert!((s[i] * dense[(i, j)] * s[j]).abs() <= 1.0 + 1e-12);
+			}
+		}
+
+		let mut order = alloc::vec![0usize; n];
+		let mut order_inv = alloc::vec![0usize; n];
+		let mut pivot_pairs = alloc::vec![false; n];
+		let n_pairs = symmetric_order(
+			&mut order,
+			&mut order_inv,
+			&mut pivot_pairs,
+			P.as_ref(),
+			A.symbolic(),
+			default(),
+			MemStack::new(&mut MemBuffer::new(symmetric_order_scratch::<usize>(n, A.compute_nnz()))),
+		)
+		.unwrap();
+		assert!(n_pairs == n / 2);
+		for k in 0..n / 2 {
+			assert!(order[2 * k + 1] == partner(order[2 * k]));
+			assert!(all(pivot_pairs[2 * k], !pivot_pairs[2 * k + 1]));
+		}
+		for j in 0..n {
+			assert!(order_inv[order[j]] == j);
+		}
+
+		// the ordering is used for the intranodal LBLT factorization of the scaled matrix
+		let mut triplets = alloc::vec![];
+		for j in 0..n {
+			for (i, v) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+				if i >= j {
+					triplets.push(Triplet::new(i, j, s[i] * v * s[j]));
+				}
+			}
+		}
+		let B = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let order = Perm::new_checked(order.into_boxed_slice(), order_inv.into_boxed_slice(), n);
+
+		let symbolic = factorize_symbolic_cholesky(
+			B.symbolic(),
+			Side::Lower,
+			SymmetricOrdering::Custom(order.as_ref()),
+			CholeskySymbolicParams {
+				supernodal_flop_ratio_threshold: SupernodalThreshold::FORCE_SUPERNODAL,
+				supernodal_params: SymbolicSupernodalParams {
+					pivot_pairs: Some(&pivot_pairs),
+					..default()
+				},
+				..default()
+			},
+		)
+		.unwrap();
+
+		let par = Par::Seq;
+		let fwd = &mut *alloc::vec![0usize; n];
+		let bwd = &mut *alloc::vec![0usize; n];
+		let subdiag = &mut *alloc::vec![0.0f64; n];
+		let L_val = &mut *alloc::vec![0.0f64; symbolic.len_val()];
+		let lblt = symbolic.factorize_numeric_intranode_lblt(
+			L_val,
+			subdiag,
+			fwd,
+			bwd,
+			B.as_ref(),
+			Side::Lower,
+			par,
+			MemStack::new(&mut MemBuffer::new(
+				symbolic.factorize_numeric_intranode_lblt_scratch::<f64>(par, default()),
+			)),
+			default(),
+		);
+
+		// A x = b, with x = S y and (S A S) y = S b
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).cos());
+		let mut y = Col::<f64>::from_fn(n, |i| s[i] * b[i]);
+		lblt.solve_in_place_with_conj(
+			Conj::No,
+			y.as_mat_mut(),
+			par,
+			MemStack::new(&mut MemBuffer::new(lblt.solve_in_place_scratch::<f64>(1, par))),
+		);
+		let x = Col::<f64>::from_fn(n, |i| s[i] * y[i]);
+
+		let approx_eq = CwiseMat(ApproxEq::eps() * 1e4);
+		assert!((&dense * &x).as_mat() ~ b.as_mat());
+	}
+}
@@ -16,6 +16,17 @@ pub struct SymbolicSupernodalParams<'a> {
 	///
 	/// `(n <= relax[0].0 && z < relax[0].1) || (n <= relax[1].0 && z < relax[1].1) || ...`
 	pub relax: Option<&'a [(usize, f64)]>,
+	/// columns that must not be split across two supernodes
+	///
+	/// if `pivot_pairs[j]` is `true`, the columns `j` and `j + 1` of the permuted matrix are kept
+	/// in the same supernode when `j + 1` is the parent of `j` in the elimination tree, so that
+	/// the intranodal $LBL^\top$ factorization can use them as a $2 \times 2$ pivot. see
+	/// [`matching::symmetric_order`]
+	pub pivot_pairs: Option<&'a [bool]>,
+	/// parallelism targeted by the mapping of the elimination tree onto the threads, which is
+	/// computed once by the supernodal cholesky symbolic factorization and used by the numeric
+	/// factorization and the solves with [`Par::Rayon`]
+	pub par: Par,
 }
 
 const DEFAULT_RELAX: &'static [(usize, f64)] = &[(4, 1.0), (16, 0.8), (48, 0.1), (usize::MAX, 0.05)];
@@ -23,7 +34,14 @@ const DEFAULT_RELAX: &'static [(usize, f64)] = &[(4, 1.0), (16, 0.8), (48, 0.1),
 impl Default for SymbolicSupernodalParams<'_> {
 	#[inline]
 	fn default() -> Self {
-		Self { relax: Some(DEFAULT_RELAX) }
+		Self {
+			relax: Some(DEFAULT_RELAX),
+			pivot_pairs: None,
+			#[cfg(feature = "rayon")]
+			par: Par::rayon(0),
+			#[cfg(not(feature = "rayon"))]
+			par: Par::Seq,
+		}
 	}
 }
 
@@ -117,6 +135,10 @@ pub mod solvers;
 
 pub mod amd;
 pub mod colamd;
+pub mod nested_dissection;
+
+pub mod equilibrate;
+pub mod matching;
 
 pub mod cholesky;
 pub mod lu;
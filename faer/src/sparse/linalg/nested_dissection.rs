@@ -0,0 +1,826 @@
+//! multilevel nested dissection ordering.
+//!
+//! the graph of $A + A^\top$ is recursively split by small vertex separators, which are ordered
+//! after the two halves they separate. each separator is computed by coarsening the graph with
+//! heavy edge matchings, bisecting the coarsest graph, then projecting the bisection back while
+//! refining it at each level. the resulting edge separator is turned into a vertex separator
+//! with a minimum vertex cover of the cut edges
+//!
+//! compared to minimum degree orderings, nested dissection usually requires fewer flops on 2d and
+//! 3d meshes, and produces shallower and more balanced elimination trees
+
+use crate::assert;
+use crate::internal_prelude_sp::*;
+use crate::sparse::linalg::amd;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// tuning parameters for the nested dissection ordering
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Control {
+	/// subgraphs with at most this many vertices are not dissected further, and are ordered
+	/// with the approximate minimum degree ordering instead
+	pub leaf_size: usize,
+	/// graphs are coarsened until they have at most this many vertices
+	pub coarsen_size: usize,
+	/// number of refinement passes at each level of the bisection
+	pub refine_passes: usize,
+	/// maximum ratio of the weight of the largest half of a bisection to half the total weight
+	pub imbalance: f64,
+	/// parameters of the ordering of the leaves
+	pub amd_params: amd::Control,
+}
+
+impl Default for Control {
+	#[inline]
+	fn default() -> Self {
+		Self {
+			leaf_size: 64,
+			coarsen_size: 64,
+			refine_passes: 8,
+			imbalance: 1.2,
+			amd_params: amd::Control::default(),
+		}
+	}
+}
+
+const NONE_: usize = usize::MAX;
+
+/// undirected weighted graph in compressed adjacency format
+struct Graph {
+	xadj: Vec<usize>,
+	adj: Vec<usize>,
+	adj_w: Vec<usize>,
+	vw: Vec<usize>,
+}
+
+impl Graph {
+	#[inline]
+	fn n(&self) -> usize {
+		self.vw.len()
+	}
+
+	#[inline]
+	fn neighbors(&self, v: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+		let range = self.xadj[v]..self.xadj[v + 1];
+		core::iter::zip(self.adj[range.clone()].iter().copied(), self.adj_w[range].iter().copied())
+	}
+}
+
+/// builds the graph of $A + A^\top$, without self loops
+fn symmetric_graph<I: Index>(A: SymbolicSparseColMatRef<'_, I>) -> Result<Graph, FaerError> {
+	let n = A.ncols();
+
+	let mut xadj = try_zeroed::<usize>(n + 1)?;
+	for j in 0..n {
+		for i in A.row_idx_of_col(j) {
+			let i = i.zx();
+			if i != j {
+				xadj[i + 1] += 1;
+				xadj[j + 1] += 1;
+			}
+		}
+	}
+	for j in 0..n {
+		xadj[j + 1] += xadj[j];
+	}
+
+	let mut pos = try_collect(xadj[..n].iter().copied())?;
+	let mut adj = try_zeroed::<usize>(xadj[n])?;
+	for j in 0..n {
+		for i in A.row_idx_of_col(j) {
+			let i = i.zx();
+			if i != j {
+				adj[pos[i]] = j;
+				pos[i] += 1;
+				adj[pos[j]] = i;
+				pos[j] += 1;
+			}
+		}
+	}
+
+	// remove duplicate edges
+	let mut mark = pos;
+	mark.fill(NONE_);
+	let mut new_xadj = try_zeroed::<usize>(n + 1)?;
+	let mut k = 0;
+	for v in 0..n {
+		for idx in xadj[v]..xadj[v + 1] {
+			let u = adj[idx];
+			if mark[u] != v {
+				mark[u] = v;
+				adj[k] = u;
+				k += 1;
+			}
+		}
+		new_xadj[v + 1] = k;
+	}
+	adj.truncate(k);
+
+	Ok(Graph {
+		xadj: new_xadj,
+		adj_w: try_collect(core::iter::repeat_n(1, k))?,
+		adj,
+		vw: try_collect(core::iter::repeat_n(1, n))?,
+	})
+}
+
+/// extracts the subgraph induced by `verts`, given a buffer `local` filled with `NONE_`, which is
+/// restored on output
+fn subgraph(g: &Graph, verts: &[usize], local: &mut [usize]) -> Graph {
+	for (k, &v) in verts.iter().enumerate() {
+		local[v] = k;
+	}
+
+	let mut xadj = Vec::with_capacity(verts.len() + 1);
+	let mut adj = Vec::new();
+	xadj.push(0);
+	for &v in verts {
+		for (u, _) in g.neighbors(v) {
+			if local[u] != NONE_ {
+				adj.push(local[u]);
+			}
+		}
+		xadj.push(adj.len());
+	}
+
+	for &v in verts {
+		local[v] = NONE_;
+	}
+
+	Graph {
+		xadj,
+		adj_w: vec![1; adj.len()],
+		adj,
+		vw: vec![1; verts.len()],
+	}
+}
+
+/// heavy edge matching coarsening. returns the map from the fine vertices to the coarse ones and
+/// the coarse graph
+fn coarsen(g: &Graph) -> (Vec<usize>, Graph) {
+	let n = g.n();
+
+	// visit the vertices by increasing degree, so that low degree vertices get matched first
+	let mut order = (0..n).collect::<Vec<_>>();
+	order.sort_by_key(|&v| (g.xadj[v + 1] - g.xadj[v], v));
+
+	let mut mate = vec![NONE_; n];
+	for &v in &order {
+		if mate[v] != NONE_ {
+			continue;
+		}
+		let mut best = v;
+		let mut best_w = 0;
+		for (u, w) in g.neighbors(v) {
+			if mate[u] == NONE_ && u != v && (w > best_w || (w == best_w && best != v && g.vw[u] < g.vw[best])) {
+				best = u;
+				best_w = w;
+			}
+		}
+		mate[v] = best;
+		mate[best] = v;
+	}
+
+	let mut cmap = vec![NONE_; n];
+	let mut nc = 0;
+	for v in 0..n {
+		if cmap[v] == NONE_ {
+			cmap[v] = nc;
+			cmap[mate[v]] = nc;
+			nc += 1;
+		}
+	}
+
+	let mut xadj = Vec::with_capacity(nc + 1);
+	let mut adj = Vec::new();
+	let mut adj_w = Vec::new();
+	let mut vw = vec![0; nc];
+	let mut pos = vec![NONE_; nc];
+	xadj.push(0);
+
+	let mut c = 0;
+	for v in 0..n {
+		if cmap[v] != c {
+			continue;
+		}
+		let start = adj.len();
+		let u = mate[v];
+		for x in if u == v { [v, NONE_] } else { [v, u] } {
+			if x == NONE_ {
+				continue;
+			}
+			vw[c] += g.vw[x];
+			for (y, w) in g.neighbors(x) {
+				let cy = cmap[y];
+				if cy == c {
+					continue;
+				}
+				if pos[cy] == NONE_ {
+					pos[cy] = adj.len();
+					adj.push(cy);
+					adj_w.push(w);
+				} else {
+					adj_w[pos[cy]] += w;
+				}
+			}
+		}
+		for &cy in &adj[start..] {
+			pos[cy] = NONE_;
+		}
+		xadj.push(adj.len());
+		c += 1;
+	}
+
+	(cmap, Graph { xadj, adj, adj_w, vw })
+}
+
+/// returns the weight of the edges crossing the bisection
+fn cut(g: &Graph, part: &[u8]) -> usize {
+	let mut total = 0;
+	for v in 0..g.n() {
+		for (u, w) in g.neighbors(v) {
+			if part[u] != part[v] {
+				total += w;
+			}
+		}
+	}
+	total / 2
+}
+
+/// greedy graph growing bisection starting from `seed`
+fn grow(g: &Graph, seed: usize, part: &mut [u8], queue: &mut Vec<usize>) {
+	let n = g.n();
+	let total: usize = g.vw.iter().sum();
+
+	part.fill(1);
+	queue.clear();
+
+	let mut weight = 0;
+	let mut next_seed = 0;
+	let mut head = 0;
+	queue.push(seed);
+	part[seed] = 0;
+
+	while 2 * weight < total {
+		if head == queue.len() {
+			// disconnected graph, continue from another component
+			while next_seed < n && part[next_seed] == 0 {
+				next_seed += 1;
+			}
+			if next_seed == n {
+				break;
+			}
+			part[next_seed] = 0;
+			queue.push(next_seed);
+		}
+		let v = queue[head];
+		head += 1;
+		weight += g.vw[v];
+		for (u, _) in g.neighbors(v) {
+			if part[u] == 1 {
+				part[u] = 0;
+				queue.push(u);
+			}
+		}
+	}
+
+	// vertices that were queued but not visited go back to the other side
+	for &v in &queue[head..] {
+		part[v] = 1;
+	}
+}
+
+/// returns the vertex farthest away from `seed` in the breadth first search order
+fn farthest(g: &Graph, seed: usize, dist: &mut [usize], queue: &mut Vec<usize>) -> usize {
+	dist.fill(NONE_);
+	queue.clear();
+	queue.push(seed);
+	dist[seed] = 0;
+	let mut head = 0;
+	while head < queue.len() {
+		let v = queue[head];
+		head += 1;
+		for (u, _) in g.neighbors(v) {
+			if dist[u] == NONE_ {
+				dist[u] = dist[v] + 1;
+				queue.push(u);
+			}
+		}
+	}
+	*queue.last().unwrap()
+}
+
+/// fiduccia-mattheyses refinement of a bisection. each pass moves the vertices by decreasing gain,
+/// allowing the cut to temporarily increase, then rolls back to the best bisection that was seen
+fn refine(g: &Graph, part: &mut [u8], control: &Control) {
+	let n = g.n();
+	let total: usize = g.vw.iter().sum();
+	let max_weight = ((total as f64) * control.imbalance / 2.0).ceil() as usize;
+	let max_weight = Ord::max(max_weight, total.div_ceil(2));
+
+	let mut weight = [0usize; 2];
+	for v in 0..n {
+		weight[part[v] as usize] += g.vw[v];
+	}
+	let excess = |weight: &[usize; 2]| Ord::max(weight[0], weight[1]).saturating_sub(max_weight);
+
+	// gain of moving v to the other side
+	let mut gain = vec![0isize; n];
+	let mut locked = vec![false; n];
+	let mut heap = alloc::collections::BinaryHeap::new();
+	let mut moves = Vec::new();
+
+	for _ in 0..control.refine_passes {
+		heap.clear();
+		moves.clear();
+		locked.fill(false);
+
+		for v in 0..n {
+			let mut gv = 0isize;
+			let mut boundary = false;
+			for (u, w) in g.neighbors(v) {
+				if part[u] == part[v] {
+					gv -= w as isize;
+				} else {
+					gv += w as isize;
+					boundary = true;
+				}
+			}
+			gain[v] = gv;
+			if boundary || excess(&weight) > 0 {
+				heap.push((gv, core::cmp::Reverse(v)));
+			}
+		}
+		let mut cur_cut = cut(g, part) as isize;
+
+		let mut best = (excess(&weight), cur_cut);
+		let mut best_len = 0;
+		// stop the pass after this many moves without improvement
+		let patience = 32 + n / 64;
+
+		while let Some((gv, core::cmp::Reverse(v))) = heap.pop() {
+			if locked[v] || gv != gain[v] {
+				continue;
+			}
+			locked[v] = true;
+
+			let from = part[v] as usize;
+			let to = 1 - from;
+			if weight[to] + g.vw[v] > max_weight && weight[from] <= max_weight {
+				continue;
+			}
+
+			part[v] = to as u8;
+			weight[from] -= g.vw[v];
+			weight[to] += g.vw[v];
+			cur_cut -= gv;
+			moves.push(v);
+
+			for (u, w) in g.neighbors(v) {
+				if part[u] as usize == to {
+					gain[u] -= 2 * w as isize;
+				} else {
+					gain[u] += 2 * w as isize;
+				}
+				if !locked[u] {
+					heap.push((gain[u], core::cmp::Reverse(u)));
+				}
+			}
+
+			let cur = (excess(&weight), cur_cut);
+			if cur < best {
+				best = cur;
+				best_len = moves.len();
+			} else if moves.len() - best_len > patience {
+				break;
+			}
+		}
+
+		for &v in moves[best_len..].iter().rev() {
+			let from = part[v] as usize;
+			part[v] = 1 - part[v];
+			weight[from] -= g.vw[v];
+			weight[1 - from] += g.vw[v];
+		}
+
+		if best_len == 0 {
+			break;
+		}
+	}
+}
+
+/// number of initial bisections tried on the coarsest graph
+const NSEEDS: usize = 12;
+
+/// multilevel bisection of the graph
+fn bisect(g: &Graph, control: &Control) -> Vec<u8> {
+	let mut levels: Vec<(Vec<usize>, Graph)> = Vec::new();
+	loop {
+		let cur = levels.last().map(|(_, g)| g).unwrap_or(g);
+		if cur.n() <= Ord::max(control.coarsen_size, 2) {
+			break;
+		}
+		let (cmap, coarse) = coarsen(cur);
+		// stop if the matching stalls, which happens with star-like graphs
+		if 10 * coarse.n() > 9 * cur.n() {
+			break;
+		}
+		levels.push((cmap, coarse));
+	}
+
+	let coarsest = levels.last().map(|(_, g)| g).unwrap_or(g);
+	let n = coarsest.n();
+
+	// try several seeds for the initial bisection, and keep the one with the smallest cut
+	let mut queue = Vec::with_capacity(n);
+	let mut dist = vec![0; n];
+	let mut part = vec![0u8; n];
+	let mut best = vec![0u8; n];
+	let mut best_cut = usize::MAX;
+
+	let mut seed = farthest(coarsest, 0, &mut dist, &mut queue);
+	for k in 0..NSEEDS {
+		grow(coarsest, seed, &mut part, &mut queue);
+		refine(coarsest, &mut part, control);
+		let c = cut(coarsest, &part);
+		if c < best_cut {
+			best_cut = c;
+			best.copy_from_slice(&part);
+		}
+		// alternate between pseudo-peripheral vertices and scattered ones
+		seed = if k % 2 == 0 {
+			farthest(coarsest, seed, &mut dist, &mut queue)
+		} else {
+			(k * 7919) % n
+		};
+	}
+	let mut part = best;
+
+	// project back to the finer graphs
+	for idx in (0..levels.len()).rev() {
+		let (cmap, _) = &levels[idx];
+		let fine = if idx == 0 { g } else { &levels[idx - 1].1 };
+		part = cmap.iter().map(|&c| part[c]).collect();
+		refine(fine, &mut part, control);
+	}
+
+	part
+}
+
+/// turns the bisection into a vertex separator, using a minimum vertex cover of the bipartite
+/// graph of the cut edges. separator vertices are marked with `2`
+fn vertex_separator(g: &Graph, part: &mut [u8]) {
+	let n = g.n();
+	let is_boundary = |part: &[u8], v: usize| g.neighbors(v).any(|(u, _)| part[u] != part[v]);
+
+	// the left side is the one with fewer boundary vertices
+	let mut boundary = [Vec::new(), Vec::new()];
+	for v in 0..n {
+		if is_boundary(part, v) {
+			boundary[part[v] as usize].push(v);
+		}
+	}
+	let left_side = if boundary[0].len() <= boundary[1].len() { 0u8 } else { 1u8 };
+	let left = &boundary[left_side as usize];
+
+	// maximum matching with augmenting paths
+	let mut mate = vec![NONE_; n];
+	let mut prev = vec![NONE_; n];
+	let mut stamp = vec![NONE_; n];
+	let mut queue = Vec::new();
+
+	for (iter, &root) in left.iter().enumerate() {
+		queue.clear();
+		queue.push(root);
+		stamp[root] = iter;
+		let mut head = 0;
+		let mut found = NONE_;
+
+		'search: while head < queue.len() {
+			let x = queue[head];
+			head += 1;
+			for (y, _) in g.neighbors(x) {
+				if part[y] == left_side || stamp[y] == iter {
+					continue;
+				}
+				stamp[y] = iter;
+				prev[y] = x;
+				if mate[y] == NONE_ {
+					found = y;
+					break 'search;
+				}
+				let z = mate[y];
+				if stamp[z] != iter {
+					stamp[z] = iter;
+					queue.push(z);
+				}
+			}
+		}
+
+		let mut y = found;
+		while y != NONE_ {
+			let x = prev[y];
+			let next = mate[x];
+			mate[y] = x;
+			mate[x] = y;
+			y = next;
+		}
+	}
+
+	// könig's theorem: the cover is made of the left vertices that are not reachable from the
+	// unmatched left vertices with alternating paths, and the right vertices that are
+	stamp.fill(NONE_);
+	queue.clear();
+	for &x in left {
+		if mate[x] == NONE_ {
+			stamp[x] = 0;
+			queue.push(x);
+		}
+	}
+	let mut head = 0;
+	while head < queue.len() {
+		let x = queue[head];
+		head += 1;
+		for (y, _) in g.neighbors(x) {
+			if part[y] == left_side || stamp[y] == 0 {
+				continue;
+			}
+			stamp[y] = 0;
+			let z = mate[y];
+			if z != NONE_ && stamp[z] != 0 {
+				stamp[z] = 0;
+				queue.push(z);
+			}
+		}
+	}
+
+	let right = &boundary[1 - left_side as usize];
+	let mut separator = Vec::new();
+	for &x in left {
+		if stamp[x] != 0 {
+			separator.push(x);
+		}
+	}
+	for &y in right {
+		if stamp[y] == 0 {
+			separator.push(y);
+		}
+	}
+	for v in separator {
+		part[v] = 2;
+	}
+}
+
+/// orders the subgraph induced by `verts` with the amd ordering, and writes the ordered vertices
+/// to `out`
+fn order_leaf<I: Index>(out: &mut [I], g: &Graph, verts: &[usize], local: &mut [usize], control: &Control) -> Result<(), FaerError> {
+	let n = verts.len();
+	if n <= 2 {
+		for (o, &v) in core::iter::zip(out, verts) {
+			*o = I::truncate(v);
+		}
+		return Ok(());
+	}
+
+	let mut sub = subgraph(g, verts, local);
+	for v in 0..n {
+		sub.adj[sub.xadj[v]..sub.xadj[v + 1]].sort_unstable();
+	}
+
+	let col_ptr = try_collect(sub.xadj.iter().map(|&p| I::truncate(p)))?;
+	let row_idx = try_collect(sub.adj.iter().map(|&i| I::truncate(i)))?;
+	let nnz = row_idx.len();
+	let A = SymbolicSparseColMatRef::new_checked(n, n, &col_ptr, None, &row_idx);
+
+	let mut perm = try_zeroed::<I>(n)?;
+	let mut perm_inv = try_zeroed::<I>(n)?;
+	amd::order(
+		&mut perm,
+		&mut perm_inv,
+		A,
+		control.amd_params,
+		MemStack::new(&mut dyn_stack::MemBuffer::try_new(amd::order_scratch::<I>(n, nnz))?),
+	)?;
+
+	for (o, &p) in core::iter::zip(out, &*perm) {
+		*o = I::truncate(verts[p.zx()]);
+	}
+	Ok(())
+}
+
+/// computes the nested dissection ordering for reducing the fill-in during the sparse cholesky
+/// factorization of a matrix with the sparsity pattern of $A + A^\top$
+///
+/// # note
+/// allows unsorted matrices
+pub fn order<I: Index>(perm: &mut [I], perm_inv: &mut [I], A: SymbolicSparseColMatRef<'_, I>, control: Control) -> Result<(), FaerError> {
+	let n = A.ncols();
+	assert!(all(A.nrows() == n, perm.len() == n, perm_inv.len() == n));
+
+	let g = symmetric_graph(A)?;
+	let mut local = try_collect(core::iter::repeat_n(NONE_, n))?;
+
+	// each job is a set of vertices, along with the position of its first vertex in the ordering
+	let mut jobs = vec![(try_collect(0..n)?, 0usize)];
+	while let Some((verts, start)) = jobs.pop() {
+		let len = verts.len();
+		let out = &mut perm[start..start + len];
+
+		if len <= Ord::max(control.leaf_size, 2) {
+			order_leaf(out, &g, &verts, &mut local, &control)?;
+			continue;
+		}
+
+		let sub = subgraph(&g, &verts, &mut local);
+		let mut part = bisect(&sub, &control);
+		vertex_separator(&sub, &mut part);
+
+		let mut halves = [Vec::new(), Vec::new()];
+		let mut separator = Vec::new();
+		for (&v, &p) in core::iter::zip(&verts, &part) {
+			match p {
+				0 | 1 => halves[p as usize].push(v),
+				_ => separator.push(v),
+			}
+		}
+
+		if halves[0].is_empty() || halves[1].is_empty() {
+			// the bisection failed to make progress
+			order_leaf(out, &g, &verts, &mut local, &control)?;
+			continue;
+		}
+
+		// the separator is eliminated last
+		let sep_start = len - separator.len();
+		for (o, &v) in core::iter::zip(&mut out[sep_start..], &separator) {
+			*o = I::truncate(v);
+		}
+		let [h0, h1] = halves;
+		let h0_len = h0.len();
+		jobs.push((h1, start + h0_len));
+		jobs.push((h0, start));
+	}
+
+	for (k, &p) in perm.iter().enumerate() {
+		perm_inv[p.zx()] = I::truncate(k);
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::sparse::linalg::cholesky::{CholeskySymbolicParams, SymmetricOrdering, factorize_symbolic_cholesky};
+
+	// 7-point laplacian pattern on a 3d grid
+	fn grid_3d(nx: usize) -> SymbolicSparseColMat<usize> {
+		let idx = |x: usize, y: usize, z: usize| x + nx * (y + nx * z);
+		let n = nx * nx * nx;
+		let mut col_ptr = vec![0usize];
+		let mut row_idx = vec![];
+		for z in 0..nx {
+			for y in 0..nx {
+				for x in 0..nx {
+					let mut col = vec![idx(x, y, z)];
+					if x > 0 {
+						col.push(idx(x - 1, y, z));
+					}
+					if x + 1 < nx {
+						col.push(idx(x + 1, y, z));
+					}
+					if y > 0 {
+						col.push(idx(x, y - 1, z));
+					}
+					if y + 1 < nx {
+						col.push(idx(x, y + 1, z));
+					}
+					if z > 0 {
+						col.push(idx(x, y, z - 1));
+					}
+					if z + 1 < nx {
+						col.push(idx(x, y, z + 1));
+					}
+					col.sort_unstable();
+					row_idx.extend_from_slice(&col);
+					col_ptr.push(row_idx.len());
+				}
+			}
+		}
+		SymbolicSparseColMat::new_checked(n, n, col_ptr, None, row_idx)
+	}
+
+	#[test]
+	fn test_nested_dissection_grid() {
+		let A = grid_3d(28);
+		let n = A.nrows();
+
+		let mut perm = vec![0usize; n];
+		let mut perm_inv = vec![0usize; n];
+		order(&mut perm, &mut perm_inv, A.as_ref(), default()).unwrap();
+		for k in 0..n {
+			assert!(perm_inv[perm[k]] == k);
+		}
+		let nd = etree_stats(A.as_ref(), &perm_inv);
+
+		amd::order(
+			&mut perm,
+			&mut perm_inv,
+			A.as_ref(),
+			default(),
+			MemStack::new(&mut dyn_stack::MemBuffer::new(amd::order_scratch::<usize>(n, A.compute_nnz()))),
+		)
+		.unwrap();
+		let amd = etree_stats(A.as_ref(), &perm_inv);
+
+		// on 3d meshes, nested dissection gives a shallower elimination tree and fewer flops than amd
+		assert!(nd.0 < amd.0);
+		assert!(nd.1 < amd.1);
+
+		let symbolic = factorize_symbolic_cholesky(
+			A.as_ref(),
+			Side::Lower,
+			SymmetricOrdering::NestedDissection(default()),
+			CholeskySymbolicParams::default(),
+		)
+		.unwrap();
+		assert!(symbolic.nrows() == n);
+	}
+
+	#[test]
+	fn test_nested_dissection_disconnected() {
+		// two disjoint grids, with extra isolated vertices
+		let A = grid_3d(6);
+		let m = A.nrows();
+		let n = 2 * m + 5;
+
+		let mut col_ptr = vec![0usize];
+		let mut row_idx = vec![];
+		for offset in [0, m] {
+			for j in 0..m {
+				row_idx.extend(A.row_idx_of_col(j).map(|i| i + offset));
+				col_ptr.push(row_idx.len());
+			}
+		}
+		for _ in 0..5 {
+			col_ptr.push(row_idx.len());
+		}
+		let B = SymbolicSparseColMat::new_checked(n, n, col_ptr, None, row_idx);
+
+		let control = Control {
+			leaf_size: 8,
+			coarsen_size: 16,
+			..default()
+		};
+		let mut perm = vec![0usize; n];
+		let mut perm_inv = vec![0usize; n];
+		order(&mut perm, &mut perm_inv, B.as_ref(), control).unwrap();
+		for k in 0..n {
+			assert!(perm_inv[perm[k]] == k);
+		}
+	}
+
+	// height of the elimination tree and flop count of the cholesky factorization of the permuted
+	// matrix
+	fn etree_stats(A: SymbolicSparseColMatRef<'_, usize>, perm_inv: &[usize]) -> (usize, f64) {
+		let n = A.nrows();
+		let mut cols = vec![vec![]; n];
+		for j in 0..n {
+			for i in A.row_idx_of_col(j) {
+				let (pi, pj) = (perm_inv[i], perm_inv[j]);
+				if pi <= pj {
+					cols[pj].push(pi);
+				}
+			}
+		}
+		let mut col_ptr = vec![0usize];
+		let mut row_idx = vec![];
+		for mut c in cols {
+			c.sort_unstable();
+			row_idx.extend_from_slice(&c);
+			col_ptr.push(row_idx.len());
+		}
+		let U = SymbolicSparseColMat::new_checked(n, n, col_ptr, None, row_idx);
+
+		let mut etree = vec![0isize; n];
+		let mut col_counts = vec![0usize; n];
+		crate::sparse::linalg::cholesky::simplicial::prefactorize_symbolic_cholesky(
+			&mut etree,
+			&mut col_counts,
+			U.as_ref(),
+			MemStack::new(&mut dyn_stack::MemBuffer::new(
+				crate::sparse::linalg::cholesky::simplicial::prefactorize_symbolic_cholesky_scratch::<usize>(n, 0),
+			)),
+		);
+		let mut depth = vec![0usize; n];
+		for j in (0..n).rev() {
+			if etree[j] >= 0 {
+				depth[j] = depth[etree[j] as usize] + 1;
+			}
+		}
+		let flops = col_counts.iter().map(|&c| (c as f64) * (c as f64)).sum();
+		(depth.iter().copied().max().unwrap() + 1, flops)
+	}
+}
@@ -1,7 +1,8 @@
-use crate::get_global_parallelism;
 use crate::internal_prelude_sp::*;
-use crate::linalg::solvers::{ShapeCore, SolveCore, SolveLstsqCore};
-use linalg_sp::{LltError, LuError};
+use crate::linalg::equilibrate::EquilibrationMethod;
+use crate::linalg::solvers::{Equilibrated, RefinementMatrix, ShapeCore, SolveCore, SolveLstsqCore, scale_rows};
+use crate::{assert, get_global_parallelism};
+use linalg_sp::{LltError, LuError, SupernodalThreshold, SymbolicSupernodalParams};
 
 /// reference-counted sparse symbolic $LL^\top$ factorization
 #[derive(Debug, Clone)]
@@ -43,6 +44,18 @@ pub struct Lu<I, T> {
 	numeric: linalg_sp::lu::NumericLu<I, T>,
 }
 
+/// sparse intranodal $LBL^\top$ factorization of a self-adjoint matrix, preprocessed with a
+/// maximum weight matching
+#[derive(Debug, Clone)]
+pub struct Lblt<I, T: ComplexField> {
+	symbolic: alloc::sync::Arc<linalg_sp::cholesky::SymbolicCholesky<I>>,
+	numeric: alloc::vec::Vec<T>,
+	subdiag: alloc::vec::Vec<T>,
+	perm_fwd: alloc::vec::Vec<I>,
+	perm_bwd: alloc::vec::Vec<I>,
+	scale: Col<T::Real>,
+}
+
 impl<I: Index> SymbolicLlt<I> {
 	/// returns the symbolic $LL^\top$ factorization of the input matrix
 	///
@@ -105,6 +118,98 @@ impl<I: Index, T: ComplexField> Llt<I, T> {
 		)?;
 		Ok(Self { symbolic, numeric })
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		crate::linalg::solvers::rcond_l1_est(self, anorm)
+	}
+
+	/// runs `f` on a copy of the factor whose symbolic structure is extended to `symbolic` if
+	/// needed, then replaces the factor by the copy if `f` succeeds. on failure, `self` is left
+	/// unchanged
+	fn try_modify(
+		&mut self,
+		symbolic: Option<linalg_sp::cholesky::SymbolicCholesky<I>>,
+		f: impl FnOnce(&linalg_sp::cholesky::SymbolicCholesky<I>, &mut [T]) -> Result<(), LltError>,
+	) -> Result<(), LltError> {
+		let grow = symbolic.is_some();
+		let symbolic = match symbolic {
+			Some(symbolic) => alloc::sync::Arc::new(symbolic),
+			None => self.symbolic.inner.clone(),
+		};
+
+		let len_val = symbolic.len_val();
+		let mut numeric = alloc::vec::Vec::new();
+		numeric.try_reserve_exact(len_val).map_err(|_| FaerError::OutOfMemory)?;
+		if grow {
+			numeric.resize(len_val, zero::<T>());
+			self.symbolic.inner.remap_values(&self.numeric, &symbolic, &mut numeric);
+		} else {
+			numeric.extend_from_slice(&self.numeric);
+		}
+
+		f(&symbolic, &mut numeric)?;
+
+		self.symbolic = SymbolicLlt { inner: symbolic };
+		self.numeric = numeric;
+		Ok(())
+	}
+
+	/// updates the factorization of $A$ to that of $A + W \operatorname{diag}(\alpha) W^H$, where
+	/// the columns of $W$ with a negative weight are downdates
+	///
+	/// the symbolic structure is extended if the update introduces new fill. on failure, the
+	/// factorization is left unchanged
+	#[track_caller]
+	pub fn rank_r_update(&mut self, W: SparseColMatRef<'_, I, T>, alpha: DiagRef<'_, T::Real>) -> Result<(), LltError> {
+		let symbolic = self.symbolic.inner.update_symbolic(W.symbolic())?;
+		self.try_modify(symbolic, |symbolic, numeric| {
+			symbolic.rank_r_update_numeric_llt(
+				numeric,
+				W,
+				alpha,
+				MemStack::new(&mut MemBuffer::try_new(symbolic.rank_r_update_numeric_llt_scratch::<T>())?),
+			)?;
+			Ok(())
+		})
+	}
+
+	/// updates the factorization of $A$ to that of the matrix obtained by replacing its row and
+	/// column `k` by `A_col` (and its adjoint)
+	///
+	/// the row and column `k` of $A$ must be those of the identity, e.g., after a call to
+	/// [`Self::row_del`]. the symbolic structure is extended if the new row introduces new fill. on
+	/// failure, the factorization is left unchanged
+	#[track_caller]
+	pub fn row_add(&mut self, k: usize, A_col: SparseColMatRef<'_, I, T>) -> Result<(), LltError> {
+		let symbolic = self.symbolic.inner.row_add_symbolic(k, A_col.symbolic())?;
+		self.try_modify(symbolic, |symbolic, numeric| {
+			symbolic.row_add_numeric_llt(
+				numeric,
+				k,
+				A_col,
+				MemStack::new(&mut MemBuffer::try_new(symbolic.row_add_numeric_scratch::<T>())?),
+			)?;
+			Ok(())
+		})
+	}
+
+	/// updates the factorization of $A$ to that of the matrix obtained by replacing its row and
+	/// column `k` by those of the identity
+	///
+	/// on failure, the factorization is left unchanged
+	#[track_caller]
+	pub fn row_del(&mut self, k: usize) -> Result<(), LltError> {
+		self.try_modify(None, |symbolic, numeric| {
+			symbolic.row_del_numeric_llt(
+				numeric,
+				k,
+				MemStack::new(&mut MemBuffer::try_new(symbolic.row_del_numeric_scratch::<T>())?),
+			)?;
+			Ok(())
+		})
+	}
 }
 
 impl<I: Index, T: ComplexField> Lu<I, T> {
@@ -125,6 +230,146 @@ impl<I: Index, T: ComplexField> Lu<I, T> {
 		)?;
 		Ok(Self { symbolic, numeric })
 	}
+
+	/// returns an estimate of the reciprocal condition number of the original matrix $A$ in the
+	/// 1-norm, given `anorm` $= \|A\|_1$
+	pub fn rcond(&self, anorm: T::Real) -> T::Real {
+		crate::linalg::solvers::rcond_l1_est(self, anorm)
+	}
+
+	/// returns the $LU$ factorization of the equilibrated matrix $R A C$, where $A$ has the same
+	/// sparsity pattern as the original one used to construct the symbolic factorization
+	#[track_caller]
+	pub fn try_new_with_symbolic_equilibrated(
+		symbolic: SymbolicLu<I>,
+		mat: SparseColMatRef<'_, I, T>,
+		method: EquilibrationMethod<T::Real>,
+	) -> Result<Equilibrated<T, Self>, LuError> {
+		let mut row_scale = Col::zeros(mat.nrows());
+		let mut col_scale = Col::zeros(mat.ncols());
+		let info = linalg_sp::equilibrate::equilibrate(row_scale.as_mut(), col_scale.as_mut(), mat, method);
+
+		let mut val = alloc::vec::Vec::new();
+		val.try_reserve_exact(mat.val().len()).map_err(|_| FaerError::OutOfMemory)?;
+		val.extend_from_slice(mat.val());
+
+		let mut scaled = SparseColMatMut::new(mat.symbolic(), &mut val);
+		linalg_sp::equilibrate::scale_in_place(scaled.rb_mut(), row_scale.as_ref(), col_scale.as_ref());
+
+		let inner = Self::try_new_with_symbolic(symbolic, scaled.rb())?;
+		Ok(Equilibrated::from_parts(inner, row_scale, col_scale, info))
+	}
+}
+
+impl<I: Index, T: ComplexField> Lblt<I, T> {
+	/// returns the intranodal $LBL^\top$ factorization of the self-adjoint matrix $A$, whose
+	/// triangular halves must both be stored
+	///
+	/// a maximum product matching of $A$ is used to compute a symmetric scaling $D$ and a
+	/// fill-reducing ordering that keeps the matched entries inside $2 \times 2$ diagonal blocks,
+	/// so that matrices with zero diagonal entries can be factorized. the factorization is then
+	/// computed for $DAD$, and solutions are computed with respect to $A$
+	#[track_caller]
+	pub fn try_new_with_matching(mat: SparseColMatRef<'_, I, T>) -> Result<Self, FaerError> {
+		use linalg_sp::matching;
+
+		assert!(mat.nrows() == mat.ncols());
+		let n = mat.nrows();
+		let par = get_global_parallelism();
+
+		let mut perm_fwd = super::try_zeroed::<I>(n)?;
+		let mut perm_bwd = super::try_zeroed::<I>(n)?;
+		let mut row_scale = Col::zeros(n);
+		let mut col_scale = Col::zeros(n);
+		matching::max_weight_matching(
+			&mut perm_fwd,
+			&mut perm_bwd,
+			Some(row_scale.as_mut()),
+			Some(col_scale.as_mut()),
+			mat,
+			matching::MatchingObjective::MaxProduct,
+			MemStack::new(&mut MemBuffer::try_new(matching::max_weight_matching_scratch::<I, T>(n))?),
+		);
+		let mut scale = Col::zeros(n);
+		matching::symmetric_scaling(scale.as_mut(), row_scale.as_ref(), col_scale.as_ref());
+
+		let mut order_fwd = super::try_zeroed::<I>(n)?;
+		let mut order_bwd = super::try_zeroed::<I>(n)?;
+		let mut pivot_pairs = alloc::vec::Vec::new();
+		pivot_pairs.try_reserve_exact(n).map_err(|_| FaerError::OutOfMemory)?;
+		pivot_pairs.resize(n, false);
+		matching::symmetric_order(
+			&mut order_fwd,
+			&mut order_bwd,
+			&mut pivot_pairs,
+			unsafe { PermRef::new_unchecked(&perm_fwd, &perm_bwd, n) },
+			mat.symbolic(),
+			Default::default(),
+			MemStack::new(&mut MemBuffer::try_new(matching::symmetric_order_scratch::<I>(n, mat.compute_nnz()))?),
+		)?;
+
+		let symbolic = linalg_sp::cholesky::factorize_symbolic_cholesky(
+			mat.symbolic(),
+			Side::Lower,
+			linalg_sp::cholesky::SymmetricOrdering::Custom(unsafe { PermRef::new_unchecked(&order_fwd, &order_bwd, n) }),
+			linalg_sp::cholesky::CholeskySymbolicParams {
+				supernodal_flop_ratio_threshold: SupernodalThreshold::FORCE_SUPERNODAL,
+				supernodal_params: SymbolicSupernodalParams {
+					pivot_pairs: Some(&pivot_pairs),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		)?;
+
+		let mut val = alloc::vec::Vec::new();
+		val.try_reserve_exact(mat.val().len()).map_err(|_| FaerError::OutOfMemory)?;
+		val.extend_from_slice(mat.val());
+		let mut scaled = SparseColMatMut::new(mat.symbolic(), &mut val);
+		linalg_sp::equilibrate::scale_in_place(scaled.rb_mut(), scale.as_ref(), scale.as_ref());
+
+		let mut numeric = alloc::vec::Vec::new();
+		numeric.try_reserve_exact(symbolic.len_val()).map_err(|_| FaerError::OutOfMemory)?;
+		numeric.resize(symbolic.len_val(), zero::<T>());
+		let mut subdiag = alloc::vec::Vec::new();
+		subdiag.try_reserve_exact(n).map_err(|_| FaerError::OutOfMemory)?;
+		subdiag.resize(n, zero::<T>());
+
+		symbolic.factorize_numeric_intranode_lblt::<T>(
+			&mut numeric,
+			&mut subdiag,
+			&mut perm_fwd,
+			&mut perm_bwd,
+			scaled.rb(),
+			Side::Lower,
+			par,
+			MemStack::new(&mut MemBuffer::try_new(
+				symbolic.factorize_numeric_intranode_lblt_scratch::<T>(par, Default::default()),
+			)?),
+			Default::default(),
+		);
+
+		Ok(Self {
+			symbolic: alloc::sync::Arc::new(symbolic),
+			numeric,
+			subdiag,
+			perm_fwd,
+			perm_bwd,
+			scale,
+		})
+	}
+
+	/// returns the diagonal of the symmetric scaling $D$
+	pub fn scale(&self) -> ColRef<'_, T::Real> {
+		self.scale.as_ref()
+	}
+
+	fn as_ref(&self) -> linalg_sp::cholesky::IntranodeLbltRef<'_, I, T> {
+		let n = self.symbolic.nrows();
+		linalg_sp::cholesky::IntranodeLbltRef::new(&self.symbolic, &self.numeric, &self.subdiag, unsafe {
+			PermRef::new_unchecked(&self.perm_fwd, &self.perm_bwd, n)
+		})
+	}
 }
 
 impl<I: Index, T: ComplexField> Qr<I, T> {
@@ -194,6 +439,18 @@ impl<I: Index, T: ComplexField> ShapeCore for Lu<I, T> {
 	}
 }
 
+impl<I: Index, T: ComplexField> ShapeCore for Lblt<I, T> {
+	#[track_caller]
+	fn nrows(&self) -> usize {
+		self.symbolic.nrows()
+	}
+
+	#[track_caller]
+	fn ncols(&self) -> usize {
+		self.symbolic.ncols()
+	}
+}
+
 impl<I: Index, T: ComplexField> SolveCore<T> for Llt<I, T> {
 	#[track_caller]
 	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
@@ -283,6 +540,58 @@ impl<I: Index, T: ComplexField> SolveCore<T> for Lu<I, T> {
 	}
 }
 
+impl<I: Index, T: ComplexField> SolveCore<T> for Lblt<I, T> {
+	#[track_caller]
+	fn solve_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		let par = get_global_parallelism();
+		let rhs_ncols = rhs.ncols();
+		let mut rhs = rhs;
+		scale_rows(rhs.rb_mut(), self.scale());
+		self.as_ref().solve_in_place_with_conj(
+			conj,
+			rhs.rb_mut(),
+			par,
+			MemStack::new(&mut MemBuffer::new(self.symbolic.solve_in_place_scratch::<T>(rhs_ncols, par))),
+		);
+		scale_rows(rhs, self.scale());
+	}
+
+	#[track_caller]
+	fn solve_transpose_in_place_with_conj(&self, conj: Conj, rhs: MatMut<'_, T>) {
+		self.solve_in_place_with_conj(conj.compose(Conj::Yes), rhs);
+	}
+}
+
+impl<I: Index, T: ComplexField> RefinementMatrix<T> for SparseColMatRef<'_, I, T> {
+	#[inline]
+	fn nrows(&self) -> usize {
+		(**self).nrows()
+	}
+
+	#[inline]
+	fn ncols(&self) -> usize {
+		(**self).ncols()
+	}
+
+	#[math]
+	fn residual(&self, r: ColMut<'_, T>, w: ColMut<'_, T::Real>, x: ColRef<'_, T>, b: ColRef<'_, T>) {
+		let A = *self;
+		let mut r = r;
+		let mut w = w;
+
+		r.copy_from(b);
+		z!(w.rb_mut(), b).for_each(|uz!(w, b)| *w = abs(*b));
+		for j in 0..A.ncols() {
+			let xj = copy(x[j]);
+			let abs_xj = abs(xj);
+			for (i, a) in core::iter::zip(A.row_idx_of_col(j), A.val_of_col(j)) {
+				r[i] = r[i] - *a * xj;
+				w[i] = w[i] + abs(*a) * abs_xj;
+			}
+		}
+	}
+}
+
 impl<I: Index, T: ComplexField, Inner: for<'short> Reborrow<'short, Target = csc_numeric::Ref<'short, I, T>>>
 	csc_numeric::generic::SparseColMat<Inner>
 {
@@ -465,3 +774,300 @@ impl<I: Index, T: ComplexField, Inner: for<'short> Reborrow<'short, Target = csr
 		Qr::try_new_with_symbolic(SymbolicQr::try_new(this.symbolic())?, this)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert;
+	use crate::linalg::solvers::{DenseSolveCore, Solve};
+
+	#[test]
+	fn test_sparse_solve_refined() {
+		let n = 50;
+		let mut triplets = alloc::vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 4.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -1.0));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let triplets32 = triplets
+			.iter()
+			.map(|t| Triplet::new(t.row, t.col, t.val as f32))
+			.collect::<alloc::vec::Vec<_>>();
+		let A32 = SparseColMat::<usize, f32>::try_new_from_triplets(n, n, &triplets32).unwrap();
+
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).sin());
+
+		let (x, info) = A32.sp_lu().unwrap().solve_refined(A.as_ref(), &b, default());
+		assert!(info.backward_error[0] < 1e-15);
+		assert!((&A * &x - &b).norm_max() < 1e-12);
+
+		let (x, info) = A32.sp_cholesky(Side::Lower).unwrap().solve_refined(A.as_ref(), &b, default());
+		assert!(info.backward_error[0] < 1e-15);
+		assert!((&A * &x - &b).norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_sparse_equilibrated_lu() {
+		let n = 50;
+		let row_scale = |i: usize| 10.0f64.powi(i as i32 % 11 - 5);
+		let col_scale = |j: usize| 2.0f64.powi((5 * j) as i32 % 13 - 6);
+		let mut triplets = alloc::vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 4.0 * row_scale(i) * col_scale(i)));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -row_scale(i) * col_scale(i + 1)));
+				triplets.push(Triplet::new(i + 1, i, -row_scale(i + 1) * col_scale(i)));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).sin() * row_scale(i));
+
+		let symbolic = SymbolicLu::try_new(A.symbolic()).unwrap();
+		let lu = Lu::try_new_with_symbolic_equilibrated(symbolic, A.as_ref(), default()).unwrap();
+
+		// both the rows and the columns need to be scaled
+		assert!(lu.info().row_ratio < 1e-8);
+		assert!(lu.info().col_ratio < 0.1);
+
+		// componentwise backward error
+		let berr = |A: MatRef<'_, f64>, x: ColRef<'_, f64>, b: ColRef<'_, f64>| {
+			let r = A * x - b;
+			let A_abs = Mat::from_fn(n, n, |i, j| A[(i, j)].abs());
+			let x_abs = Col::from_fn(n, |i| x[i].abs());
+			let w = &A_abs * &x_abs;
+			(0..n).map(|i| r[i].abs() / (w[i] + b[i].abs())).fold(0.0, f64::max)
+		};
+
+		let dense = A.to_dense();
+		let x = lu.solve(&b);
+		assert!(berr(dense.as_ref(), x.as_ref(), b.as_ref()) < 1e-14);
+
+		let mut y = b.clone();
+		lu.solve_transpose_in_place(y.as_mut());
+		assert!(berr(dense.transpose(), y.as_ref(), b.as_ref()) < 1e-14);
+	}
+
+	#[test]
+	fn test_sparse_rcond() {
+		let n = 50;
+		let mut triplets = alloc::vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 2.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -0.5));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let dense = A.to_dense();
+
+		let norm_l1 = |A: MatRef<'_, f64>| (0..A.ncols()).map(|j| A.col(j).iter().map(|x| x.abs()).sum::<f64>()).fold(0.0, f64::max);
+
+		let anorm = norm_l1(dense.as_ref());
+		let exact = 1.0 / (anorm * norm_l1(dense.partial_piv_lu().inverse().as_ref()));
+		let est = A.sp_lu().unwrap().rcond(anorm);
+		assert!(est >= exact * (1.0 - 1e-6));
+		assert!(est <= exact * 10.0);
+
+		// self-adjoint part
+		let mut triplets = alloc::vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 2.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -1.0));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let dense = A.to_dense();
+
+		let anorm = norm_l1(dense.as_ref());
+		let exact = 1.0 / (anorm * norm_l1(dense.partial_piv_lu().inverse().as_ref()));
+		let est = A.sp_cholesky(Side::Lower).unwrap().rcond(anorm);
+		assert!(est >= exact * (1.0 - 1e-6));
+		assert!(est <= exact * 10.0);
+	}
+
+	#[test]
+	fn test_sparse_llt_rank_update() {
+		let n = 50;
+		let mut triplets = alloc::vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 4.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -1.0));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		// the columns of $W$ couple distant rows of $A$, so $W W^T$ is not tridiagonal and the update
+		// introduces new fill
+		let W = SparseColMat::<usize, f64>::try_new_from_triplets(
+			n,
+			3,
+			&[
+				Triplet::new(0, 0, 1.0),
+				Triplet::new(n - 1, 0, 2.0),
+				Triplet::new(10, 1, -1.0),
+				Triplet::new(40, 1, 0.5),
+				Triplet::new(3, 2, 0.25),
+				Triplet::new(17, 2, -0.5),
+				Triplet::new(31, 2, 1.0),
+			],
+		)
+		.unwrap();
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).sin());
+
+		let symbolic = SymbolicLlt::try_new(A.symbolic(), Side::Lower).unwrap();
+		let mut llt = Llt::try_new_with_symbolic(symbolic, A.as_ref(), Side::Lower).unwrap();
+		let len_val = llt.symbolic.inner.len_val();
+
+		let alpha = Col::<f64>::from_fn(3, |j| [1.0, 3.0, 2.0][j]);
+		llt.rank_r_update(W.as_ref(), alpha.as_diagonal()).unwrap();
+		assert!(llt.symbolic.inner.len_val() > len_val);
+		let W_dense = W.to_dense();
+		let A_updated = A.to_dense() + &W_dense * alpha.as_diagonal() * W_dense.transpose();
+		let x = llt.solve(&b);
+		assert!((&A_updated * &x - &b).norm_max() < 1e-12);
+
+		let alpha = -alpha;
+		llt.rank_r_update(W.as_ref(), alpha.as_diagonal()).unwrap();
+		let x = llt.solve(&b);
+		assert!((&A * &x - &b).norm_max() < 1e-12);
+
+		// downdating past positive definiteness fails
+		let too_much = Col::<f64>::from_fn(3, |_| -1e6);
+		assert!(llt.rank_r_update(W.as_ref(), too_much.as_diagonal()).is_err());
+		// and leaves the factorization unchanged
+		let x = llt.solve(&b);
+		assert!((&A * &x - &b).norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_sparse_llt_row_add_del() {
+		let n = 50;
+		let k = 20;
+		let tridiag = |i: usize, j: usize| {
+			if i == j {
+				4.0
+			} else if i.abs_diff(j) == 1 {
+				-1.0
+			} else {
+				0.0
+			}
+		};
+		let mut triplets = alloc::vec![];
+		for i in 0..n {
+			triplets.push(Triplet::new(i, i, 4.0));
+			if i + 1 < n {
+				triplets.push(Triplet::new(i, i + 1, -1.0));
+				triplets.push(Triplet::new(i + 1, i, -1.0));
+			}
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let b = Col::<f64>::from_fn(n, |i| (i as f64).sin());
+
+		let symbolic = SymbolicLlt::try_new(A.symbolic(), Side::Lower).unwrap();
+		let mut llt = Llt::try_new_with_symbolic(symbolic, A.as_ref(), Side::Lower).unwrap();
+
+		llt.row_del(k).unwrap();
+		let A_deleted = Mat::<f64>::from_fn(n, n, |i, j| {
+			if i == k || j == k {
+				if i == j { 1.0 } else { 0.0 }
+			} else {
+				tridiag(i, j)
+			}
+		});
+		let x = llt.solve(&b);
+		assert!((&A_deleted * &x - &b).norm_max() < 1e-12);
+
+		// a row that makes the matrix indefinite is rejected, and leaves the factorization unchanged
+		let len_val = llt.symbolic.inner.len_val();
+		let entries = [(5, 0.5), (k - 1, -1.0), (k, -6.0), (k + 1, -1.0), (45, 1.0)];
+		let A_col = SparseColMat::<usize, f64>::try_new_from_triplets(n, 1, &entries.map(|(i, v)| Triplet::new(i, 0, v))).unwrap();
+		assert!(llt.row_add(k, A_col.as_ref()).is_err());
+		assert!(llt.symbolic.inner.len_val() == len_val);
+		let x = llt.solve(&b);
+		assert!((&A_deleted * &x - &b).norm_max() < 1e-12);
+
+		// the new row introduces fill outside of the tridiagonal structure
+		let entries = [(5, 0.5), (k - 1, -1.0), (k, 6.0), (k + 1, -1.0), (45, 1.0)];
+		let A_col = SparseColMat::<usize, f64>::try_new_from_triplets(n, 1, &entries.map(|(i, v)| Triplet::new(i, 0, v))).unwrap();
+		llt.row_add(k, A_col.as_ref()).unwrap();
+		assert!(llt.symbolic.inner.len_val() > len_val);
+
+		let mut A_added = A_deleted;
+		for (i, v) in entries {
+			A_added[(i, k)] = v;
+			A_added[(k, i)] = v;
+		}
+		let x = llt.solve(&b);
+		assert!((&A_added * &x - &b).norm_max() < 1e-12);
+	}
+
+	#[test]
+	fn test_lblt_with_matching() {
+		let n = 40;
+		// self-adjoint matrix with a zero diagonal
+		let mut triplets = alloc::vec![];
+		for j in 0..n {
+			triplets.push(Triplet::new(j ^ 1, j, 10.0 * (1.0 + (j / 2) as f64)));
+			let k = (j + 5) % n;
+			triplets.push(Triplet::new(k, j, 1.0));
+			triplets.push(Triplet::new(j, k, 1.0));
+		}
+		let A = SparseColMat::<usize, f64>::try_new_from_triplets(n, n, &triplets).unwrap();
+		let dense = A.to_dense();
+		let b = Mat::<f64>::from_fn(n, 2, |i, j| (i as f64 + j as f64).cos());
+
+		// without the matching, the fill-reducing ordering splits the nonzero blocks, and a zero
+		// pivot is hit
+		let symbolic = linalg_sp::cholesky::factorize_symbolic_cholesky(
+			A.symbolic(),
+			Side::Lower,
+			Default::default(),
+			linalg_sp::cholesky::CholeskySymbolicParams {
+				supernodal_flop_ratio_threshold: SupernodalThreshold::FORCE_SUPERNODAL,
+				..Default::default()
+			},
+		)
+		.unwrap();
+		let par = Par::Seq;
+		let fwd = &mut *alloc::vec![0usize; n];
+		let bwd = &mut *alloc::vec![0usize; n];
+		let subdiag = &mut *alloc::vec![0.0f64; n];
+		let L_val = &mut *alloc::vec![0.0f64; symbolic.len_val()];
+		let lblt = symbolic.factorize_numeric_intranode_lblt(
+			L_val,
+			subdiag,
+			fwd,
+			bwd,
+			A.as_ref(),
+			Side::Lower,
+			par,
+			MemStack::new(&mut MemBuffer::new(
+				symbolic.factorize_numeric_intranode_lblt_scratch::<f64>(par, default()),
+			)),
+			default(),
+		);
+		let mut x = b.clone();
+		lblt.solve_in_place_with_conj(
+			Conj::No,
+			x.as_mut(),
+			par,
+			MemStack::new(&mut MemBuffer::new(symbolic.solve_in_place_scratch::<f64>(2, par))),
+		);
+		let err = (&dense * &x - &b).norm_max();
+		assert!(!(err < 1e-6));
+
+		let lblt = Lblt::try_new_with_matching(A.as_ref()).unwrap();
+		let x = lblt.solve(&b);
+		assert!((&dense * &x - &b).norm_max() < 1e-12);
+		let x = lblt.solve_transpose(&b);
+		assert!((&dense * &x - &b).norm_max() < 1e-12);
+	}
+}